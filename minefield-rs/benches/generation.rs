@@ -0,0 +1,139 @@
+//! Headless benchmark of minefield generation and solving.
+//!
+//! Generates many minefields at each difficulty, both randomly and such that they can be cleared without guessing, runs
+//! the solver on them, and reports the throughput of each. Exits with an error if any of them is slower than its
+//! threshold, so that performance regressions (e.g. in the retry loop of the solvable generation) are caught.
+//!
+//! Run with `cargo bench -p minefield-rs`.
+
+use minefield_rs::{Minefield, SpotState};
+use std::time::{Duration, Instant};
+
+/// A difficulty to benchmark: name, width, height and number of mines
+struct Difficulty(&'static str, u16, u16, u32);
+
+const DIFFICULTIES: [Difficulty; 4] = [
+    Difficulty("Easy", 10, 10, 10),
+    Difficulty("Medium", 16, 16, 40),
+    Difficulty("Hard", 30, 16, 99),
+    Difficulty("Custom", 45, 24, 150),
+];
+
+/// Number of randomly generated minefields, per difficulty
+const RANDOM_BOARDS: u64 = 2000;
+
+/// Number of minefields generated such that they can be cleared without guessing, per difficulty
+const SOLVABLE_BOARDS: u64 = 20;
+
+/// Maximum number of attempts when generating a minefield which can be cleared without guessing
+const SOLVABLE_ATTEMPTS: u32 = 1000;
+
+/// Slowest acceptable average time for randomly generating a minefield
+const MAX_RANDOM_TIME: Duration = Duration::from_millis(1);
+
+/// Slowest acceptable average time for solving a randomly generated minefield
+const MAX_SOLVE_TIME: Duration = Duration::from_millis(5);
+
+/// Slowest acceptable average time for generating a minefield which can be cleared without guessing
+const MAX_SOLVABLE_TIME: Duration = Duration::from_millis(500);
+
+fn main() {
+    let mut failures = vec![];
+
+    println!(
+        "{:<8} {:>20} {:>20} {:>20} {:>10} {:>14}",
+        "",
+        "random (per board)",
+        "solve (per board)",
+        "no-guess (per board)",
+        "solvable",
+        "attempts (avg)"
+    );
+
+    for Difficulty(name, width, height, mines) in DIFFICULTIES {
+        // Random generation
+        let start = Instant::now();
+        let boards: Vec<Minefield> = (0..RANDOM_BOARDS)
+            .map(|seed| Minefield::new(width, height).with_mines_seeded(mines, seed))
+            .collect();
+        let random_time = start.elapsed() / RANDOM_BOARDS as u32;
+
+        // Solving, starting from a spot without neighboring mines (like the first step of a lucky player)
+        let start = Instant::now();
+        let solved = boards
+            .iter()
+            .filter(|minefield| {
+                first_step(minefield)
+                    .is_some_and(|(x, y)| minefield.is_solvable_without_guessing(x, y))
+            })
+            .count();
+        let solve_time = start.elapsed() / RANDOM_BOARDS as u32;
+
+        // Generation of minefields which can be cleared without guessing
+        let (x, y) = (width / 2, height / 2);
+        let start = Instant::now();
+        let attempts: u32 = (0..SOLVABLE_BOARDS)
+            .map(|seed| {
+                let (_, generation) = Minefield::new(width, height).with_mines_solvable_seeded(
+                    mines,
+                    (x, y),
+                    SOLVABLE_ATTEMPTS,
+                    seed,
+                );
+
+                generation.attempts
+            })
+            .sum();
+        let solvable_time = start.elapsed() / SOLVABLE_BOARDS as u32;
+
+        println!(
+            "{:<8} {:>20} {:>20} {:>20} {:>9.1}% {:>14.1}",
+            name,
+            format!("{:?}", random_time),
+            format!("{:?}", solve_time),
+            format!("{:?}", solvable_time),
+            solved as f64 * 100.0 / RANDOM_BOARDS as f64,
+            attempts as f64 / SOLVABLE_BOARDS as f64,
+        );
+
+        for (what, time, max_time) in [
+            ("random generation", random_time, MAX_RANDOM_TIME),
+            ("solving", solve_time, MAX_SOLVE_TIME),
+            ("no-guess generation", solvable_time, MAX_SOLVABLE_TIME),
+        ] {
+            if time > max_time {
+                failures.push(format!(
+                    "{} {}: {:?} per board, over {:?}",
+                    name, what, time, max_time
+                ));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        eprintln!();
+        for failure in &failures {
+            eprintln!("too slow: {}", failure);
+        }
+
+        std::process::exit(1);
+    }
+}
+
+/// A spot without neighboring mines, or any empty spot if there are none
+fn first_step(minefield: &Minefield) -> Option<(u16, u16)> {
+    let empty = |zero_only: bool| {
+        minefield
+            .spots()
+            .filter(|(_, spot)| match spot.state {
+                SpotState::HiddenEmpty { neighboring_mines } => {
+                    !zero_only || neighboring_mines == 0
+                }
+                _ => false,
+            })
+            .map(|(&coords, _)| coords)
+            .min()
+    };
+
+    empty(true).or_else(|| empty(false))
+}
@@ -0,0 +1,149 @@
+//! Headless benchmark of dense and sparse minefields, on very large boards.
+//!
+//! Measures the memory taken by each minefield, and the time taken to create it, check if it is cleared and take a
+//! first step on it. Exits with an error if a sparse minefield takes more memory than a dense one before any spot is
+//! revealed, since that's the case it's meant for.
+//!
+//! Run with `cargo bench -p minefield-rs --bench sparse`.
+
+use minefield_rs::{Minefield, SpotState};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+/// An allocator which keeps track of the number of bytes currently allocated
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// A board to benchmark: name, width, height and number of mines
+struct Board(&'static str, u16, u16, u32);
+
+const BOARDS: [Board; 3] = [
+    Board("Custom", 200, 200, 4000),
+    Board("Epic", 1000, 1000, 10_000),
+    Board("Huge", 2000, 2000, 20_000),
+];
+
+/// Seed of the generated minefields, so that dense and sparse minefields have the same mines
+const SEED: u64 = 0;
+
+/// What was measured on a minefield
+struct Measurement {
+    bytes: usize,
+    create: Duration,
+    is_cleared: Duration,
+    step: Duration,
+    bytes_after_step: usize,
+}
+
+fn main() {
+    let mut failures = vec![];
+
+    println!(
+        "{:<8} {:<7} {:>12} {:>12} {:>12} {:>12} {:>18}",
+        "", "", "memory", "create", "is_cleared", "first step", "memory after step"
+    );
+
+    for Board(name, width, height, mines) in BOARDS {
+        let dense = measure(|| Minefield::new(width, height).with_mines_seeded(mines, SEED));
+        let sparse =
+            measure(|| Minefield::new_sparse(width, height).with_mines_seeded(mines, SEED));
+
+        for (kind, m) in [("dense", &dense), ("sparse", &sparse)] {
+            println!(
+                "{:<8} {:<7} {:>12} {:>12} {:>12} {:>12} {:>18}",
+                name,
+                kind,
+                format_bytes(m.bytes),
+                format!("{:.1?}", m.create),
+                format!("{:.1?}", m.is_cleared),
+                format!("{:.1?}", m.step),
+                format_bytes(m.bytes_after_step),
+            );
+        }
+
+        if sparse.bytes >= dense.bytes {
+            failures.push(format!(
+                "{}: sparse minefield takes {}, dense one takes {}",
+                name,
+                format_bytes(sparse.bytes),
+                format_bytes(dense.bytes)
+            ));
+        }
+    }
+
+    if !failures.is_empty() {
+        eprintln!();
+        for failure in &failures {
+            eprintln!("too large: {}", failure);
+        }
+
+        std::process::exit(1);
+    }
+}
+
+/// Create a minefield, then check if it's cleared and step on a spot without neighboring mines
+fn measure(create: impl Fn() -> Minefield) -> Measurement {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+
+    let start = Instant::now();
+    let mut minefield = create();
+    let create = start.elapsed();
+    let bytes = ALLOCATED.load(Ordering::Relaxed) - before;
+
+    let start = Instant::now();
+    assert!(!minefield.is_cleared());
+    let is_cleared = start.elapsed();
+
+    // The spots are looked at one by one, since listing all of them would store their coordinates
+    let zero = (0..minefield.height())
+        .flat_map(|y| (0..minefield.width()).map(move |x| (x, y)))
+        .find(|&(x, y)| {
+            minefield.spot(x, y).map(|spot| spot.state)
+                == Some(SpotState::HiddenEmpty {
+                    neighboring_mines: 0,
+                })
+        })
+        .expect("a spot without neighboring mines");
+
+    let start = Instant::now();
+    minefield.step(zero.0, zero.1);
+    let step = start.elapsed();
+    let bytes_after_step = ALLOCATED.load(Ordering::Relaxed) - before;
+
+    drop(minefield);
+
+    Measurement {
+        bytes,
+        create,
+        is_cleared,
+        step,
+        bytes_after_step,
+    }
+}
+
+fn format_bytes(bytes: usize) -> String {
+    if bytes >= 1 << 20 {
+        format!("{:.1} MiB", bytes as f64 / (1 << 20) as f64)
+    } else {
+        format!("{:.1} KiB", bytes as f64 / (1 << 10) as f64)
+    }
+}
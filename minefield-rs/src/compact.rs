@@ -0,0 +1,138 @@
+use crate::Minefield;
+use std::fmt::Display;
+
+/// Characters of the URL safe base64 alphabet, so that a board code can be pasted in a link or a file name
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Reasons for which a board code can't be turned back into a minefield
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompactError {
+    /// The code has a character which is not part of the base64 alphabet, at the given position
+    Character(usize),
+
+    /// The code is too short or too long for the size of the minefield it encodes
+    Length,
+
+    /// The width or height of the encoded minefield is zero
+    Size,
+}
+
+impl Display for CompactError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompactError::Character(i) => write!(f, "Invalid character at position {}", i),
+            CompactError::Length => write!(f, "The board code has the wrong length"),
+            CompactError::Size => write!(f, "The board has no spots"),
+        }
+    }
+}
+
+impl std::error::Error for CompactError {}
+
+impl Minefield {
+    /// A short code holding the size of the minefield and where its mines are, which can be shared so that others can
+    /// play the same board (see `from_compact_string`). The state of the spots is not kept, only the layout of the
+    /// mines.
+    ///
+    /// The code is the URL safe base64 (without padding) of the width and height, as little endian `u16`s, followed by
+    /// one bit for each spot in row order, set for the mines.
+    pub fn to_compact_string(&self) -> String {
+        let spot_count = self.width as usize * self.height as usize;
+        let mut bytes = Vec::with_capacity(4 + spot_count.div_ceil(8));
+        bytes.extend_from_slice(&self.width.to_le_bytes());
+        bytes.extend_from_slice(&self.height.to_le_bytes());
+        bytes.resize(4 + spot_count.div_ceil(8), 0);
+
+        for ((x, y), _) in self.mine_spots() {
+            let index = y as usize * self.width as usize + x as usize;
+            bytes[4 + index / 8] |= 1 << (index % 8);
+        }
+
+        encode(&bytes)
+    }
+
+    /// Create a minefield with all its spots hidden, with the size and mines given by a code made by
+    /// `to_compact_string`
+    pub fn from_compact_string(code: &str) -> Result<Minefield, CompactError> {
+        let bytes = decode(code.trim())?;
+
+        if bytes.len() < 4 {
+            return Err(CompactError::Length);
+        }
+
+        let width = u16::from_le_bytes([bytes[0], bytes[1]]);
+        let height = u16::from_le_bytes([bytes[2], bytes[3]]);
+
+        if width == 0 || height == 0 {
+            return Err(CompactError::Size);
+        }
+
+        let spot_count = width as usize * height as usize;
+        let bits = &bytes[4..];
+
+        if bits.len() != spot_count.div_ceil(8) {
+            return Err(CompactError::Length);
+        }
+
+        let mines: Vec<(u16, u16)> = (0..spot_count)
+            .filter(|index| bits[index / 8] & (1 << (index % 8)) != 0)
+            .map(|index| {
+                (
+                    (index % width as usize) as u16,
+                    (index / width as usize) as u16,
+                )
+            })
+            .collect();
+
+        Ok(Minefield::new(width, height).with_mines_at(&mines))
+    }
+}
+
+/// Encode the bytes as URL safe base64, without padding
+fn encode(bytes: &[u8]) -> String {
+    let mut code = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (i, &b)| group | (b as u32) << (16 - 8 * i));
+
+        // Each byte of the chunk needs one more character, the rest of the group is padding which is left out
+        for i in 0..=chunk.len() {
+            code.push(ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+
+    code
+}
+
+/// Decode URL safe base64, without padding
+fn decode(code: &str) -> Result<Vec<u8>, CompactError> {
+    let mut bytes = Vec::with_capacity(code.len() * 3 / 4);
+    let mut group = 0u32;
+    let mut bits = 0;
+
+    for (i, c) in code.bytes().enumerate() {
+        let value = ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or(CompactError::Character(i))?;
+
+        group = group << 6 | value as u32;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((group >> bits) as u8);
+            group &= (1 << bits) - 1;
+        }
+    }
+
+    // A single character left over can't hold a whole byte
+    if bits >= 6 {
+        return Err(CompactError::Length);
+    }
+
+    Ok(bytes)
+}
@@ -0,0 +1,3220 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use solver::Solver;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::OnceLock;
+
+pub use compact::CompactError;
+
+mod compact;
+mod solver;
+
+/// Random spots tried for each mine moved by `ensure_open_start`, before giving up on a board too dense to make room
+const OPEN_START_ATTEMPTS: u32 = 1000;
+
+/// The spots changed by a move, with the states they had before it. Spots which were not stored in a sparse minefield
+/// have no previous state.
+type Move = Vec<((u16, u16), Option<Spot>)>;
+
+/// The characteristics of the minefield
+#[derive(Clone, Debug)]
+pub struct Minefield {
+    /// The mine field as a set of coords `(x, y)` associated with a `Spot`
+    field: HashMap<(u16, u16), Spot>,
+
+    /// Number of mines in the field
+    mines: u32,
+
+    /// Width of field grid
+    width: u16,
+
+    /// Height of field grid
+    height: u16,
+
+    /// Which spots count as neighbors of a spot
+    connectivity: Connectivity,
+
+    /// The moves taken on the minefield, most recent last, which can be undone
+    history: Vec<Move>,
+
+    /// The moves which have been undone, most recently undone last, which can be taken again
+    undone: Vec<Move>,
+
+    /// The spots changed so far by the move being taken, with their previous states
+    current_move: Option<HashMap<(u16, u16), Option<Spot>>>,
+
+    /// Number of revealed empty spots, kept up to date by every move, undo and redo
+    revealed: u32,
+
+    /// Autosteps are refused around spots with wrong flags, instead of stepping on the mines which are not flagged
+    safe_chord: bool,
+
+    /// The minefield is cleared once all its empty spots are revealed, whether its mines are flagged or not
+    win_on_all_revealed: bool,
+
+    /// No more flags can be placed than there are mines
+    strict_flags: bool,
+
+    /// Flags are replaced by question marks before being cleared
+    question_marks: bool,
+
+    /// The coordinates of all the spots, row by row, which a sparse minefield lists the first time its spots are iterated
+    /// over (see `spots`), since it doesn't store the coordinates of the spots it leaves out
+    listed_coords: OnceLock<Vec<(u16, u16)>>,
+}
+
+impl Minefield {
+    /// Create an empty minefield grid (with all spots hidden), with the given width and height
+    pub fn new(width: u16, height: u16) -> Self {
+        let mut minefield = Self::new_sparse(width, height);
+
+        minefield.field = (0..minefield.width)
+            .flat_map(move |i| (0..minefield.height).map(move |j| (i, j)))
+            .map(|(x, y)| ((x, y), Spot::default()))
+            .collect();
+
+        minefield
+    }
+
+    /// Create an empty minefield grid like `new`, which only stores the spots that are not hidden spots without
+    /// neighboring mines. This takes much less memory for very large minefields with few mines, and makes checking if
+    /// the minefield is cleared faster, at the cost of slightly slower access to each spot. Both behave the same, but the
+    /// first iteration over all the spots (see `spots`) lists their coordinates, which takes memory again.
+    pub fn new_sparse(width: u16, height: u16) -> Self {
+        // Enforce a minimum number of spots
+        let width = if width == 0 { 1 } else { width };
+        let height = if height == 0 { 1 } else { height };
+
+        // Create empty Minefield
+        Minefield {
+            field: HashMap::new(),
+            mines: 0,
+            width,
+            height,
+            connectivity: Connectivity::default(),
+            history: Vec::new(),
+            undone: Vec::new(),
+            current_move: None,
+            revealed: 0,
+            safe_chord: false,
+            win_on_all_revealed: false,
+            strict_flags: false,
+            question_marks: false,
+            listed_coords: OnceLock::new(),
+        }
+    }
+
+    /// Create a minefield grid like `new`, with mines placed at exactly the given coordinates `(x, y)` (see
+    /// `with_mines_at`), together with the number of mines which were actually placed. Coordinates outside of the field
+    /// grid, and repeated coordinates, are ignored, so fewer mines than coordinates tell that some were rejected.
+    pub fn from_mines(width: u16, height: u16, mines: &[(u16, u16)]) -> (Self, u32) {
+        let minefield = Self::new(width, height).with_mines_at(mines);
+        let placed = minefield.mines;
+
+        (minefield, placed)
+    }
+
+    /// Build an existing minefield in which the neighbors of a spot are given by `connectivity`. This affects the
+    /// numbers of neighboring mines, flood reveals and autosteps.
+    pub fn with_connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
+
+        // Recount the neighboring mines, in case mines have already been placed
+        for (x, y) in self.all_coords() {
+            let count = self
+                .neighbors_coords(x, y)
+                .filter(|&(nx, ny)| {
+                    matches!(
+                        self.spot(nx, ny).map(|spot| spot.state),
+                        Some(
+                            SpotState::HiddenMine
+                                | SpotState::FlaggedMine
+                                | SpotState::QuestionedMine
+                                | SpotState::ExplodedMine
+                        )
+                    )
+                })
+                .count() as u8;
+
+            // Spots without neighboring mines don't need to be stored in a sparse minefield
+            if count == 0 && !self.field.contains_key(&(x, y)) {
+                continue;
+            }
+
+            if let Some(spot) = self.spot_mut(x, y) {
+                match &mut spot.state {
+                    SpotState::HiddenEmpty { neighboring_mines }
+                    | SpotState::FlaggedEmpty { neighboring_mines }
+                    | SpotState::QuestionedEmpty { neighboring_mines }
+                    | SpotState::RevealedEmpty { neighboring_mines } => {
+                        *neighboring_mines = count;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Build an existing minefield in which autosteps are refused (see `auto_step`) around a revealed spot with a flag
+    /// on one of its empty neighbors, since they would step on one of its mines. Autosteps only count the flags by
+    /// default, like in classic minesweeper.
+    pub fn with_safe_chord(mut self, safe_chord: bool) -> Self {
+        self.safe_chord = safe_chord;
+
+        self
+    }
+
+    /// Build an existing minefield which is cleared (see `is_cleared`) as soon as all its empty spots are revealed, even if
+    /// some of its mines are not flagged. By default, all the mines also need to be flagged.
+    pub fn with_win_on_all_revealed(mut self, win_on_all_revealed: bool) -> Self {
+        self.win_on_all_revealed = win_on_all_revealed;
+
+        self
+    }
+
+    /// Build an existing minefield in which no flag can be placed (see `toggle_flag` and `auto_flag`) once there are as
+    /// many flags as mines. By default, any number of flags can be placed.
+    pub fn with_strict_flags(mut self, strict_flags: bool) -> Self {
+        self.strict_flags = strict_flags;
+
+        self
+    }
+
+    /// Build an existing minefield in which flagging a flagged spot marks it with a question mark, which is cleared by
+    /// flagging it once more (see `toggle_flag`). By default, flagging a flagged spot simply clears its flag.
+    pub fn with_question_marks(mut self, question_marks: bool) -> Self {
+        self.question_marks = question_marks;
+
+        self
+    }
+
+    /// Build an existing minefield with the given number of mines randomly placed in it. At least one spot is always
+    /// left without a mine, so there can be fewer mines than asked for (see `mines`).
+    pub fn with_mines(self, mines: u32) -> Self {
+        self.with_mines_seeded(mines, rand::random())
+    }
+
+    /// Build an existing minefield with the given number of mines placed in it by a random generator initialized with
+    /// `seed`. The same seed always places the mines of a minefield with the same width and height in the same spots,
+    /// on every platform, since the generator is ChaCha8 rather than the one `rand` picks as its standard generator.
+    pub fn with_mines_seeded(mut self, mines: u32, seed: u64) -> Self {
+        self.place_random_mines(mines, &[], &mut ChaCha8Rng::seed_from_u64(seed));
+
+        self
+    }
+
+    /// Build an existing minefield with mines placed at the given coordinates `(x, y)`. Coordinates outside of the
+    /// field grid, and repeated coordinates, are ignored.
+    pub fn with_mines_at(mut self, mines: &[(u16, u16)]) -> Self {
+        for &(x, y) in mines {
+            if x < self.width
+                && y < self.height
+                && self.spot(x, y).map(|spot| spot.state) != Some(SpotState::HiddenMine)
+            {
+                self.place_mine(x, y);
+                self.mines += 1;
+            }
+        }
+
+        self
+    }
+
+    /// Build an existing minefield with the given number of mines randomly placed in it, such that it can be cleared
+    /// without guessing when the first step is on the `start` spot.
+    ///
+    /// Minefields are randomly generated until a solvable one is found, or until `max_attempts` minefields have been
+    /// tried, in which case the last one is returned. The returned `Generation` tells which was the case.
+    pub fn with_mines_solvable(
+        self,
+        mines: u32,
+        start: (u16, u16),
+        max_attempts: u32,
+    ) -> (Self, Generation) {
+        self.with_mines_solvable_seeded(mines, start, max_attempts, rand::random())
+    }
+
+    /// Same as `with_mines_solvable`, but with all the minefields generated by a random generator initialized with
+    /// `seed`, so that the same seed always gives the same minefield
+    pub fn with_mines_solvable_seeded(
+        self,
+        mines: u32,
+        start: (u16, u16),
+        max_attempts: u32,
+        seed: u64,
+    ) -> (Self, Generation) {
+        let (x, y) = start;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let spot_count = self.width as usize * self.height as usize;
+
+        // Keep the first step (and, if there is room, its neighbors) free of mines, so that it reveals something. The
+        // first step is always kept free, even if that leaves room for fewer mines than asked for.
+        let mut excluded = vec![start];
+        let neighbors: Vec<(u16, u16)> = self.neighbors_coords(x, y).collect();
+        if mines as usize + neighbors.len() < spot_count {
+            excluded.extend(neighbors);
+        }
+
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+
+            let mut minefield = self.clone();
+            minefield.place_random_mines(mines, &excluded, &mut rng);
+
+            let solvable = minefield.is_solvable_without_guessing(x, y);
+
+            if solvable || attempts >= max_attempts {
+                return (minefield, Generation { attempts, solvable });
+            }
+        }
+    }
+
+    /// Check if the minefield can be cleared without guessing, when the first step is on the spot at the given
+    /// coordinates
+    pub fn is_solvable_without_guessing(&self, x: u16, y: u16) -> bool {
+        let mut solver = Solver::new(self);
+
+        solver.reveal(x, y) && solver.solve()
+    }
+
+    /// Estimate the probability of the hidden spot at the given coordinates being a mine, only from the spots which
+    /// have been revealed so far (flags are not trusted). Returns `None` if the spot doesn't exist or is revealed.
+    pub fn mine_probability(&self, x: u16, y: u16) -> Option<f32> {
+        match self.spot(x, y)?.state {
+            SpotState::RevealedEmpty { .. } | SpotState::ExplodedMine => None,
+            _ => Some(Solver::from_revealed(self).mine_probability(x, y)),
+        }
+    }
+
+    /// Estimate the probability of each spot which isn't revealed being a mine, like `mine_probability`. Spots which are
+    /// certainly safe or certainly mines have a probability of `0.0` or `1.0`, the others get the highest density of
+    /// mines among the groups of hidden spots around the revealed numbers they touch, or the density of the remaining
+    /// mines if they don't touch any.
+    pub fn mine_probabilities(&self) -> BTreeMap<(u16, u16), f32> {
+        let probabilities = Solver::from_revealed(self).mine_probabilities();
+
+        self.all_coords()
+            .zip(probabilities)
+            .filter(|&((x, y), _)| {
+                !matches!(
+                    self.spot(x, y).map(|spot| spot.state),
+                    Some(SpotState::RevealedEmpty { .. } | SpotState::ExplodedMine)
+                )
+            })
+            .collect()
+    }
+
+    /// Find a hidden spot which is certainly safe, only from the spots which have been revealed so far (flags are not
+    /// trusted). Returns `None` if there is no such spot, so that the next step would have to be a guess.
+    pub fn safe_spot(&self) -> Option<(u16, u16)> {
+        Solver::from_revealed(self)
+            .safe_spots()
+            .into_iter()
+            .find(|&(x, y)| {
+                matches!(
+                    self.spot(x, y).map(|spot| spot.state),
+                    Some(SpotState::HiddenEmpty { .. })
+                )
+            })
+    }
+
+    /// Find the next move which is certain, only from the spots which have been revealed so far (flags are not
+    /// trusted): a step on a spot which is certainly safe, or else a flag on a spot which is certainly a mine and isn't
+    /// flagged yet. A questioned mine is toggled back to hidden by its first flag move, and flagged by the next one.
+    /// Returns `None` if no move is certain, so that the next move would have to be a guess.
+    pub fn next_certain_move(&self) -> Option<SolverMove> {
+        let mut solver = Solver::from_revealed(self);
+
+        let step = solver
+            .safe_spots()
+            .into_iter()
+            .find(|&(x, y)| {
+                matches!(
+                    self.spot(x, y).map(|spot| spot.state),
+                    Some(SpotState::HiddenEmpty { .. } | SpotState::QuestionedEmpty { .. })
+                )
+            })
+            .map(|(x, y)| SolverMove::Step { x, y });
+
+        step.or_else(|| {
+            solver
+                .mine_spots()
+                .into_iter()
+                .find(|&(x, y)| {
+                    matches!(
+                        self.spot(x, y).map(|spot| spot.state),
+                        Some(SpotState::HiddenMine | SpotState::QuestionedMine)
+                    )
+                })
+                .map(|(x, y)| SolverMove::Flag { x, y })
+        })
+    }
+
+    /// Step on a given spot of the field. Coordinates [x=0, y=0] represent the top-left point of the field grid
+    pub fn step(&mut self, x: u16, y: u16) -> StepResult {
+        self.record_move(|minefield| minefield.step_unrecorded(x, y))
+    }
+
+    fn step_unrecorded(&mut self, x: u16, y: u16) -> StepResult {
+        if let Some(spot) = self.spot_mut(x, y) {
+            let mut step_result = spot.step();
+
+            // flood reveal, if this is an empty spot with no neighboring mines
+            if let SpotState::RevealedEmpty {
+                neighboring_mines: 0,
+            } = spot.state
+            {
+                let mut spots_to_visit = vec![(x, y)];
+
+                while let Some((xx, yy)) = spots_to_visit.pop() {
+                    for n_coords in self.neighbors_coords(xx, yy) {
+                        if let Some(
+                            SpotState::HiddenEmpty { neighboring_mines }
+                            | SpotState::QuestionedEmpty { neighboring_mines },
+                        ) = self.spot(n_coords.0, n_coords.1).map(|spot| spot.state)
+                        {
+                            // Reveal the hidden (or merely questioned) empty spot by stepping on it
+                            let _step_result =
+                                self.spot_mut(n_coords.0, n_coords.1).unwrap().step();
+                            assert_eq!(_step_result, StepResult::Phew { revealed: 1 });
+                            step_result = step_result.and(_step_result);
+
+                            if neighboring_mines == 0 {
+                                // contine flood revealing neighbors from this spot
+                                spots_to_visit.push(n_coords);
+                            }
+                        }
+                    }
+                }
+            }
+
+            step_result
+        } else {
+            // Step is outside minefield
+            StepResult::Invalid(InvalidStep::OutOfBounds)
+        }
+    }
+
+    /// Automatically step on all hidden neighbors (i.e. not flagged) of a revealed spot at the given coordiantes. With
+    /// a safe chord (see `with_safe_chord`), nothing is revealed if any of the flags around the spot is wrong.
+    pub fn auto_step(&mut self, x: u16, y: u16) -> StepResult {
+        self.record_move(|minefield| minefield.auto_step_unrecorded(x, y))
+    }
+
+    fn auto_step_unrecorded(&mut self, x: u16, y: u16) -> StepResult {
+        if let Some(spot) = self.spot(x, y) {
+            if let SpotState::RevealedEmpty { neighboring_mines } = spot.state {
+                // count the flags around the given coords
+                let placed_flags = self.flagged_neighbors(x, y);
+
+                // Only try to autostep if the user has placed enough flags around the spot whose neighbors will be
+                // autorevealed
+                if placed_flags == neighboring_mines {
+                    if self.safe_chord && self.has_wrong_flags_around(x, y) {
+                        // The flags can't all be on mines, so a mine would be stepped on
+                        return StepResult::Invalid(InvalidStep::WrongFlags);
+                    }
+
+                    let mut step_result = StepResult::Phew { revealed: 0 };
+
+                    for (nx, ny) in self.neighbors_coords(x, y) {
+                        match self.step_unrecorded(nx, ny) {
+                            // Eager Boom return
+                            StepResult::Boom => return StepResult::Boom,
+                            neighbor_result => step_result = step_result.and(neighbor_result),
+                        }
+                    }
+
+                    step_result
+                } else {
+                    // Not as many flags placed by user as the neighboring mines, in order to autostep
+                    StepResult::Invalid(InvalidStep::FlagCount {
+                        flagged: placed_flags,
+                        neighboring_mines,
+                    })
+                }
+            } else {
+                // Spot is not revealed yet
+                StepResult::Invalid(InvalidStep::not_revealed(spot.state))
+            }
+        } else {
+            // invalid spot coordinates
+            StepResult::Invalid(InvalidStep::OutOfBounds)
+        }
+    }
+
+    /// Flag all the hidden neighbors (including the questioned ones) of a revealed spot at the given coordinates, if
+    /// they must all be mines, i.e. if there are exactly as many of them as its neighboring mines left unflagged. This
+    /// is the complement of `auto_step`. Returns the number of flags which were added, which is 0 if the neighbors
+    /// couldn't all be flagged, or if that would make more flags than mines with strict flags (see `with_strict_flags`).
+    pub fn auto_flag(&mut self, x: u16, y: u16) -> u8 {
+        self.record_move(|minefield| minefield.auto_flag_unrecorded(x, y))
+    }
+
+    /// Flag all the mines which are not flagged yet, once the minefield is cleared without them being flagged (see
+    /// `with_win_on_all_revealed`), and return how many were flagged. Nothing is flagged while some of the empty spots
+    /// are still hidden, since that would give the mines away.
+    pub fn flag_remaining_mines(&mut self) -> u32 {
+        if self.remaining_safe_cells() > 0 {
+            return 0;
+        }
+
+        self.record_move(|minefield| {
+            // Mines always have a stored spot, even in a sparse minefield
+            let unflagged: Vec<(u16, u16)> = minefield
+                .field
+                .iter()
+                .filter(|(_, spot)| {
+                    matches!(
+                        spot.state,
+                        SpotState::HiddenMine | SpotState::QuestionedMine
+                    )
+                })
+                .map(|(&coords, _)| coords)
+                .collect();
+
+            for &(x, y) in &unflagged {
+                minefield.spot_mut(x, y).unwrap().state = SpotState::FlaggedMine;
+            }
+
+            unflagged.len() as u32
+        })
+    }
+
+    fn auto_flag_unrecorded(&mut self, x: u16, y: u16) -> u8 {
+        let Some(SpotState::RevealedEmpty { neighboring_mines }) =
+            self.spot(x, y).map(|spot| spot.state)
+        else {
+            // Spot is not revealed yet, or the coordinates are invalid
+            return 0;
+        };
+
+        let hidden: Vec<(u16, u16)> = self
+            .neighbors_coords(x, y)
+            .filter(|(nx, ny)| {
+                matches!(
+                    self.spot(*nx, *ny).unwrap().state,
+                    SpotState::HiddenEmpty { .. }
+                        | SpotState::HiddenMine
+                        | SpotState::QuestionedEmpty { .. }
+                        | SpotState::QuestionedMine
+                )
+            })
+            .collect();
+
+        if hidden.is_empty()
+            || self.flagged_neighbors(x, y) + hidden.len() as u8 != neighboring_mines
+            || self.exceeds_strict_flags(hidden.len() as u32)
+        {
+            return 0;
+        }
+
+        for &(nx, ny) in &hidden {
+            let spot = self.spot_mut(nx, ny).unwrap();
+
+            spot.state = match spot.state {
+                SpotState::HiddenEmpty { neighboring_mines }
+                | SpotState::QuestionedEmpty { neighboring_mines } => {
+                    SpotState::FlaggedEmpty { neighboring_mines }
+                }
+                _ => SpotState::FlaggedMine,
+            };
+        }
+
+        hidden.len() as u8
+    }
+
+    /// Check if placing the given number of flags would make more flags than mines, which strict flags don't allow
+    fn exceeds_strict_flags(&self, added: u32) -> bool {
+        self.strict_flags && self.flagged_spots().count() as u32 + added > self.mines
+    }
+
+    /// Check if a flag has been placed on an empty neighbor of the spot at the given coordinates
+    fn has_wrong_flags_around(&self, x: u16, y: u16) -> bool {
+        self.neighbors_coords(x, y).any(|(nx, ny)| {
+            matches!(
+                self.spot(nx, ny).unwrap().state,
+                SpotState::FlaggedEmpty { .. }
+            )
+        })
+    }
+
+    /// Number of flags placed on the neighbors of the spot at the given coordinates
+    pub fn flagged_neighbors(&self, x: u16, y: u16) -> u8 {
+        self.neighbors_coords(x, y)
+            .filter(|(x, y)| {
+                matches!(
+                    self.spot(*x, *y).unwrap().state,
+                    SpotState::FlaggedEmpty {
+                        neighboring_mines: _
+                    } | SpotState::FlaggedMine
+                )
+            })
+            .count() as u8
+    }
+
+    /// Check if the spot at the given coordinates is a revealed number which has as many flags placed around it as
+    /// its number of neighboring mines, i.e. it gives no further information (assuming the flags are right)
+    pub fn is_satisfied(&self, x: u16, y: u16) -> bool {
+        match self.spot(x, y).map(|spot| spot.state) {
+            Some(SpotState::RevealedEmpty { neighboring_mines }) if neighboring_mines > 0 => {
+                self.flagged_neighbors(x, y) == neighboring_mines
+            }
+            _ => false,
+        }
+    }
+
+    /// Automatically step around all the revealed neighbors of the spot at the given coordinates, which have enough
+    /// flags placed around them (see `auto_step`). This is meant to be used right after placing a flag on the spot, and
+    /// will step on a mine if any of the flags around those neighbors are wrong.
+    pub fn auto_step_neighbors(&mut self, x: u16, y: u16) -> StepResult {
+        self.record_move(|minefield| minefield.auto_step_neighbors_unrecorded(x, y))
+    }
+
+    fn auto_step_neighbors_unrecorded(&mut self, x: u16, y: u16) -> StepResult {
+        let mut step_result = StepResult::Invalid(InvalidStep::NothingToReveal);
+
+        for (nx, ny) in self.neighbors_coords(x, y) {
+            if let Some(SpotState::RevealedEmpty {
+                neighboring_mines: 1..,
+            }) = self.spot(nx, ny).map(|spot| spot.state)
+            {
+                match self.auto_step_unrecorded(nx, ny) {
+                    StepResult::Boom => {
+                        // Eager Boom return
+                        return StepResult::Boom;
+                    }
+                    neighbor_result => step_result = step_result.and(neighbor_result),
+                }
+            }
+        }
+
+        step_result
+    }
+
+    /// Step on the immediate hidden neighbors (i.e. not flagged) of a revealed spot at the given coordinates, without
+    /// requiring any flags to be placed around it. Unlike `step`, no flood reveal takes place beyond this ring of
+    /// neighbors.
+    pub fn reveal_ring(&mut self, x: u16, y: u16) -> StepResult {
+        self.record_move(|minefield| minefield.reveal_ring_unrecorded(x, y))
+    }
+
+    fn reveal_ring_unrecorded(&mut self, x: u16, y: u16) -> StepResult {
+        if let Some(spot) = self.spot(x, y) {
+            if let SpotState::RevealedEmpty {
+                neighboring_mines: _,
+            } = spot.state
+            {
+                let mut step_result = StepResult::Invalid(InvalidStep::NothingToReveal);
+
+                for (nx, ny) in self.neighbors_coords(x, y) {
+                    match self.spot(nx, ny).unwrap().state {
+                        SpotState::HiddenEmpty {
+                            neighboring_mines: _,
+                        }
+                        | SpotState::HiddenMine
+                        | SpotState::QuestionedEmpty {
+                            neighboring_mines: _,
+                        }
+                        | SpotState::QuestionedMine => {
+                            match self.spot_mut(nx, ny).unwrap().step() {
+                                // Eager Boom return
+                                StepResult::Boom => return StepResult::Boom,
+                                neighbor_result => step_result = step_result.and(neighbor_result),
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+
+                // `Invalid` if there were no hidden neighbors to step on
+                step_result
+            } else {
+                // Spot is not revealed yet
+                StepResult::Invalid(InvalidStep::not_revealed(spot.state))
+            }
+        } else {
+            // invalid spot coordinates
+            StepResult::Invalid(InvalidStep::OutOfBounds)
+        }
+    }
+
+    /// Number of empty spots which have not been revealed yet. This is kept up to date as spots get revealed, so it's
+    /// cheap to call after every move.
+    pub fn remaining_safe_cells(&self) -> u32 {
+        let spot_count = self.width as u32 * self.height as u32;
+
+        spot_count.saturating_sub(self.mines + self.revealed)
+    }
+
+    /// The 3BV (Bechtel's Board Benchmark Value) of the minefield: the least number of steps which clear it, without
+    /// any flag or autostep. Each opening (an area of spots without neighboring mines, together with the numbers around
+    /// it) takes a single step, and each number which isn't around an opening takes one more.
+    ///
+    /// The layout of the mines is all that counts, not which spots are revealed.
+    pub fn board_3bv(&self) -> u32 {
+        let neighboring_mines = |x, y| self.spot(x, y).and_then(Spot::neighboring_mines);
+        let mut opened: HashSet<(u16, u16)> = HashSet::new();
+        let mut clicks = 0;
+
+        // Label the openings like a flood reveal would open them up
+        for (x, y) in self.all_coords() {
+            if neighboring_mines(x, y) != Some(0) || opened.contains(&(x, y)) {
+                continue;
+            }
+
+            clicks += 1;
+            opened.insert((x, y));
+            let mut spots_to_visit = vec![(x, y)];
+
+            while let Some((xx, yy)) = spots_to_visit.pop() {
+                for (nx, ny) in self.neighbors_coords(xx, yy) {
+                    if let Some(count) = neighboring_mines(nx, ny) {
+                        if opened.insert((nx, ny)) && count == 0 {
+                            spots_to_visit.push((nx, ny));
+                        }
+                    }
+                }
+            }
+        }
+
+        // The numbers left out of the openings are stepped on one by one
+        let isolated = self
+            .all_coords()
+            .filter(|&(x, y)| {
+                neighboring_mines(x, y).is_some_and(|count| count > 0) && !opened.contains(&(x, y))
+            })
+            .count() as u32;
+
+        clicks + isolated
+    }
+
+    /// Check if the minefield has been cleared
+    pub fn is_cleared(&self) -> bool {
+        // Only look for mines which are not flagged once all the empty spots are revealed
+        if self.remaining_safe_cells() > 0 {
+            return false;
+        }
+
+        if self.win_on_all_revealed {
+            return true;
+        }
+
+        // Resolved spots are never hidden, so they're always stored, even in a sparse minefield
+        let resolved = self
+            .field
+            .values()
+            .filter(|spot| spot.is_resolved())
+            .count();
+
+        resolved == self.width as usize * self.height as usize
+    }
+
+    /// Set a flag on a hidden spot, or clear the flag if the spot had one, or do nothing if
+    /// the spot cannot be flagged. With question marks (see `with_question_marks`), a flag is first replaced by a question
+    /// mark, which is cleared the next time, so that the spot cycles from hidden to flagged to questioned and back to
+    /// hidden. With strict flags (see `with_strict_flags`), a hidden spot is not flagged once there are as many flags as
+    /// mines.
+    pub fn toggle_flag(&mut self, x: u16, y: u16) -> FlagToggleResult {
+        let adds_flag = matches!(
+            self.spot(x, y).map(|spot| spot.state),
+            Some(SpotState::HiddenEmpty { .. } | SpotState::HiddenMine)
+        );
+        if adds_flag && self.exceeds_strict_flags(1) {
+            return FlagToggleResult::None;
+        }
+
+        let question_marks = self.question_marks;
+        self.record_move(|minefield| {
+            if let Some(spot) = minefield.spot_mut(x, y) {
+                spot.flag(question_marks)
+            } else {
+                // invalid coordinates, no flag was added or removed
+                FlagToggleResult::None
+            }
+        })
+    }
+
+    /// Check if a mine has been stepped on
+    pub fn is_exploded(&self) -> bool {
+        // Exploded mines are never hidden, so they're always stored, even in a sparse minefield
+        self.field
+            .values()
+            .any(|spot| spot.state == SpotState::ExplodedMine)
+    }
+
+    /// Revert the last move (step, autostep or flag) which changed the minefield, restoring the spots it changed.
+    /// Returns `false` if there is no move to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(changes) => {
+                let redo = self.restore(changes);
+                self.undone.push(redo);
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Take the last undone move again. Returns `false` if there is no undone move, which is the case as soon as a new
+    /// move is taken after undoing.
+    pub fn redo(&mut self) -> bool {
+        match self.undone.pop() {
+            Some(changes) => {
+                let undo = self.restore(changes);
+                self.history.push(undo);
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// There is a move which can be undone
+    pub fn can_undo(&self) -> bool {
+        !self.history.is_empty()
+    }
+
+    /// There is an undone move which can be taken again
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Take a move, remembering the spots it changes so that it can be undone. Moves taken by other moves are part of
+    /// them, and moves which change nothing are not remembered.
+    fn record_move<T>(&mut self, take_move: impl FnOnce(&mut Self) -> T) -> T {
+        if self.current_move.is_some() {
+            return take_move(self);
+        }
+
+        self.current_move = Some(HashMap::new());
+        let result = take_move(self);
+
+        let changes: Move = self
+            .current_move
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(coords, previous)| {
+                let previous = previous.unwrap_or(Spot::HIDDEN_EMPTY).state;
+
+                self.field.get(coords).map(|spot| spot.state) != Some(previous)
+            })
+            .collect();
+
+        for (coords, previous) in &changes {
+            self.count_revealed(previous.as_ref(), self.field.get(coords).copied().as_ref());
+        }
+
+        if !changes.is_empty() {
+            self.history.push(changes);
+            self.undone.clear();
+        }
+
+        result
+    }
+
+    /// Put back the given states of the spots, returning the states they had instead
+    fn restore(&mut self, changes: Move) -> Move {
+        changes
+            .into_iter()
+            .map(|(coords, spot)| {
+                let current = match spot {
+                    Some(spot) => self.field.insert(coords, spot),
+                    None => self.field.remove(&coords),
+                };
+                self.count_revealed(current.as_ref(), spot.as_ref());
+
+                (coords, current)
+            })
+            .collect()
+    }
+
+    /// Account for a spot changing from `previous` to `current`, in the number of revealed spots
+    fn count_revealed(&mut self, previous: Option<&Spot>, current: Option<&Spot>) {
+        match (
+            previous.is_some_and(Spot::is_revealed),
+            current.is_some_and(Spot::is_revealed),
+        ) {
+            (false, true) => self.revealed += 1,
+            (true, false) => self.revealed -= 1,
+            _ => {}
+        }
+    }
+
+    /// The width of the minefield
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// The height of the minefield
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// The number of mines in the minefield
+    pub fn mines(&self) -> u32 {
+        self.mines
+    }
+
+    /// Get a reference to a particular `Spot` in the field
+    pub fn spot(&self, x: u16, y: u16) -> Option<&Spot> {
+        if x < self.width && y < self.height {
+            // Spots which are not stored in a sparse minefield are hidden, with no neighboring mines
+            Some(self.field.get(&(x, y)).unwrap_or(&Spot::HIDDEN_EMPTY))
+        } else {
+            None
+        }
+    }
+
+    /// Iterator for all `Spot`s in the field, together with their coordinates `(x, y)`, row by row
+    pub fn spots(&self) -> impl Iterator<Item = (&(u16, u16), &Spot)> {
+        self.all_coords().enumerate().map(move |(i, coords)| {
+            self.field.get_key_value(&coords).unwrap_or_else(|| {
+                // Spots which are not stored in a sparse minefield are hidden, with no neighboring mines
+                let listed = self
+                    .listed_coords
+                    .get_or_init(|| self.all_coords().collect());
+
+                (&listed[i], &Spot::HIDDEN_EMPTY)
+            })
+        })
+    }
+
+    /// Iterator over the revealed empty spots of the field, together with their coordinates `(x, y)`, in no particular
+    /// order. Only the stored spots are looked at, which are much fewer than all the spots of a large sparse minefield.
+    pub fn revealed_spots(&self) -> impl Iterator<Item = ((u16, u16), &Spot)> {
+        self.field
+            .iter()
+            .filter(|(_, spot)| matches!(spot.state, SpotState::RevealedEmpty { .. }))
+            .map(|(&coords, spot)| (coords, spot))
+    }
+
+    /// Iterator over the flagged spots of the field, together with their coordinates `(x, y)`, in no particular order.
+    /// Like `revealed_spots`, only the stored spots are looked at.
+    pub fn flagged_spots(&self) -> impl Iterator<Item = ((u16, u16), &Spot)> {
+        self.field
+            .iter()
+            .filter(|(_, spot)| {
+                matches!(
+                    spot.state,
+                    SpotState::FlaggedEmpty { .. } | SpotState::FlaggedMine
+                )
+            })
+            .map(|(&coords, spot)| (coords, spot))
+    }
+
+    /// Iterator over the spots of the field which hold a mine, whatever their state, together with their coordinates
+    /// `(x, y)`, in no particular order. Like `revealed_spots`, only the stored spots are looked at.
+    pub fn mine_spots(&self) -> impl Iterator<Item = ((u16, u16), &Spot)> {
+        self.field
+            .iter()
+            .filter(|(_, spot)| {
+                matches!(
+                    spot.state,
+                    SpotState::HiddenMine
+                        | SpotState::FlaggedMine
+                        | SpotState::QuestionedMine
+                        | SpotState::ExplodedMine
+                )
+            })
+            .map(|(&coords, spot)| (coords, spot))
+    }
+
+    /// Get a mutable reference to a particular `Spot` in the field, storing it first if the minefield is sparse
+    fn spot_mut(&mut self, x: u16, y: u16) -> Option<&mut Spot> {
+        if x < self.width && y < self.height {
+            // Remember the state of the spot before the move being taken changes it
+            if let Some(changes) = &mut self.current_move {
+                changes
+                    .entry((x, y))
+                    .or_insert_with(|| self.field.get(&(x, y)).copied());
+            }
+
+            Some(self.field.entry((x, y)).or_default())
+        } else {
+            None
+        }
+    }
+
+    /// Iterator over the coordinates `(x, y)` of all the spots in the field, row by row
+    fn all_coords(&self) -> impl Iterator<Item = (u16, u16)> {
+        let width = self.width;
+
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Randomly place the given number of mines in the minefield, avoiding the `excluded` coordinates
+    fn place_random_mines(&mut self, mines: u32, excluded: &[(u16, u16)], rng: &mut impl Rng) {
+        // Total number of spots in our field
+        let spot_count = self.width as usize * self.height as usize;
+
+        // We could just start randomly picking indices in the field and hope we haven't picked them before, but if a
+        // user desires a field full of mines, then waiting for the last mines to be placed might take a long time
+        // (e.g. if the field is very large).
+        // That's a problem for an immediate GUI.
+        // So, instead, we'll use some memory in order to ensure that the user can step on a mine as soon as humanly
+        // possible.
+        let mut spots_remaining: Vec<usize> = (0..spot_count)
+            .filter(|index| !excluded.contains(&self.coords(*index)))
+            .collect();
+
+        // Limit the max number of mines to the number of available spots, always leaving at least one spot without a
+        // mine, since a minefield made only of mines could not be played
+        let available = if excluded.is_empty() {
+            spots_remaining.len().saturating_sub(1)
+        } else {
+            spots_remaining.len()
+        };
+        self.mines = mines.min(available as u32);
+
+        // Pick the spots of all the mines first, and then place them all at once
+        let picked: Vec<usize> = (0..self.mines)
+            .map(|_| {
+                let index_rm = rng.gen_range(0..spots_remaining.len());
+                spots_remaining.swap_remove(index_rm)
+            })
+            .collect();
+        self.place_mines(&picked);
+    }
+
+    /// Place mines at the spots with the given indices (see `coords`), and update neighboring spots. The minefield ends
+    /// up the same as if `place_mine` was called for each of them, but the neighboring mines are first counted apart,
+    /// and then added to the spots in a single sweep: each spot is looked up once, rather than once for each mine
+    /// around it, which is most of the time taken to generate a large minefield with many mines.
+    fn place_mines(&mut self, indices: &[usize]) {
+        let spot_count = self.width as usize * self.height as usize;
+
+        // Number of mines placed around each spot, by index
+        let mut placed_around = vec![0u8; spot_count];
+
+        for &index in indices {
+            let (x, y) = self.coords(index);
+
+            if let Some(spot) = self.spot_mut(x, y) {
+                // Only place a mine in an empty spot
+                if spot.is_mine() {
+                    continue;
+                }
+
+                let was_revealed = spot.is_revealed();
+                spot.state = SpotState::HiddenMine;
+
+                if was_revealed {
+                    self.revealed -= 1;
+                }
+
+                for (nx, ny) in self.neighbors_coords(x, y) {
+                    placed_around[ny as usize * self.width as usize + nx as usize] += 1;
+                }
+            }
+        }
+
+        // Update counts of empty neighboring spots, leaving out the spots which were made mines themselves
+        for (index, &count) in placed_around.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+
+            let (x, y) = self.coords(index);
+            if let Some(spot) = self.spot_mut(x, y) {
+                match &mut spot.state {
+                    SpotState::HiddenEmpty { neighboring_mines }
+                    | SpotState::FlaggedEmpty { neighboring_mines }
+                    | SpotState::QuestionedEmpty { neighboring_mines }
+                    | SpotState::RevealedEmpty { neighboring_mines } => {
+                        *neighboring_mines += count;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Coordinates `(x, y)` of the spot with the given index, when counting spots row by row
+    fn coords(&self, index: usize) -> (u16, u16) {
+        let x = (index as u32 % self.width as u32) as u16;
+        let y = (index as u32 / self.width as u32) as u16;
+
+        (x, y)
+    }
+
+    /// Place a mine at a given field coordiantes, and update neighboring spots
+    fn place_mine(&mut self, x: u16, y: u16) {
+        assert!(x < self.width);
+        assert!(y < self.height);
+
+        if let Some(spot) = self.spot_mut(x, y) {
+            match spot.state {
+                // Only place a mine in an emty field
+                SpotState::HiddenEmpty {
+                    neighboring_mines: _,
+                }
+                | SpotState::FlaggedEmpty {
+                    neighboring_mines: _,
+                }
+                | SpotState::QuestionedEmpty {
+                    neighboring_mines: _,
+                }
+                | SpotState::RevealedEmpty {
+                    neighboring_mines: _,
+                } => {
+                    let was_revealed = spot.is_revealed();
+                    spot.state = SpotState::HiddenMine;
+
+                    if was_revealed {
+                        self.revealed -= 1;
+                    }
+
+                    // Update counts of empty neighboring spots
+                    for (nx, ny) in self.neighbors_coords(x, y) {
+                        if let Some(spot) = self.spot_mut(nx, ny) {
+                            match &mut spot.state {
+                                // Only place a mine in an emty field
+                                SpotState::HiddenEmpty { neighboring_mines }
+                                | SpotState::FlaggedEmpty { neighboring_mines }
+                                | SpotState::QuestionedEmpty { neighboring_mines }
+                                | SpotState::RevealedEmpty { neighboring_mines } => {
+                                    *neighboring_mines += 1;
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Remove the mine at a given field coordinates, keeping its flag or question mark, and update neighboring spots
+    fn remove_mine(&mut self, x: u16, y: u16) {
+        let neighboring_mines = self
+            .neighbors_coords(x, y)
+            .filter(|&(nx, ny)| self.spot(nx, ny).is_some_and(Spot::is_mine))
+            .count() as u8;
+
+        if let Some(spot) = self.spot_mut(x, y) {
+            spot.state = match spot.state {
+                SpotState::HiddenMine => SpotState::HiddenEmpty { neighboring_mines },
+                SpotState::FlaggedMine => SpotState::FlaggedEmpty { neighboring_mines },
+                SpotState::QuestionedMine => SpotState::QuestionedEmpty { neighboring_mines },
+                _ => return,
+            };
+
+            // Update counts of empty neighboring spots
+            for (nx, ny) in self.neighbors_coords(x, y) {
+                if let Some(spot) = self.spot_mut(nx, ny) {
+                    match &mut spot.state {
+                        SpotState::HiddenEmpty { neighboring_mines }
+                        | SpotState::FlaggedEmpty { neighboring_mines }
+                        | SpotState::QuestionedEmpty { neighboring_mines }
+                        | SpotState::RevealedEmpty { neighboring_mines } => {
+                            *neighboring_mines -= 1;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    /// Move the mines away from the spot at the given coordinates and from its neighbors, so that stepping on it opens
+    /// up an area instead of revealing a single number. Meant to be called before the first step of a game. Returns
+    /// `true` if the spot has no neighboring mines afterwards.
+    ///
+    /// On a board too dense to make room around the spot, only the mines which could be moved are, starting with the
+    /// one on the spot itself, so the first step is at least safe whenever there is another hidden spot left for its
+    /// mine.
+    pub fn ensure_open_start(&mut self, x: u16, y: u16) -> bool {
+        self.ensure_open_start_seeded(x, y, rand::random())
+    }
+
+    /// Same as `ensure_open_start`, with the spots the mines are moved to picked by a random generator initialized
+    /// with `seed`
+    pub fn ensure_open_start_seeded(&mut self, x: u16, y: u16, seed: u64) -> bool {
+        if x >= self.width || y >= self.height {
+            return false;
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let opening: Vec<(u16, u16)> = std::iter::once((x, y))
+            .chain(self.neighbors_coords(x, y))
+            .collect();
+        let mut moved = false;
+
+        for &(mx, my) in &opening {
+            if !self.spot(mx, my).is_some_and(Spot::is_mine) {
+                continue;
+            }
+
+            // Random spots are tried rather than listing all the free ones, which would take long on a large sparse
+            // minefield, so a board with hardly any free spot may not find one
+            let target = (0..OPEN_START_ATTEMPTS)
+                .map(|_| (rng.gen_range(0..self.width), rng.gen_range(0..self.height)))
+                .find(|&(tx, ty)| {
+                    !opening.contains(&(tx, ty))
+                        && matches!(
+                            self.spot(tx, ty).map(|spot| spot.state),
+                            Some(SpotState::HiddenEmpty { .. })
+                        )
+                });
+
+            match target {
+                Some((tx, ty)) => {
+                    self.remove_mine(mx, my);
+                    self.place_mine(tx, ty);
+                    moved = true;
+                }
+                None => break,
+            }
+        }
+
+        // The moves taken so far were taken with the mines where they were, so they can't be undone anymore
+        if moved {
+            self.history.clear();
+            self.undone.clear();
+        }
+
+        matches!(
+            self.spot(x, y).map(|spot| spot.state),
+            Some(
+                SpotState::HiddenEmpty {
+                    neighboring_mines: 0
+                } | SpotState::FlaggedEmpty {
+                    neighboring_mines: 0
+                } | SpotState::QuestionedEmpty {
+                    neighboring_mines: 0
+                } | SpotState::RevealedEmpty {
+                    neighboring_mines: 0
+                }
+            )
+        )
+    }
+
+    /// Which spots count as neighbors of a spot
+    pub fn connectivity(&self) -> Connectivity {
+        self.connectivity
+    }
+
+    /// Iterator over the coordinates of all neighbors in a range of 1 unit, relative to the given coordiantes. With
+    /// `Connectivity::Four`, diagonal neighbors are left out. With `Connectivity::Six`, only the two diagonal neighbors
+    /// on the side towards which the row of the spot is shifted are kept.
+    pub fn neighbors_coords(&self, x: u16, y: u16) -> impl Iterator<Item = (u16, u16)> {
+        let min_x = x.saturating_sub(1);
+        let max_x = x.saturating_add(1);
+
+        let min_y = y.saturating_sub(1);
+        let max_y = y.saturating_add(1);
+
+        let width = self.width;
+        let height = self.height;
+        let orthogonal_only = self.connectivity == Connectivity::Four;
+        let hexagonal = self.connectivity == Connectivity::Six;
+
+        // Odd rows of a hexagonal grid are shifted right by half a spot, so their diagonal neighbors are on the right
+        let shifted_right = y % 2 == 1;
+
+        (min_x..=max_x)
+            .flat_map(move |i| (min_y..=max_y).map(move |j| (i, j)))
+            .filter(move |(neighbor_x, neighbor_y)| {
+                // the neighbor coords are within the minefield grid
+                *neighbor_x < width && *neighbor_y < height &&
+                // the neighbor coords are not same as `self`
+                !(*neighbor_x == x && *neighbor_y == y) &&
+                // the neighbor coords are not diagonal, if only orthogonal neighbors count
+                !(orthogonal_only && *neighbor_x != x && *neighbor_y != y) &&
+                // the diagonal neighbor coords touch the spot, on a hexagonal grid
+                !(hexagonal && *neighbor_x != x && *neighbor_y != y && (*neighbor_x > x) != shifted_right)
+            })
+    }
+}
+
+/// Which spots count as neighbors of a spot in the minefield
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Connectivity {
+    /// All 8 surrounding spots, including the diagonal ones (standard minesweeper)
+    #[default]
+    Eight,
+
+    /// Only the 4 orthogonally adjacent spots
+    Four,
+
+    /// The 6 spots around a spot of a hexagonal grid, in which the odd rows are shifted right by half a spot. The
+    /// minefield is still indexed by rows and columns: a spot touches its left and right neighbors, and two spots of
+    /// each of the rows above and below it.
+    Six,
+}
+
+/// State of the spot in a minefield
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SpotState {
+    /// This spot has not been visited
+    HiddenEmpty { neighboring_mines: u8 },
+
+    /// This is a hidden mine
+    HiddenMine,
+
+    /// This spot is empty, but was flagged as a mine
+    FlaggedEmpty { neighboring_mines: u8 },
+
+    /// This spot contains a mine which was correctly flagged
+    FlaggedMine,
+
+    /// This spot is empty, and was marked with a question mark
+    QuestionedEmpty { neighboring_mines: u8 },
+
+    /// This spot contains a mine, and was marked with a question mark
+    QuestionedMine,
+
+    /// This spot is empty and was correctly revealed
+    RevealedEmpty { neighboring_mines: u8 },
+
+    /// This spot contains a mine and was incorrectly revealed
+    ExplodedMine,
+}
+
+/// Spot struct describing the characteristics of the minefield at a particular position
+#[derive(Copy, Clone, Debug)]
+pub struct Spot {
+    pub state: SpotState,
+}
+
+impl Spot {
+    /// A hidden spot without neighboring mines, which is how every spot starts out
+    const HIDDEN_EMPTY: Spot = Spot {
+        state: SpotState::HiddenEmpty {
+            neighboring_mines: 0,
+        },
+    };
+
+    /// Step on this spot, if possible
+    fn step(&mut self) -> StepResult {
+        match self.state {
+            SpotState::HiddenEmpty { neighboring_mines }
+            | SpotState::QuestionedEmpty { neighboring_mines } => {
+                self.state = SpotState::RevealedEmpty { neighboring_mines };
+                StepResult::Phew { revealed: 1 }
+            }
+            SpotState::HiddenMine | SpotState::QuestionedMine => {
+                self.state = SpotState::ExplodedMine;
+                StepResult::Boom
+            }
+            SpotState::FlaggedEmpty { .. } | SpotState::FlaggedMine => {
+                StepResult::Invalid(InvalidStep::Flagged)
+            }
+            SpotState::RevealedEmpty { .. } | SpotState::ExplodedMine => {
+                StepResult::Invalid(InvalidStep::AlreadyRevealed)
+            }
+        }
+    }
+
+    /// Toggle a flag this spot, if possible, going through a question mark before clearing it if `question_marks` is
+    /// set
+    fn flag(&mut self, question_marks: bool) -> FlagToggleResult {
+        match self.state {
+            SpotState::HiddenEmpty { neighboring_mines } => {
+                self.state = SpotState::FlaggedEmpty { neighboring_mines };
+                FlagToggleResult::Added
+            }
+            SpotState::HiddenMine => {
+                self.state = SpotState::FlaggedMine {};
+                FlagToggleResult::Added
+            }
+            SpotState::FlaggedEmpty { neighboring_mines } if question_marks => {
+                self.state = SpotState::QuestionedEmpty { neighboring_mines };
+                FlagToggleResult::Questioned
+            }
+            SpotState::FlaggedMine if question_marks => {
+                self.state = SpotState::QuestionedMine;
+                FlagToggleResult::Questioned
+            }
+            SpotState::FlaggedEmpty { neighboring_mines } => {
+                self.state = SpotState::HiddenEmpty { neighboring_mines };
+                FlagToggleResult::Removed
+            }
+            SpotState::FlaggedMine => {
+                self.state = SpotState::HiddenMine {};
+                FlagToggleResult::Removed
+            }
+            SpotState::QuestionedEmpty { neighboring_mines } => {
+                self.state = SpotState::HiddenEmpty { neighboring_mines };
+                FlagToggleResult::Unquestioned
+            }
+            SpotState::QuestionedMine => {
+                self.state = SpotState::HiddenMine;
+                FlagToggleResult::Unquestioned
+            }
+            _ => FlagToggleResult::None,
+        }
+    }
+
+    /// Is there a mine in this spot?
+    fn is_mine(&self) -> bool {
+        matches!(
+            self.state,
+            SpotState::HiddenMine
+                | SpotState::FlaggedMine
+                | SpotState::QuestionedMine
+                | SpotState::ExplodedMine
+        )
+    }
+
+    /// Number of mines around this spot, if it's an empty one
+    fn neighboring_mines(&self) -> Option<u8> {
+        match self.state {
+            SpotState::HiddenEmpty { neighboring_mines }
+            | SpotState::FlaggedEmpty { neighboring_mines }
+            | SpotState::QuestionedEmpty { neighboring_mines }
+            | SpotState::RevealedEmpty { neighboring_mines } => Some(neighboring_mines),
+            _ => None,
+        }
+    }
+
+    /// Is this an empty spot which has been revealed?
+    fn is_revealed(&self) -> bool {
+        matches!(self.state, SpotState::RevealedEmpty { .. })
+    }
+
+    /// Has this spot been cleared (either correctly flagged or correctly revealed)?
+    fn is_resolved(&self) -> bool {
+        matches!(
+            self.state,
+            SpotState::FlaggedMine
+                | SpotState::RevealedEmpty {
+                    neighboring_mines: _
+                }
+        )
+    }
+}
+
+impl Default for Spot {
+    fn default() -> Self {
+        Self::HIDDEN_EMPTY
+    }
+}
+
+/// The result of steppin on a spot in the minefield
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum StepResult {
+    /// Stepped on empty spot, revealing the given number of spots (more than one with a flood reveal or an autostep,
+    /// or none if an autostep found all the neighbors already revealed)
+    Phew { revealed: u32 },
+
+    /// Stepped on a mine
+    Boom,
+
+    /// Step not taken, for the given reason
+    Invalid(InvalidStep),
+}
+
+/// Why a step was not taken
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum InvalidStep {
+    /// The coordinates are outside the minefield
+    OutOfBounds,
+
+    /// The spot was already revealed (or exploded)
+    AlreadyRevealed,
+
+    /// The spot is flagged, so it's kept from being stepped on
+    Flagged,
+
+    /// Autosteps and ring reveals only start from a revealed spot, and this one is still hidden or questioned
+    NotRevealed,
+
+    /// An autostep needs as many flags around the spot as its neighboring mines
+    FlagCount { flagged: u8, neighboring_mines: u8 },
+
+    /// A safe autostep (see `Minefield::with_safe_chord`) was refused, since a flag around the spot is on an empty spot
+    WrongFlags,
+
+    /// There were no hidden neighbors to step on
+    NothingToReveal,
+}
+
+impl InvalidStep {
+    /// Why a move which starts from a revealed spot was not taken from a spot in the given state
+    fn not_revealed(state: SpotState) -> Self {
+        match state {
+            SpotState::FlaggedEmpty { .. } | SpotState::FlaggedMine => InvalidStep::Flagged,
+            _ => InvalidStep::NotRevealed,
+        }
+    }
+}
+
+impl StepResult {
+    /// The result of a move made of this step and the `other` one: a mine was stepped on if either step did, and the
+    /// spots revealed by both steps add up
+    fn and(self, other: StepResult) -> StepResult {
+        match (self, other) {
+            (StepResult::Boom, _) | (_, StepResult::Boom) => StepResult::Boom,
+            (StepResult::Phew { revealed: a }, StepResult::Phew { revealed: b }) => {
+                StepResult::Phew { revealed: a + b }
+            }
+            (phew @ StepResult::Phew { .. }, StepResult::Invalid(_))
+            | (StepResult::Invalid(_), phew @ StepResult::Phew { .. }) => phew,
+            // The reason of the last step is kept when neither step was taken
+            (StepResult::Invalid(_), invalid @ StepResult::Invalid(_)) => invalid,
+        }
+    }
+}
+
+/// A move which is certain to be right, given the spots which have been revealed
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SolverMove {
+    /// Step on a spot which is certainly safe
+    Step { x: u16, y: u16 },
+
+    /// Flag a spot which is certainly a mine
+    Flag { x: u16, y: u16 },
+}
+
+/// The outcome of generating a minefield which can be cleared without guessing
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Generation {
+    /// Number of minefields which were generated, including the returned one
+    pub attempts: u32,
+
+    /// The returned minefield can be cleared without guessing
+    pub solvable: bool,
+}
+
+/// The result of toggling a flag in the mine field
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FlagToggleResult {
+    /// Exstng flag was removed
+    Removed,
+    /// A flag was added
+    Added,
+    /// Existing flag was replaced by a question mark
+    Questioned,
+    /// Existing question mark was removed
+    Unquestioned,
+    /// No flag placed or removed
+    None,
+}
+
+impl FlagToggleResult {
+    /// Change in the number of flags placed on the minefield
+    pub fn flags_change(&self) -> i64 {
+        match self {
+            FlagToggleResult::Added => 1,
+            FlagToggleResult::Removed | FlagToggleResult::Questioned => -1,
+            FlagToggleResult::Unquestioned | FlagToggleResult::None => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_minefield() {
+        // Create empty test minefield:
+        //     0 1 2
+        // 0 [       ]
+        // 1 [       ]
+        // 2 [       ]
+        // 3 [       ]
+        //
+        let width = 3;
+        let height = 4;
+        let minefield = Minefield::new(width, height);
+
+        for ((x, y), spot) in &minefield.field {
+            assert_eq!(
+                spot.state,
+                SpotState::HiddenEmpty {
+                    neighboring_mines: 0
+                }
+            );
+            assert!(*x < width);
+            assert!(*y < height);
+        }
+    }
+
+    #[test]
+    fn place_mines() {
+        // Create empty minefield
+        let width = 3;
+        let height = 4;
+        let mut minefield = Minefield::new(width, height);
+
+        // Place Mine
+        //     0 1 2
+        // 0 [   1 ☢ ]
+        // 1 [   1 1 ]
+        // 2 [       ]
+        // 3 [       ]
+        //
+        let mine_x = 2;
+        let mine_y = 0;
+        minefield.place_mine(mine_x, mine_y);
+
+        // Was mine placed correctly?
+        assert_eq!(
+            minefield.field.get(&(mine_x, mine_y)).unwrap().state,
+            SpotState::HiddenMine
+        );
+
+        // Were the neighbors updated correctly?
+        for (nx, ny) in minefield.neighbors_coords(mine_x, mine_y) {
+            assert_eq!(
+                minefield.field.get(&(nx, ny)).unwrap().state,
+                SpotState::HiddenEmpty {
+                    neighboring_mines: 1
+                }
+            );
+        }
+
+        // Place another mine
+        //     0 1 2
+        // 0 [   1 ☢ ]
+        // 1 [   1 1 ]
+        // 2 [ 1 1   ]
+        // 3 [ ☢ 1   ]
+        let mine_x = 0;
+        let mine_y = 3;
+        minefield.place_mine(mine_x, mine_y);
+
+        // Was mine placed correctly?
+        assert_eq!(
+            minefield.field.get(&(mine_x, mine_y)).unwrap().state,
+            SpotState::HiddenMine
+        );
+
+        // Were the neighbors updated correctly?
+        for (nx, ny) in minefield.neighbors_coords(mine_x, mine_y) {
+            assert_eq!(
+                minefield.field.get(&(nx, ny)).unwrap().state,
+                SpotState::HiddenEmpty {
+                    neighboring_mines: 1
+                }
+            );
+        }
+
+        // Place another mine
+        //     0 1 2
+        // 0 [ 1 2 ☢ ]
+        // 1 [ ☢ 2 1 ]
+        // 2 [ 2 2   ]
+        // 3 [ ☢ 1   ]
+        let mine_x = 0;
+        let mine_y = 1;
+        minefield.place_mine(mine_x, mine_y);
+
+        // Was mine placed correctly?
+        assert_eq!(
+            minefield.field.get(&(mine_x, mine_y)).unwrap().state,
+            SpotState::HiddenMine
+        );
+
+        // Were the neighbors updated correctly?
+        for n_coords in minefield.neighbors_coords(mine_x, mine_y) {
+            let expected_mine_count = if n_coords == (0, 0) { 1 } else { 2 };
+            assert_eq!(
+                minefield.field.get(&n_coords).unwrap().state,
+                SpotState::HiddenEmpty {
+                    neighboring_mines: expected_mine_count
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn step() {
+        // Create empty minefield
+        let width = 3;
+        let height = 4;
+        let mut minefield = Minefield::new(width, height);
+
+        // Place mines
+        //     0 1 2
+        // 0 [   1 ☢ ]
+        // 1 [   1 1 ]
+        // 2 [ 1 1   ]
+        // 3 [ ☢ 1   ]
+        let mine_x = 2;
+        let mine_y = 0;
+        minefield.place_mine(mine_x, mine_y);
+        let mine_x = 0;
+        let mine_y = 3;
+        minefield.place_mine(mine_x, mine_y);
+
+        // Step on spot neighboring mine
+        let step_x = 1;
+        let step_y = 2;
+        let step_result = minefield.step(step_x, step_y);
+
+        // Step was success, and only one spot was revealed
+        //     0 1 2
+        // 0 [ • • • ]
+        // 1 [ • • • ]
+        // 2 [ • 1 • ]
+        // 3 [ • • • ]
+        assert_eq!(step_result, StepResult::Phew { revealed: 1 });
+        // assert_eq!(minefield.field[step_x as usize][step_y as usize].state, SpotState::Revealed);
+        // for (nx, ny) in minefield.neighbors_coords(step_x, step_y) {
+        //     assert_eq!(minefield.field[nx as usize][ny as usize].state, SpotState::Hidden);
+        // }
+
+        // Step on spot with no neighboring mines
+        let step_x = 0;
+        let step_y = 1;
+        let step_result = minefield.step(step_x, step_y);
+
+        // Step was success, and neighbors were flood revealed
+        //     0 1 2
+        // 0 [   1 • ]
+        // 1 [   1 • ]
+        // 2 [ 1 1 • ]
+        // 3 [ • • • ]
+        assert_eq!(step_result, StepResult::Phew { revealed: 5 });
+        // assert_eq!(minefield.field[step_x as usize][step_y as usize].state, SpotState::Revealed);
+        // for (nx, ny) in minefield.neighbors_coords(step_x, step_y) {
+        //     assert_eq!(minefield.field[nx as usize][ny as usize].state, SpotState::Revealed);
+        // }
+
+        // Step on mine
+        let step_x = 2;
+        let step_y = 0;
+        let step_result = minefield.step(step_x, step_y);
+
+        // Step was Boom, and only mine spot was newly revealed
+        //     0 1 2
+        // 0 [   1 ☢ ]
+        // 1 [   1 • ]
+        // 2 [ 1 1 • ]
+        // 3 [ • • • ]
+        assert_eq!(step_result, StepResult::Boom);
+        // assert_eq!(minefield.field[step_x as usize][step_y as usize].state, SpotState::Exploded);
+        // for (x, y) in minefield.neighbors_coords(step_x,  step_y) {
+        //     let expected_spot_state= if (x, y) == (2, 1) { SpotState::Hidden } else { SpotState::Revealed };
+        //     assert_eq!(minefield.field[x as usize][y as usize].state, expected_spot_state);
+        // }
+    }
+
+    #[test]
+    fn flood_reveal() {
+        // Create empty bigger minefield
+        //     0 1 2 3 4 5 6 7 8 9
+        // 0 [     1 ☢ 1           ]
+        // 1 [     1 1 1           ]
+        // 2 [           1 1 1     ]
+        // 3 [   1 1 1   1 ☢ 1 1 1 ]
+        // 4 [   1 ☢ 1   1 1 1 1 ☢ ]
+        // 5 [   1 1 1         1 1 ]
+        // 6 [         1 1 2 1 1   ]
+        // 7 [         1 ☢ 2 ☢ 1   ]
+        // 8 [         1 1 2 1 1   ]
+        // 9 [                     ]
+        let width = 10;
+        let height = 10;
+        let mut minefield = Minefield::new(width, height);
+
+        let mine_coords = [(2, 4), (5, 7), (7, 7), (9, 4), (6, 3), (3, 0)];
+        for (x, y) in mine_coords {
+            minefield.place_mine(x, y);
+        }
+
+        // Place a flag
+        //     0 1 2 3 4 5 6 7 8 9
+        // 0 [ • • • • • • • • • • ]
+        // 1 [ • • • • • ⚐ • • • • ]
+        // 2 [ • • • • • • • • • • ]
+        // 3 [ • • • • • • • • • • ]
+        // 4 [ • • • • • • • • • • ]
+        // 5 [ • • • • • • • • • • ]
+        // 6 [ • • • • • • • • • • ]
+        // 7 [ • • • • • • • • • • ]
+        // 8 [ • • • • • • • • • • ]
+        // 9 [ • • • • • • • • • • ]
+        let flag_x = 5;
+        let flag_y = 1;
+        let toggle_result = minefield.toggle_flag(flag_x, flag_y);
+        assert_eq!(toggle_result, FlagToggleResult::Added);
+
+        // Step on spot (x=9, y=6)
+        //     0 1 2 3 4 5 6 7 8 9
+        // 0 [     1 • • • • • • • ]
+        // 1 [     1 1 1 ⚐ • • • • ]
+        // 2 [           1 • • • • ]
+        // 3 [   1 1 1   1 • • • • ]
+        // 4 [   1 • 1   1 1 1 1 • ]
+        // 5 [   1 1 1         1 1 ]
+        // 6 [         1 1 2 1 1   ]
+        // 7 [         1 • • • 1   ]
+        // 8 [         1 1 2 1 1   ]
+        // 9 [                     ]
+        let step_x = 9;
+        let step_y = 6;
+        let step_result = minefield.step(step_x, step_y);
+        assert_eq!(step_result, StepResult::Phew { revealed: 75 });
+
+        // All mines are still hidden
+        for n_coords in mine_coords {
+            assert_eq!(
+                minefield.field.get(&n_coords).unwrap().state,
+                SpotState::HiddenMine
+            );
+        }
+
+        // Flood revealed half maze
+        assert_eq!(
+            minefield.field.get(&(7, 5)).unwrap().state,
+            SpotState::RevealedEmpty {
+                neighboring_mines: 0
+            }
+        );
+
+        // Flag is still there
+        assert_eq!(
+            minefield.field.get(&(flag_x, flag_y)).unwrap().state,
+            SpotState::FlaggedEmpty {
+                neighboring_mines: 0
+            }
+        );
+
+        // Insulated portion of field is still hidden
+        assert_eq!(
+            minefield.field.get(&(9, 0)).unwrap().state,
+            SpotState::HiddenEmpty {
+                neighboring_mines: 0
+            }
+        );
+        assert_eq!(
+            minefield.field.get(&(7, 1)).unwrap().state,
+            SpotState::HiddenEmpty {
+                neighboring_mines: 0
+            }
+        );
+    }
+
+    #[test]
+    fn reveal_ring() {
+        // Create minefield
+        //     0 1 2 3 4
+        // 0 [           ]
+        // 1 [ 1 1 1     ]
+        // 2 [ 1 ☢ 1     ]
+        // 3 [ 1 1 1     ]
+        let (mut minefield, _) = Minefield::from_mines(5, 4, &[(1, 2)]);
+
+        // Reveal ring around a spot which is not revealed yet
+        assert_eq!(
+            minefield.reveal_ring(3, 1),
+            StepResult::Invalid(InvalidStep::NotRevealed)
+        );
+
+        // Reveal the spot with no neighboring mines, without flood revealing
+        let spot = minefield.field.get_mut(&(3, 1)).unwrap();
+        assert_eq!(spot.step(), StepResult::Phew { revealed: 1 });
+
+        // Reveal a safe ring, which does not flood reveal past its neighbors
+        //     0 1 2 3 4
+        // 0 [ • •       ]
+        // 1 [ • • 1     ]
+        // 2 [ • • 1     ]
+        // 3 [ • • • • • ]
+        assert_eq!(
+            minefield.reveal_ring(3, 1),
+            StepResult::Phew { revealed: 8 }
+        );
+        for n_coords in minefield.neighbors_coords(3, 1) {
+            assert!(matches!(
+                minefield.field.get(&n_coords).unwrap().state,
+                SpotState::RevealedEmpty {
+                    neighboring_mines: _
+                }
+            ));
+        }
+        assert_eq!(
+            minefield.field.get(&(3, 3)).unwrap().state,
+            SpotState::HiddenEmpty {
+                neighboring_mines: 0
+            }
+        );
+
+        // Nothing left to reveal around the spot
+        assert_eq!(
+            minefield.reveal_ring(3, 1),
+            StepResult::Invalid(InvalidStep::NothingToReveal)
+        );
+
+        // Reveal a ring containing a mine, which booms even though a flag is not required
+        assert_eq!(minefield.reveal_ring(2, 2), StepResult::Boom);
+        assert_eq!(
+            minefield.field.get(&(1, 2)).unwrap().state,
+            SpotState::ExplodedMine
+        );
+    }
+
+    #[test]
+    fn auto_step_neighbors() {
+        // Create minefield
+        //     0 1 2 3
+        // 0 [ 1 ☢ 1   ]
+        // 1 [ 1 1 1   ]
+        // 2 [         ]
+        let (mut minefield, _) = Minefield::from_mines(4, 3, &[(1, 0)]);
+
+        // Reveal a single numbered spot
+        //     0 1 2 3
+        // 0 [ • • • • ]
+        // 1 [ • 1 • • ]
+        // 2 [ • • • • ]
+        assert_eq!(minefield.step(1, 1), StepResult::Phew { revealed: 1 });
+
+        // A flag which does not satisfy any revealed neighbor does not autostep
+        assert_eq!(
+            minefield.auto_step_neighbors(3, 2),
+            StepResult::Invalid(InvalidStep::NothingToReveal)
+        );
+
+        // Flag the mine, which satisfies the revealed neighbor and autosteps around it
+        //     0 1 2 3
+        // 0 [ 1 ⚐ 1   ]
+        // 1 [ 1 1 1   ]
+        // 2 [         ]
+        assert_eq!(minefield.toggle_flag(1, 0), FlagToggleResult::Added);
+        assert_eq!(
+            minefield.auto_step_neighbors(1, 0),
+            StepResult::Phew { revealed: 10 }
+        );
+        assert_eq!(
+            minefield.field.get(&(3, 2)).unwrap().state,
+            SpotState::RevealedEmpty {
+                neighboring_mines: 0
+            }
+        );
+        assert!(minefield.is_cleared());
+    }
+
+    #[test]
+    fn auto_flag() {
+        // Create minefield
+        //     0 1 2
+        // 0 [ ☢ ☢ 1 ]
+        // 1 [ 2 2 1 ]
+        // 2 [       ]
+        let mut minefield = Minefield::new(3, 3).with_mines_at(&[(0, 0), (1, 0)]);
+
+        // Hidden spots and numbers with too many hidden neighbors are not autoflagged
+        assert_eq!(minefield.auto_flag(0, 0), 0);
+        assert_eq!(minefield.step(0, 1), StepResult::Phew { revealed: 1 });
+        assert_eq!(minefield.auto_flag(0, 1), 0);
+
+        // Once the other spots are revealed, the hidden neighbors of the number can only be mines
+        //     0 1 2
+        // 0 [ ⚐ ⚐ 1 ]
+        // 1 [ 2 2 1 ]
+        // 2 [       ]
+        assert_eq!(minefield.step(2, 2), StepResult::Phew { revealed: 5 });
+        assert_eq!(minefield.step(2, 0), StepResult::Phew { revealed: 1 });
+        assert_eq!(minefield.auto_flag(0, 1), 2);
+        assert_eq!(minefield.flagged_neighbors(0, 1), 2);
+        assert!(minefield.is_cleared());
+
+        // Nothing is left to flag, and undoing removes all the flags at once
+        assert_eq!(minefield.auto_flag(1, 1), 0);
+        assert!(minefield.undo());
+        assert_eq!(minefield.flagged_neighbors(0, 1), 0);
+    }
+
+    #[test]
+    fn auto_step_neighbors_wrong_flag() {
+        // Create minefield
+        //     0 1 2 3
+        // 0 [ 1 ☢ 1   ]
+        // 1 [ 1 1 1   ]
+        // 2 [         ]
+        let (mut minefield, _) = Minefield::from_mines(4, 3, &[(1, 0)]);
+
+        // Reveal a single numbered spot, and place a wrong flag next to it
+        //     0 1 2 3
+        // 0 [ ⚐ • • • ]
+        // 1 [ • 1 • • ]
+        // 2 [ • • • • ]
+        assert_eq!(minefield.step(1, 1), StepResult::Phew { revealed: 1 });
+        assert_eq!(minefield.toggle_flag(0, 0), FlagToggleResult::Added);
+
+        // Autostepping around the satisfied neighbor steps on the mine
+        assert_eq!(minefield.auto_step_neighbors(0, 0), StepResult::Boom);
+        assert_eq!(
+            minefield.field.get(&(1, 0)).unwrap().state,
+            SpotState::ExplodedMine
+        );
+    }
+
+    #[test]
+    fn auto_step_safe_chord() {
+        // Create minefield
+        //     0 1 2 3
+        // 0 [ 1 ☢ 1   ]
+        // 1 [ 1 1 1   ]
+        // 2 [         ]
+        let mut minefield = Minefield::new(4, 3)
+            .with_mines_at(&[(1, 0)])
+            .with_safe_chord(true);
+
+        // Reveal a single numbered spot, and place a wrong flag next to it
+        //     0 1 2 3
+        // 0 [ ⚐ • • • ]
+        // 1 [ • 1 • • ]
+        // 2 [ • • • • ]
+        assert_eq!(minefield.step(1, 1), StepResult::Phew { revealed: 1 });
+        assert_eq!(minefield.toggle_flag(0, 0), FlagToggleResult::Added);
+
+        // The autostep is refused, directly or after placing the flag
+        assert_eq!(
+            minefield.auto_step(1, 1),
+            StepResult::Invalid(InvalidStep::WrongFlags)
+        );
+        assert_eq!(
+            minefield.auto_step_neighbors(0, 0),
+            StepResult::Invalid(InvalidStep::WrongFlags)
+        );
+        assert!(!minefield.is_exploded());
+        assert_eq!(minefield.remaining_safe_cells(), 10);
+
+        // Once the flag is moved to the mine, the autostep goes through
+        assert_eq!(minefield.toggle_flag(0, 0), FlagToggleResult::Removed);
+        assert_eq!(minefield.toggle_flag(1, 0), FlagToggleResult::Added);
+        assert_eq!(minefield.auto_step(1, 1), StepResult::Phew { revealed: 10 });
+        assert!(minefield.is_cleared());
+    }
+
+    #[test]
+    fn steps_not_taken_tell_why() {
+        // Create minefield
+        //     0 1 2 3
+        // 0 [ 1 ☢ 1   ]
+        // 1 [ 1 1 1   ]
+        // 2 [         ]
+        let mut minefield = Minefield::new(4, 3).with_mines_at(&[(1, 0)]);
+
+        assert_eq!(
+            minefield.step(4, 0),
+            StepResult::Invalid(InvalidStep::OutOfBounds)
+        );
+        assert_eq!(
+            minefield.auto_step(0, 3),
+            StepResult::Invalid(InvalidStep::OutOfBounds)
+        );
+
+        // A flagged spot is kept from being stepped on, and autostepped from
+        assert_eq!(minefield.toggle_flag(1, 0), FlagToggleResult::Added);
+        assert_eq!(
+            minefield.step(1, 0),
+            StepResult::Invalid(InvalidStep::Flagged)
+        );
+        assert_eq!(
+            minefield.auto_step(1, 0),
+            StepResult::Invalid(InvalidStep::Flagged)
+        );
+        assert_eq!(minefield.toggle_flag(1, 0), FlagToggleResult::Removed);
+
+        // An autostep needs as many flags as the neighboring mines
+        assert_eq!(minefield.step(1, 1), StepResult::Phew { revealed: 1 });
+        assert_eq!(
+            minefield.step(1, 1),
+            StepResult::Invalid(InvalidStep::AlreadyRevealed)
+        );
+        assert_eq!(
+            minefield.auto_step(1, 1),
+            StepResult::Invalid(InvalidStep::FlagCount {
+                flagged: 0,
+                neighboring_mines: 1
+            })
+        );
+        assert_eq!(minefield.toggle_flag(0, 0), FlagToggleResult::Added);
+        assert_eq!(minefield.toggle_flag(2, 0), FlagToggleResult::Added);
+        assert_eq!(
+            minefield.auto_step(1, 1),
+            StepResult::Invalid(InvalidStep::FlagCount {
+                flagged: 2,
+                neighboring_mines: 1
+            })
+        );
+    }
+
+    #[test]
+    fn strict_flags_are_limited_to_the_mines() {
+        // Create minefield
+        //     0 1 2 3
+        // 0 [ 1 ☢ ☢ 1 ]
+        // 1 [ 1 2 2 1 ]
+        // 2 [         ]
+        let mut minefield = Minefield::new(4, 3)
+            .with_mines_at(&[(1, 0), (2, 0)])
+            .with_strict_flags(true)
+            .with_question_marks(true);
+
+        // Flags can be placed until there are as many as mines, whether they are right or not
+        assert_eq!(minefield.toggle_flag(1, 0), FlagToggleResult::Added);
+        assert_eq!(minefield.toggle_flag(3, 2), FlagToggleResult::Added);
+        assert_eq!(minefield.toggle_flag(2, 0), FlagToggleResult::None);
+        assert_eq!(minefield.flagged_spots().count(), 2);
+
+        // Flags can still be removed, or turned into question marks, which makes room for another flag
+        assert_eq!(minefield.toggle_flag(3, 2), FlagToggleResult::Questioned);
+        assert_eq!(minefield.toggle_flag(2, 0), FlagToggleResult::Added);
+        assert_eq!(minefield.toggle_flag(3, 2), FlagToggleResult::Unquestioned);
+
+        // An autoflag which would make more flags than mines is refused as a whole
+        let mut minefield = Minefield::new(4, 3)
+            .with_mines_at(&[(1, 0), (2, 0)])
+            .with_strict_flags(true);
+        assert_eq!(minefield.step(0, 2), StepResult::Phew { revealed: 8 });
+        assert_eq!(minefield.step(0, 0), StepResult::Phew { revealed: 1 });
+        assert_eq!(minefield.toggle_flag(3, 0), FlagToggleResult::Added);
+        assert_eq!(minefield.auto_flag(1, 1), 0);
+        assert_eq!(minefield.toggle_flag(3, 0), FlagToggleResult::Removed);
+        assert_eq!(minefield.auto_flag(1, 1), 2);
+        assert_eq!(minefield.toggle_flag(3, 0), FlagToggleResult::None);
+
+        // Without strict flags, any number of flags can be placed
+        let mut minefield = Minefield::new(4, 3).with_mines_at(&[(1, 0), (2, 0)]);
+        for x in 0..4 {
+            assert_eq!(minefield.toggle_flag(x, 2), FlagToggleResult::Added);
+        }
+    }
+
+    #[test]
+    fn satisfied() {
+        // Create minefield
+        //     0 1 2 3
+        // 0 [ 1 ☢ 1   ]
+        // 1 [ 1 1 1   ]
+        // 2 [         ]
+        let mut minefield = Minefield::new(4, 3).with_mines_at(&[(1, 0)]);
+
+        // Reveal everything but the mine
+        //     0 1 2 3
+        // 0 [ 1 • 1   ]
+        // 1 [ 1 1 1   ]
+        // 2 [         ]
+        assert_eq!(minefield.step(3, 2), StepResult::Phew { revealed: 10 });
+        assert_eq!(minefield.step(0, 0), StepResult::Phew { revealed: 1 });
+        assert!(!minefield.is_satisfied(0, 0));
+        assert!(!minefield.is_satisfied(1, 1));
+
+        // Flagging the mine satisfies all its neighbors, but not the spots without neighboring mines
+        assert_eq!(minefield.toggle_flag(1, 0), FlagToggleResult::Added);
+        assert_eq!(minefield.flagged_neighbors(1, 1), 1);
+        for (x, y) in [(0, 0), (2, 0), (0, 1), (1, 1), (2, 1)] {
+            assert!(minefield.is_satisfied(x, y));
+        }
+        assert!(!minefield.is_satisfied(3, 2));
+
+        // Removing the flag makes them unsatisfied again
+        assert_eq!(minefield.toggle_flag(1, 0), FlagToggleResult::Removed);
+        assert!(!minefield.is_satisfied(1, 1));
+
+        // A hidden spot is never satisfied
+        assert!(!minefield.is_satisfied(1, 0));
+    }
+
+    #[test]
+    fn connectivity() {
+        // Create the same minefield with both connectivities
+        //     0 1 2 3 4          0 1 2 3 4
+        // 0 [ 1 1 1       ]  0 [   1       ]
+        // 1 [ 1 ☢ 1       ]  1 [ 1 ☢ 1     ]
+        // 2 [ 1 1 1       ]  2 [   1       ]
+        // 3 [             ]  3 [           ]
+        let eight = Minefield::new(5, 4).with_mines_at(&[(1, 1)]);
+        let four = Minefield::new(5, 4)
+            .with_connectivity(Connectivity::Four)
+            .with_mines_at(&[(1, 1)]);
+
+        assert_eq!(eight.connectivity(), Connectivity::Eight);
+        assert_eq!(four.connectivity(), Connectivity::Four);
+        assert_eq!(eight.neighbors_coords(2, 2).count(), 8);
+        assert_eq!(four.neighbors_coords(2, 2).count(), 4);
+
+        // Diagonal neighbors of the mine only count it with 8-connectivity
+        for (minefield, diagonal_count) in [(&eight, 1), (&four, 0)] {
+            assert_eq!(
+                minefield.spot(0, 0).unwrap().state,
+                SpotState::HiddenEmpty {
+                    neighboring_mines: diagonal_count
+                }
+            );
+            assert_eq!(
+                minefield.spot(1, 0).unwrap().state,
+                SpotState::HiddenEmpty {
+                    neighboring_mines: 1
+                }
+            );
+        }
+
+        // Switching an existing minefield to 4-connectivity recounts its neighboring mines
+        let switched = eight.clone().with_connectivity(Connectivity::Four);
+        assert_eq!(
+            switched.spot(2, 2).unwrap().state,
+            SpotState::HiddenEmpty {
+                neighboring_mines: 0
+            }
+        );
+
+        // Flood reveal from the far corner:
+        //     0 1 2 3 4          0 1 2 3 4
+        // 0 [ • • 1       ]  0 [ • 1       ]
+        // 1 [ • • 1       ]  1 [ 1 • 1     ]
+        // 2 [ 1 1 1       ]  2 [   1       ]
+        // 3 [             ]  3 [           ]
+        // With 8-connectivity, no empty spot without neighboring mines touches the top left numbers. With
+        // 4-connectivity, the flood goes around the mine and reveals all the numbers, but the top left corner stays
+        // hidden, since both its neighbors are numbers
+        let reveal = |mut minefield: Minefield| {
+            let StepResult::Phew { revealed } = minefield.step(4, 3) else {
+                panic!("the step should be safe");
+            };
+            let count = minefield
+                .spots()
+                .filter(|(_, spot)| matches!(spot.state, SpotState::RevealedEmpty { .. }))
+                .count();
+            assert_eq!(revealed as usize, count);
+            count
+        };
+        assert_eq!(reveal(eight), 5 * 4 - 4);
+        assert_eq!(reveal(four), 5 * 4 - 2);
+    }
+
+    #[test]
+    fn hexagonal_connectivity() {
+        let six = Minefield::new(5, 4).with_connectivity(Connectivity::Six);
+        let neighbors = |x, y| {
+            let mut neighbors: Vec<(u16, u16)> = six.neighbors_coords(x, y).collect();
+            neighbors.sort();
+            neighbors
+        };
+
+        // Even rows touch the spots up and down to their left, odd rows the ones up and down to their right
+        assert_eq!(
+            neighbors(2, 2),
+            [(1, 1), (1, 2), (1, 3), (2, 1), (2, 3), (3, 2)]
+        );
+        assert_eq!(
+            neighbors(2, 1),
+            [(1, 1), (2, 0), (2, 2), (3, 0), (3, 1), (3, 2)]
+        );
+
+        // Neighbors are symmetric, and are cut off at the edges
+        for (x, y) in six.all_coords() {
+            for (nx, ny) in six.neighbors_coords(x, y) {
+                assert!(six.neighbors_coords(nx, ny).any(|n| n == (x, y)));
+            }
+        }
+        assert_eq!(neighbors(0, 0), [(0, 1), (1, 0)]);
+        assert_eq!(neighbors(4, 1), [(3, 1), (4, 0), (4, 2)]);
+
+        // Numbers and flood reveals follow the hexagonal neighbors
+        //     0 1 2 3 4
+        // 0 [   1 1      ]
+        // 1 [  1 ☢ 1     ]
+        // 2 [   1 1      ]
+        // 3 [            ]
+        let mut minefield = six.with_mines_at(&[(1, 1)]);
+        let count = |minefield: &Minefield, x, y| match minefield.spot(x, y).unwrap().state {
+            SpotState::HiddenEmpty { neighboring_mines }
+            | SpotState::RevealedEmpty { neighboring_mines } => neighboring_mines,
+            state => panic!("{:?} at {:?}", state, (x, y)),
+        };
+        assert_eq!(count(&minefield, 0, 0), 0);
+        assert_eq!(count(&minefield, 1, 0), 1);
+        assert_eq!(count(&minefield, 2, 0), 1);
+        assert_eq!(count(&minefield, 0, 1), 1);
+        assert_eq!(count(&minefield, 2, 2), 1);
+        assert_eq!(count(&minefield, 0, 2), 0);
+
+        assert_eq!(minefield.step(4, 3), StepResult::Phew { revealed: 17 });
+        let hidden: Vec<(u16, u16)> = minefield
+            .spots()
+            .filter(|(_, spot)| !matches!(spot.state, SpotState::RevealedEmpty { .. }))
+            .map(|(&coords, _)| coords)
+            .collect();
+        // The flood doesn't reach the top left corner, since both its neighbors are numbers, nor the number between it
+        // and the mine
+        assert_eq!(hidden, [(0, 0), (1, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn with_mines_seeded() {
+        let mines = |minefield: &Minefield| {
+            let mut mines: Vec<(u16, u16)> = minefield
+                .spots()
+                .filter(|(_, spot)| spot.state == SpotState::HiddenMine)
+                .map(|(&coords, _)| coords)
+                .collect();
+            mines.sort();
+            mines
+        };
+
+        // The same seed places the same mines
+        let minefield = Minefield::new(16, 16).with_mines_seeded(40, 42);
+        assert_eq!(mines(&minefield).len(), 40);
+        assert_eq!(
+            mines(&minefield),
+            mines(&Minefield::new(16, 16).with_mines_seeded(40, 42))
+        );
+
+        // Another seed places them elsewhere
+        assert_ne!(
+            mines(&minefield),
+            mines(&Minefield::new(16, 16).with_mines_seeded(40, 43))
+        );
+
+        // The generator doesn't depend on the platform or on the version of `rand`, so a seed always places its mines
+        // in the same spots
+        assert_eq!(
+            mines(&Minefield::new(6, 4).with_mines_seeded(5, 42)),
+            [(0, 1), (2, 2), (3, 0), (3, 3), (4, 2)]
+        );
+
+        // The numbers of the empty spots match the mines which were placed around them
+        for (&(x, y), spot) in minefield.spots() {
+            if let SpotState::HiddenEmpty { neighboring_mines } = spot.state {
+                let placed = minefield
+                    .neighbors_coords(x, y)
+                    .filter(|&(nx, ny)| mines(&minefield).contains(&(nx, ny)))
+                    .count();
+
+                assert_eq!(neighboring_mines as usize, placed);
+            }
+        }
+    }
+
+    #[test]
+    fn random_mines_are_counted_like_mines_placed_one_by_one() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+        for _ in 0..200 {
+            let (width, height) = (rng.gen_range(1..=40), rng.gen_range(1..=40));
+            let mines = rng.gen_range(0..=width as u32 * height as u32);
+            let connectivity =
+                [Connectivity::Four, Connectivity::Six, Connectivity::Eight][rng.gen_range(0..3)];
+            let mut minefield = if rng.gen() {
+                Minefield::new_sparse(width, height)
+            } else {
+                Minefield::new(width, height)
+            }
+            .with_connectivity(connectivity);
+
+            // Flags placed before the mines are replaced by the mines placed under them
+            for _ in 0..3 {
+                minefield.toggle_flag(rng.gen_range(0..width), rng.gen_range(0..height));
+            }
+
+            let random = minefield.clone().with_mines_seeded(mines, rng.gen());
+            let placed: Vec<(u16, u16)> = random
+                .spots()
+                .filter(|(_, spot)| spot.state == SpotState::HiddenMine)
+                .map(|(&coords, _)| coords)
+                .collect();
+            let one_by_one = minefield.with_mines_at(&placed);
+
+            assert_eq!(random.mines(), one_by_one.mines());
+            assert!(random
+                .spots()
+                .zip(one_by_one.spots())
+                .all(|((_, a), (_, b))| a.state == b.state));
+        }
+    }
+
+    #[test]
+    fn from_mines() {
+        // Create minefield, ignoring the repeated and out of bounds mines
+        //     0 1 2 3
+        // 0 [ ☢ 1     ]
+        // 1 [ 2 2     ]
+        // 2 [ ☢ 1     ]
+        let mines = [(0, 0), (0, 2), (0, 0), (4, 1), (1, 3)];
+        let (minefield, placed) = Minefield::from_mines(4, 3, &mines);
+
+        // The mines which were left out are told by their count
+        assert_eq!(placed, 2);
+        assert_eq!(minefield.mines(), 2);
+        assert_eq!(Minefield::from_mines(4, 3, &[(0, 0), (3, 2)]).1, 2);
+        assert_eq!(Minefield::from_mines(4, 3, &[(4, 0), (0, 3), (9, 9)]).1, 0);
+        assert_eq!((minefield.width(), minefield.height()), (4, 3));
+        assert!(minefield
+            .spots()
+            .zip(Minefield::new(4, 3).with_mines_at(&mines).spots())
+            .all(|((_, a), (_, b))| a.state == b.state));
+        assert_eq!(
+            minefield.spot(0, 1).unwrap().state,
+            SpotState::HiddenEmpty {
+                neighboring_mines: 2
+            }
+        );
+        assert_eq!(
+            minefield.spot(3, 1).unwrap().state,
+            SpotState::HiddenEmpty {
+                neighboring_mines: 0
+            }
+        );
+    }
+
+    #[test]
+    fn with_mines_leaves_a_safe_spot() {
+        let safe_spots = |minefield: &Minefield| {
+            minefield
+                .all_coords()
+                .filter(|&(x, y)| {
+                    minefield.spot(x, y).map(|spot| spot.state) != Some(SpotState::HiddenMine)
+                })
+                .count()
+        };
+
+        for (width, height) in [(1, 1), (1, 2), (3, 3), (10, 10)] {
+            let spot_count = width as u32 * height as u32;
+
+            let minefield = Minefield::new(width, height).with_mines(spot_count + 5);
+            assert_eq!(minefield.mines(), spot_count - 1);
+            assert_eq!(safe_spots(&minefield), 1);
+
+            // The first step of a minefield generated for it is always safe
+            let (minefield, _) =
+                Minefield::new(width, height).with_mines_solvable(spot_count, (0, 0), 1);
+            assert_eq!(minefield.mines(), spot_count - 1);
+            assert_ne!(
+                minefield.spot(0, 0).map(|spot| spot.state),
+                Some(SpotState::HiddenMine)
+            );
+        }
+    }
+
+    #[test]
+    fn board_3bv() {
+        //     0 1 2 3 4
+        // 0 [ ☢ 1       ]
+        // 1 [ 1 1   1 1 ]
+        // 2 [       1 ☢ ]
+        //
+        // A single opening, which opens up all the numbers
+        let minefield = Minefield::new(5, 3).with_mines_at(&[(0, 0), (4, 2)]);
+        assert_eq!(minefield.board_3bv(), 1);
+
+        //     0 1 2
+        // 0 [ 2 ☢ 2 ]
+        // 1 [ ☢ 3 ☢ ]
+        //
+        // Without any opening, each number is a step of its own
+        let minefield = Minefield::new(3, 2).with_mines_at(&[(1, 0), (0, 1), (2, 1)]);
+        assert_eq!(minefield.board_3bv(), 3);
+
+        //     0 1 2 3 4
+        // 0 [   1 ☢ 1   ]
+        // 1 [   1 1 1   ]
+        //
+        // Two openings, split by the mine, and the number below the mine which neither of them opens up
+        let mut minefield = Minefield::new(5, 2).with_mines_at(&[(2, 0)]);
+        assert_eq!(minefield.board_3bv(), 3);
+
+        // Revealing spots doesn't change the value of the board
+        minefield.step(0, 0);
+        assert_eq!(minefield.board_3bv(), 3);
+    }
+
+    #[test]
+    fn ensure_open_start() {
+        // Every empty spot counts the mines around it, after mines were moved
+        let counts_match = |minefield: &Minefield| {
+            minefield
+                .all_coords()
+                .all(|(x, y)| match minefield.spot(x, y).map(|spot| spot.state) {
+                    Some(SpotState::HiddenEmpty { neighboring_mines }) => {
+                        let mines = minefield
+                            .neighbors_coords(x, y)
+                            .filter(|&(nx, ny)| minefield.spot(nx, ny).is_some_and(Spot::is_mine))
+                            .count();
+                        mines == neighboring_mines as usize
+                    }
+                    _ => true,
+                })
+        };
+
+        //     0 1 2 3 4 5
+        // 0 [ ☢ ☢ ☢       ]
+        // 1 [ ☢   ☢       ]
+        // 2 [ ☢ ☢ ☢       ]
+        // 3 [             ]
+        let ring = [
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (0, 1),
+            (2, 1),
+            (0, 2),
+            (1, 2),
+            (2, 2),
+        ];
+        let mut minefield =
+            Minefield::new(6, 4).with_mines_at(&[ring.as_slice(), &[(1, 1)]].concat());
+
+        assert!(minefield.ensure_open_start_seeded(1, 1, 3));
+        assert_eq!(minefield.mines(), 9);
+        assert_eq!(minefield.mine_spots().count(), 9);
+        assert!(counts_match(&minefield));
+        assert!(ring
+            .iter()
+            .all(|&(x, y)| !minefield.spot(x, y).unwrap().is_mine()));
+        assert!(matches!(minefield.step(1, 1), StepResult::Phew { revealed } if revealed > 1));
+
+        // Without room for all the mines around the spot, the mine on the spot is still moved away
+        let mines: Vec<(u16, u16)> = Minefield::new(4, 4)
+            .all_coords()
+            .filter(|&c| c != (3, 3))
+            .collect();
+        let mut minefield = Minefield::new(4, 4).with_mines_at(&mines);
+
+        assert!(!minefield.ensure_open_start_seeded(0, 0, 3));
+        assert_eq!(minefield.mine_spots().count(), 15);
+        assert!(counts_match(&minefield));
+        assert!(!minefield.spot(0, 0).unwrap().is_mine());
+        assert!(minefield.spot(3, 3).unwrap().is_mine());
+    }
+
+    #[test]
+    fn with_mines_at() {
+        // Create minefield, ignoring the repeated and out of bounds mines
+        //     0 1 2
+        // 0 [ ☢ 2 1 ]
+        // 1 [ 2 ☢ 1 ]
+        // 2 [ 1 1 1 ]
+        let minefield = Minefield::new(3, 3).with_mines_at(&[(0, 0), (1, 1), (0, 0), (3, 1)]);
+
+        assert_eq!(minefield.mines(), 2);
+        assert_eq!(minefield.spot(0, 0).unwrap().state, SpotState::HiddenMine);
+        assert_eq!(minefield.spot(1, 1).unwrap().state, SpotState::HiddenMine);
+        assert_eq!(
+            minefield.spot(1, 0).unwrap().state,
+            SpotState::HiddenEmpty {
+                neighboring_mines: 2
+            }
+        );
+        assert_eq!(
+            minefield.spot(2, 2).unwrap().state,
+            SpotState::HiddenEmpty {
+                neighboring_mines: 1
+            }
+        );
+    }
+
+    #[test]
+    fn solvable_without_guessing() {
+        // Create minefield
+        //     0 1 2 3
+        // 0 [ ☢ 1 1 ☢ ]
+        // 1 [ 1 1 1 1 ]
+        // 2 [         ]
+        let (minefield, _) = Minefield::from_mines(4, 3, &[(0, 0), (3, 0)]);
+
+        // Stepping on the bottom row reveals the middle one, and the pairs of 1s on each side of it tell that the top
+        // middle spots are safe, which in turn tell where both mines are
+        assert!(minefield.is_solvable_without_guessing(0, 2));
+
+        // Stepping on a mine does not solve anything
+        assert!(!minefield.is_solvable_without_guessing(0, 0));
+    }
+
+    #[test]
+    fn unsolvable_without_guessing() {
+        // Create minefield
+        //     0 1
+        // 0 [     ]
+        // 1 [ 1 1 ]
+        // 2 [ ☢ 1 ]
+        let (minefield, _) = Minefield::from_mines(2, 3, &[(0, 2)]);
+
+        // The mine could be on either of the bottom spots, since both have the same neighbors
+        assert!(!minefield.is_solvable_without_guessing(0, 0));
+
+        // Every minefield generated with the same first step has the same 50/50 guess, so the budget runs out
+        let (minefield, generation) = Minefield::new(2, 3).with_mines_solvable(1, (0, 0), 5);
+        assert_eq!(
+            generation,
+            Generation {
+                attempts: 5,
+                solvable: false
+            }
+        );
+        assert_eq!(minefield.mines(), 1);
+    }
+
+    #[test]
+    fn mine_probability() {
+        // Create minefield
+        //     0 1
+        // 0 [     ]
+        // 1 [ 1 1 ]
+        // 2 [ ☢ 1 ]
+        let mut minefield = Minefield::new(2, 3).with_mines_at(&[(0, 2)]);
+
+        // Nothing is known before the first step, so every spot has the density of the whole minefield
+        assert_eq!(minefield.mine_probability(1, 2), Some(1.0 / 6.0));
+        assert_eq!(minefield.mine_probability(2, 0), None);
+
+        assert_eq!(minefield.step(0, 0), StepResult::Phew { revealed: 4 });
+
+        // Revealed spots have no probability, and the mine could be on either of the bottom spots
+        assert_eq!(minefield.mine_probability(0, 0), None);
+        assert_eq!(minefield.mine_probability(0, 2), Some(0.5));
+        assert_eq!(minefield.mine_probability(1, 2), Some(0.5));
+
+        // Flags are not trusted
+        minefield.toggle_flag(1, 2);
+        assert_eq!(minefield.mine_probability(0, 2), Some(0.5));
+
+        // Create minefield
+        //     0 1 2
+        // 0 [ ☢ 1   ]
+        let mut minefield = Minefield::new(3, 1).with_mines_at(&[(0, 0)]);
+        assert_eq!(minefield.step(2, 0), StepResult::Phew { revealed: 2 });
+
+        // The only hidden neighbor of a `1` is certainly a mine
+        assert_eq!(minefield.mine_probability(0, 0), Some(1.0));
+    }
+
+    #[test]
+    fn mine_probabilities() {
+        // Create minefield
+        //     0 1 2 3
+        // 0 [ ☢ 1     ]
+        // 1 [ 1 1     ]
+        // 2 [         ]
+        // 3 [       ☢ ]
+        let mut minefield = Minefield::new(4, 4).with_mines_at(&[(0, 0), (3, 3)]);
+        assert_eq!(minefield.step(1, 1), StepResult::Phew { revealed: 1 });
+
+        // Every spot which isn't revealed gets the same probability as when asked for on its own
+        let probabilities = minefield.mine_probabilities();
+        assert_eq!(probabilities.len(), 15);
+        assert!(!probabilities.contains_key(&(1, 1)));
+        for (&(x, y), &probability) in &probabilities {
+            assert_eq!(minefield.mine_probability(x, y), Some(probability));
+        }
+
+        // The neighbors of the revealed `1` share its mine, the others get the density of the whole hidden minefield
+        assert_eq!(probabilities[&(0, 0)], 1.0 / 8.0);
+        assert_eq!(probabilities[&(3, 3)], 2.0 / 15.0);
+
+        for mut minefield in [
+            Minefield::new(8, 8).with_mines_seeded(10, 3),
+            Minefield::new_sparse(8, 8).with_mines_seeded(10, 3),
+        ] {
+            let zero = minefield
+                .spots()
+                .find(|(_, spot)| {
+                    spot.state
+                        == SpotState::HiddenEmpty {
+                            neighboring_mines: 0,
+                        }
+                })
+                .map(|(coords, _)| coords)
+                .unwrap();
+            minefield.step(zero.0, zero.1);
+
+            for (&(x, y), &probability) in &minefield.mine_probabilities() {
+                assert_eq!(minefield.mine_probability(x, y), Some(probability));
+            }
+        }
+    }
+
+    #[test]
+    fn undo_redo() {
+        for new in [Minefield::new, Minefield::new_sparse] {
+            // Create minefield
+            //     0 1 2
+            // 0 [ ☢ 1   ]
+            // 1 [ 1 1   ]
+            // 2 [       ]
+            let mut minefield = new(3, 3).with_mines_at(&[(0, 0)]);
+            let fresh = minefield.clone();
+            assert!(!minefield.can_undo());
+            assert!(!minefield.undo());
+
+            // Undoing a flood reveal hides all the revealed spots again
+            assert_eq!(minefield.step(2, 2), StepResult::Phew { revealed: 8 });
+            let revealed = minefield.clone();
+            assert!(minefield.undo());
+            assert_same_spots(&minefield, &fresh);
+            assert!(!minefield.can_undo());
+
+            assert!(minefield.redo());
+            assert_same_spots(&minefield, &revealed);
+            assert!(!minefield.can_redo());
+
+            // Moves which change nothing are not remembered
+            assert_eq!(
+                minefield.step(2, 2),
+                StepResult::Invalid(InvalidStep::AlreadyRevealed)
+            );
+            assert_eq!(minefield.toggle_flag(1, 1), FlagToggleResult::None);
+            assert!(minefield.undo());
+            assert_same_spots(&minefield, &fresh);
+            assert!(minefield.redo());
+
+            // A step on a mine can be undone
+            assert_eq!(minefield.step(0, 0), StepResult::Boom);
+            assert!(minefield.is_exploded());
+            assert!(minefield.undo());
+            assert!(!minefield.is_exploded());
+            assert_same_spots(&minefield, &revealed);
+
+            // Taking a new move after undoing forgets the undone one
+            assert_eq!(minefield.toggle_flag(0, 0), FlagToggleResult::Added);
+            assert!(!minefield.can_redo());
+            assert!(minefield.is_cleared());
+
+            // Moves are undone in turn, back to the first one
+            assert_eq!(minefield.toggle_flag(0, 0), FlagToggleResult::Removed);
+            assert!(minefield.undo());
+            assert!(minefield.undo());
+            assert!(minefield.undo());
+            assert!(!minefield.can_undo());
+        }
+    }
+
+    #[test]
+    fn safe_spot() {
+        // Create minefield
+        //     0 1 2 3
+        // 0 [ 1 ☢ 1   ]
+        let mut minefield = Minefield::new(4, 1).with_mines_at(&[(1, 0)]);
+
+        // Nothing is known before the first step
+        assert_eq!(minefield.safe_spot(), None);
+
+        assert_eq!(minefield.step(3, 0), StepResult::Phew { revealed: 2 });
+
+        // The only hidden neighbor of the `1` is the only mine, so the remaining spot is safe
+        assert_eq!(minefield.safe_spot(), Some((0, 0)));
+
+        // Flagged spots are not suggested, even when they are safe
+        minefield.toggle_flag(0, 0);
+        assert_eq!(minefield.safe_spot(), None);
+    }
+
+    #[test]
+    fn next_certain_move() {
+        // Create minefield
+        //     0 1 2 3
+        // 0 [ 1 ☢ 1   ]
+        let mut minefield = Minefield::new(4, 1).with_mines_at(&[(1, 0)]);
+
+        // The first step is always a guess
+        assert_eq!(minefield.next_certain_move(), None);
+
+        assert_eq!(minefield.step(3, 0), StepResult::Phew { revealed: 2 });
+
+        // Safe steps come before flags
+        assert_eq!(
+            minefield.next_certain_move(),
+            Some(SolverMove::Step { x: 0, y: 0 })
+        );
+        minefield.step(0, 0);
+
+        assert_eq!(
+            minefield.next_certain_move(),
+            Some(SolverMove::Flag { x: 1, y: 0 })
+        );
+        minefield.toggle_flag(1, 0);
+
+        assert_eq!(minefield.next_certain_move(), None);
+        assert!(minefield.is_cleared());
+    }
+
+    #[test]
+    fn with_mines_solvable() {
+        let (mut minefield, generation) =
+            Minefield::new(9, 9).with_mines_solvable(10, (4, 4), 1000);
+
+        assert!(generation.solvable);
+        assert!(generation.attempts >= 1 && generation.attempts <= 1000);
+        assert_eq!(minefield.mines(), 10);
+        assert_eq!(
+            minefield
+                .spots()
+                .filter(|(_, spot)| spot.state == SpotState::HiddenMine)
+                .count(),
+            10
+        );
+
+        // The first step and its neighbors are free of mines
+        assert_eq!(
+            minefield.spot(4, 4).unwrap().state,
+            SpotState::HiddenEmpty {
+                neighboring_mines: 0
+            }
+        );
+        // The mines are random, so the size of the flood reveal isn't known, only that it goes beyond the first step
+        assert!(matches!(
+            minefield.step(4, 4),
+            StepResult::Phew { revealed: 9.. }
+        ));
+    }
+
+    #[test]
+    fn with_mines_solvable_gives_up() {
+        // With nearly every spot a mine, the few remaining empty spots can't be told apart, so no attempt is solvable
+        let (minefield, generation) =
+            Minefield::new(9, 9).with_mines_solvable_seeded(75, (4, 4), 50, 0);
+
+        assert!(!generation.solvable);
+        assert_eq!(generation.attempts, 50);
+        assert_eq!(minefield.mines(), 75);
+        assert!(!minefield.is_solvable_without_guessing(4, 4));
+
+        // The given seed always picks the same minefields
+        let (other, _) = Minefield::new(9, 9).with_mines_solvable_seeded(75, (4, 4), 50, 0);
+        assert!(minefield
+            .spots()
+            .map(|(c, spot)| (c, spot.state))
+            .eq(other.spots().map(|(c, spot)| (c, spot.state))));
+
+        // A hard minefield is found (or given up on) in a bounded number of attempts
+        let (_, generation) =
+            Minefield::new(30, 16).with_mines_solvable_seeded(99, (15, 8), 100, 1);
+        assert!(generation.attempts >= 1 && generation.attempts <= 100);
+    }
+
+    #[test]
+    fn question_marks() {
+        for minefield in [Minefield::new(3, 3), Minefield::new_sparse(3, 3)] {
+            let mut minefield = minefield.with_mines_at(&[(0, 0)]);
+
+            // Without question marks, flags are only toggled
+            assert_eq!(minefield.toggle_flag(0, 0), FlagToggleResult::Added);
+            assert_eq!(minefield.toggle_flag(0, 0), FlagToggleResult::Removed);
+
+            // With question marks, a spot cycles from hidden to flagged to questioned and back to hidden
+            let mut minefield = minefield.with_question_marks(true);
+            let result = minefield.toggle_flag(0, 0);
+            assert_eq!(result, FlagToggleResult::Added);
+            assert_eq!(result.flags_change(), 1);
+
+            let result = minefield.toggle_flag(0, 0);
+            assert_eq!(result, FlagToggleResult::Questioned);
+            assert_eq!(result.flags_change(), -1);
+            assert_eq!(
+                minefield.spot(0, 0).unwrap().state,
+                SpotState::QuestionedMine
+            );
+            assert_eq!(minefield.flagged_neighbors(1, 1), 0);
+
+            let result = minefield.toggle_flag(0, 0);
+            assert_eq!(result, FlagToggleResult::Unquestioned);
+            assert_eq!(result.flags_change(), 0);
+            assert_eq!(minefield.spot(0, 0).unwrap().state, SpotState::HiddenMine);
+
+            // Question marks are stepped on like hidden spots, and flood revealed
+            minefield.toggle_flag(2, 1);
+            minefield.toggle_flag(2, 1);
+            assert_eq!(
+                minefield.spot(2, 1).unwrap().state,
+                SpotState::QuestionedEmpty {
+                    neighboring_mines: 0
+                }
+            );
+            assert_eq!(minefield.step(2, 2), StepResult::Phew { revealed: 8 });
+            assert_eq!(
+                minefield.spot(2, 1).unwrap().state,
+                SpotState::RevealedEmpty {
+                    neighboring_mines: 0
+                }
+            );
+
+            minefield.toggle_flag(0, 0);
+            minefield.toggle_flag(0, 0);
+            assert!(!minefield.is_cleared());
+            assert_eq!(minefield.step(0, 0), StepResult::Boom);
+            assert!(minefield.is_exploded());
+        }
+    }
+
+    #[test]
+    fn sparse_matches_dense() {
+        let boards = [
+            (3, 4, 0, Connectivity::Eight),
+            (2, 3, 1, Connectivity::Eight),
+            (10, 10, 10, Connectivity::Eight),
+            (16, 16, 40, Connectivity::Eight),
+            (30, 16, 99, Connectivity::Four),
+        ];
+
+        for (width, height, mines, connectivity) in boards {
+            for seed in 0..3 {
+                let mut dense = Minefield::new(width, height)
+                    .with_mines_seeded(mines, seed)
+                    .with_connectivity(connectivity);
+                let mut sparse = Minefield::new_sparse(width, height)
+                    .with_mines_seeded(mines, seed)
+                    .with_connectivity(connectivity);
+
+                assert_same_spots(&dense, &sparse);
+                assert_eq!(dense.mine_probability(0, 0), sparse.mine_probability(0, 0));
+
+                // Clear both minefields the same way, by flagging the mines and stepping on every other spot in order
+                let coords: Vec<(u16, u16)> = dense.all_coords().collect();
+                for &(x, y) in &coords {
+                    if dense.spot(x, y).unwrap().state == SpotState::HiddenMine {
+                        assert_eq!(dense.toggle_flag(x, y), sparse.toggle_flag(x, y));
+                    } else {
+                        assert_eq!(dense.step(x, y), sparse.step(x, y));
+                    }
+                    assert_eq!(dense.auto_step(x, y), sparse.auto_step(x, y));
+                    assert_eq!(dense.is_cleared(), sparse.is_cleared());
+                    assert_same_spots(&dense, &sparse);
+                }
+
+                assert!(sparse.is_cleared());
+
+                // Removing a flag and revealing the ring around it steps on the mine in both minefields
+                if let Some(&(x, y)) = coords
+                    .iter()
+                    .find(|&&(x, y)| dense.spot(x, y).unwrap().state == SpotState::FlaggedMine)
+                {
+                    assert_eq!(dense.toggle_flag(x, y), sparse.toggle_flag(x, y));
+                    for (nx, ny) in dense.neighbors_coords(x, y) {
+                        assert_eq!(dense.reveal_ring(nx, ny), sparse.reveal_ring(nx, ny));
+                    }
+                    assert_eq!(dense.spot(x, y).unwrap().state, SpotState::ExplodedMine);
+                    assert_same_spots(&dense, &sparse);
+                }
+            }
+        }
+
+        // A sparse minefield only stores the mines and the spots around them, until spots are revealed
+        let sparse = Minefield::new_sparse(100, 100).with_mines_seeded(10, 0);
+        assert!(sparse.field.len() <= 10 * 9);
+        assert_eq!(sparse.spots().count(), 100 * 100);
+    }
+
+    #[test]
+    fn remaining_safe_cells() {
+        let scanned = |minefield: &Minefield| {
+            minefield
+                .spots()
+                .filter(|(_, spot)| matches!(spot.state, SpotState::HiddenEmpty { .. }))
+                .count() as u32
+                + minefield
+                    .spots()
+                    .filter(|(_, spot)| {
+                        matches!(
+                            spot.state,
+                            SpotState::FlaggedEmpty { .. } | SpotState::QuestionedEmpty { .. }
+                        )
+                    })
+                    .count() as u32
+        };
+
+        for mut minefield in [
+            Minefield::new(8, 8).with_mines_seeded(10, 4),
+            Minefield::new_sparse(8, 8).with_mines_seeded(10, 4),
+        ] {
+            assert_eq!(minefield.remaining_safe_cells(), 8 * 8 - 10);
+
+            // Flood reveals, ring reveals, undos and redos all keep the count up to date
+            let zero = minefield
+                .spots()
+                .find(|(_, spot)| {
+                    spot.state
+                        == SpotState::HiddenEmpty {
+                            neighboring_mines: 0,
+                        }
+                })
+                .map(|(coords, _)| coords)
+                .unwrap();
+            minefield.step(zero.0, zero.1);
+            assert!(minefield.remaining_safe_cells() < 8 * 8 - 10 - 1);
+            assert_eq!(minefield.remaining_safe_cells(), scanned(&minefield));
+
+            while let Some((x, y)) = minefield.safe_spot() {
+                minefield.step(x, y);
+                assert_eq!(minefield.remaining_safe_cells(), scanned(&minefield));
+            }
+            assert_eq!(minefield.remaining_safe_cells(), 0);
+
+            assert!(minefield.undo());
+            assert_eq!(minefield.remaining_safe_cells(), scanned(&minefield));
+            assert!(minefield.remaining_safe_cells() > 0);
+            assert!(minefield.redo());
+            assert_eq!(minefield.remaining_safe_cells(), 0);
+
+            // All the empty spots are revealed, but the minefield is only cleared once all the mines are flagged
+            assert!(!minefield.is_cleared());
+            let mines: Vec<(u16, u16)> = minefield
+                .spots()
+                .filter(|(_, spot)| spot.state == SpotState::HiddenMine)
+                .map(|(&coords, _)| coords)
+                .collect();
+            for (x, y) in mines {
+                minefield.toggle_flag(x, y);
+            }
+            assert!(minefield.is_cleared());
+        }
+    }
+
+    #[test]
+    fn win_on_all_revealed() {
+        for mut minefield in [
+            Minefield::new(8, 8)
+                .with_win_on_all_revealed(true)
+                .with_question_marks(true)
+                .with_mines_seeded(10, 3),
+            Minefield::new_sparse(8, 8)
+                .with_win_on_all_revealed(true)
+                .with_question_marks(true)
+                .with_mines_seeded(10, 3),
+        ] {
+            let (mx, my) = minefield
+                .spots()
+                .find(|(_, spot)| spot.state == SpotState::HiddenMine)
+                .map(|(&coords, _)| coords)
+                .unwrap();
+            minefield.toggle_flag(mx, my);
+            minefield.toggle_flag(mx, my);
+            assert_eq!(
+                minefield.spot(mx, my).unwrap().state,
+                SpotState::QuestionedMine
+            );
+
+            // Mines are not given away while some of the empty spots are still hidden
+            assert_eq!(minefield.flag_remaining_mines(), 0);
+
+            let empty: Vec<(u16, u16)> = minefield
+                .spots()
+                .filter(|(_, spot)| matches!(spot.state, SpotState::HiddenEmpty { .. }))
+                .map(|(&coords, _)| coords)
+                .collect();
+            assert!(!minefield.is_cleared());
+            for (x, y) in empty {
+                minefield.step(x, y);
+            }
+
+            // Revealing all the empty spots is enough, the mines can then be flagged for show
+            assert!(minefield.is_cleared());
+            assert_eq!(minefield.flag_remaining_mines(), 10);
+            assert_eq!(
+                minefield
+                    .spots()
+                    .filter(|(_, spot)| spot.state == SpotState::FlaggedMine)
+                    .count(),
+                10
+            );
+            assert!(minefield.is_cleared());
+            assert_eq!(minefield.flag_remaining_mines(), 0);
+        }
+    }
+
+    #[test]
+    fn compact_string() {
+        for minefield in [
+            Minefield::new(8, 8).with_mines_seeded(10, 3),
+            Minefield::new_sparse(30, 16).with_mines_seeded(99, 4),
+            Minefield::new(7, 3).with_mines_at(&[(0, 0), (6, 2)]),
+            Minefield::new(1, 1),
+        ] {
+            let code = minefield.to_compact_string();
+            let decoded = Minefield::from_compact_string(&code).unwrap();
+
+            assert_eq!(decoded.width(), minefield.width());
+            assert_eq!(decoded.height(), minefield.height());
+            assert_eq!(decoded.mines(), minefield.mines());
+            for ((coords, spot), (decoded_coords, decoded_spot)) in
+                minefield.spots().zip(decoded.spots())
+            {
+                assert_eq!(coords, decoded_coords);
+                assert_eq!(spot.state, decoded_spot.state);
+            }
+        }
+
+        // Only the mines are kept, the spots of a shared board start out hidden
+        let mut minefield = Minefield::new(7, 3).with_mines_at(&[(0, 0), (6, 2)]);
+        minefield.step(3, 1);
+        minefield.toggle_flag(0, 0);
+        let code = minefield.to_compact_string();
+        assert_eq!(code.len(), 10);
+        let decoded = Minefield::from_compact_string(&code).unwrap();
+        assert_eq!(decoded.spot(0, 0).unwrap().state, SpotState::HiddenMine);
+        assert_eq!(
+            decoded.spot(3, 1).unwrap().state,
+            SpotState::HiddenEmpty {
+                neighboring_mines: 0
+            }
+        );
+        assert_eq!(
+            decoded.spot(1, 1).unwrap().state,
+            SpotState::HiddenEmpty {
+                neighboring_mines: 1
+            }
+        );
+
+        assert_eq!(
+            Minefield::from_compact_string("BwADAAE*").err(),
+            Some(CompactError::Character(7))
+        );
+        assert_eq!(
+            Minefield::from_compact_string(&code[..8]).err(),
+            Some(CompactError::Length)
+        );
+        assert_eq!(
+            Minefield::from_compact_string(&code[..6]).err(),
+            Some(CompactError::Length)
+        );
+        assert_eq!(
+            Minefield::from_compact_string("AAADAAE").err(),
+            Some(CompactError::Size)
+        );
+    }
+
+    #[test]
+    fn revealed_and_flagged_spots() {
+        for mut minefield in [
+            Minefield::new(5, 4).with_mines_at(&[(1, 1), (4, 0)]),
+            Minefield::new_sparse(5, 4).with_mines_at(&[(1, 1), (4, 0)]),
+        ] {
+            assert_eq!(minefield.revealed_spots().count(), 0);
+            assert_eq!(minefield.flagged_spots().count(), 0);
+
+            minefield.step(0, 3);
+            minefield.toggle_flag(1, 1);
+            minefield.toggle_flag(0, 0);
+
+            // Both match filtering all the spots, whether they are stored or not
+            let mut revealed: Vec<(u16, u16)> = minefield
+                .revealed_spots()
+                .map(|(coords, _)| coords)
+                .collect();
+            revealed.sort();
+            let mut expected: Vec<(u16, u16)> = minefield
+                .spots()
+                .filter(|(_, spot)| matches!(spot.state, SpotState::RevealedEmpty { .. }))
+                .map(|(&coords, _)| coords)
+                .collect();
+            expected.sort();
+            assert!(!revealed.is_empty());
+            assert_eq!(revealed, expected);
+
+            let mut flagged: Vec<(u16, u16)> = minefield
+                .flagged_spots()
+                .map(|(coords, _)| coords)
+                .collect();
+            flagged.sort();
+            assert_eq!(flagged, [(0, 0), (1, 1)]);
+
+            // Mines are found whether they are flagged or not
+            let mut mines: Vec<(u16, u16)> =
+                minefield.mine_spots().map(|(coords, _)| coords).collect();
+            mines.sort();
+            assert_eq!(mines, [(1, 1), (4, 0)]);
+        }
+    }
+
+    fn assert_same_spots(a: &Minefield, b: &Minefield) {
+        assert_eq!(a.mines(), b.mines());
+        assert!(a
+            .spots()
+            .map(|(coords, spot)| (coords, spot.state))
+            .eq(b.spots().map(|(coords, spot)| (coords, spot.state))));
+    }
+
+    #[allow(dead_code)]
+    fn print_minefield(minefield: &Minefield) {
+        // X axis
+        println!();
+        print!("   ");
+        for y in 0..minefield.width {
+            print!(" {}", y);
+        }
+        println!();
+
+        for y in 0..minefield.height {
+            // Y Axis
+            print!("{:?} [", y);
+            for x in 0..minefield.width {
+                match minefield.field.get(&(x, y)).unwrap().state {
+                    SpotState::FlaggedMine
+                    | SpotState::HiddenMine
+                    | SpotState::QuestionedMine
+                    | SpotState::ExplodedMine => {
+                        print!(" ☢");
+                    }
+                    SpotState::FlaggedEmpty { neighboring_mines }
+                    | SpotState::QuestionedEmpty { neighboring_mines }
+                    | SpotState::HiddenEmpty { neighboring_mines }
+                    | SpotState::RevealedEmpty { neighboring_mines } => {
+                        if neighboring_mines > 0 {
+                            print!(" {}", neighboring_mines);
+                        } else {
+                            print!("  ");
+                        }
+                    }
+                }
+            }
+            println!(" ]");
+        }
+    }
+
+    #[allow(dead_code)]
+    fn print_minefield_state(minefield: &Minefield) {
+        // X axis
+        println!();
+        print!("   ");
+        for y in 0..minefield.width {
+            print!(" {}", y);
+        }
+        println!();
+
+        for y in 0..minefield.height {
+            // Y Axis
+            print!("{:?} [", y);
+            for x in 0..minefield.width {
+                match minefield.field.get(&(x, y)).unwrap().state {
+                    SpotState::HiddenEmpty {
+                        neighboring_mines: _,
+                    } => {
+                        print!(" •");
+                    }
+                    SpotState::HiddenMine => {
+                        print!(" •");
+                    }
+                    SpotState::FlaggedEmpty {
+                        neighboring_mines: _,
+                    } => {
+                        print!(" ⚐");
+                    }
+                    SpotState::FlaggedMine => {
+                        print!(" ⚐");
+                    }
+                    SpotState::QuestionedEmpty {
+                        neighboring_mines: _,
+                    }
+                    | SpotState::QuestionedMine => {
+                        print!(" ?");
+                    }
+                    SpotState::RevealedEmpty { neighboring_mines } => {
+                        if neighboring_mines > 0 {
+                            print!(" {}", neighboring_mines);
+                        } else {
+                            print!("  ");
+                        }
+                    }
+                    SpotState::ExplodedMine => {
+                        print!(" 💥");
+                    }
+                }
+            }
+            println!(" ]");
+        }
+    }
+}
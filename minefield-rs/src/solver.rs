@@ -0,0 +1,364 @@
+use crate::{Minefield, SpotState};
+use std::collections::BTreeSet;
+
+/// What the solver knows about a spot of the minefield
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Knowledge {
+    /// Nothing is known about the spot
+    Unknown,
+
+    /// The spot is certainly a mine
+    Mine,
+
+    /// The spot is certainly safe, but has not been revealed
+    Safe,
+
+    /// The spot has been revealed, and has the given number of neighboring mines
+    Revealed(u8),
+}
+
+/// A constraint given by a revealed spot: exactly `mines` of the `spots` around it are mines
+#[derive(Clone, Debug)]
+struct Constraint {
+    /// Indices of the unknown neighbors of the revealed spot, in ascending order
+    spots: Vec<usize>,
+
+    /// Number of mines among `spots`
+    mines: usize,
+}
+
+/// Deductions which can be made with certainty from what is known about a minefield
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Deductions {
+    /// Coordinates of the spots which are certainly safe
+    pub(crate) safe: BTreeSet<(u16, u16)>,
+
+    /// Coordinates of the spots which are certainly mines
+    pub(crate) mines: BTreeSet<(u16, u16)>,
+}
+
+impl Deductions {
+    /// No deductions could be made, so the next move would have to be a guess
+    pub(crate) fn is_empty(&self) -> bool {
+        self.safe.is_empty() && self.mines.is_empty()
+    }
+}
+
+/// A solver which only makes the moves which are certain, given what it knows about the minefield
+#[derive(Clone, Debug)]
+pub(crate) struct Solver<'a> {
+    minefield: &'a Minefield,
+
+    /// What is known about each spot, indexed by `y * width + x`
+    knowledge: Vec<Knowledge>,
+}
+
+impl<'a> Solver<'a> {
+    /// Create a solver which knows nothing about the given minefield
+    pub(crate) fn new(minefield: &'a Minefield) -> Self {
+        let spot_count = minefield.width as usize * minefield.height as usize;
+
+        Self {
+            minefield,
+            knowledge: vec![Knowledge::Unknown; spot_count],
+        }
+    }
+
+    /// Create a solver which only knows the spots of the given minefield which have been revealed. Flags are not
+    /// trusted, since they might be wrong.
+    pub(crate) fn from_revealed(minefield: &'a Minefield) -> Self {
+        let mut solver = Self::new(minefield);
+
+        for (&(x, y), spot) in &minefield.field {
+            if let SpotState::RevealedEmpty { neighboring_mines } = spot.state {
+                let index = solver.index(x, y);
+                solver.knowledge[index] = Knowledge::Revealed(neighboring_mines);
+            }
+        }
+
+        solver
+    }
+
+    /// Estimate the probability of the spot at the given coordinates being a mine, given what the solver knows. Spots
+    /// which can be decided with certainty have a probability of `0.0` or `1.0`. For the others, the estimate is the
+    /// highest density of mines among the unknown neighbors of the revealed spots around them, or the density of the
+    /// remaining mines in the rest of the minefield if no revealed spot touches them.
+    pub(crate) fn mine_probability(&mut self, x: u16, y: u16) -> f32 {
+        let index = self.index(x, y);
+
+        self.mine_probabilities()[index]
+    }
+
+    /// Estimate the probability of each spot being a mine like `mine_probability`, indexed by `y * width + x`. The
+    /// deductions and constraints are only found once for all the spots, which is much faster than asking for each spot.
+    pub(crate) fn mine_probabilities(&mut self) -> Vec<f32> {
+        self.mark_deductions();
+
+        // The highest density of mines among the unknown spots of each of the constraints a spot is part of
+        let mut local: Vec<Option<f32>> = vec![None; self.knowledge.len()];
+        for constraint in self.constraints() {
+            let density = constraint.mines as f32 / constraint.spots.len() as f32;
+
+            for i in constraint.spots {
+                local[i] = Some(local[i].map_or(density, |d| d.max(density)));
+            }
+        }
+
+        let unknown = self
+            .knowledge
+            .iter()
+            .filter(|&&k| k == Knowledge::Unknown)
+            .count();
+        let known_mines = self
+            .knowledge
+            .iter()
+            .filter(|&&k| k == Knowledge::Mine)
+            .count();
+        let remaining_mines = (self.minefield.mines as usize).saturating_sub(known_mines);
+        let density = remaining_mines as f32 / unknown as f32;
+
+        self.knowledge
+            .iter()
+            .zip(local)
+            .map(|(knowledge, local)| match knowledge {
+                Knowledge::Mine => 1.0,
+                Knowledge::Safe | Knowledge::Revealed(_) => 0.0,
+                Knowledge::Unknown => local.unwrap_or(density),
+            })
+            .collect()
+    }
+
+    /// Coordinates of the spots which are certainly safe given what the solver knows, but have not been revealed, in
+    /// row order
+    pub(crate) fn safe_spots(&mut self) -> Vec<(u16, u16)> {
+        self.mark_deductions();
+
+        (0..self.knowledge.len())
+            .filter(|&i| self.knowledge[i] == Knowledge::Safe)
+            .map(|i| self.coords(i))
+            .collect()
+    }
+
+    /// Coordinates of the spots which are certainly mines given what the solver knows, in row order
+    pub(crate) fn mine_spots(&mut self) -> Vec<(u16, u16)> {
+        self.mark_deductions();
+
+        (0..self.knowledge.len())
+            .filter(|&i| self.knowledge[i] == Knowledge::Mine)
+            .map(|i| self.coords(i))
+            .collect()
+    }
+
+    /// Mark everything which can be decided without revealing any more spots
+    fn mark_deductions(&mut self) {
+        loop {
+            let deductions = self.deduce();
+
+            if deductions.is_empty() {
+                break;
+            }
+
+            for (x, y) in deductions.mines {
+                let index = self.index(x, y);
+                self.knowledge[index] = Knowledge::Mine;
+            }
+
+            for (x, y) in deductions.safe {
+                let index = self.index(x, y);
+                self.knowledge[index] = Knowledge::Safe;
+            }
+        }
+    }
+
+    /// Reveal the spot at the given coordinates, flood revealing its neighbors if it has no neighboring mines (just
+    /// like `Minefield::step`). Returns `false` if the spot is a mine.
+    pub(crate) fn reveal(&mut self, x: u16, y: u16) -> bool {
+        let mut spots_to_visit = vec![(x, y)];
+
+        while let Some((xx, yy)) = spots_to_visit.pop() {
+            let index = self.index(xx, yy);
+
+            if self.knowledge[index] != Knowledge::Unknown {
+                continue;
+            }
+
+            match self.minefield.spot(xx, yy).map(|spot| spot.state) {
+                Some(SpotState::HiddenEmpty { neighboring_mines })
+                | Some(SpotState::FlaggedEmpty { neighboring_mines })
+                | Some(SpotState::QuestionedEmpty { neighboring_mines })
+                | Some(SpotState::RevealedEmpty { neighboring_mines }) => {
+                    self.knowledge[index] = Knowledge::Revealed(neighboring_mines);
+
+                    if neighboring_mines == 0 {
+                        spots_to_visit.extend(self.minefield.neighbors_coords(xx, yy));
+                    }
+                }
+                _ => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Keep applying the certain deductions (revealing safe spots and marking mines) until either the minefield is
+    /// cleared, in which case `true` is returned, or a guess would be needed
+    pub(crate) fn solve(&mut self) -> bool {
+        loop {
+            if self.is_cleared() {
+                return true;
+            }
+
+            let deductions = self.deduce();
+
+            if deductions.is_empty() {
+                return false;
+            }
+
+            for (x, y) in deductions.mines {
+                let index = self.index(x, y);
+                self.knowledge[index] = Knowledge::Mine;
+            }
+
+            for (x, y) in deductions.safe {
+                self.reveal(x, y);
+            }
+        }
+    }
+
+    /// All the empty spots of the minefield are known to the solver
+    pub(crate) fn is_cleared(&self) -> bool {
+        let revealed = self
+            .knowledge
+            .iter()
+            .filter(|k| matches!(k, Knowledge::Revealed(_)))
+            .count();
+
+        revealed + self.minefield.mines as usize == self.knowledge.len()
+    }
+
+    /// Find the spots which are certainly safe and the ones which are certainly mines, given what the solver knows
+    pub(crate) fn deduce(&self) -> Deductions {
+        let constraints = self.constraints();
+        let mut deductions = Deductions::default();
+
+        // A single revealed spot is enough to decide its unknown neighbors if either all or none of them are mines
+        for constraint in &constraints {
+            if constraint.mines == 0 {
+                deductions
+                    .safe
+                    .extend(constraint.spots.iter().map(|&i| self.coords(i)));
+            } else if constraint.mines == constraint.spots.len() {
+                deductions
+                    .mines
+                    .extend(constraint.spots.iter().map(|&i| self.coords(i)));
+            }
+        }
+
+        if !deductions.is_empty() {
+            return deductions;
+        }
+
+        // When the unknown neighbors of a revealed spot are a subset of the unknown neighbors of another revealed spot,
+        // the difference between their numbers of mines are in the remaining spots of the latter
+        let mut constraints_by_spot = vec![vec![]; self.knowledge.len()];
+        for (c, constraint) in constraints.iter().enumerate() {
+            for &i in &constraint.spots {
+                constraints_by_spot[i].push(c);
+            }
+        }
+
+        for a in &constraints {
+            let overlapping: BTreeSet<usize> = a
+                .spots
+                .iter()
+                .flat_map(|&i| constraints_by_spot[i].iter().copied())
+                .collect();
+
+            for b in overlapping.into_iter().map(|c| &constraints[c]) {
+                if b.spots.len() <= a.spots.len() || !a.spots.iter().all(|i| b.spots.contains(i)) {
+                    continue;
+                }
+
+                let rest = b.spots.iter().filter(|i| !a.spots.contains(i));
+                let rest_mines = b.mines - a.mines;
+
+                if rest_mines == 0 {
+                    deductions.safe.extend(rest.map(|&i| self.coords(i)));
+                } else if rest_mines == b.spots.len() - a.spots.len() {
+                    deductions.mines.extend(rest.map(|&i| self.coords(i)));
+                }
+            }
+        }
+
+        if !deductions.is_empty() {
+            return deductions;
+        }
+
+        // Finally, the total number of mines might decide all the unknown spots at once
+        let unknown: Vec<usize> = (0..self.knowledge.len())
+            .filter(|&i| self.knowledge[i] == Knowledge::Unknown)
+            .collect();
+        let known_mines = self
+            .knowledge
+            .iter()
+            .filter(|&&k| k == Knowledge::Mine)
+            .count();
+        let remaining_mines = (self.minefield.mines as usize).saturating_sub(known_mines);
+
+        if remaining_mines == 0 {
+            deductions
+                .safe
+                .extend(unknown.iter().map(|&i| self.coords(i)));
+        } else if remaining_mines == unknown.len() {
+            deductions
+                .mines
+                .extend(unknown.iter().map(|&i| self.coords(i)));
+        }
+
+        deductions
+    }
+
+    /// The constraints given by all the revealed spots which still have unknown neighbors
+    fn constraints(&self) -> Vec<Constraint> {
+        let mut constraints = vec![];
+
+        for (index, knowledge) in self.knowledge.iter().enumerate() {
+            if let Knowledge::Revealed(neighboring_mines) = *knowledge {
+                let (x, y) = self.coords(index);
+                let mut spots = vec![];
+                let mut known_mines = 0;
+
+                for (nx, ny) in self.minefield.neighbors_coords(x, y) {
+                    let n_index = self.index(nx, ny);
+
+                    match self.knowledge[n_index] {
+                        Knowledge::Unknown => spots.push(n_index),
+                        Knowledge::Mine => known_mines += 1,
+                        Knowledge::Safe | Knowledge::Revealed(_) => {}
+                    }
+                }
+
+                if !spots.is_empty() {
+                    spots.sort_unstable();
+
+                    constraints.push(Constraint {
+                        spots,
+                        mines: (neighboring_mines as usize).saturating_sub(known_mines),
+                    });
+                }
+            }
+        }
+
+        constraints
+    }
+
+    fn index(&self, x: u16, y: u16) -> usize {
+        y as usize * self.minefield.width as usize + x as usize
+    }
+
+    fn coords(&self, index: usize) -> (u16, u16) {
+        let width = self.minefield.width as usize;
+
+        ((index % width) as u16, (index / width) as u16)
+    }
+}
@@ -0,0 +1,261 @@
+use crate::minesweep::{ConfigError, GameConfig};
+use minefield_rs::{Connectivity, Minefield};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+/// Name of the directory where edited boards are saved, next to the file of the game configs
+pub const BOARDS_DIR: &str = "boards";
+
+/// Extension of saved board files
+pub const BOARD_EXTENSION: &str = "board";
+
+/// A minefield laid out by the player, mine by mine, which can then be played
+#[derive(Debug, Clone)]
+pub struct BoardEditor {
+    mines: BTreeSet<(u16, u16)>,
+
+    /// The minefield with the placed mines, whose neighbor counts are kept up to date
+    field: Minefield,
+
+    /// Names of the saved boards, which can be loaded in the editor
+    pub saved: Vec<String>,
+
+    /// Name of the saved board which was last saved or loaded
+    pub selected: Option<String>,
+
+    /// Path of a board file written by hand, which can be played
+    pub file_path: String,
+
+    /// Why the last action in the editor failed, if it did
+    pub error: Option<String>,
+}
+
+impl BoardEditor {
+    /// An editor with no mines on a minefield of the given size, listing the boards saved in `boards_dir`
+    pub fn new(width: u16, height: u16, connectivity: Connectivity, boards_dir: &Path) -> Self {
+        Self {
+            mines: BTreeSet::new(),
+            field: Minefield::new(width, height).with_connectivity(connectivity),
+            saved: SavedBoard::list(boards_dir),
+            selected: None,
+            file_path: String::new(),
+            error: None,
+        }
+    }
+
+    /// An editor with the mines of the given board, listing the boards saved in `boards_dir`
+    pub fn from_board(board: &SavedBoard, connectivity: Connectivity, boards_dir: &Path) -> Self {
+        let mut editor = Self::new(board.width, board.height, connectivity, boards_dir);
+        for &(x, y) in &board.mines {
+            if x < board.width && y < board.height {
+                editor.mines.insert((x, y));
+            }
+        }
+        editor.update_field();
+
+        editor
+    }
+
+    /// The minefield with the placed mines, none of them revealed
+    pub fn field(&self) -> &Minefield {
+        &self.field
+    }
+
+    /// The game config matching the edited board
+    pub fn game_config(&self) -> GameConfig {
+        GameConfig {
+            width: self.field.width(),
+            height: self.field.height(),
+            mines: self.mines.len() as u32,
+            ..GameConfig::default()
+        }
+    }
+
+    /// The board can be played: it has at least one mine, and at least one spot without a mine
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.game_config().validate()
+    }
+
+    /// Place a mine on the given spot, or remove the one which is already there
+    pub fn toggle_mine(&mut self, x: u16, y: u16) {
+        if !self.mines.remove(&(x, y)) {
+            self.mines.insert((x, y));
+        }
+
+        self.error = None;
+        self.update_field();
+    }
+
+    /// Remove all the mines
+    pub fn clear(&mut self) {
+        self.mines.clear();
+
+        self.error = None;
+        self.update_field();
+    }
+
+    /// The edited board, as it is saved to file
+    pub fn board(&self) -> SavedBoard {
+        SavedBoard {
+            width: self.field.width(),
+            height: self.field.height(),
+            mines: self.mines.iter().copied().collect(),
+        }
+    }
+
+    /// Place the mines on a new minefield, so that the neighbor counts match them
+    fn update_field(&mut self) {
+        let mines: Vec<(u16, u16)> = self.mines.iter().copied().collect();
+
+        self.field = Minefield::new(self.field.width(), self.field.height())
+            .with_connectivity(self.field.connectivity())
+            .with_mines_at(&mines);
+    }
+}
+
+/// A board saved from the editor, which can be shared with other players
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedBoard {
+    pub width: u16,
+    pub height: u16,
+
+    /// Coordinates `(x, y)` of all the mines of the board
+    pub mines: Vec<(u16, u16)>,
+}
+
+impl SavedBoard {
+    /// Write the board to the file with the given name in `dir`. Returns the path of the file.
+    pub fn save(&self, dir: &Path, name: &str) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+
+        let path = dir.join(format!("{}.{}", name, BOARD_EXTENSION));
+        std::fs::write(&path, serde_json::to_vec(self)?)?;
+
+        Ok(path)
+    }
+
+    /// Load the board saved with the given name in `dir`
+    pub fn load(dir: &Path, name: &str) -> Result<Self, String> {
+        let path = dir.join(format!("{}.{}", name, BOARD_EXTENSION));
+        let bytes = std::fs::read(path).map_err(|e| format!("Could not read board: {}", e))?;
+
+        serde_json::from_slice(&bytes).map_err(|e| format!("Corrupt board: {}", e))
+    }
+
+    /// Read a board written by hand from the file at `path`. The file either holds the JSON of a saved board, or a grid
+    /// of spots with one row per line, `*` being a mine and `.` a spot without one (see `from_grid`).
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| format!("Could not read board: {}", e))?;
+
+        let board = if text.trim_start().starts_with('{') {
+            serde_json::from_str(&text).map_err(|e| format!("Corrupt board: {}", e))?
+        } else {
+            Self::from_grid(&text)?
+        };
+        board.validate()?;
+
+        Ok(board)
+    }
+
+    /// Parse a board from a grid of spots with one row per line, `*` being a mine and `.` a spot without one. Blank
+    /// lines and lines starting with `#` are left out, so that the board can be commented.
+    pub fn from_grid(text: &str) -> Result<Self, String> {
+        let rows: Vec<(usize, &str)> = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+
+        let width = rows.first().map_or(0, |(_, row)| row.chars().count());
+        let mut mines = Vec::new();
+        for (y, &(line, row)) in rows.iter().enumerate() {
+            if row.chars().count() != width {
+                return Err(format!(
+                    "Line {} has {} spots, while the first row has {}",
+                    line,
+                    row.chars().count(),
+                    width
+                ));
+            }
+
+            for (x, spot) in row.chars().enumerate() {
+                match spot {
+                    '*' => mines.push((x as u16, y as u16)),
+                    '.' => {}
+                    _ => {
+                        return Err(format!(
+                            "Unexpected '{}' on line {}, only '*' and '.' make spots",
+                            spot, line
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            width: width.min(u16::MAX as usize) as u16,
+            height: rows.len().min(u16::MAX as usize) as u16,
+            mines,
+        })
+    }
+
+    /// The board can be played: its size is supported, and each of its mines is on a distinct spot of it
+    pub fn validate(&self) -> Result<(), String> {
+        let mut spots = BTreeSet::new();
+        for &(x, y) in &self.mines {
+            if x >= self.width || y >= self.height {
+                return Err(format!(
+                    "Mine at ({}, {}) is outside of the {}x{} board",
+                    x, y, self.width, self.height
+                ));
+            }
+            if !spots.insert((x, y)) {
+                return Err(format!("Mine at ({}, {}) is listed twice", x, y));
+            }
+        }
+
+        self.game_config().validate().map_err(|e| e.to_string())
+    }
+
+    /// The game config matching the board, with the classic rules
+    pub fn game_config(&self) -> GameConfig {
+        GameConfig {
+            width: self.width,
+            height: self.height,
+            mines: self.mines.len() as u32,
+            ..GameConfig::default()
+        }
+    }
+
+    /// A minefield with the mines of the board, none of them revealed
+    pub fn field(&self, connectivity: Connectivity) -> Minefield {
+        Minefield::new(self.width, self.height)
+            .with_connectivity(connectivity)
+            .with_mines_at(&self.mines)
+    }
+
+    /// Names of all the boards saved in `dir`, most recent first
+    pub fn list(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .map(|read_dir| {
+                read_dir
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.extension().is_some_and(|e| e == BOARD_EXTENSION))
+                    .filter_map(|path| {
+                        path.file_stem()
+                            .map(|stem| stem.to_string_lossy().into_owned())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        names.sort_by(|a, b| b.cmp(a));
+
+        names
+    }
+}
@@ -0,0 +1,73 @@
+use crate::minesweep::{GameEvent, Message, Minesweep};
+use iced::{Element, Subscription, Task, Theme};
+
+/// The game, embedded in another iced application with its own message type `M` (see `Minesweep::embed`).
+///
+/// The embedding application keeps the `Embedded` game in its state, and forwards to `update` the game messages it
+/// gets back from `on_message`. The games won, lost and recorded as high scores come back as messages too, once a
+/// mapping for them is given to `on_game_event`.
+pub struct Embedded<M> {
+    game: Minesweep,
+
+    /// Makes the messages of the embedding application from those of the game
+    on_message: fn(Message) -> M,
+
+    /// Makes the messages of the embedding application from the events of the game, if it wants to be told about them
+    on_game_event: Option<Box<dyn Fn(GameEvent) -> M>>,
+}
+
+impl<M: Send + 'static> Embedded<M> {
+    pub(crate) fn new(game: Minesweep, on_message: fn(Message) -> M) -> Self {
+        Self {
+            game,
+            on_message,
+            on_game_event: None,
+        }
+    }
+
+    /// Build an embedded game which tells the embedding application about the games won, lost and recorded as high
+    /// scores, with the messages made by `on_game_event`
+    pub fn on_game_event(mut self, on_game_event: impl Fn(GameEvent) -> M + 'static) -> Self {
+        self.on_game_event = Some(Box::new(on_game_event));
+
+        self
+    }
+
+    /// The embedded game, which can be looked at but is only changed through `update`
+    pub fn game(&self) -> &Minesweep {
+        &self.game
+    }
+
+    /// Handle a message of the game. The events of the game which it caused are returned after its own tasks.
+    pub fn update(&mut self, message: Message) -> Task<M> {
+        let task = self.game.update(message).map(self.on_message);
+        let events = self.game.take_events();
+
+        match &self.on_game_event {
+            Some(on_game_event) => Task::batch(
+                std::iter::once(task).chain(
+                    events
+                        .into_iter()
+                        .map(|event| Task::done(on_game_event(event))),
+                ),
+            ),
+            None => task,
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, M> {
+        self.game.view().map(self.on_message)
+    }
+
+    pub fn subscription(&self) -> Subscription<M> {
+        // Subscriptions can only be mapped by functions which don't capture anything, so the mapping is passed along
+        self.game
+            .subscription()
+            .with(self.on_message)
+            .map(|(on_message, message)| on_message(message))
+    }
+
+    pub fn theme(&self) -> Theme {
+        self.game.theme()
+    }
+}
@@ -0,0 +1,17 @@
+//! A Minesweeper game made with iced. It runs as an application of its own (see `main.rs`), and can also be embedded
+//! in a larger iced application (see `Minesweep::embed`).
+
+extern crate log;
+
+mod editor;
+mod embed;
+mod minesweep;
+mod minimap;
+mod replay;
+mod sound;
+mod tutorial;
+
+pub use embed::Embedded;
+pub use minesweep::{
+    DifficultyLevel, GameConfig, GameEvent, GameMode, Message, Minesweep, StartupConfig,
+};
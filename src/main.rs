@@ -1,15 +1,27 @@
 use iced::window;
-use minesweep::Minesweep;
-
-extern crate log;
-
-mod minesweep;
+use iced_minesweep_rs::{Minesweep, StartupConfig};
 
 pub fn main() -> iced::Result {
     env_logger::builder().format_timestamp(None).init();
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", StartupConfig::USAGE);
+        return Ok(());
+    }
+
+    // Command line arguments take precedence over the environment variables
+    let startup = match StartupConfig::from_env().with_args(args) {
+        Ok(startup) => startup,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, StartupConfig::USAGE);
+            std::process::exit(2);
+        }
+    };
+
     iced::application(Minesweep::APP_NAME, Minesweep::update, Minesweep::view)
         .subscription(Minesweep::subscription)
+        .theme(Minesweep::theme)
         .font(include_bytes!("../res/fonts/emoji-icon-font.ttf").as_slice())
         .font(include_bytes!("../res/fonts/NotoEmoji-Regular.ttf").as_slice())
         .font(include_bytes!("../res/fonts/Ubuntu-Light.ttf").as_slice())
@@ -18,5 +30,5 @@ pub fn main() -> iced::Result {
             resizable: false,
             ..window::Settings::default()
         })
-        .run_with(Minesweep::initialize)
+        .run_with(move || Minesweep::initialize(startup))
 }
@@ -1,39 +1,120 @@
+use crate::editor::{BoardEditor, SavedBoard, BOARDS_DIR};
+use crate::embed::Embedded;
+use crate::minimap::{Minimap, MinimapColors};
+use crate::replay::{
+    format_date, format_timestamp, now_timestamp, Playback, Replay, ReplayEntry, ReplayEvent,
+    ReplayHeader, REPLAYS_DIR, REPLAY_VERSION,
+};
+use crate::sound::{Sound, SoundPlayer};
+use crate::tutorial::{Tutorial, TutorialStep};
 use iced::{
-    alignment,
+    alignment, keyboard,
     mouse::{self, Cursor},
-    time,
+    time, touch,
     widget::{
         self, button,
         canvas::{self, event, stroke, Cache, Event, Frame, LineCap, Path, Stroke, Text},
-        container,
+        container, scrollable,
         text_input::{self},
         Canvas,
     },
     window, Alignment, Color, Element, Font, Length, Point, Rectangle, Renderer, Size,
     Subscription, Task, Theme, Vector,
 };
-use minefield_rs::{FlagToggleResult, Minefield, StepResult};
+use minefield_rs::{
+    Connectivity, FlagToggleResult, Generation, InvalidStep, Minefield, SolverMove, SpotState,
+    StepResult,
+};
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::BTreeMap,
+    cell::RefCell,
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
     fmt::Display,
+    ops::Range,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering as AtomicOrdering},
+        Mutex, PoisonError,
+    },
     time::{Duration, Instant},
 };
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    /// Restart the game
+    /// Restart the game, asking the player first if the game is running
     Reset,
 
+    /// The new board key was pressed, which only restarts the game from the game view
+    ResetKey,
+
+    /// Play the same board again from the start, with its mines where they were, asking the player first if the game
+    /// is running
+    RestartSameBoard,
+
+    /// The player answered whether the running game should really be restarted
+    ConfirmReset(bool),
+
+    /// Copy the seed of the current board to the clipboard
+    CopySeed,
+
+    /// Pause the running game, or resume it if it's paused
+    TogglePause,
+
+    /// Start or stop showing where the mines are, without ending the game
+    TogglePeek,
+
+    /// Start or stop shading the hidden spots by how likely they are to be mines
+    ToggleProbabilities,
+
+    /// Switch the left button and touch taps between stepping on spots and flagging them
+    ToggleFlaggingMode,
+
+    /// Start or stop making the certain moves, one on each tick of the game timer
+    AutoSolve,
+
     /// The info view has been requested
     Info,
 
     /// The high scores view has been requested
     HighScores,
 
+    /// The statistics view has been requested
+    Stats,
+
+    /// Clear the statistics of all difficulty levels
+    ResetStats,
+
+    /// The view listing the saved replays has been requested
+    Replays,
+
+    /// Play back the replay saved in the given file
+    PlayReplay(PathBuf),
+
+    /// Start today's daily challenge of the given difficulty, whose board is the same for every player
+    DailyChallenge(DifficultyLevel),
+
+    /// Message which informs us that the replay being played back might have new events due
+    ReplayFrame(Instant),
+
+    /// Control the playback of a replay
+    Playback(PlaybackMessage),
+
+    /// Pick, add or delete a player profile
+    Profile(ProfileMessage),
+
+    /// Close the view shown over the game, like its cancel or ok button would, and get back to the game
+    CloseView,
+
     /// A new high score needs to be recorded
     HighScore(RecordHighScore),
 
+    /// Messages related to the board editor
+    Editor(EditorMessage),
+
+    /// Messages related to the tutorial
+    Tutorial(TutorialMessage),
+
     /// Messages related to game settings
     Settings(SettingsMessage),
 
@@ -46,12 +127,60 @@ pub enum Message {
     /// Message which informs us that a second has passed
     Tick(Instant),
 
+    /// Message which informs us that a new frame of the running animation can be drawn
+    AnimationFrame(Instant),
+
     /// The `window::Id` of the game window
     WindowId(Option<window::Id>),
+
+    /// The size of the monitor showing the game window, if it could be detected
+    MonitorSize(Option<Size>),
+
+    /// The warning about the current minefield possibly requiring guesses has been dismissed
+    DismissGenerationWarning,
+
+    /// The summary of the game which just ended has been dismissed, to look at the board
+    DismissGameSummary,
+
+    /// Zoom the minefield in (larger spots) or out (smaller spots) by one step
+    Zoom { zoom_in: bool },
+
+    /// The minefield has been scrolled, so that the given part of the canvas is shown
+    FieldScrolled(Rectangle),
+
+    /// Scroll the minefield so that it's centered on the given point, as fractions of the width and height of its grid
+    PanField(Point),
+}
+
+/// Something which happened in the game, which an application embedding it can react to (see `Embedded`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    /// A game played with the given config was won, in the given number of milliseconds
+    Won {
+        game_config: GameConfig,
+        millis: u64,
+    },
+
+    /// A game played with the given config was lost after the given number of milliseconds. The game can still go on,
+    /// and be won, if the step on a mine is undone.
+    Lost {
+        game_config: GameConfig,
+        millis: u64,
+    },
+
+    /// The player entered their name for a new high score, at the given rank (the best one being 0) of the high scores
+    /// of a difficulty level, or of its flagless high scores
+    HighScore {
+        difficulty_level: DifficultyLevel,
+        flagless: bool,
+        rank: usize,
+        name: String,
+        millis: u64,
+    },
 }
 
 /// Lower level game logic messages
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MinesweepMessage {
     /// User is stepping on a spot
     Step { x: u16, y: u16 },
@@ -59,8 +188,20 @@ pub enum MinesweepMessage {
     /// User is autostepping around a spot
     AutoStep { x: u16, y: u16 },
 
+    /// User is revealing the hidden neighbors of a spot, without requiring flags around it
+    RevealRing { x: u16, y: u16 },
+
     /// User is toggling a flag on a spot
     Flag { x: u16, y: u16 },
+
+    /// User is flagging the hidden neighbors of a spot, which must all be mines
+    AutoFlag { x: u16, y: u16 },
+
+    /// User is reverting their last move
+    Undo,
+
+    /// User is taking their last undone move again
+    Redo,
 }
 
 #[derive(Debug, Clone)]
@@ -68,30 +209,158 @@ pub enum SettingsMessage {
     /// Show settings view
     Show,
 
-    /// Apply the game configuration specified in the settings view
-    Set(GameDifficulty),
+    /// Apply the settings specified in the settings view
+    Set(Box<SettingsDraft>),
 
     /// A new game difficulty has been picked, but not yet applied
     Picked(GameDifficulty),
 
+    /// The player's preset with the given name has been picked, but not yet applied
+    PickedPreset(String),
+
+    /// The name under which the custom game config is saved as a preset has been edited
+    PresetName(String),
+
+    /// Save the custom game config as a preset, replacing the preset with the same name if there is one
+    SavePreset,
+
+    /// Delete the preset with the given name
+    DeletePreset(String),
+
     /// A new custom game config has been edited, but not yet applied
     CustomConfig(GameConfig),
 
+    /// Countdown games have been turned on or off, but not yet applied
+    Countdown(bool),
+
+    /// Safe autosteps have been turned on or off, but not yet applied
+    SafeChord(bool),
+
+    /// Winning by revealing all the empty spots, without flagging the mines, has been turned on or off, but not yet
+    /// applied
+    WinOnAllRevealed(bool),
+
+    /// Limiting the flags to the number of mines has been turned on or off, but not yet applied
+    StrictFlags(bool),
+
+    /// The number of high scores kept for each difficulty level has been changed, but not yet applied
+    MaxHighScores(usize),
+
+    /// A new control scheme has been picked, but not yet applied
+    PickedControls(ControlScheme),
+
+    /// A new custom mouse button mapping has been edited, but not yet applied
+    CustomControls(ControlsConfig),
+
+    /// Player preferences have been edited, but not yet applied
+    Preferences(Preferences),
+
+    /// The text used for filtering the options shown in the settings view has been edited
+    Search(String),
+
     /// Discard the settings view without aplying any settings
     Discard,
 }
 
+#[derive(Debug, Clone)]
+pub enum EditorMessage {
+    /// Show the board editor view, or get back to the game if it's already shown
+    Show,
+
+    /// Place a mine on a spot of the edited board, or remove the one which is already there
+    ToggleMine { x: u16, y: u16 },
+
+    /// Remove all the mines of the edited board
+    Clear,
+
+    /// Save the edited board to file
+    Save,
+
+    /// Load the saved board with the given name in the editor
+    Load(String),
+
+    /// Start a game on the edited board
+    Play,
+
+    /// The path of the board file to play was edited
+    FilePath(String),
+
+    /// Start a game on the board written by hand in the file at the entered path
+    PlayFile,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum TutorialMessage {
+    /// Show the tutorial, on its own board which replaces the current game
+    Show,
+
+    /// Go on from the welcome text to the first move
+    Start,
+
+    /// Set the tutorial board up again after a step on its mine, and start over from the first move
+    Retry,
+
+    /// Leave the tutorial, whether it's finished or skipped, and get back to a new game
+    Close,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum PlaybackMessage {
+    /// Pause or resume the playback
+    TogglePause,
+
+    /// Pause the playback and play the next recorded move
+    Step,
+
+    /// Switch to the next playback speed
+    CycleSpeed,
+}
+
+#[derive(Debug, Clone)]
+pub enum ProfileMessage {
+    /// Make the profile with the given name the active one
+    Picked(String),
+
+    /// The name of a new profile is being entered
+    NameChanged(String),
+
+    /// Add a profile with the entered name, and make it the active one
+    Add,
+
+    /// Delete the active profile, with its high scores and stats, and make another one active
+    Delete,
+}
+
 #[derive(Debug, Clone)]
 pub enum RecordHighScore {
     NameChanged(String),
     RecordName,
     Discard,
+
+    /// Enter a new name for a high score which was already recorded
+    Rename(HighScoreLocation),
+
+    /// Remove a recorded high score, moving the lower ones up
+    Delete(HighScoreLocation),
 }
 
 #[derive(Debug, Clone)]
 pub enum PersistenceMessage {
-    LoadedConfigs(Option<GamePersistence>),
-    SavedConfigs,
+    /// The persisted configs were loaded, boxed since they're much larger than the other messages
+    LoadedConfigs(Option<Box<GamePersistence>>),
+    SavedConfigs(Result<(), String>),
+
+    /// The replay of the game which just ended was written to its file
+    SavedReplay(Result<(), String>),
+
+    /// The configs could not be loaded or saved, which is shown in a banner for a while
+    Error(String),
+
+    /// The banner showing why the configs could not be loaded or saved has been dismissed
+    DismissError,
+
+    /// The banner showing why the configs could not be loaded or saved is hidden if it was shown long enough
+    ExpireError(Instant),
 }
 
 #[derive(Debug, Clone)]
@@ -100,7 +369,7 @@ enum MainViewContent {
     Game,
 
     /// Show the settings view
-    Settings(GameDifficulty),
+    Settings(SettingsDraft),
 
     /// Show the Info view
     Info,
@@ -108,19 +377,55 @@ enum MainViewContent {
     /// Show the High Scores view
     HighScores,
 
+    /// Show the statistics of the games played on each difficulty level
+    Stats,
+
+    /// Show the saved replays, with their headers or the reasons why they can't be played back
+    Replays(Vec<ReplayEntry>),
+
+    /// Show the board editor, in which the player lays out a minefield to play
+    Editor(BoardEditor),
+
+    /// Show the tutorial, which walks the player through a game on a small board, with prompts below it
+    Tutorial(Tutorial),
+
+    /// Ask the player whether the running game should be thrown away for a new board, or for a restart of the same board
+    /// if `same_board` is set, while the game is paused
+    ConfirmReset { same_board: bool },
+
     /// Show Enter High Score view, with `HighScoreLocation` showing which entry in `high_scores` contains the
     /// preliminary name to be recorded as high score for a particular `DifficultyLevel`, and the `Id` of a `text_input`
-    /// which takes the focus when the `Enter High Score` view is shown
-    EnterHighScore(HighScoreLocation, text_input::Id),
+    /// which takes the focus when the `Enter High Score` view is shown. When a recorded high score is renamed, its
+    /// previous name is kept so that it can be restored if the player cancels.
+    EnterHighScore(HighScoreLocation, text_input::Id, Option<String>),
 }
 
 pub struct Minesweep {
     /// Model
     field: Minefield,
 
-    /// View: a cache of the canvas holding the minefield. A redraw can be forced on it by calling `field_cache.clear()`
+    /// Seed of the random generator which placed the mines of `field`
+    seed: u64,
+
+    /// Seed to be used for the next new game, instead of a random one
+    pending_seed: Option<u64>,
+
+    /// Game config requested when launching the game, overriding the persisted one
+    startup: StartupConfig,
+
+    /// View: a cache of the canvas holding the minefield. A redraw can be forced on it by calling `redraw_field()`
     field_cache: Cache,
 
+    /// View: a cache of the spots which changed since the minefield was cached, drawn on top of it. Moves which change a
+    /// few spots only draw these again, by calling `redraw_spots()`.
+    changes_cache: Cache,
+
+    /// The spots drawn in `changes_cache`
+    changed_spots: BTreeSet<(u16, u16)>,
+
+    /// Size of the spots drawn on the canvas, including padding, which is changed by zooming with Ctrl+scroll
+    spot_size: f32,
+
     /// What the main view of the game is currently showing
     main_view: MainViewContent,
 
@@ -136,46 +441,292 @@ pub struct Minesweep {
     /// The specifications of the current game (width, height, number of mines)
     game_config: GameConfig,
 
+    /// The current game was configured as a custom game, even if its `game_config` matches a standard difficulty
+    custom_game: bool,
+
     /// High Scores for each difficulty level
     high_scores: BTreeMap<DifficultyLevel, Vec<Score>>,
 
+    /// High scores of the games won without placing a single flag, for each difficulty level
+    flagless_high_scores: BTreeMap<DifficultyLevel, Vec<Score>>,
+
     /// Empty high score
     empty_scores: Vec<Score>,
 
+    /// Number of high scores kept for each difficulty level
+    max_high_scores: usize,
+
+    /// Statistics of the games played on each difficulty level
+    stats: BTreeMap<DifficultyLevel, Stats>,
+
+    /// Name of the player profile which owns `high_scores` and `stats`
+    active_profile: String,
+
+    /// High scores and stats of the other player profiles, by name, which are swapped with `high_scores` and `stats`
+    /// when another profile is made the active one
+    profiles: BTreeMap<String, ProfileData>,
+
+    /// Name of a new profile, being entered in the high scores view
+    new_profile_name: String,
+
+    /// The current game has already been counted in the statistics, e.g. before a step on a mine was undone
+    stats_recorded: bool,
+
     /// The `window::Id` of the game window
     window_id: Option<window::Id>,
+
+    /// The window is resized to fit the minefield, unless the game is embedded in an application which sizes its own
+    /// window
+    resizes_window: bool,
+
+    /// Games won, lost and recorded as high scores since the application embedding the game last took them (see
+    /// `Embedded`)
+    events: Vec<GameEvent>,
+
+    /// The size of the monitor showing the game window, if it could be detected
+    monitor_size: Option<Size>,
+
+    /// Player preferences
+    preferences: Preferences,
+
+    /// Named custom game configs saved by the player, which can be picked like the standard difficulties
+    presets: Vec<Preset>,
+
+    /// Controls for each difficulty level, used instead of `preferences.controls` if
+    /// `preferences.controls_per_difficulty` is set
+    difficulty_controls: BTreeMap<DifficultyLevel, ControlsConfig>,
+
+    /// Animation of the spots revealed by the last step, if it is still running. No steps can be taken in the meantime
+    reveal_animation: Option<RevealAnimation>,
+
+    /// Animation of the mines popping in after a mine was stepped on, if it is still running
+    explosion_animation: Option<ExplosionAnimation>,
+
+    /// An assist has been used in the current game, so it cannot be recorded as a high score
+    assisted: bool,
+
+    /// A flag was placed in the current game, so a win isn't recorded on the flagless high scores
+    flags_used: bool,
+
+    /// Number of hints given in the current game, as idle suggestions or risky step warnings. Unlike other assists,
+    /// hints only keep a game from the high scores when they are ranked by time, and otherwise lower its points.
+    hints: u32,
+
+    /// Number of moves taken on the spots in the current game, which are compared to its `board_3bv`
+    clicks: u32,
+
+    /// 3BV of the board (see `Minefield::board_3bv`), worked out once the game is over
+    board_3bv: Option<u32>,
+
+    /// The hidden mines are shown while the game goes on, for practice or debugging
+    peek: bool,
+
+    /// The hidden spots are shaded by how likely they are to be mines, for teaching. This is kept on for the next games.
+    show_probabilities: bool,
+
+    /// The left button and touch taps flag spots instead of stepping on them, for devices with a single button. This is
+    /// kept on for the next games.
+    flagging_mode: bool,
+
+    /// The certain moves are made one after the other, until the game is over or the next move would be a guess
+    auto_solving: bool,
+
+    /// The current game is played on a board laid out in the editor, so it cannot be recorded as a high score
+    edited_board: bool,
+
+    /// The minefield will be regenerated on the first step, so that it can be cleared without guessing
+    no_guess_pending: bool,
+
+    /// The mines around the first step will be moved away, so that it opens up an area
+    open_start_pending: bool,
+
+    /// Outcome of the last generation of a minefield which can be cleared without guessing
+    generation: Option<Generation>,
+
+    /// Warn the player that no minefield which can be cleared without guessing was found for the current game
+    generation_warning: bool,
+
+    /// The summary of the game which just ended is shown over the minefield, until it's dismissed to look at the board
+    game_summary: bool,
+
+    /// Everything the player did in the current game, so that it can be saved as a replay
+    recording: Vec<ReplayEvent>,
+
+    /// The replay being played back instead of the player's own game. No actions can be taken in the meantime
+    playback: Option<Playback>,
+
+    /// File in which the current game was saved as a replay, if it was
+    saved_replay: Option<PathBuf>,
+
+    /// Replay of the game which just ended, yet to be written to its file, along with the file of an earlier replay of
+    /// the same game to remove
+    unsaved_replay: Option<(Replay, PathBuf, Option<PathBuf>)>,
+
+    /// The player paused the game, which then stays paused until they resume it, even when switching views
+    pause_requested: bool,
+
+    /// Spot of a risky step which has not been taken yet, because it must be confirmed by stepping on it again
+    armed_step: Option<(u16, u16)>,
+
+    /// Game time of the player's last move, from which they are idle
+    idle_since: Duration,
+
+    /// A safe spot has already been suggested since the player's last move, so it's not suggested again
+    idle_hinted: bool,
+
+    /// Pulse of the safe spot suggested to an idle player, if it is still running
+    hint_pulse: Option<HintPulse>,
+
+    /// Result of the last meaningful action, shown in the status line for players who can't easily see the minefield
+    announcement: Option<String>,
+
+    /// Plays the sound effects, once the first one is played
+    sound: Option<SoundPlayer>,
+
+    /// The player's own game config and custom flag, which are restored by the next new board after a replay has
+    /// replaced them with its own
+    own_config: Option<(GameConfig, bool)>,
+
+    /// The persisted game config, which is saved in place of a game config requested at launch, since that one is only
+    /// played until the player applies settings of their own
+    saved_config: Option<GameConfig>,
+
+    /// Path of the file holding the game config and high scores
+    persistence_path: PathBuf,
+
+    /// There was no persisted file to load the configs from at launch, so the game is played for the first time
+    first_launch: bool,
+
+    /// The `YYYY-MM-DD` date and the difficulty of the daily challenge being played, if the current game is one
+    daily: Option<(String, DifficultyLevel)>,
+
+    /// Results of the daily challenges of the last days, by `YYYY-MM-DD` date
+    daily_results: BTreeMap<String, Vec<DailyResult>>,
+
+    /// Why the configs could not be loaded or saved, and when the banner telling it was shown
+    persistence_error: Option<(String, Instant)>,
+
+    /// The part of the minefield canvas which is shown, once it's scrolled because it doesn't fit in the window
+    field_viewport: Option<Rectangle>,
 }
 
 impl Minesweep {
-    pub fn initialize() -> (Self, Task<Message>) {
-        let minesweep = Self::default();
-        let message =
-            Message::Persistence(PersistenceMessage::LoadedConfigs(Self::load_persistence()));
+    pub fn initialize(startup: StartupConfig) -> (Self, Task<Message>) {
+        // The configs saved by older versions in the working directory are moved to the config directory, unless the
+        // player picked another file
+        let persistence_path = match &startup.persistence_path {
+            Some(path) => path.clone(),
+            None => {
+                let path = Self::default_persistence_path();
+                Self::migrate_persistence(&Self::legacy_persistence_path(), &path);
+
+                path
+            }
+        };
+        Self::remove_temporary_files(&persistence_path);
+
+        // The default configs are used when the persisted ones can't be loaded, and the player is told why
+        let persistence = Self::load_persistence_from(&persistence_path);
+        let first_launch = matches!(persistence, Ok(None));
+        let loaded = match persistence {
+            Ok(configs) => Task::done(Message::Persistence(PersistenceMessage::LoadedConfigs(
+                configs.map(Box::new),
+            ))),
+            Err(e) => Task::done(Message::Persistence(PersistenceMessage::LoadedConfigs(
+                None,
+            )))
+            .chain(Task::done(Message::Persistence(PersistenceMessage::Error(
+                e,
+            )))),
+        };
+        let minesweep = Self {
+            startup,
+            persistence_path,
+            first_launch,
+            ..Self::default()
+        };
 
-        (
-            minesweep,
-            Task::done(message).chain(window::get_latest().map(Message::WindowId)),
-        )
+        // The monitor size is detected from the position of the freshly opened (and centered) window, so it needs to be
+        // queried before the configs are loaded and the window is resized to fit the minefield
+        let window_info = window::get_latest().then(|id| {
+            let monitor_size = match id {
+                Some(id) => Self::detect_monitor_size(id),
+                None => Task::done(None),
+            };
+
+            Task::done(Message::WindowId(id)).chain(monitor_size.map(Message::MonitorSize))
+        });
+
+        (minesweep, window_info.chain(loaded))
     }
 
     pub fn update(&mut self, message: Message) -> Task<Message> {
+        // The canvas draws the edited board while the editor is shown, which must not linger once any other view
+        // replaces it
+        if let MainViewContent::Editor(_) = self.main_view {
+            self.redraw_field();
+        }
+
         match message {
             Message::Minesweep(message) => {
+                let was_ready = matches!(self.game_state, GameState::Ready);
+                let was_over = matches!(self.game_state, GameState::Stopped { .. });
+                let armed_step = self.armed_step;
+
+                // Any move, even one which has no effect, shows that the player isn't stuck
+                self.idle_since = self.game_time(Instant::now());
+                self.idle_hinted = false;
+                self.hint_pulse = None;
+
+                // A risky step is only taken once it's confirmed, so it's neither recorded nor played yet
+                if self.arm_risky_step(message) {
+                    self.redraw_field();
+
+                    return Task::none();
+                }
+
+                // Record the actions which can have an effect on the game, unless they come from a replay. A lost game can
+                // still be brought back by undoing the step on a mine.
+                let is_undoable_loss = message == MinesweepMessage::Undo
+                    && self.game_state == GameState::Stopped { is_won: false };
+                if self.playback.is_none()
+                    && (matches!(self.game_state, GameState::Ready | GameState::Running(_))
+                        || is_undoable_loss)
+                {
+                    self.recording.push(ReplayEvent {
+                        millis: self.game_time(Instant::now()).as_millis() as u64,
+                        message,
+                    });
+                }
+
+                if !matches!(message, MinesweepMessage::Undo | MinesweepMessage::Redo)
+                    && matches!(self.game_state, GameState::Ready | GameState::Running(_))
+                {
+                    self.clicks += 1;
+                }
+
+                // The spots which the move changed, along with the spot of a risky step which it disarmed
+                let mut changed_spots: Vec<(u16, u16)> = armed_step.into_iter().collect();
+
                 match message {
                     MinesweepMessage::Step { x, y } => {
-                        self.check_ready_to_running();
+                        self.check_ready_to_running(message);
 
                         if let GameState::Running(_) = self.game_state {
+                            let hidden_spots = self.hidden_spots();
                             let step_result = self.field.step(x, y);
+                            self.announce_reveal((x, y), &hidden_spots, step_result);
+                            self.explain_step_not_taken(step_result);
+                            let revealed_spots = self.revealed_among(hidden_spots);
+                            self.animate_reveal((x, y), &revealed_spots);
+                            changed_spots.extend(revealed_spots);
 
                             match step_result {
                                 StepResult::Boom => {
                                     self.game_over(false);
                                 }
-                                StepResult::Phew => {
-                                    if self.field.is_cleared() {
-                                        self.game_over(true);
-                                    }
+                                StepResult::Phew { .. } if self.field.is_cleared() => {
+                                    self.game_over(true);
                                 }
                                 _ => {}
                             }
@@ -183,1423 +734,8450 @@ impl Minesweep {
                     }
                     MinesweepMessage::AutoStep { x, y } => {
                         if let GameState::Running(_) = self.game_state {
-                            match self.field.auto_step(x, y) {
+                            let hidden_spots = self.hidden_spots();
+                            let step_result = self.field.auto_step(x, y);
+                            self.announce_reveal((x, y), &hidden_spots, step_result);
+                            self.explain_step_not_taken(step_result);
+                            let revealed_spots = self.revealed_among(hidden_spots);
+                            self.animate_reveal((x, y), &revealed_spots);
+                            changed_spots.extend(revealed_spots);
+
+                            match step_result {
                                 StepResult::Boom => {
                                     self.game_over(false);
                                 }
-                                StepResult::Phew => {
-                                    if self.field.is_cleared() {
-                                        self.game_over(true);
-                                    }
+                                StepResult::Phew { .. } if self.field.is_cleared() => {
+                                    self.game_over(true);
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    MinesweepMessage::RevealRing { x, y } => {
+                        if let GameState::Running(_) = self.game_state {
+                            let hidden_spots = self.hidden_spots();
+                            let step_result = self.field.reveal_ring(x, y);
+                            self.announce_reveal((x, y), &hidden_spots, step_result);
+                            let revealed_spots = self.revealed_among(hidden_spots);
+                            self.animate_reveal((x, y), &revealed_spots);
+                            changed_spots.extend(revealed_spots);
+
+                            match step_result {
+                                StepResult::Boom => {
+                                    self.game_over(false);
+                                }
+                                StepResult::Phew { .. } if self.field.is_cleared() => {
+                                    self.game_over(true);
                                 }
                                 _ => {}
                             }
                         }
                     }
                     MinesweepMessage::Flag { x, y } => {
-                        self.check_ready_to_running();
+                        self.check_ready_to_running(message);
+
+                        // Flags can be placed before the game starts, if it only starts on the first reveal
+                        if let GameState::Ready | GameState::Running(_) = self.game_state {
+                            let toggle_result = self.field.toggle_flag(x, y);
+                            self.remaining_flags -= toggle_result.flags_change();
+                            changed_spots.push((x, y));
+
+                            if toggle_result != FlagToggleResult::None {
+                                self.flags_used = true;
+                                self.play_sound(Sound::Flag);
+                            } else if self.game_config.strict_flags && self.remaining_flags <= 0 {
+                                self.announce("No flags left, remove one first".to_string());
+                            }
 
-                        if let GameState::Running(_) = self.game_state {
-                            match self.field.toggle_flag(x, y) {
+                            match toggle_result {
                                 FlagToggleResult::Removed => {
-                                    self.remaining_flags += 1;
+                                    self.announce(format!(
+                                        "Flag removed, {} flags left",
+                                        self.remaining_flags
+                                    ));
+                                }
+                                FlagToggleResult::Questioned => {
+                                    self.announce(format!(
+                                        "Question mark, {} flags left",
+                                        self.remaining_flags
+                                    ));
+                                }
+                                FlagToggleResult::Unquestioned => {
+                                    self.announce("Question mark removed".to_string());
                                 }
                                 FlagToggleResult::Added => {
-                                    self.remaining_flags -= 1;
+                                    self.announce(format!(
+                                        "Flagged, {} flags left",
+                                        self.remaining_flags
+                                    ));
 
                                     if self.field.is_cleared() {
                                         self.game_over(true);
+                                    } else if self.auto_step_on_flag() {
+                                        let hidden_spots = self.hidden_spots();
+                                        let step_result = self.field.auto_step_neighbors(x, y);
+                                        self.announce_reveal((x, y), &hidden_spots, step_result);
+                                        let revealed_spots = self.revealed_among(hidden_spots);
+                                        self.animate_reveal((x, y), &revealed_spots);
+                                        changed_spots.extend(revealed_spots);
+
+                                        if !matches!(step_result, StepResult::Invalid(_)) {
+                                            self.assisted = true;
+                                        }
+
+                                        match step_result {
+                                            StepResult::Boom => {
+                                                self.game_over(false);
+                                            }
+                                            StepResult::Phew { .. } if self.field.is_cleared() => {
+                                                self.game_over(true);
+                                            }
+                                            _ => {}
+                                        }
                                     }
                                 }
                                 _ => {}
                             }
                         }
                     }
+                    MinesweepMessage::AutoFlag { x, y } => {
+                        if let GameState::Running(_) = self.game_state {
+                            let added_flags = self.field.auto_flag(x, y);
+                            // The flags are all placed next to the spot, and so are redrawn along with it
+                            changed_spots.push((x, y));
+
+                            if added_flags > 0 {
+                                self.remaining_flags -= added_flags as i64;
+                                self.assisted = true;
+                                self.flags_used = true;
+                                self.play_sound(Sound::Flag);
+                                self.announce(format!(
+                                    "{} flags placed, {} flags left",
+                                    added_flags, self.remaining_flags
+                                ));
+
+                                if self.field.is_cleared() {
+                                    self.game_over(true);
+                                }
+                            }
+                        }
+                    }
+                    MinesweepMessage::Undo => {
+                        // A countdown game which ran out of time can't go on, whatever move is undone
+                        let can_undo = matches!(
+                            self.game_state,
+                            GameState::Running(_) | GameState::Stopped { is_won: false }
+                        ) && self.time_left() != Some(Duration::ZERO);
+
+                        if can_undo && self.field.undo() {
+                            // The clock goes on from the time at which the game was lost
+                            if let GameState::Stopped { .. } = self.game_state {
+                                self.game_state = GameState::Running(Instant::now());
+                            }
+
+                            self.assisted = true;
+                            self.reveal_animation = None;
+                            self.explosion_animation = None;
+                            self.count_flags();
+                            self.announce(format!(
+                                "Move undone, {} flags left",
+                                self.remaining_flags
+                            ));
+                        }
+                    }
+                    MinesweepMessage::Redo => {
+                        if let (GameState::Running(_), true) = (self.game_state, self.field.redo())
+                        {
+                            self.count_flags();
+                            self.announce(format!(
+                                "Move redone, {} flags left",
+                                self.remaining_flags
+                            ));
+
+                            if self.field.is_exploded() {
+                                self.game_over(false);
+                            } else if self.field.is_cleared() {
+                                self.game_over(true);
+                            }
+                        }
+                    }
+                }
+
+                if let MainViewContent::Tutorial(tutorial) = &mut self.main_view {
+                    let is_won = match self.game_state {
+                        GameState::Stopped { is_won } => Some(is_won),
+                        _ => None,
+                    };
+                    tutorial.observe(message, is_won);
                 }
 
-                self.field_cache.clear();
+                // The whole minefield may look different once the game is over, after its first step (which may move
+                // its mines), after undoing or redoing moves, and when it's shaded by the probabilities of mines
+                if was_ready
+                    || matches!(self.game_state, GameState::Stopped { .. })
+                    || matches!(message, MinesweepMessage::Undo | MinesweepMessage::Redo)
+                    || self.show_probabilities
+                {
+                    self.redraw_field();
+                } else {
+                    self.redraw_spots(changed_spots);
+                }
+
+                // The statistics of a game which just ended are saved right away
+                let save = if !was_over
+                    && self.stats_recorded
+                    && matches!(self.game_state, GameState::Stopped { .. })
+                {
+                    let gp = self.game_persistence();
+
+                    Task::perform(
+                        Self::save_persistence(self.persistence_path.clone(), gp),
+                        |result| Message::Persistence(PersistenceMessage::SavedConfigs(result)),
+                    )
+                } else {
+                    Task::none()
+                };
+
+                let save = Task::batch([save, self.save_replay_task()]);
 
                 // If the `Enter High Score` is about to be shown, make sure to focus the text input for the `name`,
                 // so that the user does not have to do an extra click to enter their name
-                if let MainViewContent::EnterHighScore(_, input_id) = &self.main_view {
-                    text_input::focus(input_id.clone())
+                if let MainViewContent::EnterHighScore(_, input_id, _) = &self.main_view {
+                    Task::batch([save, text_input::focus(input_id.clone())])
                 } else {
-                    Task::none()
+                    save
+                }
+            }
+            Message::ResetKey => {
+                // The new board key is only meant for the game, which the other views pause, so that it doesn't throw
+                // away the game in progress, or a settings draft. The new board button is meant for every view.
+                if !matches!(self.main_view, MainViewContent::Game) {
+                    return Task::none();
                 }
+
+                self.update(Message::Reset)
             }
             Message::Reset => {
-                self.field = Minefield::new(self.game_config.width, self.game_config.height)
-                    .with_mines(self.game_config.mines);
+                // A game in progress, running or paused, is only thrown away once the player confirms it, so that it isn't
+                // lost to a stray click
+                if let GameState::Running(_) | GameState::Paused = self.game_state {
+                    self.pause_game();
+                    self.main_view = MainViewContent::ConfirmReset { same_board: false };
 
-                self.game_state = GameState::Ready;
-                self.main_view = MainViewContent::Game;
-                self.elapsed_seconds = Duration::default();
-                self.remaining_flags = self.game_config.mines as i64;
+                    return Task::none();
+                }
 
-                self.field_cache.clear();
+                self.reset_game();
 
                 Task::none()
             }
-            Message::Info => {
-                match self.main_view {
-                    MainViewContent::Info => {
+            Message::RestartSameBoard => {
+                if !self.can_restart_same_board() {
+                    return Task::none();
+                }
+
+                if let GameState::Running(_) | GameState::Paused = self.game_state {
+                    self.pause_game();
+                    self.main_view = MainViewContent::ConfirmReset { same_board: true };
+
+                    return Task::none();
+                }
+
+                self.restart_same_board();
+
+                Task::none()
+            }
+            Message::ConfirmReset(confirmed) => {
+                if let MainViewContent::ConfirmReset { same_board } = self.main_view {
+                    if !confirmed {
                         // Get back to the game
                         self.resume_game();
                         self.main_view = MainViewContent::Game;
-                    }
-                    _ => {
-                        self.pause_game();
-                        self.main_view = MainViewContent::Info;
+                    } else if same_board {
+                        self.restart_same_board();
+                    } else {
+                        self.reset_game();
                     }
                 }
 
                 Task::none()
             }
-            Message::Settings(settings_message) => {
-                match settings_message {
-                    SettingsMessage::Show => {
-                        match self.main_view {
-                            MainViewContent::Settings(_) => {
-                                // Get back to the game
-                                self.resume_game();
-                                self.main_view = MainViewContent::Game;
+            Message::CopySeed => iced::clipboard::write(self.seed.to_string()),
+            Message::Zoom { zoom_in } => {
+                let step = if zoom_in {
+                    Self::SPOT_SIZE_STEP
+                } else {
+                    -Self::SPOT_SIZE_STEP
+                };
+                let spot_size =
+                    (self.spot_size + step).clamp(Self::MIN_SPOT_SIZE, Self::MAX_SPOT_SIZE);
 
-                                Task::none()
-                            }
-                            _ => {
-                                self.pause_game();
-                                self.main_view = MainViewContent::Settings(
-                                    GameDifficulty::from_config(&self.game_config),
-                                );
+                if spot_size == self.spot_size {
+                    return Task::none();
+                }
 
-                                Task::none()
-                            }
+                self.spot_size = spot_size;
+                self.redraw_field();
+
+                self.resize_window()
+            }
+            Message::FieldScrolled(viewport) => {
+                // Only the shown spots are drawn, so the newly shown ones need drawing
+                if self.field_viewport != Some(viewport) {
+                    self.redraw_field();
+                }
+                self.field_viewport = Some(viewport);
+
+                Task::none()
+            }
+            Message::PanField(center) => {
+                let Some(viewport) = self.field_viewport else {
+                    return Task::none();
+                };
+
+                let (field_width, field_height) = self.desired_field_size();
+                let grid = self.grid_size();
+                let x = (Self::FIELD_PAD + center.x * grid.width - viewport.width / 2.0)
+                    .clamp(0.0, (field_width - viewport.width).max(0.0));
+                let y = (Self::FIELD_PAD + center.y * grid.height - viewport.height / 2.0)
+                    .clamp(0.0, (field_height - viewport.height).max(0.0));
+
+                scrollable::scroll_to(
+                    Self::field_scrollable_id(),
+                    scrollable::AbsoluteOffset { x, y },
+                )
+            }
+            Message::TogglePause => {
+                // A replay is paused instead of the game, so that the minefield stays shown
+                if self.playback.is_some() {
+                    return self.update(Message::Playback(PlaybackMessage::TogglePause));
+                }
+
+                if let MainViewContent::Game = self.main_view {
+                    match self.game_state {
+                        GameState::Running(_) => {
+                            self.pause_game();
+                            self.pause_requested = true;
+                        }
+                        GameState::Paused => {
+                            self.pause_requested = false;
+                            self.resume_game();
                         }
+                        _ => {}
                     }
-                    SettingsMessage::Set(game_difficulty) => {
-                        self.game_config = game_difficulty.into();
 
-                        self.field =
-                            Minefield::new(self.game_config.width, self.game_config.height)
-                                .with_mines(self.game_config.mines);
-                        self.game_state = GameState::Ready;
-                        self.main_view = MainViewContent::Game;
-                        self.elapsed_seconds = Duration::default();
-                        self.remaining_flags = self.game_config.mines as i64;
+                    self.redraw_field();
+                }
 
-                        let (width, height) = self.desired_window_size();
+                Task::none()
+            }
+            Message::TogglePeek => {
+                if let (MainViewContent::Game, GameState::Ready | GameState::Running(_)) =
+                    (&self.main_view, self.game_state)
+                {
+                    self.peek = !self.peek;
+
+                    // Knowing where the mines are is the ultimate assist
+                    if self.peek {
+                        self.assisted = true;
+                        self.announce("Peeking at the mines".to_string());
+                    }
 
-                        self.field_cache.clear();
+                    self.redraw_field();
+                }
 
-                        let gp = GamePersistence {
-                            game_config: self.game_config,
-                            high_scores: self.high_scores.clone(),
-                        };
+                Task::none()
+            }
+            Message::ToggleProbabilities => {
+                if let MainViewContent::Game = self.main_view {
+                    self.show_probabilities = !self.show_probabilities;
+
+                    // The probabilities tell which spots are safe to step on, so games played with them aren't scored
+                    if self.show_probabilities {
+                        if let GameState::Ready | GameState::Running(_) = self.game_state {
+                            self.assisted = true;
+                        }
+                        self.announce("Showing the mine probabilities".to_string());
+                    }
 
-                        Task::batch(vec![
-                            iced_runtime::window::resize(
-                                // FIXME: this is unsafe. Check if a `self.window_id` is `Some` first!
-                                self.window_id.unwrap(),
-                                Size { width, height },
-                            ),
-                            Task::perform(Self::save_persistence(gp), |_| {
-                                Message::Persistence(PersistenceMessage::SavedConfigs)
-                            }),
-                        ])
+                    self.redraw_field();
+                }
+
+                Task::none()
+            }
+            Message::ToggleFlaggingMode => {
+                self.flagging_mode = !self.flagging_mode;
+                self.announce(match self.flagging_mode {
+                    true => "Clicks and taps flag spots".to_string(),
+                    false => "Clicks and taps step on spots".to_string(),
+                });
+
+                Task::none()
+            }
+            Message::AutoSolve => {
+                if let (MainViewContent::Game, GameState::Ready | GameState::Running(_)) =
+                    (&self.main_view, self.game_state)
+                {
+                    if self.auto_solving {
+                        self.auto_solving = false;
+                        self.announce("Auto-solving stopped".to_string());
+                    } else if self.playback.is_none() {
+                        self.auto_solving = true;
+
+                        // The first move is made right away, so that a board which needs a guess is reported at once
+                        return self.auto_solve_move();
                     }
-                    SettingsMessage::Picked(gdif) => {
-                        self.main_view = MainViewContent::Settings(gdif);
+                }
+
+                Task::none()
+            }
+            Message::Editor(editor_message) => {
+                let command = match editor_message {
+                    EditorMessage::Show => {
+                        match self.main_view {
+                            MainViewContent::Editor(_) => {
+                                // Get back to the game
+                                self.resume_game();
+                                self.main_view = MainViewContent::Game;
+                            }
+                            _ => {
+                                self.pause_game();
+                                self.reveal_animation = None;
+                                self.explosion_animation = None;
+                                self.main_view = MainViewContent::Editor(BoardEditor::new(
+                                    self.field.width(),
+                                    self.field.height(),
+                                    self.preferences.connectivity(),
+                                    &self.boards_dir(),
+                                ));
+                            }
+                        }
+
+                        self.resize_window()
+                    }
+                    EditorMessage::ToggleMine { x, y } => {
+                        if let MainViewContent::Editor(editor) = &mut self.main_view {
+                            editor.toggle_mine(x, y);
+                        }
 
                         Task::none()
                     }
-                    SettingsMessage::Discard => match self.main_view {
-                        MainViewContent::Settings(_) => {
-                            self.main_view = MainViewContent::Game;
-                            self.resume_game();
+                    EditorMessage::Clear => {
+                        if let MainViewContent::Editor(editor) = &mut self.main_view {
+                            editor.clear();
+                        }
 
-                            Task::none()
+                        Task::none()
+                    }
+                    EditorMessage::Save => {
+                        let boards_dir = self.boards_dir();
+
+                        if let MainViewContent::Editor(editor) = &mut self.main_view {
+                            let config = editor.game_config();
+                            let name = format!(
+                                "{}-{}x{}-{}",
+                                now_timestamp(),
+                                config.width,
+                                config.height,
+                                config.mines
+                            );
+
+                            match editor.board().save(&boards_dir, &name) {
+                                Ok(_) => {
+                                    editor.saved = SavedBoard::list(&boards_dir);
+                                    editor.selected = Some(name);
+                                    editor.error = None;
+                                }
+                                Err(e) => {
+                                    editor.error = Some(format!("Could not save board: {}", e));
+                                }
+                            }
                         }
-                        _ => Task::none(),
-                    },
-                    SettingsMessage::CustomConfig(new_custom_game_config) => {
-                        if let MainViewContent::Settings(GameDifficulty::Custom(
-                            _cur_custom_game_config,
-                        )) = self.main_view
-                        {
-                            self.main_view = MainViewContent::Settings(GameDifficulty::Custom(
-                                new_custom_game_config,
-                            ))
+
+                        Task::none()
+                    }
+                    EditorMessage::Load(name) => {
+                        let connectivity = self.preferences.connectivity();
+                        let boards_dir = self.boards_dir();
+
+                        if let MainViewContent::Editor(editor) = &mut self.main_view {
+                            match SavedBoard::load(&boards_dir, &name) {
+                                Ok(board) => {
+                                    // The board must at least fit in the supported minefield sizes
+                                    let config = GameConfig {
+                                        width: board.width,
+                                        height: board.height,
+                                        mines: 1,
+                                        ..GameConfig::default()
+                                    };
+
+                                    match config.validate() {
+                                        Ok(()) => {
+                                            *editor = BoardEditor::from_board(
+                                                &board,
+                                                connectivity,
+                                                &boards_dir,
+                                            );
+                                            editor.selected = Some(name);
+                                        }
+                                        Err(e) => editor.error = Some(e.to_string()),
+                                    }
+                                }
+                                Err(e) => editor.error = Some(e),
+                            }
                         }
+
+                        self.resize_window()
+                    }
+                    EditorMessage::FilePath(path) => {
+                        if let MainViewContent::Editor(editor) = &mut self.main_view {
+                            editor.file_path = path;
+                            editor.error = None;
+                        }
+
                         Task::none()
                     }
-                }
+                    EditorMessage::Play | EditorMessage::PlayFile => {
+                        let connectivity = self.preferences.connectivity();
+                        let board = match &mut self.main_view {
+                            MainViewContent::Editor(editor)
+                                if matches!(editor_message, EditorMessage::PlayFile) =>
+                            {
+                                // The board file is played as it is, without being loaded in the editor, so that
+                                // its mines aren't shown
+                                match SavedBoard::read(std::path::Path::new(
+                                    editor.file_path.trim(),
+                                )) {
+                                    Ok(board) => {
+                                        Some((board.game_config(), board.field(connectivity)))
+                                    }
+                                    Err(e) => {
+                                        editor.error = Some(e);
+                                        None
+                                    }
+                                }
+                            }
+                            MainViewContent::Editor(editor) => match editor.validate() {
+                                Ok(()) => Some((editor.game_config(), editor.field().clone())),
+                                Err(e) => {
+                                    editor.error = Some(e.to_string());
+                                    None
+                                }
+                            },
+                            _ => None,
+                        };
+
+                        match board {
+                            Some((game_config, field)) => {
+                                // The edited board only replaces the player's config for this game
+                                let own_config = self
+                                    .own_config
+                                    .take()
+                                    .unwrap_or((self.game_config, self.custom_game));
+                                self.own_config = Some(own_config);
+                                self.game_config = game_config;
+                                self.custom_game = true;
+                                self.start_new_game();
+
+                                self.field =
+                                    field.with_question_marks(self.preferences.question_marks);
+                                self.no_guess_pending = false;
+                                self.open_start_pending = false;
+                                self.edited_board = true;
+                                self.main_view = MainViewContent::Game;
+
+                                self.resize_window()
+                            }
+                            None => Task::none(),
+                        }
+                    }
+                };
+
+                self.redraw_field();
+
+                command
             }
+            Message::Tutorial(tutorial_message) => match tutorial_message {
+                TutorialMessage::Show => self.start_tutorial(Tutorial::new()),
+                TutorialMessage::Start => {
+                    if let MainViewContent::Tutorial(tutorial) = &mut self.main_view {
+                        tutorial.start();
+                    }
 
-            Message::HighScores => {
+                    Task::none()
+                }
+                TutorialMessage::Retry => self.start_tutorial(Tutorial::retry()),
+                TutorialMessage::Close => {
+                    self.reset_game();
+
+                    // The tutorial is only offered until the configs are saved for the first time
+                    let gp = self.game_persistence();
+                    Task::batch([
+                        self.resize_window(),
+                        Task::perform(
+                            Self::save_persistence(self.persistence_path.clone(), gp),
+                            |result| Message::Persistence(PersistenceMessage::SavedConfigs(result)),
+                        ),
+                    ])
+                }
+            },
+            Message::CloseView => {
+                let close = match &self.main_view {
+                    MainViewContent::Game if self.shows_game_summary() => {
+                        Some(Message::DismissGameSummary)
+                    }
+                    MainViewContent::Game => None,
+                    MainViewContent::Settings(_) => {
+                        Some(Message::Settings(SettingsMessage::Discard))
+                    }
+                    MainViewContent::Info => Some(Message::Info),
+                    MainViewContent::HighScores => Some(Message::HighScores),
+                    MainViewContent::Stats => Some(Message::Stats),
+                    MainViewContent::Replays(_) => Some(Message::Replays),
+                    MainViewContent::Editor(_) => Some(Message::Editor(EditorMessage::Show)),
+                    MainViewContent::Tutorial(_) => Some(Message::Tutorial(TutorialMessage::Close)),
+                    MainViewContent::ConfirmReset { .. } => Some(Message::ConfirmReset(false)),
+                    // A new high score is only thrown away with its cancel button, not by a stray key
+                    MainViewContent::EnterHighScore(..) => None,
+                };
+
+                match close {
+                    Some(message) => self.update(message),
+                    None => Task::none(),
+                }
+            }
+            Message::Info => {
                 match self.main_view {
-                    MainViewContent::HighScores => {
+                    MainViewContent::Info => {
                         // Get back to the game
                         self.resume_game();
                         self.main_view = MainViewContent::Game;
                     }
                     _ => {
                         self.pause_game();
-                        self.main_view = MainViewContent::HighScores;
+                        self.main_view = MainViewContent::Info;
                     }
                 }
 
                 Task::none()
             }
+            Message::Settings(settings_message) => {
+                match settings_message {
+                    SettingsMessage::Show => {
+                        match self.main_view {
+                            MainViewContent::Settings(_) => {
+                                // Get back to the game
+                                self.resume_game();
+                                self.main_view = MainViewContent::Game;
 
-            Message::Tick(new_tick) => {
-                if let GameState::Running(cur_tick) = &mut self.game_state {
-                    self.elapsed_seconds += new_tick - *cur_tick;
-                    *cur_tick = new_tick;
-                }
+                                Task::none()
+                            }
+                            _ => {
+                                self.pause_game();
+                                self.main_view = MainViewContent::Settings(
+                                    self.settings_draft(self.game_difficulty()),
+                                );
 
-                Task::none()
-            }
-            Message::HighScore(rec) => {
-                match rec {
-                    RecordHighScore::NameChanged(name) => {
-                        if let MainViewContent::EnterHighScore(hs, _) = self.main_view.clone() {
-                            // Enforce maximum name length
-                            if name.chars().count() < Self::MAX_HIGHSCORE_NAME_LEN {
-                                if let Some(scores) = self.high_scores.get_mut(&hs.difficulty_level)
-                                {
-                                    if let Some(score) = scores.get_mut(hs.index) {
-                                        score.name = name;
-                                    }
-                                }
+                                Task::none()
                             }
                         }
+                    }
+                    SettingsMessage::Set(settings)
+                        if GameConfig::from(settings.game_difficulty)
+                            .validate()
+                            .is_err() =>
+                    {
+                        // Invalid custom configs are refused, and the settings stay shown with the error
+                        self.main_view = MainViewContent::Settings(*settings);
 
                         Task::none()
                     }
-                    RecordHighScore::RecordName => {
-                        if let MainViewContent::EnterHighScore(_hs, _) = self.main_view.clone() {
-                            self.main_view = MainViewContent::HighScores;
+                    SettingsMessage::Set(mut settings) => {
+                        let game_config = settings.game_config();
+                        let custom_game =
+                            matches!(settings.game_difficulty, GameDifficulty::Custom(_));
+                        settings.store_controls();
+                        let rank_by_points = self.preferences.rank_by_points;
+                        let question_marks = self.preferences.question_marks;
+                        self.preferences = settings.preferences;
+                        if self.preferences.rank_by_points != rank_by_points
+                            || settings.max_high_scores != self.max_high_scores
+                        {
+                            self.max_high_scores = settings.max_high_scores;
+                            self.rank_high_scores();
+                        }
+                        if self.preferences.question_marks != question_marks {
+                            self.apply_question_marks();
+                        }
+                        if self.preferences.animation_speed.factor().is_none() {
+                            self.reveal_animation = None;
+                            self.explosion_animation = None;
+                        }
+                        self.difficulty_controls = settings.difficulty_controls;
+                        self.main_view = MainViewContent::Game;
 
-                            let gp = GamePersistence {
-                                game_config: self.game_config,
-                                high_scores: self.high_scores.clone(),
-                            };
+                        // The applied settings are now the player's own, even if they were shown for a replay
+                        self.own_config = None;
+                        self.saved_config = None;
 
-                            Task::perform(Self::save_persistence(gp), |_| {
-                                Message::Persistence(PersistenceMessage::SavedConfigs)
-                            })
+                        // Only start a new game if the game config or rules have actually changed, or a specific board
+                        // was requested
+                        if game_config != self.game_config
+                            || custom_game != self.custom_game
+                            || self.preferences.connectivity() != self.field.connectivity()
+                            || self.pending_seed.is_some()
+                        {
+                            self.game_config = game_config;
+                            self.custom_game = custom_game;
+                            self.start_new_game();
                         } else {
-                            Task::none()
-                        }
-                    }
-                    RecordHighScore::Discard => {
-                        if let MainViewContent::EnterHighScore(hs, _) = &self.main_view {
-                            if let Some(scores) = self.high_scores.get_mut(&hs.difficulty_level) {
-                                if hs.index < scores.len() {
-                                    scores.remove(hs.index);
-                                }
+                            // The no-guess and open start preferences can still apply to a game in which no step was
+                            // taken
+                            if let GameState::Ready = self.game_state {
+                                self.no_guess_pending = self.preferences.no_guess;
+                                self.open_start_pending = self.preferences.open_start;
                             }
 
-                            self.main_view = MainViewContent::Game;
+                            self.resume_game();
                         }
 
-                        Task::none()
+                        self.redraw_field();
+
+                        let gp = self.game_persistence();
+
+                        Task::batch(vec![
+                            self.resize_window(),
+                            Task::perform(
+                                Self::save_persistence(self.persistence_path.clone(), gp),
+                                |result| {
+                                    Message::Persistence(PersistenceMessage::SavedConfigs(result))
+                                },
+                            ),
+                        ])
                     }
-                }
-            }
-            Message::Persistence(pmsg) => {
-                let command;
+                    SettingsMessage::Picked(gdif) => {
+                        if let MainViewContent::Settings(settings) = &mut self.main_view {
+                            settings.store_controls();
+                            settings.game_difficulty = gdif;
+                            settings.load_controls();
+                            settings.preset = None;
+                        }
 
-                match pmsg {
-                    PersistenceMessage::LoadedConfigs(game_p) => {
-                        if let Some(game_p) = game_p {
-                            // load High Scores
-                            self.high_scores = game_p.high_scores;
+                        Task::none()
+                    }
+                    SettingsMessage::PickedPreset(name) => {
+                        let preset = self.presets.iter().find(|preset| preset.name == name);
 
-                            // Load game config, if it's not custom
-                            let game_difficulty = GameDifficulty::from_config(&game_p.game_config);
+                        if let (MainViewContent::Settings(settings), Some(preset)) =
+                            (&mut self.main_view, preset)
+                        {
+                            settings.store_controls();
+                            settings.game_difficulty = GameDifficulty::Custom(preset.game_config);
+                            settings.load_controls();
+                            settings.preset = Some(preset.name.clone());
+                            settings.preset_name = preset.name.clone();
+                            settings.preset_error = None;
+                        }
 
-                            match game_difficulty {
-                                GameDifficulty::Easy
-                                | GameDifficulty::Medium
-                                | GameDifficulty::Hard => {
-                                    // Apply the game config loaded from file
-                                    command = Task::perform(
-                                        async move {
-                                            Message::Settings(SettingsMessage::Set(game_difficulty))
-                                        },
-                                        |m| m,
-                                    )
-                                }
-                                GameDifficulty::Custom(_) => {
-                                    // FIXME: wrong custom configs can crash the game or make it unusable
-                                    command = Task::none();
-                                }
+                        Task::none()
+                    }
+                    SettingsMessage::PresetName(name) => {
+                        if let MainViewContent::Settings(settings) = &mut self.main_view {
+                            // Enforce maximum name length
+                            if name.chars().count() < Self::MAX_PRESET_NAME_LEN {
+                                settings.preset_name = name;
+                                settings.preset_error = None;
                             }
-                        } else {
-                            command = Task::none();
                         }
-                    }
-                    PersistenceMessage::SavedConfigs => {
-                        command = Task::none();
-                    }
-                }
 
-                command
-            }
-            Message::WindowId(id) => {
-                self.window_id = id;
+                        Task::none()
+                    }
+                    SettingsMessage::SavePreset => {
+                        let MainViewContent::Settings(settings) = &mut self.main_view else {
+                            return Task::none();
+                        };
+                        let GameDifficulty::Custom(game_config) = settings.game_difficulty else {
+                            return Task::none();
+                        };
 
-                Task::none()
-            }
-        }
-    }
+                        let name = settings.preset_name.trim().to_string();
+                        let validation = if name.is_empty() {
+                            Err("The preset needs a name".to_string())
+                        } else {
+                            game_config.validate().map_err(|e| e.to_string())
+                        };
 
-    pub fn view(&self) -> iced::Element<'_, Message> {
-        let main_view = match &self.main_view {
-            MainViewContent::Game => self.view_field(),
-            MainViewContent::Settings(game_difficulty) => self.view_settings(game_difficulty),
-            MainViewContent::Info => self.view_info(),
-            MainViewContent::HighScores => {
-                // self.view_high_scores().explain(Color::WHITE)
-                self.view_high_scores()
-            }
-            MainViewContent::EnterHighScore(hs, name_input_id) => {
-                self.view_record_high_score(hs.clone(), name_input_id)
-            }
-        };
+                        match validation {
+                            Ok(()) => {
+                                let preset = Preset {
+                                    name: name.clone(),
+                                    game_config,
+                                };
 
-        let content = widget::column![self.view_controls(), main_view]
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .align_x(Alignment::Start);
+                                match self.presets.iter_mut().find(|p| p.name == name) {
+                                    Some(existing) => *existing = preset,
+                                    None => self.presets.push(preset),
+                                }
 
-        widget::container(content)
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .into()
-    }
+                                settings.preset = Some(name);
+                                settings.preset_error = None;
 
-    pub fn subscription(&self) -> Subscription<Message> {
-        if let GameState::Running(_) = self.game_state {
-            time::every(Duration::from_millis(1000)).map(Message::Tick)
-        } else {
-            Subscription::none()
-        }
-    }
+                                let gp = self.game_persistence();
 
-    pub const APP_NAME: &'static str = "iced minesweep-rs";
+                                Task::perform(
+                                    Self::save_persistence(self.persistence_path.clone(), gp),
+                                    |result| {
+                                        Message::Persistence(PersistenceMessage::SavedConfigs(
+                                            result,
+                                        ))
+                                    },
+                                )
+                            }
+                            Err(e) => {
+                                settings.preset_error = Some(e);
 
-    // Fonts for mines and flags
-    const MINES_FLAGS_ICONS: Font = Font::with_name("emoji");
+                                Task::none()
+                            }
+                        }
+                    }
+                    SettingsMessage::DeletePreset(name) => {
+                        self.presets.retain(|preset| preset.name != name);
 
-    // Fonts for mines and flags
-    const COMMANDS_ICONS: Font = Font::with_name("Noto Emoji");
+                        if let MainViewContent::Settings(settings) = &mut self.main_view {
+                            if settings.preset.as_ref() == Some(&name) {
+                                settings.preset = None;
+                            }
+                        }
 
-    // Fonts for text
-    const TEXT_FONT: Font = Font::with_name("Ubuntu Light");
+                        let gp = self.game_persistence();
+
+                        Task::perform(
+                            Self::save_persistence(self.persistence_path.clone(), gp),
+                            |result| Message::Persistence(PersistenceMessage::SavedConfigs(result)),
+                        )
+                    }
+                    SettingsMessage::Discard => match self.main_view {
+                        MainViewContent::Settings(_) => {
+                            self.main_view = MainViewContent::Game;
+                            self.resume_game();
+
+                            Task::none()
+                        }
+                        _ => Task::none(),
+                    },
+                    SettingsMessage::Countdown(countdown) => {
+                        if let MainViewContent::Settings(settings) = &mut self.main_view {
+                            settings.countdown = countdown;
+                        }
+
+                        Task::none()
+                    }
+                    SettingsMessage::SafeChord(safe_chord) => {
+                        if let MainViewContent::Settings(settings) = &mut self.main_view {
+                            settings.safe_chord = safe_chord;
+                        }
+
+                        Task::none()
+                    }
+                    SettingsMessage::WinOnAllRevealed(win_on_all_revealed) => {
+                        if let MainViewContent::Settings(settings) = &mut self.main_view {
+                            settings.win_on_all_revealed = win_on_all_revealed;
+                        }
+
+                        Task::none()
+                    }
+                    SettingsMessage::StrictFlags(strict_flags) => {
+                        if let MainViewContent::Settings(settings) = &mut self.main_view {
+                            settings.strict_flags = strict_flags;
+                        }
+
+                        Task::none()
+                    }
+                    SettingsMessage::MaxHighScores(max_high_scores) => {
+                        if let MainViewContent::Settings(settings) = &mut self.main_view {
+                            settings.max_high_scores = max_high_scores;
+                        }
+
+                        Task::none()
+                    }
+                    SettingsMessage::CustomConfig(new_custom_game_config) => {
+                        if let MainViewContent::Settings(SettingsDraft {
+                            game_difficulty: game_difficulty @ GameDifficulty::Custom(_),
+                            preset,
+                            ..
+                        }) = &mut self.main_view
+                        {
+                            // An edited config is no longer the one of the picked preset
+                            if *game_difficulty != GameDifficulty::Custom(new_custom_game_config) {
+                                *preset = None;
+                            }
+
+                            *game_difficulty = GameDifficulty::Custom(new_custom_game_config);
+                        }
+                        Task::none()
+                    }
+                    SettingsMessage::PickedControls(control_scheme) => {
+                        if let MainViewContent::Settings(settings) = &mut self.main_view {
+                            // Start editing a custom mapping from the currently picked one
+                            settings.control_scheme = match control_scheme {
+                                ControlScheme::Custom(_) => {
+                                    ControlScheme::Custom(settings.control_scheme.into())
+                                }
+                                _ => control_scheme,
+                            };
+                        }
+
+                        Task::none()
+                    }
+                    SettingsMessage::CustomControls(new_custom_controls) => {
+                        if let MainViewContent::Settings(SettingsDraft {
+                            control_scheme: control_scheme @ ControlScheme::Custom(_),
+                            ..
+                        }) = &mut self.main_view
+                        {
+                            *control_scheme = ControlScheme::Custom(new_custom_controls);
+                        }
+                        Task::none()
+                    }
+                    SettingsMessage::Search(search) => {
+                        if let MainViewContent::Settings(settings) = &mut self.main_view {
+                            settings.search = search;
+                        }
+
+                        Task::none()
+                    }
+                    SettingsMessage::Preferences(preferences) => {
+                        if let MainViewContent::Settings(settings) = &mut self.main_view {
+                            // The controls being edited might now belong to a different difficulty
+                            settings.store_controls();
+                            settings.preferences = Preferences {
+                                controls: settings.preferences.controls,
+                                ..preferences
+                            };
+                            settings.load_controls();
+                        }
+
+                        Task::none()
+                    }
+                }
+            }
+
+            Message::HighScores => {
+                match self.main_view {
+                    MainViewContent::HighScores => {
+                        // Get back to the game
+                        self.resume_game();
+                        self.main_view = MainViewContent::Game;
+                    }
+                    _ => {
+                        self.pause_game();
+                        self.main_view = MainViewContent::HighScores;
+                    }
+                }
+
+                Task::none()
+            }
+
+            Message::Stats => {
+                match self.main_view {
+                    MainViewContent::Stats => {
+                        // Get back to the game
+                        self.resume_game();
+                        self.main_view = MainViewContent::Game;
+                    }
+                    _ => {
+                        self.pause_game();
+                        self.main_view = MainViewContent::Stats;
+                    }
+                }
+
+                Task::none()
+            }
+
+            Message::ResetStats => {
+                self.stats.clear();
+
+                let gp = self.game_persistence();
+
+                Task::perform(
+                    Self::save_persistence(self.persistence_path.clone(), gp),
+                    |result| Message::Persistence(PersistenceMessage::SavedConfigs(result)),
+                )
+            }
+
+            Message::Replays => {
+                match self.main_view {
+                    MainViewContent::Replays(_) => {
+                        // Get back to the game
+                        self.resume_game();
+                        self.main_view = MainViewContent::Game;
+                    }
+                    _ => {
+                        self.pause_game();
+                        self.main_view =
+                            MainViewContent::Replays(Replay::list(&self.replays_dir()));
+                    }
+                }
+
+                Task::none()
+            }
+            Message::DailyChallenge(difficulty_level) => {
+                self.start_daily_challenge(difficulty_level, &format_date(now_timestamp()))
+            }
+            Message::PlayReplay(path) => match Replay::load(&path) {
+                Ok(replay) => {
+                    let own_config = self
+                        .own_config
+                        .take()
+                        .unwrap_or((self.game_config, self.custom_game));
+                    self.own_config = Some(own_config);
+                    self.game_config = replay.header.game_config;
+                    self.custom_game = replay.header.custom_game;
+                    self.start_new_game();
+
+                    self.field = Minefield::new(self.game_config.width, self.game_config.height)
+                        .with_connectivity(replay.header.connectivity())
+                        .with_safe_chord(self.game_config.safe_chord)
+                        .with_win_on_all_revealed(self.game_config.win_on_all_revealed)
+                        .with_strict_flags(self.game_config.strict_flags)
+                        .with_question_marks(replay.header.question_marks)
+                        .with_mines_at(&replay.mines);
+                    self.remaining_flags = self.field.mines() as i64;
+                    self.no_guess_pending = false;
+                    self.open_start_pending = false;
+                    self.playback = Some(Playback::new(replay));
+                    self.main_view = MainViewContent::Game;
+
+                    self.redraw_field();
+
+                    self.resize_window()
+                }
+                Err(error) => {
+                    // Show why the replay can't be played back, instead of its header, or in a banner if it was
+                    // picked from the high scores
+                    if let MainViewContent::Replays(entries) = &mut self.main_view {
+                        if let Some(entry) = entries.iter_mut().find(|entry| entry.path == path) {
+                            entry.header = Err(error);
+                        }
+
+                        Task::none()
+                    } else {
+                        self.update(Message::Persistence(PersistenceMessage::Error(format!(
+                            "{}: {}",
+                            path.display(),
+                            error
+                        ))))
+                    }
+                }
+            },
+            Message::ReplayFrame(now) => {
+                let messages = self
+                    .playback
+                    .as_mut()
+                    .map(|playback| playback.advance(now))
+                    .unwrap_or_default();
+                self.sync_playback_clock(now);
+
+                Task::batch(
+                    messages
+                        .into_iter()
+                        .map(|message| self.update(Message::Minesweep(message))),
+                )
+            }
+            Message::Playback(playback_message) => {
+                let Some(playback) = self.playback.as_mut() else {
+                    return Task::none();
+                };
+
+                match playback_message {
+                    PlaybackMessage::TogglePause => playback.toggle_pause(),
+                    PlaybackMessage::CycleSpeed => playback.cycle_speed(),
+                    PlaybackMessage::Step => {
+                        if let Some(message) = playback.step() {
+                            self.sync_playback_clock(Instant::now());
+
+                            return self.update(Message::Minesweep(message));
+                        }
+                    }
+                }
+
+                Task::none()
+            }
+            Message::Tick(new_tick) => {
+                if let GameState::Running(cur_tick) = &mut self.game_state {
+                    self.elapsed_seconds += new_tick - *cur_tick;
+                    *cur_tick = new_tick;
+
+                    if self.time_left() == Some(Duration::ZERO) {
+                        self.reveal_animation = None;
+                        self.game_over(false);
+                        self.announce("Time's up, game over".to_string());
+                        self.redraw_field();
+                    }
+                }
+
+                self.hint_if_idle(new_tick);
+
+                if self.auto_solving {
+                    return self.auto_solve_move();
+                }
+
+                self.save_replay_task()
+            }
+            Message::AnimationFrame(now) => {
+                if let Some(animation) = &self.reveal_animation {
+                    if animation.is_finished(now) {
+                        self.reveal_animation = None;
+                    }
+                }
+
+                if let Some(animation) = &self.explosion_animation {
+                    if animation.is_finished(now) {
+                        self.explosion_animation = None;
+                    }
+                }
+
+                if let Some(pulse) = &self.hint_pulse {
+                    if pulse.is_finished(now) {
+                        self.hint_pulse = None;
+                    }
+                }
+
+                Task::none()
+            }
+            Message::Profile(profile_message) => {
+                match profile_message {
+                    ProfileMessage::NameChanged(name) => {
+                        if name.chars().count() <= Self::MAX_PROFILE_NAME_LEN {
+                            self.new_profile_name = name;
+                        }
+
+                        return Task::none();
+                    }
+                    ProfileMessage::Picked(name) => {
+                        if !self.profiles.contains_key(&name) {
+                            return Task::none();
+                        }
+
+                        self.switch_profile(name);
+                    }
+                    ProfileMessage::Add => {
+                        let name = self.new_profile_name.trim().to_string();
+                        if name.is_empty()
+                            || name == self.active_profile
+                            || self.profiles.contains_key(&name)
+                        {
+                            return Task::none();
+                        }
+
+                        self.new_profile_name.clear();
+                        self.switch_profile(name);
+                    }
+                    ProfileMessage::Delete => {
+                        // The last profile is never deleted, so that there's always one to record the games in
+                        let Some((name, profile)) = self.profiles.pop_first() else {
+                            return Task::none();
+                        };
+
+                        self.active_profile = name;
+                        self.high_scores = profile.high_scores;
+                        self.flagless_high_scores = profile.flagless_high_scores;
+                        self.stats = profile.stats;
+                    }
+                }
+
+                let gp = self.game_persistence();
+
+                Task::perform(
+                    Self::save_persistence(self.persistence_path.clone(), gp),
+                    |result| Message::Persistence(PersistenceMessage::SavedConfigs(result)),
+                )
+            }
+            Message::HighScore(rec) => {
+                match rec {
+                    RecordHighScore::NameChanged(name) => {
+                        if let MainViewContent::EnterHighScore(hs, _, _) = self.main_view.clone() {
+                            // Enforce maximum name length
+                            if name.chars().count() < Self::MAX_HIGHSCORE_NAME_LEN {
+                                if let Some(scores) = self
+                                    .high_score_board_mut(hs.flagless)
+                                    .get_mut(&hs.difficulty_level)
+                                {
+                                    if let Some(score) = scores.get_mut(hs.index) {
+                                        score.name = name;
+                                    }
+                                }
+                            }
+                        }
+
+                        Task::none()
+                    }
+                    RecordHighScore::RecordName => {
+                        if let MainViewContent::EnterHighScore(hs, _, previous_name) =
+                            &self.main_view
+                        {
+                            // Renaming a high score doesn't record a new one
+                            if previous_name.is_none() {
+                                if let Some(score) = self
+                                    .high_score_board(hs.flagless)
+                                    .get(&hs.difficulty_level)
+                                    .and_then(|scores| scores.get(hs.index))
+                                {
+                                    self.events.push(GameEvent::HighScore {
+                                        difficulty_level: hs.difficulty_level,
+                                        flagless: hs.flagless,
+                                        rank: hs.index,
+                                        name: score.name.clone(),
+                                        millis: score.millis(),
+                                    });
+                                }
+                            }
+
+                            self.main_view = MainViewContent::HighScores;
+
+                            let gp = self.game_persistence();
+
+                            Task::perform(
+                                Self::save_persistence(self.persistence_path.clone(), gp),
+                                |result| {
+                                    Message::Persistence(PersistenceMessage::SavedConfigs(result))
+                                },
+                            )
+                        } else {
+                            Task::none()
+                        }
+                    }
+                    RecordHighScore::Discard => {
+                        if let MainViewContent::EnterHighScore(hs, _, previous_name) =
+                            self.main_view.clone()
+                        {
+                            if let Some(scores) = self
+                                .high_score_board_mut(hs.flagless)
+                                .get_mut(&hs.difficulty_level)
+                            {
+                                match &previous_name {
+                                    // A renamed high score is kept, with the name it had
+                                    Some(name) => {
+                                        if let Some(score) = scores.get_mut(hs.index) {
+                                            score.name = name.clone();
+                                        }
+                                    }
+                                    None => {
+                                        if hs.index < scores.len() {
+                                            scores.remove(hs.index);
+                                        }
+                                    }
+                                }
+                            }
+
+                            self.main_view = match previous_name {
+                                Some(_) => MainViewContent::HighScores,
+                                None => MainViewContent::Game,
+                            };
+                        }
+
+                        Task::none()
+                    }
+                    RecordHighScore::Rename(hs) => {
+                        if let MainViewContent::HighScores = self.main_view {
+                            if let Some(score) = self
+                                .high_score_board(hs.flagless)
+                                .get(&hs.difficulty_level)
+                                .and_then(|scores| scores.get(hs.index))
+                            {
+                                let input_id = text_input::Id::unique();
+                                self.main_view = MainViewContent::EnterHighScore(
+                                    hs,
+                                    input_id.clone(),
+                                    Some(score.name.clone()),
+                                );
+
+                                return text_input::focus(input_id);
+                            }
+                        }
+
+                        Task::none()
+                    }
+                    RecordHighScore::Delete(hs) => {
+                        if let MainViewContent::HighScores = self.main_view {
+                            if let Some(scores) = self
+                                .high_score_board_mut(hs.flagless)
+                                .get_mut(&hs.difficulty_level)
+                            {
+                                if hs.index < scores.len() {
+                                    scores.remove(hs.index);
+
+                                    let gp = self.game_persistence();
+
+                                    return Task::perform(
+                                        Self::save_persistence(self.persistence_path.clone(), gp),
+                                        |result| {
+                                            Message::Persistence(PersistenceMessage::SavedConfigs(
+                                                result,
+                                            ))
+                                        },
+                                    );
+                                }
+                            }
+                        }
+
+                        Task::none()
+                    }
+                }
+            }
+            Message::Persistence(pmsg) => {
+                let mut command;
+
+                match pmsg {
+                    PersistenceMessage::LoadedConfigs(game_p) => {
+                        let persisted_config = game_p.as_ref().map(|gp| gp.game_config);
+
+                        if let Some(game_p) = game_p.map(|game_p| *game_p) {
+                            // load High Scores
+                            self.high_scores = game_p.high_scores;
+                            self.flagless_high_scores = game_p.flagless_high_scores;
+                            self.max_high_scores = game_p.max_high_scores;
+                            self.stats = game_p.stats;
+                            self.active_profile = game_p.active_profile;
+                            self.profiles = game_p.profiles;
+                            self.daily_results = game_p.daily_results;
+
+                            // load player preferences
+                            self.preferences = game_p.preferences;
+                            self.apply_question_marks();
+                            self.difficulty_controls = game_p.difficulty_controls;
+
+                            // load the presets, which might have been edited by hand
+                            self.presets = game_p
+                                .presets
+                                .into_iter()
+                                .filter(|preset| match preset.game_config.validate() {
+                                    Ok(()) => true,
+                                    Err(e) => {
+                                        log::warn!("Ignoring preset {:?}: {}", preset.name, e);
+                                        false
+                                    }
+                                })
+                                .collect();
+                        }
+
+                        self.pending_seed = self.startup.seed;
+
+                        if let Some(game_config) = self.startup.game_config {
+                            // The game config requested at launch (already validated) takes precedence over the
+                            // persisted one, but is only played: the persisted one is still the one saved
+                            self.saved_config = Some(persisted_config.unwrap_or_default());
+                            self.game_config = game_config;
+                            self.custom_game = matches!(
+                                GameDifficulty::from_config(&game_config),
+                                GameDifficulty::Custom(_)
+                            );
+                            self.start_new_game();
+                            self.redraw_field();
+                            command = self.resize_window();
+                        } else if let Some(game_config) = persisted_config {
+                            // Load game config, unless it's a custom one which can't be played
+                            let game_difficulty = GameDifficulty::from_config(&game_config);
+
+                            match game_config.validate() {
+                                Ok(()) => {
+                                    // Apply the game config loaded from file
+                                    let settings = SettingsDraft {
+                                        countdown: matches!(
+                                            game_config.mode,
+                                            GameMode::Countdown { .. }
+                                        ),
+                                        safe_chord: game_config.safe_chord,
+                                        win_on_all_revealed: game_config.win_on_all_revealed,
+                                        strict_flags: game_config.strict_flags,
+                                        ..self.settings_draft(game_difficulty)
+                                    };
+                                    command = Task::perform(
+                                        async move {
+                                            Message::Settings(SettingsMessage::Set(Box::new(
+                                                settings,
+                                            )))
+                                        },
+                                        |m| m,
+                                    )
+                                }
+                                Err(e) => {
+                                    log::warn!("Ignoring the persisted game config: {}", e);
+                                    command = Task::none();
+                                }
+                            }
+                        } else if self.pending_seed.is_some() {
+                            // Only a seed was requested at launch
+                            self.start_new_game();
+                            command = Task::none();
+                        } else {
+                            command = Task::none();
+                        }
+
+                        // First-time players are offered the tutorial, once the game config is applied
+                        if self.first_launch {
+                            self.first_launch = false;
+                            command =
+                                command.chain(Task::done(Message::Tutorial(TutorialMessage::Show)));
+                        }
+                    }
+                    PersistenceMessage::SavedReplay(result) => {
+                        if let Err(e) = result {
+                            log::warn!("{}", e);
+                        }
+
+                        command = Task::none();
+                    }
+                    PersistenceMessage::SavedConfigs(result) => {
+                        command = match result {
+                            Ok(()) => Task::none(),
+                            Err(e) => {
+                                Task::done(Message::Persistence(PersistenceMessage::Error(e)))
+                            }
+                        };
+                    }
+                    PersistenceMessage::Error(e) => {
+                        log::warn!("{}", e);
+                        self.persistence_error = Some((e, Instant::now()));
+
+                        command = Task::none();
+                    }
+                    PersistenceMessage::DismissError => {
+                        self.persistence_error = None;
+
+                        command = Task::none();
+                    }
+                    PersistenceMessage::ExpireError(now) => {
+                        if let Some((_, shown)) = self.persistence_error {
+                            if now.saturating_duration_since(shown) >= Self::PERSISTENCE_ERROR_TIME
+                            {
+                                self.persistence_error = None;
+                            }
+                        }
+
+                        command = Task::none();
+                    }
+                }
+
+                command
+            }
+            Message::WindowId(id) => {
+                self.window_id = id;
+
+                Task::none()
+            }
+            Message::MonitorSize(size) => {
+                self.monitor_size = size;
+
+                // The window was sized for the assumed monitor until now, which may be larger than the detected one
+                self.resize_window()
+            }
+            Message::DismissGenerationWarning => {
+                self.generation_warning = false;
+
+                Task::none()
+            }
+            Message::DismissGameSummary => {
+                self.game_summary = false;
+
+                Task::none()
+            }
+        }
+    }
+
+    pub fn view(&self) -> iced::Element<'_, Message> {
+        let main_view = match &self.main_view {
+            MainViewContent::Game => self.view_field(),
+            MainViewContent::Settings(settings) => self.view_settings(settings),
+            MainViewContent::Info => self.view_info(),
+            MainViewContent::HighScores => {
+                // self.view_high_scores().explain(Color::WHITE)
+                self.view_high_scores()
+            }
+            MainViewContent::Stats => self.view_stats(),
+            MainViewContent::Replays(entries) => self.view_replays(entries),
+            MainViewContent::Editor(editor) => self.view_editor(editor),
+            MainViewContent::Tutorial(tutorial) => self.view_tutorial(tutorial),
+            MainViewContent::ConfirmReset { same_board } => self.view_confirm_reset(*same_board),
+            MainViewContent::EnterHighScore(hs, name_input_id, previous_name) => {
+                self.view_record_high_score(hs.clone(), name_input_id, previous_name.is_some())
+            }
+        };
+
+        // Failures to load or save the configs are shown on top of any view, since they can happen in most of them
+        let main_view = match &self.persistence_error {
+            Some((error, _)) => {
+                let banner = widget::container(
+                    widget::row![
+                        widget::text(error.as_str())
+                            .size(12.0)
+                            .color(Self::COLOR_LIGHT_RED)
+                            .width(Length::Fill),
+                        widget::button(widget::text("OK").size(12.0))
+                            .on_press(Message::Persistence(PersistenceMessage::DismissError))
+                            .style(button::primary),
+                    ]
+                    .spacing(10.0)
+                    .align_y(Alignment::Center),
+                )
+                .padding(5.0)
+                .style(widget::container::rounded_box);
+
+                widget::stack![main_view, banner].into()
+            }
+            None => main_view,
+        };
+
+        let content = widget::column![self.view_controls(), main_view]
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Alignment::Start);
+
+        widget::container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// The theme of the application, which follows the preferred color theme
+    pub fn theme(&self) -> Theme {
+        self.preferences.color_theme.theme()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let timer = if let GameState::Running(_) = self.game_state {
+            // The timer ticks faster while auto-solving, so that the moves can be watched without taking too long
+            let interval = if self.auto_solving {
+                Self::AUTO_SOLVE_INTERVAL
+            } else {
+                Duration::from_millis(1000)
+            };
+
+            time::every(interval).map(Message::Tick)
+        } else {
+            Subscription::none()
+        };
+
+        let animation = if self.reveal_animation.is_some()
+            || self.explosion_animation.is_some()
+            || self.hint_pulse.is_some()
+        {
+            window::frames().map(Message::AnimationFrame)
+        } else {
+            Subscription::none()
+        };
+
+        let persistence_error = if self.persistence_error.is_some() {
+            time::every(Duration::from_secs(1))
+                .map(|now| Message::Persistence(PersistenceMessage::ExpireError(now)))
+        } else {
+            Subscription::none()
+        };
+
+        // Frames keep coming while the playback is paused, so that they hold the game timer still
+        let playback = match (&self.playback, &self.main_view) {
+            (Some(playback), MainViewContent::Game) if !playback.is_finished() => {
+                window::frames().map(Message::ReplayFrame)
+            }
+            _ => Subscription::none(),
+        };
+
+        // Keys which are pressed while typing in a text input never get here, since the input captures them
+        let game_keys = keyboard::on_key_press(|key, _modifiers| match key {
+            keyboard::Key::Named(keyboard::key::Named::Space) => Some(Message::TogglePause),
+            keyboard::Key::Character(c) if c.eq_ignore_ascii_case("x") => Some(Message::TogglePeek),
+            keyboard::Key::Character(c) if c.eq_ignore_ascii_case("p") => {
+                Some(Message::ToggleProbabilities)
+            }
+            keyboard::Key::Character(c) if c.eq_ignore_ascii_case("a") => Some(Message::AutoSolve),
+            keyboard::Key::Character(c) if c.eq_ignore_ascii_case("r") => Some(Message::ResetKey),
+            keyboard::Key::Character(c) if c.eq_ignore_ascii_case("s") => {
+                Some(Message::Settings(SettingsMessage::Show))
+            }
+            keyboard::Key::Character(c) if c.eq_ignore_ascii_case("h") => Some(Message::HighScores),
+            keyboard::Key::Character(c) if c.eq_ignore_ascii_case("i") => Some(Message::Info),
+            keyboard::Key::Named(keyboard::key::Named::Escape) => Some(Message::CloseView),
+            _ => None,
+        });
+
+        Subscription::batch([timer, animation, persistence_error, playback, game_keys])
+    }
+
+    pub const APP_NAME: &'static str = "iced minesweep-rs";
+
+    // Fonts for mines and flags
+    const MINES_FLAGS_ICONS: Font = Font::with_name("emoji");
+
+    // Fonts for mines and flags
+    const COMMANDS_ICONS: Font = Font::with_name("Noto Emoji");
+
+    // Fonts for text
+    const TEXT_FONT: Font = Font::with_name("Ubuntu Light");
 
     const LICESE_BYTES: &'static [u8] = include_bytes!("../LICENSE");
 
-    const REFRESH_BTN_CHAR: &'static str = "🔄";
-    const SETTINGS_BTN_CHAR: &'static str = "🛠";
-    const ABOUT_BTN_CHAR: &'static str = "ℹ";
-    const HIGH_SCORES_CHAR: &'static str = "🏆";
+    /// Keys handled by the `game_keys` subscription, as listed in the About view
+    const KEYBOARD_SHORTCUTS: &'static str = "R: new board\n\
+        S: settings\n\
+        H: high scores\n\
+        I: about\n\
+        Esc: back to the game\n\
+        Space: pause or resume\n\
+        X: peek at the mines\n\
+        P: show the mine probabilities\n\
+        A: auto-solve";
+
+    // Faces of the new board button, which follow the game like the classic smiley button
+    const PLAYING_FACE_CHAR: &'static str = "🙂";
+    const WON_FACE_CHAR: &'static str = "😎";
+    const LOST_FACE_CHAR: &'static str = "😵";
+    const NEW_BOARD_LABEL: &'static str = "New board, same settings";
+    const RESTART_CHAR: &'static str = "🔁";
+    const SETTINGS_BTN_CHAR: &'static str = "🛠";
+    const ABOUT_BTN_CHAR: &'static str = "ℹ";
+    const HIGH_SCORES_CHAR: &'static str = "🏆";
+    const STATS_CHAR: &'static str = "📊";
+    const REPLAYS_CHAR: &'static str = "🎞";
+    const EDITOR_CHAR: &'static str = "✏";
+    const UNDO_CHAR: &'static str = "↩";
+    const REDO_CHAR: &'static str = "↪";
+    const PAUSE_CHAR: &'static str = "⏸";
+    const RESUME_CHAR: &'static str = "▶";
+    const STEP_CHAR: &'static str = "⏭";
+    const FLAGS_WARNING_CHAR: &'static str = "⚠";
+
+    const TOOLBAR_HEIGHT: f32 = 70.0;
+    const EDITOR_BAR_HEIGHT: f32 = 100.0;
+    const TUTORIAL_BAR_HEIGHT: f32 = 130.0;
+    /// Monitor size assumed when it can't be detected
+    const FALLBACK_MONITOR_SIZE: Size = Size::new(1920.0, 1080.0);
+    /// Height of the monitor which is left for the window decorations and desktop panels
+    const MONITOR_RESERVED_HEIGHT: f32 = 80.0;
+    const MIN_WINDOW_SIZE: Size = Size::new(400.0, 300.0);
+    const FIELD_PAD: f32 = 20.0;
+    /// Size of spot on canvas, including padding, until the board is zoomed
+    const DEFAULT_SPOT_SIZE: f32 = 30.0;
+    /// Range of spot sizes the board can be zoomed to, and the change in size of each zoom step
+    const MIN_SPOT_SIZE: f32 = 20.0;
+    const MAX_SPOT_SIZE: f32 = 60.0;
+    const SPOT_SIZE_STEP: f32 = 5.0;
+    /// Interior padding of spot
+    const SPOT_PAD: f32 = 1.0;
+    const CELL_PAD: f32 = 8.0;
+
+    #[allow(clippy::eq_op)]
+    const COLOR_RED: Color = Color::from_rgb(255.0 / 255.0, 0.0 / 255.0, 0.0 / 255.0);
+    #[allow(clippy::eq_op)]
+    const COLOR_LIGHT_RED: Color = Color::from_rgb(255.0 / 255.0, 128.0 / 255.0, 128.0 / 255.0);
+    #[allow(clippy::eq_op)]
+    const COLOR_GREEN: Color = Color::from_rgb(0.0 / 255.0, 255.0 / 255.0, 0.0 / 255.0);
+    const COLOR_GRAY: Color = Color::from_rgb(60.0 / 255.0, 60.0 / 255.0, 60.0 / 255.0);
+    const COLOR_DARK_GRAY: Color = Color::from_rgb(27.0 / 255.0, 27.0 / 255.0, 27.0 / 255.0);
+
+    const MINE_CHAR: &'static str = "☢";
+    const MINE_COLOR: Color = Self::COLOR_RED;
+    const MINE_EXPLODED_CHAR: &'static str = "💥";
+    const MINE_EXPLODED_COLOR: Color = Self::COLOR_RED;
+    const EXPLODED_HIGHLIGHT_ALPHA: f32 = 0.35;
+    const FLAG_CHAR: &'static str = "⚐";
+    const EXTRA_FLAG_CHAR: &'static str = "⚑";
+    const QUESTION_CHAR: &'static str = "?";
+
+    /// Number of flag icons shown for the remaining flags, before the rest are summed up as `+N`
+    const FLAG_ICONS_SHOWN: u64 = 5;
+
+    /// Remaining flags above this number are shown as a number, even if flag icons are preferred
+    const FLAG_ICONS_MAX: u64 = 15;
+    const FLAG_COLOR_CORRECT: Color = Self::COLOR_GREEN;
+    const FLAG_COLOR_WRONG: Color = Self::COLOR_RED;
+    const EMPTY_SPOT_CHARS: [&'static str; 9] = [" ", "1", "2", "3", "4", "5", "6", "7", "8"];
+    const SATISFIED_NUMBER_ALPHA: f32 = 0.3;
+
+    /// Opacity of the shade of a hidden spot which is certainly a mine, when showing the mine probabilities
+    const PROBABILITY_SHADE_ALPHA: f32 = 0.6;
+
+    /// Opacity of the outline of the spot under the cursor, and of the neighbors which an autostep would reveal
+    const HOVER_ALPHA: f32 = 0.5;
+
+    /// Size of the ruler labels around the board, relative to the spot size, until they no longer fit in its margin
+    const RULER_TEXT_SCALE: f32 = 0.4;
+
+    /// Time between the moves of the auto-solver
+    const AUTO_SOLVE_INTERVAL: Duration = Duration::from_millis(250);
+
+    /// A flag locked by the flag lock is removed if it's flagged twice within this time
+    const FLAG_UNLOCK_TIME: Duration = Duration::from_millis(500);
+
+    /// A finger held on a spot for at least this time flags it instead of stepping on it
+    const LONG_PRESS_TIME: Duration = Duration::from_millis(400);
+
+    const READY_COLOR: Color = Self::COLOR_GRAY;
+    /// The time left in a countdown game is shown in red below this time
+    const COUNTDOWN_WARNING: Duration = Duration::from_secs(10);
+    const WON_COLOR: Color = Self::COLOR_GREEN;
+    const LOST_COLOR: Color = Self::COLOR_RED;
+
+    const FLAG_COUNT_ERR_COLOR: Color = Self::COLOR_LIGHT_RED;
+
+    /// Number of high scores kept for each difficulty level, unless the persisted configs say otherwise
+    const DEFAULT_HIGH_SCORES_PER_LEVEL: usize = 10;
+    /// Most high scores which can be kept for each difficulty level, whatever the persisted configs say
+    const MAX_HIGH_SCORES_PER_LEVEL: usize = 100;
+    const MAX_HIGHSCORE_NAME_LEN: usize = 32;
+    const MAX_PRESET_NAME_LEN: usize = 32;
+    const MAX_PROFILE_NAME_LEN: usize = 32;
+
+    /// Most spots drawn again over the cached minefield, past which the whole minefield is drawn again instead
+    const MAX_CHANGED_SPOTS: usize = 1024;
+
+    /// Number of days whose daily challenge results are kept
+    const MAX_DAILY_DAYS: usize = 30;
+
+    /// Number of wins shown on the leaderboard of each daily challenge
+    const DAILY_LEADERBOARD_LEN: usize = 5;
+
+    /// Largest board whose 3BV is worked out at the end of a game
+    const MAX_3BV_SPOTS: u32 = 1_000_000;
+
+    /// Name of the profile owning the high scores and stats, until the player adds others
+    const DEFAULT_PROFILE: &'static str = "Player";
+
+    /// Embed the game in another iced application, whose messages are made from the messages of the game by
+    /// `on_message`: its view, subscription and tasks are all mapped to them. The game doesn't resize the window once
+    /// embedded.
+    pub fn embed<M: Send + 'static>(mut self, on_message: fn(Message) -> M) -> Embedded<M> {
+        self.resizes_window = false;
+
+        Embedded::new(self, on_message)
+    }
+
+    /// Take the events which happened in the game since they were last taken, oldest first
+    pub(crate) fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    #[allow(dead_code)]
+    pub fn with_configs(mut self, game_config: GameConfig) -> Self {
+        self.game_config = game_config;
+        self.field = Minefield::new(self.game_config.width, self.game_config.height)
+            .with_safe_chord(self.game_config.safe_chord)
+            .with_win_on_all_revealed(self.game_config.win_on_all_revealed)
+            .with_strict_flags(self.game_config.strict_flags)
+            .with_question_marks(self.preferences.question_marks)
+            .with_mines(self.game_config.mines);
+        self.game_config.mines = self.field.mines();
+
+        self
+    }
+
+    /// The size of the window which fits the whole minefield, clamped to the maximum window size. The minefield can be
+    /// scrolled when it doesn't fit.
+    fn desired_window_size(&self) -> (f32, f32) {
+        let (field_width, field_height) = self.desired_field_size();
+        let max_size = self.max_window_size();
+
+        let width = field_width.min(max_size.width);
+        let height = (field_height + self.chrome_height()).min(max_size.height);
+
+        (width, height)
+    }
+
+    /// The maximum size of the window, as configured by the player, or fitting the monitor otherwise. The window can't
+    /// be resized by the player, so it never gets larger than a detected monitor, whatever size is configured.
+    fn max_window_size(&self) -> Size {
+        let monitor_size = self.monitor_size.unwrap_or(Self::FALLBACK_MONITOR_SIZE);
+        let screen_size = Size::new(
+            monitor_size.width,
+            monitor_size.height - Self::MONITOR_RESERVED_HEIGHT,
+        );
+
+        let mut width = self
+            .preferences
+            .max_window_width
+            .map(|w| w as f32)
+            .unwrap_or(screen_size.width);
+        let mut height = self
+            .preferences
+            .max_window_height
+            .map(|h| h as f32)
+            .unwrap_or(screen_size.height);
+        if self.monitor_size.is_some() {
+            width = width.min(screen_size.width);
+            height = height.min(screen_size.height);
+        }
+
+        Size::new(
+            width.max(Self::MIN_WINDOW_SIZE.width),
+            height.max(Self::MIN_WINDOW_SIZE.height),
+        )
+    }
+
+    /// The whole minefield doesn't fit in the window, so it needs to be scrolled
+    fn is_field_scrollable(&self) -> bool {
+        let (field_width, field_height) = self.desired_field_size();
+        let (width, height) = self.desired_window_size();
+
+        field_width > width || field_height + self.chrome_height() > height
+    }
+
+    /// Height of the window which is not taken by the minefield
+    fn chrome_height(&self) -> f32 {
+        match self.main_view {
+            MainViewContent::Editor(_) => Self::TOOLBAR_HEIGHT + Self::EDITOR_BAR_HEIGHT,
+            MainViewContent::Tutorial(_) => Self::TOOLBAR_HEIGHT + Self::TUTORIAL_BAR_HEIGHT,
+            _ => Self::TOOLBAR_HEIGHT,
+        }
+    }
+
+    /// The minefield drawn on the canvas: the one being edited in the board editor, or the one being played otherwise
+    fn shown_field(&self) -> &Minefield {
+        match &self.main_view {
+            MainViewContent::Editor(editor) => editor.field(),
+            _ => &self.field,
+        }
+    }
+
+    /// Detect the size of the monitor showing the window with the given `id`, assuming that the window is centered on
+    /// it (which is only true right after it was opened). Some platforms (e.g. Wayland) don't report window positions,
+    /// in which case no size is detected.
+    fn detect_monitor_size(id: window::Id) -> Task<Option<Size>> {
+        window::get_position(id).then(move |position| {
+            window::get_size(id).map(move |size| {
+                position.map(|position| {
+                    Size::new(
+                        position.x * 2.0 + size.width,
+                        position.y * 2.0 + size.height,
+                    )
+                })
+            })
+        })
+    }
+
+    fn desired_field_size(&self) -> (f32, f32) {
+        let width = (self.spot_size * self.shown_field().width() as f32) + (Self::FIELD_PAD * 2.0);
+        let height =
+            (self.spot_size * self.shown_field().height() as f32) + (Self::FIELD_PAD * 2.0);
+
+        (width, height)
+    }
+
+    /// Controls view
+    fn view_controls(&self) -> Element<'_, Message> {
+        let time_left = self.time_left();
+        let text_color = match self.game_state {
+            GameState::Ready => Self::READY_COLOR,
+            GameState::Running(_) if time_left.is_some_and(|t| t < Self::COUNTDOWN_WARNING) => {
+                Self::LOST_COLOR
+            }
+            GameState::Running(_) => self.preferences.color_theme.field_palette().text,
+            GameState::Paused => Self::READY_COLOR,
+            GameState::Stopped { is_won } => match is_won {
+                true => Self::WON_COLOR,
+                false => Self::LOST_COLOR,
+            },
+        };
+
+        let time_text_size = 20;
+        // Countdown games show the time left instead of the game time
+        let shown_time = time_left.unwrap_or(self.elapsed_seconds).as_millis() as u64;
+        let time_text = match self.game_state {
+            GameState::Ready if time_left.is_none() => widget::text("---").size(time_text_size),
+            GameState::Ready | GameState::Running(_) | GameState::Paused => {
+                widget::text(format_time(shown_time, false)).size(time_text_size)
+            }
+            // The final time is exact, so it's shown with the same precision as the high scores
+            GameState::Stopped { is_won: _ } => {
+                widget::text(format_time(shown_time, self.preferences.precise_times))
+                    .size(time_text_size)
+            }
+        };
+
+        let time_label = if time_left.is_some() {
+            "Time left"
+        } else {
+            "Time"
+        };
+        let display_seconds = widget::column![
+            widget::text(time_label).size(10).color(text_color),
+            time_text.color(text_color)
+        ]
+        .align_x(Alignment::Center);
+
+        let flags_text_size = 20;
+
+        let flags_text_color = match self.game_state {
+            GameState::Running(_) if self.remaining_flags >= 0 => {
+                self.preferences.color_theme.field_palette().text
+            }
+            GameState::Running(_) => Self::FLAG_COUNT_ERR_COLOR,
+            _ => text_color,
+        };
+        let flags_text: Element<'_, Message> = match self.game_state {
+            GameState::Ready if self.remaining_flags == self.field.mines() as i64 => {
+                widget::text("---")
+                    .size(flags_text_size)
+                    .color(text_color)
+                    .into()
+            }
+            // Only a few flags fit in the toolbar, so larger counts are always numeric
+            _ if self.preferences.flag_icons
+                && self.remaining_flags.unsigned_abs() <= Self::FLAG_ICONS_MAX =>
+            {
+                self.view_flag_icons(flags_text_color)
+            }
+            _ => widget::text(self.remaining_flags)
+                .size(flags_text_size)
+                .color(flags_text_color)
+                .into(),
+        };
+        // A subtle warning that all the flags are used up while the board isn't cleared, so one of them may be wrong
+        let flags_label: Element<'_, Message> = if self.all_flags_placed_uncleared() {
+            widget::tooltip(
+                widget::row![
+                    widget::text("Flags").size(10).color(text_color),
+                    widget::text(Self::FLAGS_WARNING_CHAR)
+                        .font(Self::COMMANDS_ICONS)
+                        .size(10)
+                        .color(Self::FLAG_COUNT_ERR_COLOR),
+                ]
+                .spacing(2.0),
+                widget::text("All the flags are placed, but the board isn't cleared").size(12.0),
+                widget::tooltip::Position::Bottom,
+            )
+            .padding(5.0)
+            .style(container::rounded_box)
+            .into()
+        } else {
+            widget::text("Flags").size(10).color(text_color).into()
+        };
+        let display_flags = widget::column![flags_label, flags_text].align_x(Alignment::Center);
+
+        // The seed of a replay or of an edited board isn't known, since only their mines are
+        let mut counters = widget::row![widget::horizontal_space(), display_seconds, display_flags];
+        if self.preferences.show_remaining_cells {
+            counters = counters.push(
+                widget::column![
+                    widget::text("Cells left").size(10).color(text_color),
+                    widget::text(self.field.remaining_safe_cells())
+                        .size(flags_text_size)
+                        .color(text_color),
+                ]
+                .align_x(Alignment::Center),
+            );
+        }
+        if self.preferences.show_seed && self.playback.is_none() && !self.edited_board {
+            counters = counters.push(
+                widget::column![
+                    widget::text("Seed").size(10).color(text_color),
+                    widget::tooltip(
+                        widget::button(widget::text(self.seed.to_string()).size(12))
+                            .on_press(Message::CopySeed)
+                            .padding(2.0)
+                            .style(button::text),
+                        widget::text("Copy seed").size(12.0),
+                        widget::tooltip::Position::Bottom,
+                    )
+                    .padding(5.0)
+                    .style(container::rounded_box),
+                ]
+                .align_x(Alignment::Center),
+            );
+        }
+
+        // Once the game is over, its efficiency is shown: the 3BV of the board per second for a won game, or only the 3BV
+        // otherwise, with the clicks taken in the tooltip
+        if let (GameState::Stopped { is_won }, Some(board_3bv)) = (self.game_state, self.board_3bv)
+        {
+            let (label, value) = match self.efficiency() {
+                Some(efficiency) if is_won => ("3BV/s", format!("{:.2}", efficiency)),
+                _ => ("3BV", board_3bv.to_string()),
+            };
+
+            counters = counters.push(
+                widget::tooltip(
+                    widget::column![
+                        widget::text(label).size(10).color(text_color),
+                        widget::text(value).size(flags_text_size).color(text_color),
+                    ]
+                    .align_x(Alignment::Center),
+                    widget::text(format!("3BV {}, {} clicks", board_3bv, self.clicks)).size(12.0),
+                    widget::tooltip::Position::Bottom,
+                )
+                .padding(5.0)
+                .style(container::rounded_box),
+            );
+        }
+
+        // Once the game is over, the next thing to do is most likely to start a new board, so it's spelled out
+        let face = widget::text(self.face_char()).font(Self::COMMANDS_ICONS);
+        let new_board_content: Element<'_, Message> = match self.game_state {
+            GameState::Stopped { is_won: _ } => {
+                widget::row![face, widget::text(Self::NEW_BOARD_LABEL)]
+                    .spacing(5.0)
+                    .into()
+            }
+            _ => face.into(),
+        };
+
+        widget::row![
+            widget::row![widget::tooltip(
+                widget::button(new_board_content)
+                    .on_press(Message::Reset)
+                    .style(button::primary),
+                widget::text(format!("{} (R)", Self::NEW_BOARD_LABEL)).size(12.0),
+                widget::tooltip::Position::Bottom,
+            )
+            .padding(5.0)
+            .style(container::rounded_box)]
+            .push(
+                self.view_toolbar_button(
+                    Self::RESTART_CHAR,
+                    "Restart the same board",
+                    self.can_restart_same_board()
+                        .then_some(Message::RestartSameBoard),
+                )
+            )
+            .push(
+                self.view_toolbar_button(
+                    Self::UNDO_CHAR,
+                    "Undo",
+                    self.can_undo()
+                        .then_some(Message::Minesweep(MinesweepMessage::Undo)),
+                )
+            )
+            .push(
+                self.view_toolbar_button(
+                    Self::REDO_CHAR,
+                    "Redo",
+                    self.can_redo()
+                        .then_some(Message::Minesweep(MinesweepMessage::Redo)),
+                )
+            )
+            .push(self.view_flagging_mode_toggle())
+            .push(match (&self.playback, self.game_state) {
+                (Some(playback), _) => self.view_playback_controls(playback),
+                (None, GameState::Paused) => self.view_toolbar_button(
+                    Self::RESUME_CHAR,
+                    "Resume (Space)",
+                    Some(Message::TogglePause),
+                ),
+                (None, _) => self.view_toolbar_button(
+                    Self::PAUSE_CHAR,
+                    "Pause (Space)",
+                    matches!(self.game_state, GameState::Running(_))
+                        .then_some(Message::TogglePause),
+                ),
+            })
+            .spacing(5.0)
+            .width(Length::Shrink)
+            .align_y(Alignment::Start),
+            counters
+                .push(widget::horizontal_space())
+                .spacing(20.0)
+                .width(Length::Fill)
+                .align_y(Alignment::Center),
+            widget::row![
+                widget::button(widget::text(Self::SETTINGS_BTN_CHAR).font(Self::MINES_FLAGS_ICONS))
+                    .on_press(Message::Settings(SettingsMessage::Show))
+                    .style(button::primary),
+                widget::button(widget::text(Self::ABOUT_BTN_CHAR).font(Self::COMMANDS_ICONS))
+                    .on_press(Message::Info)
+                    .style(button::primary),
+                widget::button(widget::text(Self::HIGH_SCORES_CHAR).font(Self::COMMANDS_ICONS))
+                    .on_press(Message::HighScores)
+                    .style(button::primary),
+                widget::button(widget::text(Self::STATS_CHAR).font(Self::COMMANDS_ICONS))
+                    .on_press(Message::Stats)
+                    .style(button::primary),
+                widget::button(widget::text(Self::REPLAYS_CHAR).font(Self::COMMANDS_ICONS))
+                    .on_press(Message::Replays)
+                    .style(button::primary),
+                widget::button(widget::text(Self::EDITOR_CHAR).font(Self::COMMANDS_ICONS))
+                    .on_press(Message::Editor(EditorMessage::Show))
+                    .style(button::primary),
+            ]
+            .spacing(10.0)
+            .width(Length::Shrink)
+            .align_y(Alignment::End),
+        ]
+        .padding(10.0)
+        .spacing(10.0)
+        .align_y(Alignment::Center)
+        .width(Length::Fill)
+        .into()
+    }
+
+    /// Toolbar buttons controlling the playback of a replay, in place of the pause button
+    fn view_playback_controls(&self, playback: &Playback) -> Element<'_, Message> {
+        let playing = !playback.is_finished();
+        let pause = if playback.paused {
+            self.view_toolbar_button(
+                Self::RESUME_CHAR,
+                "Resume the replay (Space)",
+                playing.then_some(Message::Playback(PlaybackMessage::TogglePause)),
+            )
+        } else {
+            self.view_toolbar_button(
+                Self::PAUSE_CHAR,
+                "Pause the replay (Space)",
+                playing.then_some(Message::Playback(PlaybackMessage::TogglePause)),
+            )
+        };
+
+        widget::row![
+            pause,
+            self.view_toolbar_button(
+                Self::STEP_CHAR,
+                "Play the next move",
+                playing.then_some(Message::Playback(PlaybackMessage::Step)),
+            ),
+            widget::tooltip(
+                widget::button(widget::text(format!("{}×", playback.speed())))
+                    .on_press(Message::Playback(PlaybackMessage::CycleSpeed))
+                    .style(button::secondary),
+                widget::text("Playback speed").size(12.0),
+                widget::tooltip::Position::Bottom,
+            )
+            .padding(5.0)
+            .style(container::rounded_box),
+        ]
+        .spacing(5.0)
+        .into()
+    }
+
+    /// Toggle between stepping on spots and flagging them with the left button and touch taps. The current mode is
+    /// shown by the icon, and the button stands out while flagging.
+    fn view_flagging_mode_toggle(&self) -> Element<'_, Message> {
+        let (icon, label, style): (_, _, fn(&Theme, button::Status) -> button::Style) =
+            match self.flagging_mode {
+                true => (
+                    Self::FLAG_CHAR,
+                    "Flagging: clicks and taps flag spots",
+                    button::primary,
+                ),
+                false => (
+                    Self::MINE_CHAR,
+                    "Revealing: clicks and taps step on spots",
+                    button::secondary,
+                ),
+            };
+
+        widget::tooltip(
+            widget::button(widget::text(icon).font(Self::MINES_FLAGS_ICONS))
+                .on_press(Message::ToggleFlaggingMode)
+                .style(style),
+            widget::text(label).size(12.0),
+            widget::tooltip::Position::Bottom,
+        )
+        .padding(5.0)
+        .style(container::rounded_box)
+        .into()
+    }
+
+    /// Face shown on the new board button: a smiley while the game goes on, which puts sunglasses on once it's won
+    /// and has crossed out eyes once it's lost
+    fn face_char(&self) -> &'static str {
+        match self.game_state {
+            GameState::Ready | GameState::Running(_) | GameState::Paused => Self::PLAYING_FACE_CHAR,
+            GameState::Stopped { is_won: true } => Self::WON_FACE_CHAR,
+            GameState::Stopped { is_won: false } => Self::LOST_FACE_CHAR,
+        }
+    }
+
+    /// A toolbar button with an icon and a tooltip, disabled if there is no `message` to send
+    fn view_toolbar_button(
+        &self,
+        icon: &'static str,
+        label: &'static str,
+        message: Option<Message>,
+    ) -> Element<'_, Message> {
+        widget::tooltip(
+            widget::button(widget::text(icon).font(Self::COMMANDS_ICONS))
+                .on_press_maybe(message)
+                .style(button::secondary),
+            widget::text(label).size(12.0),
+            widget::tooltip::Position::Bottom,
+        )
+        .padding(5.0)
+        .style(container::rounded_box)
+        .into()
+    }
+
+    /// 3BV of the board per second of the game, once the game is over and the 3BV is worked out
+    fn efficiency(&self) -> Option<f64> {
+        let seconds = self.elapsed_seconds.as_secs_f64();
+
+        self.board_3bv
+            .filter(|_| seconds > 0.0)
+            .map(|board_3bv| board_3bv as f64 / seconds)
+    }
+
+    /// As many flags as there are mines are placed in the running game, which isn't cleared, so at least one of them is
+    /// misplaced or spots are left to reveal. The flags are counted on the minefield instead of trusting
+    /// `remaining_flags`.
+    fn all_flags_placed_uncleared(&self) -> bool {
+        matches!(self.game_state, GameState::Running(_))
+            && self.field.flagged_spots().count() as u32 == self.field.mines()
+            && !self.field.is_cleared()
+    }
+
+    /// A move of the player's own game can be undone: while it is running, or once it was lost
+    fn can_undo(&self) -> bool {
+        self.playback.is_none()
+            && self.field.can_undo()
+            && matches!(
+                self.game_state,
+                GameState::Running(_) | GameState::Stopped { is_won: false }
+            )
+    }
+
+    /// An undone move of the player's own game can be taken again, while it is running
+    fn can_redo(&self) -> bool {
+        self.playback.is_none()
+            && self.field.can_redo()
+            && matches!(self.game_state, GameState::Running(_))
+    }
+
+    /// Remaining flags shown as a row of flag icons, with the ones which don't fit summed up as `+N`. Extra flags
+    /// (placed beyond the number of mines) are shown as red, filled flags after a minus sign instead.
+    fn view_flag_icons(&self, color: Color) -> Element<'_, Message> {
+        let count = self.remaining_flags.unsigned_abs();
+        let shown = count.min(Self::FLAG_ICONS_SHOWN);
+        let (flag_char, color) = if self.remaining_flags >= 0 {
+            (Self::FLAG_CHAR, color)
+        } else {
+            (Self::EXTRA_FLAG_CHAR, Self::FLAG_COUNT_ERR_COLOR)
+        };
+
+        let mut icons = widget::row![].spacing(1.0).align_y(Alignment::Center);
+        if count == 0 {
+            icons = icons.push(widget::text("0").size(20).color(color));
+        } else if self.remaining_flags < 0 {
+            icons = icons.push(widget::text("−").size(20).color(color));
+        }
+        for _ in 0..shown {
+            icons = icons.push(
+                widget::text(flag_char)
+                    .font(Self::MINES_FLAGS_ICONS)
+                    .size(16)
+                    .color(color),
+            );
+        }
+        if count > shown {
+            icons = icons.push(
+                widget::text(format!("+{}", count - shown))
+                    .size(14)
+                    .color(color),
+            );
+        }
+
+        icons.into()
+    }
+
+    /// Minefield view
+    fn view_field(&self) -> Element<'_, Message> {
+        let field = self.view_canvas();
+
+        let field: Element<'_, Message> = match &self.announcement {
+            // The status line is drawn in the padding below the minefield, so the window doesn't need to grow for it
+            Some(announcement) => widget::stack![
+                field,
+                widget::container(
+                    widget::text(announcement.as_str())
+                        .size(12.0)
+                        .color(Self::READY_COLOR)
+                )
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .align_x(alignment::Horizontal::Center)
+                .align_y(alignment::Vertical::Bottom)
+                .padding(2.0)
+            ]
+            .into(),
+            None => field,
+        };
+
+        // The summary of a game which just ended is shown over the board, which can still be looked at once it's dismissed
+        let field = if self.shows_game_summary() {
+            widget::stack![field, self.view_game_summary()].into()
+        } else {
+            field
+        };
+
+        if self.generation_warning {
+            let attempts = self.generation.map(|g| g.attempts).unwrap_or_default();
+            let warning = widget::container(
+                widget::row![
+                    widget::text(format!(
+                        "No board without guessing was found in {} attempts. This board may require guessing.",
+                        attempts
+                    ))
+                    .size(12.0)
+                    .color(Self::COLOR_LIGHT_RED)
+                    .width(Length::Fill),
+                    widget::button(widget::text("OK").size(12.0))
+                        .on_press(Message::DismissGenerationWarning)
+                        .style(button::primary),
+                ]
+                .spacing(10.0)
+                .align_y(Alignment::Center),
+            )
+            .padding(5.0)
+            .style(widget::container::rounded_box);
+
+            widget::stack![field, warning].into()
+        } else {
+            field
+        }
+    }
+
+    /// The summary of the game is shown once it's over, until it's dismissed. Undoing the step on a mine gets back to
+    /// the game, without its summary.
+    fn shows_game_summary(&self) -> bool {
+        self.game_summary && matches!(self.game_state, GameState::Stopped { .. })
+    }
+
+    /// Summary of the game which just ended: its result, time and efficiency, and what can be done next
+    fn view_game_summary(&self) -> Element<'_, Message> {
+        let is_won = self.game_state == GameState::Stopped { is_won: true };
+        let (title, color) = match is_won {
+            true => ("Minefield cleared!", Self::WON_COLOR),
+            false => ("Boom, game over", Self::LOST_COLOR),
+        };
+        let millis = self.elapsed_seconds.as_millis() as u64;
+
+        let mut details = widget::column![widget::text(format!(
+            "Time: {}",
+            format_time(millis, self.preferences.precise_times)
+        ))]
+        .spacing(5.0)
+        .align_x(Alignment::Center);
+        if let Some(board_3bv) = self.board_3bv {
+            details = details.push(widget::text(format!(
+                "3BV: {}, in {} clicks",
+                board_3bv, self.clicks
+            )));
+        }
+        if let (true, Some(efficiency)) = (is_won, self.efficiency()) {
+            details = details.push(widget::text(format!("3BV/s: {:.2}", efficiency)));
+        }
+
+        let content = widget::column![
+            widget::text(title)
+                .font(Self::TEXT_FONT)
+                .size(25.0)
+                .color(color),
+            details,
+            // The buttons are laid out in two rows, to fit the summary over the smallest boards
+            widget::row![
+                widget::button("New board")
+                    .on_press(Message::Reset)
+                    .style(button::primary),
+                widget::button("Same board")
+                    .on_press_maybe(
+                        self.can_restart_same_board()
+                            .then_some(Message::RestartSameBoard)
+                    )
+                    .style(button::secondary),
+            ]
+            .spacing(10.0),
+            widget::row![
+                widget::button("High scores")
+                    .on_press(Message::HighScores)
+                    .style(button::secondary),
+                widget::button("Show board")
+                    .on_press(Message::DismissGameSummary)
+                    .style(button::secondary),
+            ]
+            .spacing(10.0),
+        ]
+        .spacing(15.0)
+        .align_x(Alignment::Center);
+
+        container(
+            container(content)
+                .padding(20.0)
+                .style(container::rounded_box),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .into()
+    }
+
+    /// The minefield canvas, which can be scrolled if it doesn't fit in the window. A minimap of the whole minefield is
+    /// then shown over it, in which the minefield can be panned.
+    fn view_canvas(&self) -> Element<'_, Message> {
+        let (field_width, field_height) = self.desired_field_size();
+        let canvas = Canvas::new(self).width(field_width).height(field_height);
+
+        // The scrollable translates the cursor position into the coordinates of the whole canvas, so hit-testing spots
+        // under the cursor still works when the minefield is scrolled
+        if self.is_field_scrollable() {
+            let field = widget::scrollable(canvas)
+                .id(Self::field_scrollable_id())
+                .direction(scrollable::Direction::Both {
+                    vertical: scrollable::Scrollbar::default(),
+                    horizontal: scrollable::Scrollbar::default(),
+                })
+                .on_scroll(|viewport| {
+                    let offset = viewport.absolute_offset();
+
+                    Message::FieldScrolled(Rectangle::new(
+                        Point::new(offset.x, offset.y),
+                        viewport.bounds().size(),
+                    ))
+                })
+                .width(Length::Fill)
+                .height(Length::Fill);
+
+            let palette = self.preferences.color_theme.field_palette();
+            let minimap = Minimap::new(
+                self.shown_field(),
+                self.minimap_viewport(),
+                MinimapColors {
+                    hidden: palette.hidden,
+                    revealed: palette.revealed,
+                    flagged: self.flag_color(true),
+                    viewport: palette.text,
+                },
+            );
+            let minimap_size = minimap.size();
+            let minimap = widget::container(
+                widget::container(
+                    Canvas::new(minimap)
+                        .width(minimap_size.width)
+                        .height(minimap_size.height),
+                )
+                .padding(2.0)
+                .style(container::rounded_box),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(alignment::Horizontal::Right)
+            .align_y(alignment::Vertical::Bottom)
+            // Keep clear of the scrollbars
+            .padding(15.0);
+
+            widget::stack![field, minimap].into()
+        } else {
+            canvas.into()
+        }
+    }
+
+    /// Color of the mines shown on the minefield. With colorblind glyphs, mines are drawn in the color of the text, so
+    /// that they stand out against the hidden spots whatever colors the player can see.
+    fn mine_color(&self) -> Color {
+        if self.preferences.colorblind_glyphs {
+            self.preferences.color_theme.field_palette().text
+        } else {
+            Self::MINE_COLOR
+        }
+    }
+
+    /// Color of the mine which was stepped on, see `mine_color`
+    fn exploded_mine_color(&self) -> Color {
+        if self.preferences.colorblind_glyphs {
+            self.preferences.color_theme.field_palette().text
+        } else {
+            Self::MINE_EXPLODED_COLOR
+        }
+    }
+
+    /// Color of a flag, depending on whether it's known to be `correct`. With colorblind glyphs, all the flags have the
+    /// color of the text, and wrong flags are crossed out instead.
+    fn flag_color(&self, correct: bool) -> Color {
+        if self.preferences.colorblind_glyphs {
+            self.preferences.color_theme.field_palette().text
+        } else if correct {
+            Self::FLAG_COLOR_CORRECT
+        } else {
+            Self::FLAG_COLOR_WRONG
+        }
+    }
+
+    /// Id of the scrollable holding the minefield canvas, which is scrolled when the minefield is panned in the minimap
+    fn field_scrollable_id() -> scrollable::Id {
+        scrollable::Id::new("minefield")
+    }
+
+    /// Size of the grid of spots of the minefield, without the padding around it
+    fn grid_size(&self) -> Size {
+        Size::new(
+            self.spot_size * self.shown_field().width() as f32,
+            self.spot_size * self.shown_field().height() as f32,
+        )
+    }
+
+    /// The part of the grid of spots which is shown when the minefield is scrolled, as fractions of the grid size
+    fn minimap_viewport(&self) -> Option<Rectangle> {
+        let viewport = self.field_viewport?;
+        let grid = self.grid_size();
+
+        let left = ((viewport.x - Self::FIELD_PAD) / grid.width).clamp(0.0, 1.0);
+        let top = ((viewport.y - Self::FIELD_PAD) / grid.height).clamp(0.0, 1.0);
+        let right = ((viewport.x + viewport.width - Self::FIELD_PAD) / grid.width).clamp(0.0, 1.0);
+        let bottom =
+            ((viewport.y + viewport.height - Self::FIELD_PAD) / grid.height).clamp(0.0, 1.0);
+
+        Some(Rectangle::new(
+            Point::new(left, top),
+            Size::new(right - left, bottom - top),
+        ))
+    }
+
+    /// Board editor view
+    fn view_editor<'a>(&'a self, editor: &'a BoardEditor) -> Element<'a, Message> {
+        let game_config = editor.game_config();
+        let status = match &editor.error {
+            Some(error) => widget::text(error.as_str())
+                .size(12.0)
+                .color(Self::COLOR_LIGHT_RED),
+            None => widget::text(format!(
+                "{}x{}, {} mines. Click a spot to place or remove a mine.",
+                game_config.width, game_config.height, game_config.mines
+            ))
+            .size(12.0)
+            .color(Self::READY_COLOR),
+        };
+
+        let bar = widget::column![
+            widget::row![
+                widget::button("Clear")
+                    .on_press(Message::Editor(EditorMessage::Clear))
+                    .style(button::primary),
+                widget::pick_list(editor.saved.as_slice(), editor.selected.as_ref(), |name| {
+                    Message::Editor(EditorMessage::Load(name))
+                })
+                .placeholder("Load board"),
+                widget::button("Save")
+                    .on_press(Message::Editor(EditorMessage::Save))
+                    .style(button::primary),
+                widget::horizontal_space(),
+                widget::button("Cancel")
+                    .on_press(Message::Editor(EditorMessage::Show))
+                    .style(button::primary),
+                widget::button("Play")
+                    .on_press(Message::Editor(EditorMessage::Play))
+                    .style(button::primary),
+            ]
+            .spacing(10.0)
+            .align_y(Alignment::Center),
+            widget::row![
+                widget::text_input(
+                    "Board file, with the JSON of a saved board or a grid of '*' and '.'",
+                    &editor.file_path
+                )
+                .on_input(|path| Message::Editor(EditorMessage::FilePath(path)))
+                .on_submit(Message::Editor(EditorMessage::PlayFile)),
+                widget::button("Play file")
+                    .on_press(Message::Editor(EditorMessage::PlayFile))
+                    .style(button::primary),
+            ]
+            .spacing(10.0)
+            .align_y(Alignment::Center),
+            status,
+        ]
+        .spacing(5.0)
+        .padding([0.0, 10.0])
+        .height(Self::EDITOR_BAR_HEIGHT);
+
+        widget::column![self.view_canvas(), bar].into()
+    }
+
+    /// Tutorial view: the tutorial board, with what the player is asked to do below it
+    fn view_tutorial<'a>(&'a self, tutorial: &'a Tutorial) -> Element<'a, Message> {
+        let button = |label, message| {
+            widget::button(label)
+                .on_press(Message::Tutorial(message))
+                .style(button::primary)
+        };
+        let buttons = match tutorial.step() {
+            TutorialStep::Welcome => widget::row![
+                button("Skip", TutorialMessage::Close),
+                button("Start", TutorialMessage::Start),
+            ],
+            TutorialStep::Exploded => widget::row![
+                button("Skip", TutorialMessage::Close),
+                button("Try again", TutorialMessage::Retry),
+            ],
+            TutorialStep::Done => widget::row![button("Play", TutorialMessage::Close)],
+            TutorialStep::Step | TutorialStep::Flag | TutorialStep::AutoStep => {
+                widget::row![button("Skip", TutorialMessage::Close)]
+            }
+        };
+
+        let bar = widget::column![
+            widget::text(tutorial.prompt(&self.active_controls())).size(15.0),
+            widget::row![widget::horizontal_space(), buttons.spacing(10.0)],
+        ]
+        .spacing(10.0)
+        .padding([0.0, 10.0])
+        .height(Self::TUTORIAL_BAR_HEIGHT);
+
+        widget::column![self.view_canvas(), bar].into()
+    }
+
+    /// Saved replays view
+    fn view_replays<'a>(&'a self, entries: &'a [ReplayEntry]) -> Element<'a, Message> {
+        let mut content = widget::column![]
+            .spacing(10)
+            .width(Length::Fill)
+            .padding(20.0);
+        content = content.push(
+            widget::column![widget::text("Replays").font(Self::TEXT_FONT).size(25.0)]
+                .width(Length::Fill)
+                .align_x(Alignment::Center),
+        );
+        content = content.push(widget::horizontal_rule(10.0));
+
+        if entries.is_empty() {
+            content = content.push(
+                widget::text("No saved replays. Finished games are saved as replays, unless disabled in the settings.")
+                    .size(15.0)
+                    .color(Self::READY_COLOR),
+            );
+        }
+
+        let mut list = widget::column![].spacing(10.0);
+
+        for entry in entries {
+            let row = match &entry.header {
+                Ok(header) => widget::row![
+                    widget::column![
+                        widget::text(header.game_difficulty().to_string()).size(15.0),
+                        widget::text(format!(
+                            "{} · {} seconds · {}",
+                            format_timestamp(header.date),
+                            header.seconds,
+                            if header.is_won { "Won" } else { "Lost" }
+                        ))
+                        .size(12.0),
+                    ]
+                    .width(Length::Fill),
+                    widget::button(widget::text("Play").size(15.0))
+                        .on_press(Message::PlayReplay(entry.path.clone()))
+                        .style(button::primary),
+                ],
+                Err(error) => widget::row![
+                    widget::column![
+                        widget::text(
+                            entry
+                                .path
+                                .file_name()
+                                .map(|name| name.to_string_lossy().into_owned())
+                                .unwrap_or_default()
+                        )
+                        .size(15.0)
+                        .color(Self::READY_COLOR),
+                        widget::text(error.to_string())
+                            .size(12.0)
+                            .color(Self::READY_COLOR),
+                    ]
+                    .width(Length::Fill),
+                    widget::button(widget::text("Play").size(15.0)).style(button::secondary),
+                ],
+            };
+
+            list = list.push(row.spacing(20.0).align_y(Alignment::Center));
+        }
+
+        content = content.push(widget::scrollable(list).height(Length::Fill));
+
+        content.into()
+    }
+
+    /// Settings view
+    fn view_settings(&self, settings: &SettingsDraft) -> Element<'_, Message> {
+        let game_difficulty = &settings.game_difficulty;
+
+        // The game difficulty is the most used setting, so it's always shown at the top, regardless of search filter
+        let mut settings_page = widget::column![
+            widget::text("Game Difficulty"),
+            widget::pick_list(
+                DifficultyChoice::all(&self.presets),
+                Some(match &settings.preset {
+                    Some(name) => DifficultyChoice::Preset(Preset {
+                        name: name.clone(),
+                        game_config: (*game_difficulty).into(),
+                    }),
+                    None => DifficultyChoice::Difficulty(*game_difficulty),
+                }),
+                |choice| match choice {
+                    DifficultyChoice::Difficulty(x) =>
+                        Message::Settings(SettingsMessage::Picked(x)),
+                    DifficultyChoice::Preset(preset) => {
+                        Message::Settings(SettingsMessage::PickedPreset(preset.name))
+                    }
+                }
+            )
+        ]
+        .spacing(10.0);
+
+        if let GameDifficulty::Custom(game_config) = game_difficulty {
+            let game_config = *game_config;
+            let width = game_config.width;
+            let height = game_config.height;
+            let density = game_config.mine_density();
+            let config_error = game_config.validate().err().map(|e| e.to_string());
+
+            let custom_game = widget::column![
+                widget::text("Custom Game"),
+                widget::row![
+                    widget::text("Width:"),
+                    widget::text_input("", game_config.width.to_string().as_str()).on_input(
+                        move |s| {
+                            let game_config = match s.parse::<u16>() {
+                                Ok(i) => GameConfig {
+                                    width: i,
+                                    ..game_config
+                                },
+                                Err(_) => game_config,
+                            };
+                            Message::Settings(SettingsMessage::CustomConfig(game_config))
+                        }
+                    )
+                ]
+                .spacing(10.0),
+                widget::row![
+                    widget::text("Height:"),
+                    widget::text_input("", game_config.height.to_string().as_str()).on_input(
+                        move |s| {
+                            let game_config = match s.parse::<u16>() {
+                                Ok(i) => GameConfig {
+                                    height: i,
+                                    ..game_config
+                                },
+                                Err(_) => game_config,
+                            };
+                            Message::Settings(SettingsMessage::CustomConfig(game_config))
+                        }
+                    )
+                ]
+                .spacing(10.0),
+                widget::row![
+                    widget::text("Mines:"),
+                    widget::text_input("", game_config.mines.to_string().as_str()).on_input(
+                        move |s| {
+                            let game_config = match s.parse::<u32>() {
+                                Ok(i) => GameConfig {
+                                    mines: i,
+                                    ..game_config
+                                },
+                                Err(_) => game_config,
+                            };
+                            Message::Settings(SettingsMessage::CustomConfig(game_config))
+                        }
+                    ),
+                    // Boards which are too dense can hardly be played without guessing
+                    widget::text(format!("{:.1}% mines", density * 100.0)).color(
+                        if density > GameConfig::MAX_PLAYABLE_DENSITY {
+                            Self::FLAG_COUNT_ERR_COLOR
+                        } else {
+                            Self::READY_COLOR
+                        }
+                    ),
+                    widget::button(widget::text("Suggest"))
+                        .style(button::secondary)
+                        .on_press(Message::Settings(SettingsMessage::CustomConfig(
+                            GameConfig {
+                                mines: GameConfig::suggested_mines(
+                                    width,
+                                    height,
+                                    GameDifficulty::MEDIUM.mine_density()
+                                ),
+                                ..game_config
+                            }
+                        ))),
+                ]
+                .spacing(10.0)
+                .align_y(Alignment::Center),
+                widget::row![
+                    widget::text_input("Preset name", &settings.preset_name)
+                        .on_input(|s| Message::Settings(SettingsMessage::PresetName(s)))
+                        .on_submit(Message::Settings(SettingsMessage::SavePreset)),
+                    widget::button(widget::text("Save as preset"))
+                        .style(button::secondary)
+                        .on_press(Message::Settings(SettingsMessage::SavePreset)),
+                ]
+                .push_maybe(settings.preset.as_ref().map(|name| {
+                    widget::button(widget::text("Delete preset"))
+                        .style(button::secondary)
+                        .on_press(Message::Settings(SettingsMessage::DeletePreset(
+                            name.clone(),
+                        )))
+                }))
+                .spacing(10.0)
+                .align_y(Alignment::Center),
+            ]
+            .push_maybe(
+                config_error
+                    .clone()
+                    .map(|e| widget::text(e).size(12.0).color(Self::FLAG_COUNT_ERR_COLOR)),
+            )
+            // A preset which could not be saved because of the config is already explained above
+            .push_maybe(
+                settings
+                    .preset_error
+                    .as_ref()
+                    .filter(|&e| Some(e) != config_error.as_ref())
+                    .map(|e| {
+                        widget::text(e.clone())
+                            .size(12.0)
+                            .color(Self::FLAG_COUNT_ERR_COLOR)
+                    }),
+            )
+            .spacing(10.0);
+
+            settings_page = settings_page.push(custom_game);
+        }
+
+        settings_page = settings_page.push(
+            widget::text_input("Search settings", &settings.search)
+                .on_input(|s| Message::Settings(SettingsMessage::Search(s))),
+        );
+
+        let preferences = settings.preferences;
+
+        // The visible labels of each option are matched against the search filter along with its keywords, so they're
+        // recorded while the option is built, and taken along with it once it's pushed
+        let labels = RefCell::new(Vec::<String>::new());
+        let record = |text: &str| {
+            labels.borrow_mut().push(text.to_string());
+            text.to_string()
+        };
+        let label = |text: &str| widget::text(record(text));
+        let toggle =
+            |label: &'static str, is_checked, with_value: fn(Preferences, bool) -> Preferences| {
+                Element::from(
+                    widget::checkbox(record(label), is_checked).on_toggle(move |b| {
+                        Message::Settings(SettingsMessage::Preferences(with_value(preferences, b)))
+                    }),
+                )
+            };
+        let note = |content: String| {
+            Element::from(widget::text(content).size(12.0).color(Self::READY_COLOR))
+        };
+
+        // All options, each in its section, together with the text which is matched against the search filter
+        let mut options: Vec<(SettingsSection, String, Element<'_, Message>)> = vec![];
+        let mut push = |section: SettingsSection, keywords: &str, element| {
+            let text = format!("{} {} {}", section, keywords, labels.take().join(" "));
+            options.push((section, text.to_lowercase(), element));
+        };
+
+        push(
+            SettingsSection::Gameplay,
+            "careful expansion reveal neighbors ring",
+            toggle(
+                "Careful expansion: stepping on a revealed number reveals its hidden neighbors",
+                preferences.careful_expansion,
+                |p, b| Preferences {
+                    careful_expansion: b,
+                    ..p
+                },
+            ),
+        );
+
+        push(
+            SettingsSection::Gameplay,
+            "timer clock start first flag reveal step competitive",
+            widget::column![
+                widget::row![
+                    label("Start the timer on:"),
+                    widget::pick_list(
+                        TimerStart::ALL,
+                        Some(preferences.timer_start),
+                        move |timer_start| {
+                            Message::Settings(SettingsMessage::Preferences(Preferences {
+                                timer_start,
+                                ..preferences
+                            }))
+                        }
+                    ),
+                ]
+                .spacing(10.0)
+                .align_y(Alignment::Center),
+                note(
+                    "On the first step or flag, flags placed before stepping anywhere are timed. On the first reveal, \
+                    they can be placed before the timer starts."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Gameplay,
+            "flag lock unflag remove accident protect",
+            toggle(
+                "Flag lock: removing a flag requires Shift or Ctrl, or a double click",
+                preferences.flag_lock,
+                |p, b| Preferences { flag_lock: b, ..p },
+            ),
+        );
+
+        push(
+            SettingsSection::Gameplay,
+            "auto autostep chord flag assist",
+            widget::column![
+                toggle(
+                    "Autostep around the numbers satisfied by a newly placed flag",
+                    preferences.auto_step_on_flag,
+                    |p, b| Preferences {
+                        auto_step_on_flag: b,
+                        ..p
+                    },
+                ),
+                note(
+                    "A wrong flag will make this step on a mine. Games in which a flag has autostepped are not \
+                    recorded as high scores."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        let generation_note = match self.generation {
+            Some(Generation {
+                attempts,
+                solvable: true,
+            }) => format!("The last board was found after {} attempts.", attempts),
+            Some(Generation {
+                attempts,
+                solvable: false,
+            }) => format!(
+                "No board without guessing was found in {} attempts, so the last board may require guessing.",
+                attempts
+            ),
+            None => "Dense boards may need many attempts, which take longer.".to_string(),
+        };
+        push(
+            SettingsSection::Gameplay,
+            "no guess guessing solvable generation retry attempts budget",
+            widget::column![
+                toggle(
+                    "Generate boards which can be cleared without guessing",
+                    preferences.no_guess,
+                    |p, b| Preferences { no_guess: b, ..p },
+                ),
+                widget::row![
+                    label("Maximum generation attempts:"),
+                    widget::text_input("", preferences.no_guess_attempts.to_string().as_str())
+                        .on_input(move |s| {
+                            let no_guess_attempts = match s.parse::<u32>() {
+                                Ok(i) if i > 0 => i,
+                                _ => preferences.no_guess_attempts,
+                            };
+
+                            Message::Settings(SettingsMessage::Preferences(Preferences {
+                                no_guess_attempts,
+                                ..preferences
+                            }))
+                        })
+                ]
+                .spacing(10.0),
+                note(generation_note),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Gameplay,
+            "open start first step opening area safe",
+            widget::column![
+                toggle(
+                    "Open start: the first step always opens up an area",
+                    preferences.open_start,
+                    |p, b| Preferences { open_start: b, ..p },
+                ),
+                note(
+                    "The mines around the first step are moved elsewhere on the board. Boards too dense to make \
+                    room keep the mines which don't fit, but always move away the one under the first step when \
+                    they can. Boards generated without guessing already start with an opening."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Gameplay,
+            "tutorial help learn how to play first time",
+            widget::row![
+                label("Tutorial: how to play, on a small board"),
+                widget::button("Start the tutorial")
+                    .on_press(Message::Tutorial(TutorialMessage::Show))
+                    .style(button::primary),
+            ]
+            .spacing(10.0)
+            .align_y(Alignment::Center)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Gameplay,
+            "question mark flag cycle unsure",
+            toggle(
+                "Question marks: flagging a flag again marks it with a question mark",
+                preferences.question_marks,
+                |p, b| Preferences {
+                    question_marks: b,
+                    ..p
+                },
+            ),
+        );
+
+        let countdown_limit = settings.game_difficulty.countdown_limit().as_millis() as u64;
+        push(
+            SettingsSection::Gameplay,
+            "countdown count down race timer time limit mode",
+            widget::column![
+                widget::checkbox(
+                    record("Countdown: the time counts down, and the game is lost when it runs out"),
+                    settings.countdown
+                )
+                .on_toggle(|b| Message::Settings(SettingsMessage::Countdown(b))),
+                note(format!(
+                    "The time limit of this difficulty is {}. Countdown games are not recorded as high scores.",
+                    format_time(countdown_limit, false)
+                )),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Gameplay,
+            "safe chord autostep wrong flag mistake protect",
+            widget::column![
+                widget::checkbox(
+                    record("Safe autostep: autostepping does nothing around a number with a wrong flag"),
+                    settings.safe_chord
+                )
+                .on_toggle(|b| Message::Settings(SettingsMessage::SafeChord(b))),
+                note(
+                    "Instead of stepping on a mine which isn't flagged. A refused autostep shows that a flag is wrong, so the game is not recorded as a high score."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Gameplay,
+            "win reveal all empty spots cells flag mines",
+            widget::column![
+                widget::checkbox(
+                    record("Win by revealing all the empty spots, without flagging the mines"),
+                    settings.win_on_all_revealed
+                )
+                .on_toggle(|b| Message::Settings(SettingsMessage::WinOnAllRevealed(b))),
+                note(
+                    "Flagging all the mines once the empty spots are revealed also wins."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Gameplay,
+            "auto flag mines won win final board clean",
+            toggle(
+                "Flag the mines left unflagged once the game is won",
+                preferences.flag_mines_on_win,
+                |p, b| Preferences {
+                    flag_mines_on_win: b,
+                    ..p
+                },
+            ),
+        );
+
+        push(
+            SettingsSection::Gameplay,
+            "strict flags hardcore limit count mines negative",
+            widget::column![
+                widget::checkbox(
+                    record("Strict flags: no more flags can be placed than there are mines"),
+                    settings.strict_flags
+                )
+                .on_toggle(|b| Message::Settings(SettingsMessage::StrictFlags(b))),
+                note(
+                    "Once all the flags are placed, one of them must be removed before flagging another spot."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Gameplay,
+            "confirm risky step mine probability chance threshold misclick assist",
+            widget::column![
+                toggle(
+                    "Confirm steps on spots which are likely to be mines, by stepping again",
+                    preferences.confirm_risky_steps,
+                    |p, b| Preferences {
+                        confirm_risky_steps: b,
+                        ..p
+                    },
+                ),
+                widget::row![
+                    label("Confirm from a mine chance of (%):"),
+                    widget::pick_list(
+                        Preferences::RISKY_STEP_THRESHOLDS,
+                        Some(preferences.risky_step_threshold),
+                        move |risky_step_threshold| {
+                            Message::Settings(SettingsMessage::Preferences(Preferences {
+                                risky_step_threshold,
+                                ..preferences
+                            }))
+                        }
+                    ),
+                ]
+                .spacing(10.0)
+                .align_y(Alignment::Center),
+                note(
+                    "Games in which a step had to be confirmed are not recorded as high scores."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Gameplay,
+            "idle hint nudge suggest safe move stuck beginner pulse assist",
+            widget::column![
+                toggle(
+                    "Suggest a safe spot when no move has been made for a while",
+                    preferences.idle_hint,
+                    |p, b| Preferences { idle_hint: b, ..p },
+                ),
+                widget::row![
+                    label("Suggest after (seconds):"),
+                    widget::pick_list(
+                        Preferences::IDLE_HINT_SECONDS,
+                        Some(preferences.idle_hint_seconds),
+                        move |idle_hint_seconds| {
+                            Message::Settings(SettingsMessage::Preferences(Preferences {
+                                idle_hint_seconds,
+                                ..preferences
+                            }))
+                        }
+                    ),
+                ]
+                .spacing(10.0)
+                .align_y(Alignment::Center),
+                note(
+                    "The suggested spot pulses once, and is certainly safe given the revealed numbers. Games in which \
+                    a spot was suggested are not recorded as high scores."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Gameplay,
+            "orthogonal neighbors diagonal connectivity flood variant rules",
+            widget::column![
+                toggle(
+                    "Orthogonal neighbors only: numbers and flood reveals ignore diagonals",
+                    preferences.orthogonal_neighbors,
+                    |p, b| Preferences {
+                        orthogonal_neighbors: b,
+                        ..p
+                    },
+                ),
+                note(
+                    "Changing this starts a new game. Games with orthogonal neighbors are not recorded as high scores."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Appearance,
+            "theme color colour dark light high contrast palette numbers",
+            widget::row![
+                label("Theme:"),
+                widget::pick_list(
+                    ColorTheme::ALL,
+                    Some(preferences.color_theme),
+                    move |color_theme| {
+                        Message::Settings(SettingsMessage::Preferences(Preferences {
+                            color_theme,
+                            ..preferences
+                        }))
+                    }
+                ),
+            ]
+            .spacing(10.0)
+            .align_y(Alignment::Center)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Appearance,
+            "animate animation speed off slow normal fast",
+            widget::column![
+                widget::row![
+                    label("Animation speed:"),
+                    widget::pick_list(
+                        AnimationSpeed::ALL,
+                        Some(preferences.animation_speed),
+                        move |animation_speed| {
+                            Message::Settings(SettingsMessage::Preferences(Preferences {
+                                animation_speed,
+                                ..preferences
+                            }))
+                        }
+                    ),
+                ]
+                .spacing(10.0)
+                .align_y(Alignment::Center),
+                note(
+                    "Turning animations off makes the board jump straight to its final state, and never keeps it busy."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Accessibility,
+            "announce announcements status screen reader result",
+            widget::column![
+                toggle(
+                    "Announce the result of each action in a status line",
+                    preferences.announcements,
+                    |p, b| Preferences {
+                        announcements: b,
+                        ..p
+                    },
+                ),
+                note(
+                    "E.g. \"Revealed, 3 neighboring mines\", \"Flagged\" or \"Cleared 12 cells\"."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Appearance,
+            "animate animation reveal flood",
+            toggle(
+                "Animate spots revealed by a step",
+                preferences.reveal_animation,
+                |p, b| Preferences {
+                    reveal_animation: b,
+                    ..p
+                },
+            ),
+        );
+
+        push(
+            SettingsSection::Appearance,
+            "flag icons remaining counter toolbar bar",
+            toggle(
+                "Show few remaining flags as flag icons",
+                preferences.flag_icons,
+                |p, b| Preferences { flag_icons: b, ..p },
+            ),
+        );
+
+        push(
+            SettingsSection::Appearance,
+            "remaining cells left safe spots progress counter toolbar",
+            toggle(
+                "Show the number of empty spots left to reveal in the toolbar",
+                preferences.show_remaining_cells,
+                |p, b| Preferences {
+                    show_remaining_cells: b,
+                    ..p
+                },
+            ),
+        );
+
+        let sound_toggle = toggle("Play sound effects", preferences.sound_effects, |p, b| {
+            Preferences {
+                sound_effects: b,
+                ..p
+            }
+        });
+        push(
+            SettingsSection::Appearance,
+            "sound effects audio click explosion fanfare mute",
+            if SoundPlayer::AVAILABLE {
+                sound_toggle
+            } else {
+                widget::column![
+                    sound_toggle,
+                    note("This build has no sound support (see the `sound` feature)".to_string()),
+                ]
+                .spacing(5.0)
+                .into()
+            },
+        );
+
+        push(
+            SettingsSection::Appearance,
+            "seed show share copy toolbar board",
+            toggle(
+                "Show the seed of the board in the toolbar (click it to copy)",
+                preferences.show_seed,
+                |p, b| Preferences { show_seed: b, ..p },
+            ),
+        );
+
+        push(
+            SettingsSection::Appearance,
+            "precise time timer hundredths seconds high scores precision",
+            toggle(
+                "Show final times and high scores with hundredths of a second",
+                preferences.precise_times,
+                |p, b| Preferences {
+                    precise_times: b,
+                    ..p
+                },
+            ),
+        );
+
+        push(
+            SettingsSection::Appearance,
+            "rulers coordinates grid labels columns rows numbers margin",
+            widget::column![
+                toggle(
+                    "Number the columns and rows around the board",
+                    preferences.show_rulers,
+                    |p, b| Preferences {
+                        show_rulers: b,
+                        ..p
+                    },
+                ),
+                note(
+                    "Columns and rows are counted from 0, like the mines of the boards saved from the editor."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Appearance,
+            "dim satisfied numbers flags gray grey",
+            widget::column![
+                toggle(
+                    "Dim the numbers which have enough flags around them",
+                    preferences.dim_satisfied,
+                    |p, b| Preferences {
+                        dim_satisfied: b,
+                        ..p
+                    },
+                ),
+                note(
+                    "This only shows what can already be seen on the board, so games are still recorded as high \
+                    scores."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Accessibility,
+            "colorblind color blind colour glyphs shapes flags mines red green",
+            widget::column![
+                toggle(
+                    "Colorblind glyphs: tell flags and mines apart by their shapes",
+                    preferences.colorblind_glyphs,
+                    |p, b| Preferences {
+                        colorblind_glyphs: b,
+                        ..p
+                    },
+                ),
+                note(
+                    "Wrong flags are crossed out and the mine which was stepped on is outlined, instead of relying on red and green."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Appearance,
+            "highlight exploded mine stepped lost color colour",
+            widget::column![
+                toggle(
+                    "Highlight the mine which was stepped on",
+                    preferences.highlight_exploded,
+                    |p, b| Preferences {
+                        highlight_exploded: b,
+                        ..p
+                    },
+                ),
+                widget::row![
+                    label("Highlight color:"),
+                    widget::pick_list(
+                        HighlightColor::ALL,
+                        Some(preferences.exploded_highlight_color),
+                        move |c| {
+                            Message::Settings(SettingsMessage::Preferences(Preferences {
+                                exploded_highlight_color: c,
+                                ..preferences
+                            }))
+                        }
+                    ),
+                ]
+                .spacing(10.0)
+                .align_y(Alignment::Center),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        let max_size_input =
+            |placeholder: &'static str,
+             value: Option<u32>,
+             with_value: fn(Preferences, Option<u32>) -> Preferences| {
+                widget::text_input(
+                    placeholder,
+                    &value.map(|v| v.to_string()).unwrap_or_default(),
+                )
+                .on_input(move |s| {
+                    let value = if s.trim().is_empty() {
+                        None
+                    } else {
+                        s.trim().parse::<u32>().ok().or(value)
+                    };
+
+                    Message::Settings(SettingsMessage::Preferences(with_value(preferences, value)))
+                })
+                .width(80.0)
+            };
+        let monitor_size = self.monitor_size.unwrap_or(Self::FALLBACK_MONITOR_SIZE);
+        push(
+            SettingsSection::Appearance,
+            "maximum max window size width height screen monitor scroll",
+            widget::column![
+                widget::row![
+                    label("Maximum window size:"),
+                    max_size_input("screen", preferences.max_window_width, |p, v| {
+                        Preferences {
+                            max_window_width: v,
+                            ..p
+                        }
+                    }),
+                    widget::text("x"),
+                    max_size_input("screen", preferences.max_window_height, |p, v| {
+                        Preferences {
+                            max_window_height: v,
+                            ..p
+                        }
+                    }),
+                ]
+                .spacing(10.0)
+                .align_y(Alignment::Center),
+                note(format!(
+                    "Leave empty to fit the screen ({}x{}{}). The window never gets larger than a \
+                    detected screen, and larger boards can be scrolled.",
+                    monitor_size.width,
+                    monitor_size.height,
+                    if self.monitor_size.is_some() {
+                        ""
+                    } else {
+                        ", assumed"
+                    }
+                )),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        let controls_title = match settings.controls_level() {
+            Some(difficulty_level) => format!("Control scheme for {} games:", difficulty_level),
+            None if preferences.controls_per_difficulty => {
+                "Control scheme for custom games:".to_string()
+            }
+            None => "Control scheme:".to_string(),
+        };
+        push(
+            SettingsSection::Controls,
+            "control scheme mouse buttons gnome windows classic chord autostep",
+            widget::column![
+                label(&controls_title),
+                widget::pick_list(ControlScheme::ALL, Some(settings.control_scheme), |x| {
+                    Message::Settings(SettingsMessage::PickedControls(x))
+                }),
+                note(format!(
+                    "Active controls: {}",
+                    ControlScheme::from_config(&self.active_controls())
+                )),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        if let ControlScheme::Custom(controls) = settings.control_scheme {
+            let action_picker =
+                |title, action, with_action: fn(ControlsConfig, SpotAction) -> ControlsConfig| {
+                    widget::row![
+                        label(title),
+                        widget::pick_list(SpotAction::ALL, Some(action), move |a| {
+                            Message::Settings(SettingsMessage::CustomControls(with_action(
+                                controls, a,
+                            )))
+                        })
+                    ]
+                    .spacing(10.0)
+                    .align_y(Alignment::Center)
+                };
+
+            push(
+                SettingsSection::Controls,
+                "custom controls mouse buttons left right middle chord autostep",
+                widget::column![
+                    action_picker("Left button:", controls.left, |c, a| ControlsConfig {
+                        left: a,
+                        ..c
+                    }),
+                    action_picker("Right button:", controls.right, |c, a| ControlsConfig {
+                        right: a,
+                        ..c
+                    }),
+                    action_picker("Middle button:", controls.middle, |c, a| ControlsConfig {
+                        middle: a,
+                        ..c
+                    }),
+                    widget::checkbox(
+                        record("Left + right buttons together autostep"),
+                        controls.left_right_chord
+                    )
+                    .on_toggle(move |b| {
+                        Message::Settings(SettingsMessage::CustomControls(ControlsConfig {
+                            left_right_chord: b,
+                            ..controls
+                        }))
+                    }),
+                ]
+                .spacing(10.0)
+                .into(),
+            );
+        }
+
+        push(
+            SettingsSection::Controls,
+            "middle button click wheel hidden spot accidental flag step",
+            widget::column![
+                widget::row![
+                    label("Middle button over a hidden spot:"),
+                    widget::pick_list(
+                        MiddleOnHidden::ALL,
+                        Some(preferences.middle_on_hidden),
+                        move |middle_on_hidden| {
+                            Message::Settings(SettingsMessage::Preferences(Preferences {
+                                middle_on_hidden,
+                                ..preferences
+                            }))
+                        }
+                    ),
+                ]
+                .spacing(10.0)
+                .align_y(Alignment::Center),
+                note(
+                    "Only applies when the middle button autosteps, which it still does over revealed numbers."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Controls,
+            "step satisfied number chord autostep single button click",
+            toggle(
+                "Stepping on a number with all its flags placed autosteps around it",
+                preferences.step_on_satisfied_autosteps,
+                |p, b| Preferences {
+                    step_on_satisfied_autosteps: b,
+                    ..p
+                },
+            ),
+        );
+
+        push(
+            SettingsSection::Controls,
+            "double click chord autostep number left button interval",
+            widget::column![
+                toggle(
+                    "Double clicking a number with the left button autosteps around it",
+                    preferences.double_click_autosteps,
+                    |p, b| Preferences {
+                        double_click_autosteps: b,
+                        ..p
+                    },
+                ),
+                widget::row![
+                    label("Double click within (milliseconds):"),
+                    widget::pick_list(
+                        Preferences::DOUBLE_CLICK_MILLIS,
+                        Some(preferences.double_click_millis),
+                        move |double_click_millis| {
+                            Message::Settings(SettingsMessage::Preferences(Preferences {
+                                double_click_millis,
+                                ..preferences
+                            }))
+                        }
+                    ),
+                ]
+                .spacing(10.0)
+                .align_y(Alignment::Center),
+                note(
+                    "Only applies when the left button steps. Both clicks must be on a number which was already \
+                    revealed, so stepping on a hidden spot never autosteps."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Controls,
+            "remember controls per each difficulty",
+            toggle(
+                "Remember controls for each difficulty",
+                preferences.controls_per_difficulty,
+                |p, b| Preferences {
+                    controls_per_difficulty: b,
+                    ..p
+                },
+            ),
+        );
+
+        push(
+            SettingsSection::Data,
+            "record high scores custom games standard difficulty",
+            widget::column![
+                toggle(
+                    "Record high scores for custom games which match a standard difficulty",
+                    preferences.custom_games_record_high_scores,
+                    |p, b| Preferences {
+                        custom_games_record_high_scores: b,
+                        ..p
+                    },
+                ),
+                note(
+                    "High scores are only kept for Easy, Medium and Hard. A custom game with the exact width, height \
+                    and mines of one of them is recorded on its high scores only if this is checked."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Data,
+            "rank high scores points score formula time difficulty hints",
+            widget::column![
+                toggle(
+                    "Rank high scores by points instead of time",
+                    preferences.rank_by_points,
+                    |p, b| Preferences {
+                        rank_by_points: b,
+                        ..p
+                    },
+                ),
+                note(
+                    "Points are higher for more mines, denser boards and faster games, and lower for each hint \
+                    given. Games with hints are only recorded when ranking by points."
+                        .to_string()
+                ),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        let max_high_scores = settings.max_high_scores;
+        push(
+            SettingsSection::Data,
+            "high scores kept number count limit slots",
+            widget::column![
+                widget::row![
+                    label("High scores kept per difficulty:"),
+                    widget::text_input("", max_high_scores.to_string().as_str()).on_input(
+                        move |s| {
+                            let max_high_scores = match s.parse::<usize>() {
+                                Ok(i) if (1..=Self::MAX_HIGH_SCORES_PER_LEVEL).contains(&i) => i,
+                                _ => max_high_scores,
+                            };
+
+                            Message::Settings(SettingsMessage::MaxHighScores(max_high_scores))
+                        }
+                    )
+                ]
+                .spacing(10.0),
+                note(format!(
+                    "From 1 to {}. Lowering it deletes the high scores which no longer fit.",
+                    Self::MAX_HIGH_SCORES_PER_LEVEL
+                )),
+            ]
+            .spacing(5.0)
+            .into(),
+        );
+
+        push(
+            SettingsSection::Data,
+            "save replays record playback",
+            toggle(
+                "Save finished games as replays",
+                preferences.save_replays,
+                |p, b| Preferences {
+                    save_replays: b,
+                    ..p
+                },
+            ),
+        );
+
+        // Only show the options (and their sections) which match the search filter
+        options.retain(|(_, text, _)| SettingsSection::matches(text, &settings.search));
+        options.sort_by_key(|(section, _, _)| *section);
+
+        let mut sections = widget::column![].spacing(10.0).padding(5.0);
+
+        if options.is_empty() {
+            sections = sections.push(note(format!("No settings match \"{}\"", settings.search)));
+        }
+
+        let mut current_section = None;
+        for (section, _, element) in options {
+            if current_section != Some(section) {
+                current_section = Some(section);
+                sections = sections
+                    .push(widget::horizontal_rule(10.0))
+                    .push(widget::text(section.to_string()).font(Self::TEXT_FONT));
+            }
+
+            sections = sections.push(element);
+        }
+
+        settings_page = settings_page.push(widget::scrollable(sections).height(Length::Fill));
+
+        widget::column![
+            settings_page.height(Length::Fill).width(Length::Fill),
+            widget::column![widget::row![
+                widget::button("Cancel")
+                    .on_press(Message::Settings(SettingsMessage::Discard))
+                    .style(button::primary),
+                // Custom configs which can't be played can't be applied either
+                widget::button("Apply")
+                    .on_press_maybe(
+                        GameConfig::from(settings.game_difficulty)
+                            .validate()
+                            .is_ok()
+                            .then(|| Message::Settings(SettingsMessage::Set(Box::new(
+                                settings.clone()
+                            ))))
+                    )
+                    .style(button::primary),
+            ]
+            .spacing(10.0)
+            .width(Length::Shrink)
+            .align_y(Alignment::End)]
+            .width(Length::Fill)
+            .align_x(Alignment::End)
+        ]
+        .align_x(Alignment::End)
+        .width(Length::Fill)
+        .spacing(10.0)
+        .padding(Self::FIELD_PAD)
+        .into()
+    }
+
+    /// Info/"About" view
+    fn view_info(&self) -> Element<'_, Message> {
+        let license_text = std::str::from_utf8(Self::LICESE_BYTES).unwrap_or("");
+
+        let content = widget::column![
+            widget::row![widget::text("About").font(Self::TEXT_FONT)],
+            widget::row![widget::text("Copyright (c) 2023 Bogdan Olar").size(15.0)].padding(10),
+            widget::row![
+                widget::text("https://github.com/BogdanOlar/iced-minesweep-rs").size(15.0)
+            ]
+            .padding(10),
+            widget::row![widget::text("Keyboard shortcuts").font(Self::TEXT_FONT)],
+            widget::row![widget::text(Self::KEYBOARD_SHORTCUTS).size(15.0)].padding(10),
+            widget::row![widget::text("License").font(Self::TEXT_FONT)],
+            widget::row![widget::text(license_text).font(Self::TEXT_FONT).size(12.0)].padding(10),
+            widget::column![widget::row![widget::button("Ok")
+                .on_press(Message::Info)
+                .style(button::primary),]
+            .spacing(10.0)
+            .width(Length::Shrink)
+            .align_y(Alignment::End)]
+            .width(Length::Fill)
+            .align_x(Alignment::End)
+            .padding(20.0)
+        ]
+        .align_x(Alignment::Start)
+        .spacing(10);
+
+        widget::column![widget::scrollable(container(content).width(Length::Fill)),]
+            .padding(Self::FIELD_PAD)
+            .into()
+    }
+
+    /// High Scores view
+    fn view_high_scores(&self) -> Element<'_, Message> {
+        let mut content = widget::column![]
+            .spacing(10)
+            .width(Length::Fill)
+            .padding(20.0);
+        content = content.push(
+            widget::column![widget::text("High Scores").font(Self::TEXT_FONT).size(25.0)]
+                .width(Length::Fill)
+                .align_x(Alignment::Center),
+        );
+
+        // Each profile has its own high scores and stats, so that players sharing the game don't mix them up
+        let new_profile_name = self.new_profile_name.trim();
+        let can_add_profile = !new_profile_name.is_empty()
+            && new_profile_name != self.active_profile
+            && !self.profiles.contains_key(new_profile_name);
+        content = content.push(
+            widget::row![
+                widget::text("Profile"),
+                widget::pick_list(
+                    self.profile_names(),
+                    Some(self.active_profile.clone()),
+                    |name| Message::Profile(ProfileMessage::Picked(name))
+                ),
+                widget::button(widget::text("Delete").size(15.0))
+                    .on_press_maybe(
+                        (!self.profiles.is_empty())
+                            .then_some(Message::Profile(ProfileMessage::Delete))
+                    )
+                    .style(button::danger),
+                widget::horizontal_space(),
+                widget::text_input("New profile", &self.new_profile_name)
+                    .on_input(|name| Message::Profile(ProfileMessage::NameChanged(name)))
+                    .on_submit(Message::Profile(ProfileMessage::Add))
+                    .width(200.0),
+                widget::button(widget::text("Add").size(15.0))
+                    .on_press_maybe(
+                        can_add_profile.then_some(Message::Profile(ProfileMessage::Add))
+                    )
+                    .style(button::primary),
+            ]
+            .spacing(10.0)
+            .align_y(Alignment::Center),
+        );
+
+        content = content.push(widget::horizontal_rule(10.0));
+        content = content.push(self.view_daily_challenge(&format_date(now_timestamp())));
+
+        // The games won without any flag are ranked apart, after all those won with flags
+        let boards = [false, true].into_iter().flat_map(|flagless| {
+            DifficultyLevel::ALL
+                .iter()
+                .map(move |difficulty_level| (flagless, *difficulty_level))
+        });
+        for (flagless, difficulty_level) in boards {
+            content = content.push(widget::horizontal_rule(10.0));
+
+            content = content.push(
+                widget::row![widget::text(difficulty_level.high_scores_title(flagless))
+                    .font(Self::TEXT_FONT)]
+                .width(Length::Fill)
+                .align_y(Alignment::Center),
+            );
+
+            let scores =
+                if let Some(scores) = self.high_score_board(flagless).get(&difficulty_level) {
+                    scores
+                } else {
+                    &self.empty_scores
+                };
+
+            // The slots left are shown empty
+            let slots = scores.iter().map(Some).chain(std::iter::repeat(None));
+            for (i, score) in slots.take(self.max_high_scores).enumerate() {
+                let columns = score.map_or_else(Vec::new, |score| {
+                    let location = HighScoreLocation {
+                        difficulty_level,
+                        flagless,
+                        index: i,
+                    };
+
+                    vec![
+                        Self::high_score_column(
+                            widget::text(score.date.map(format_timestamp).unwrap_or_default())
+                                .color(Self::READY_COLOR),
+                        ),
+                        Self::high_score_column(widget::text(
+                            score.time(self.preferences.precise_times),
+                        )),
+                        Self::high_score_column(widget::text(
+                            score
+                                .points
+                                .map(|points| format!("{} pts", points))
+                                .unwrap_or_default(),
+                        )),
+                        widget::row![
+                            widget::button(widget::text("Watch").size(15.0))
+                                .on_press_maybe(score.replay.clone().map(Message::PlayReplay))
+                                .style(button::secondary),
+                            widget::button(widget::text("Rename").size(15.0))
+                                .on_press(Message::HighScore(RecordHighScore::Rename(
+                                    location.clone()
+                                )))
+                                .style(button::secondary),
+                            widget::button(widget::text("Delete").size(15.0))
+                                .on_press(Message::HighScore(RecordHighScore::Delete(location)))
+                                .style(button::danger),
+                        ]
+                        .spacing(10.0)
+                        .into(),
+                    ]
+                });
+
+                content = content.push(Self::high_score_row(i, score, None, columns));
+            }
+        }
+
+        content = content.push(
+            widget::column![widget::row![widget::button("Ok")
+                .on_press(Message::HighScores)
+                .style(button::primary),]
+            .spacing(10.0)
+            .width(Length::Shrink)
+            .align_y(Alignment::End)]
+            .width(Length::Fill)
+            .align_x(Alignment::End)
+            .padding(20.0),
+        );
+
+        widget::column![widget::scrollable(container(content).width(Length::Fill)),]
+            .width(Length::Fill)
+            .padding(Self::FIELD_PAD)
+            .into()
+    }
+
+    /// The daily challenges of the given `YYYY-MM-DD` date, with the fastest wins of each difficulty
+    fn view_daily_challenge(&self, date: &str) -> Element<'_, Message> {
+        let results = self
+            .daily_results
+            .get(date)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let mut content = widget::column![widget::row![
+            widget::text("Daily challenge").font(Self::TEXT_FONT),
+            widget::text(format!("{}, the same boards for everyone", date))
+                .size(15.0)
+                .color(Self::READY_COLOR),
+        ]
+        .spacing(10.0)
+        .align_y(Alignment::Center)]
+        .spacing(10.0);
+
+        for &difficulty_level in DifficultyLevel::ALL {
+            let played = results
+                .iter()
+                .filter(|result| result.difficulty_level == difficulty_level)
+                .count();
+            let mut wins: Vec<&DailyResult> = results
+                .iter()
+                .filter(|result| {
+                    result.difficulty_level == difficulty_level && result.is_won && !result.assisted
+                })
+                .collect();
+            wins.sort_by_key(|result| result.millis);
+
+            let leaderboard = if wins.is_empty() {
+                widget::text(match played {
+                    0 => "Not played yet".to_string(),
+                    1 => "1 game played, no wins yet".to_string(),
+                    n => format!("{} games played, no wins yet", n),
+                })
+                .color(Self::READY_COLOR)
+            } else {
+                widget::text(
+                    wins.iter()
+                        .take(Self::DAILY_LEADERBOARD_LEN)
+                        .enumerate()
+                        .map(|(i, result)| {
+                            format!(
+                                "{}. {} {}",
+                                i + 1,
+                                result.name,
+                                format_time(result.millis, self.preferences.precise_times)
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("   "),
+                )
+            };
+
+            content = content.push(
+                widget::row![
+                    widget::button(widget::text(difficulty_level.to_string()).size(15.0))
+                        .on_press(Message::DailyChallenge(difficulty_level))
+                        .style(button::primary)
+                        .width(90.0),
+                    leaderboard.size(15.0),
+                ]
+                .spacing(20.0)
+                .align_y(Alignment::Center),
+            );
+        }
+
+        content.into()
+    }
+
+    /// View asking the player whether the running game should be thrown away for a new one, or for a restart of the same
+    /// board if `same_board` is set
+    fn view_confirm_reset(&self, same_board: bool) -> Element<'_, Message> {
+        let question = if same_board {
+            "Restart the same board?"
+        } else {
+            "Start a new game?"
+        };
+
+        let content = widget::column![
+            widget::text(question).font(Self::TEXT_FONT).size(25.0),
+            widget::text("The game in progress will be lost.").size(15.0),
+            widget::row![
+                widget::button("No")
+                    .on_press(Message::ConfirmReset(false))
+                    .style(button::secondary),
+                widget::button("Yes")
+                    .on_press(Message::ConfirmReset(true))
+                    .style(button::primary),
+            ]
+            .spacing(10.0)
+            .padding(20.0),
+        ]
+        .spacing(10)
+        .align_x(Alignment::Center);
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .padding(Self::FIELD_PAD)
+            .into()
+    }
+
+    /// Statistics view
+    fn view_stats(&self) -> Element<'_, Message> {
+        let mut content = widget::column![]
+            .spacing(10)
+            .width(Length::Fill)
+            .padding(20.0);
+        content = content.push(
+            widget::column![
+                widget::text("Statistics").font(Self::TEXT_FONT).size(25.0),
+                widget::text(format!("Profile: {}", self.active_profile))
+                    .size(15.0)
+                    .color(Self::READY_COLOR),
+            ]
+            .width(Length::Fill)
+            .align_x(Alignment::Center),
+        );
+
+        let precise_times = self.preferences.precise_times;
+        let none = || "-".to_string();
+
+        for difficulty_level in DifficultyLevel::ALL {
+            content = content.push(widget::horizontal_rule(10.0));
+
+            content = content.push(
+                widget::row![widget::text(difficulty_level.to_string()).font(Self::TEXT_FONT)]
+                    .width(Length::Fill)
+                    .align_y(Alignment::Center),
+            );
+
+            let stats = self
+                .stats
+                .get(difficulty_level)
+                .copied()
+                .unwrap_or_default();
+            let rows = [
+                ("Games played", stats.played.to_string()),
+                ("Games won", stats.won.to_string()),
+                (
+                    "Win rate",
+                    stats
+                        .win_rate()
+                        .map_or_else(none, |rate| format!("{:.0}%", rate)),
+                ),
+                (
+                    "Best time",
+                    stats
+                        .best_millis
+                        .map_or_else(none, |millis| format_time(millis, precise_times)),
+                ),
+                (
+                    "Average time",
+                    stats
+                        .average_millis()
+                        .map_or_else(none, |millis| format_time(millis, precise_times)),
+                ),
+                ("Current win streak", stats.current_streak.to_string()),
+                ("Longest win streak", stats.longest_streak.to_string()),
+            ];
+
+            for (label, value) in rows {
+                content = content.push(
+                    widget::row![
+                        widget::text(label).size(15.0).width(Length::Fill),
+                        widget::text(value).size(15.0),
+                    ]
+                    .width(Length::Fill)
+                    .spacing(40.0)
+                    .align_y(Alignment::End),
+                );
+            }
+        }
+
+        content = content.push(
+            widget::column![widget::row![
+                widget::button("Reset statistics")
+                    .on_press(Message::ResetStats)
+                    .style(button::secondary),
+                widget::button("Ok")
+                    .on_press(Message::Stats)
+                    .style(button::primary),
+            ]
+            .spacing(10.0)
+            .width(Length::Shrink)
+            .align_y(Alignment::End)]
+            .width(Length::Fill)
+            .align_x(Alignment::End)
+            .padding(20.0),
+        );
+
+        widget::column![widget::scrollable(container(content).width(Length::Fill)),]
+            .width(Length::Fill)
+            .padding(Self::FIELD_PAD)
+            .into()
+    }
+
+    /// New high score view, also used to rename a recorded high score if `renaming` is set
+    fn view_record_high_score(
+        &self,
+        hs: HighScoreLocation,
+        name_input_id: &text_input::Id,
+        renaming: bool,
+    ) -> Element<'_, Message> {
+        let mut content = widget::column![]
+            .spacing(10)
+            .width(Length::Fill)
+            .padding(20.0);
+
+        content = content.push(
+            widget::column![widget::text(if renaming {
+                "Rename High Score"
+            } else {
+                "New High Score!"
+            })
+            .font(Self::TEXT_FONT)
+            .size(25.0)]
+            .width(Length::Fill)
+            .align_x(Alignment::Center),
+        );
+
+        content = content.push(widget::horizontal_rule(10.0));
+
+        content = content.push(
+            widget::row![
+                widget::text(hs.difficulty_level.high_scores_title(hs.flagless))
+                    .font(Self::TEXT_FONT)
+            ]
+            .width(Length::Fill)
+            .align_y(Alignment::Center),
+        );
+
+        let scores =
+            if let Some(scores) = self.high_score_board(hs.flagless).get(&hs.difficulty_level) {
+                scores
+            } else {
+                &self.empty_scores
+            };
+
+        // The slots left are shown empty
+        let slots = scores.iter().map(Some).chain(std::iter::repeat(None));
+        for (i, score) in slots.take(self.max_high_scores).enumerate() {
+            let name_input = score.filter(|_| i == hs.index).map(|score| {
+                widget::text_input("Your name", &score.name)
+                    .on_input(move |s| Message::HighScore(RecordHighScore::NameChanged(s)))
+                    .on_submit(Message::HighScore(RecordHighScore::RecordName))
+                    .id(name_input_id.clone())
+                    .into()
+            });
+            let columns = score.map_or_else(Vec::new, |score| {
+                vec![Self::high_score_column(widget::text(
+                    score.time(self.preferences.precise_times),
+                ))]
+            });
+
+            content = content.push(Self::high_score_row(i, score, name_input, columns));
+        }
+
+        widget::column![
+            widget::scrollable(content.width(Length::Fill)).height(Length::Fill),
+            widget::column![widget::row![
+                widget::button("Cancel")
+                    .on_press(Message::HighScore(RecordHighScore::Discard))
+                    .style(button::primary),
+                widget::button("Apply")
+                    .on_press(Message::HighScore(RecordHighScore::RecordName))
+                    .style(button::primary),
+            ]
+            .spacing(10.0)
+            .width(Length::Shrink)
+            .align_y(Alignment::End)]
+            .width(Length::Fill)
+            .align_x(Alignment::End)
+        ]
+        .align_x(Alignment::End)
+        .width(Length::Fill)
+        .spacing(10.0)
+        .padding(Self::FIELD_PAD)
+        .into()
+    }
+
+    /// A row of a high score board for the slot `i`, with the rank and name of its `score` followed by the given
+    /// `columns`, or shown empty. The name can be replaced by another widget, e.g. to edit it.
+    fn high_score_row<'a>(
+        i: usize,
+        score: Option<&'a Score>,
+        name: Option<Element<'a, Message>>,
+        columns: Vec<Element<'a, Message>>,
+    ) -> Element<'a, Message> {
+        let rank = widget::text(format!("# {}. ", i + 1)).size(15.0);
+
+        match score {
+            Some(score) => widget::row![
+                widget::column![rank]
+                    .width(Length::Shrink)
+                    .height(Length::Shrink)
+                    .align_x(Alignment::Start),
+                widget::column![
+                    name.unwrap_or_else(|| widget::text(score.name.as_str()).size(15.0).into())
+                ]
+                .width(Length::Fill)
+                .height(Length::Shrink)
+                .align_x(Alignment::Start),
+            ]
+            .extend(columns)
+            .width(Length::Fill)
+            .spacing(40.0)
+            .align_y(Alignment::Center)
+            .into(),
+            None => widget::row![
+                widget::column![rank.color(Self::READY_COLOR)]
+                    .width(Length::Shrink)
+                    .height(Length::Shrink)
+                    .align_x(Alignment::Start),
+                widget::column![widget::text("Empty").size(15.0).color(Self::READY_COLOR)]
+                    .width(Length::Fill)
+                    .height(Length::Shrink)
+                    .align_x(Alignment::Start),
+                widget::horizontal_space(),
+            ]
+            .width(Length::Fill)
+            .spacing(40.0)
+            .align_y(Alignment::End)
+            .into(),
+        }
+    }
+
+    /// A column of a high score row, aligned to its end
+    fn high_score_column(text: widget::Text<'_>) -> Element<'_, Message> {
+        widget::column![text.size(15.0)]
+            .width(Length::Shrink)
+            .height(Length::Shrink)
+            .align_x(Alignment::End)
+            .into()
+    }
+
+    /// Start a new game with a new minefield, built from the current game config
+    fn start_new_game(&mut self) {
+        self.seed = self.pending_seed.take().unwrap_or_else(rand::random);
+        self.field = Minefield::new(self.game_config.width, self.game_config.height)
+            .with_connectivity(self.preferences.connectivity())
+            .with_safe_chord(self.game_config.safe_chord)
+            .with_win_on_all_revealed(self.game_config.win_on_all_revealed)
+            .with_strict_flags(self.game_config.strict_flags)
+            .with_question_marks(self.preferences.question_marks)
+            .with_mines_seeded(self.game_config.mines, self.seed);
+
+        // A minefield always keeps a spot without a mine, so the config shows the mines which were actually placed
+        self.game_config.mines = self.field.mines();
+
+        self.game_state = GameState::Ready;
+        self.elapsed_seconds = Duration::default();
+        self.remaining_flags = self.game_config.mines as i64;
+        self.reveal_animation = None;
+        self.explosion_animation = None;
+        self.assisted = self.show_probabilities;
+        self.flags_used = false;
+        self.hints = 0;
+        self.clicks = 0;
+        self.board_3bv = None;
+        self.peek = false;
+        self.auto_solving = false;
+        self.edited_board = false;
+        self.daily = None;
+        self.no_guess_pending = self.preferences.no_guess;
+        self.open_start_pending = self.preferences.open_start;
+        self.generation_warning = false;
+        self.game_summary = false;
+        self.recording.clear();
+        self.playback = None;
+        self.saved_replay = None;
+        self.stats_recorded = false;
+        self.armed_step = None;
+        self.idle_since = Duration::default();
+        self.idle_hinted = false;
+        self.hint_pulse = None;
+        self.announcement = None;
+        self.pause_requested = false;
+    }
+
+    /// Time spent playing the current game until `now`, excluding pauses
+    fn game_time(&self, now: Instant) -> Duration {
+        match self.game_state {
+            GameState::Running(since) => {
+                self.elapsed_seconds + now.saturating_duration_since(since)
+            }
+            _ => self.elapsed_seconds,
+        }
+    }
+
+    /// Keep the game timer on the time of the replay which has been played back, since the replay can be played faster or
+    /// slower than it was recorded, or paused
+    fn sync_playback_clock(&mut self, now: Instant) {
+        if let (Some(playback), GameState::Running(since)) = (&self.playback, &mut self.game_state)
+        {
+            self.elapsed_seconds = playback.position();
+            *since = now;
+        }
+    }
+
+    /// Placing a flag autosteps around the numbers it satisfies. When playing back a replay, this is decided by the
+    /// preferences of the recorded game instead of the current ones.
+    fn auto_step_on_flag(&self) -> bool {
+        match &self.playback {
+            Some(playback) => playback.replay.header.auto_step_on_flag,
+            None => self.preferences.auto_step_on_flag,
+        }
+    }
+
+    /// Flags are replaced by question marks before being cleared, as in the game being played or played back
+    fn question_marks(&self) -> bool {
+        match &self.playback {
+            Some(playback) => playback.replay.header.question_marks,
+            None => self.preferences.question_marks,
+        }
+    }
+
+    /// Rebuild the minefield being played with the question marks of the player's preferences, which might have just
+    /// changed
+    fn apply_question_marks(&mut self) {
+        self.field = self
+            .field
+            .clone()
+            .with_question_marks(self.question_marks());
+    }
+
+    /// Save the current game as a replay, if it was not itself played back from a replay. A game which goes on after
+    /// undoing the step on a mine replaces the replay saved when it was lost.
+    fn save_replay(&mut self, is_won: bool) {
+        if self.playback.is_some()
+            || matches!(self.main_view, MainViewContent::Tutorial(_))
+            || !self.preferences.save_replays
+        {
+            return;
+        }
+
+        let replay = Replay {
+            header: ReplayHeader {
+                version: REPLAY_VERSION,
+                game_config: self.game_config,
+                custom_game: self.custom_game,
+                seconds: self.elapsed_seconds.as_secs(),
+                date: now_timestamp(),
+                is_won,
+                auto_step_on_flag: self.preferences.auto_step_on_flag,
+                question_marks: self.preferences.question_marks,
+                orthogonal_neighbors: self.field.connectivity() == Connectivity::Four,
+            },
+            mines: self
+                .field
+                .spots()
+                .filter(|(_, spot)| {
+                    matches!(
+                        spot.state,
+                        SpotState::HiddenMine
+                            | SpotState::FlaggedMine
+                            | SpotState::QuestionedMine
+                            | SpotState::ExplodedMine
+                    )
+                })
+                .map(|(&coords, _)| coords)
+                .collect(),
+            events: self.recording.clone(),
+        };
+
+        // The file is only written by `save_replay_task`, but its path is known right away so that a high score can
+        // refer to it. A replay saved earlier in the same game, before undoing the step on a mine, is replaced.
+        let previous = self.saved_replay.take();
+        let path = replay.new_file_path(&self.replays_dir(), previous.as_deref());
+        self.saved_replay = Some(path.clone());
+        self.unsaved_replay = Some((replay, path, previous));
+    }
+
+    /// Write the replay of the game which just ended to its file in the background, if there's one
+    fn save_replay_task(&mut self) -> Task<Message> {
+        match self.unsaved_replay.take() {
+            Some((replay, path, previous)) => {
+                Task::perform(Self::write_replay(replay, path, previous), |result| {
+                    Message::Persistence(PersistenceMessage::SavedReplay(result))
+                })
+            }
+            None => Task::none(),
+        }
+    }
+
+    /// Write the replay to the file at `path`, then remove the file of the replay it replaces, if any
+    async fn write_replay(
+        replay: Replay,
+        path: PathBuf,
+        previous: Option<PathBuf>,
+    ) -> Result<(), String> {
+        replay
+            .save_to(&path)
+            .map_err(|e| format!("Could not save replay {:?}: {}", path, e))?;
+
+        match previous {
+            Some(previous) => std::fs::remove_file(&previous)
+                .map_err(|e| format!("Could not remove replay {:?}: {}", previous, e)),
+            None => Ok(()),
+        }
+    }
+
+    /// Directory of the saved replays, next to the file of the game configs
+    fn replays_dir(&self) -> PathBuf {
+        self.files_dir().join(REPLAYS_DIR)
+    }
+
+    /// Directory of the saved boards, next to the file of the game configs
+    fn boards_dir(&self) -> PathBuf {
+        self.files_dir().join(BOARDS_DIR)
+    }
+
+    /// Directory of the game's files, which is the game's config directory unless another file was given for the game
+    /// configs
+    fn files_dir(&self) -> PathBuf {
+        self.persistence_path
+            .parent()
+            .map(PathBuf::from)
+            .unwrap_or_default()
+    }
+
+    /// Game time left before a countdown game is lost, or `None` in classic games
+    fn time_left(&self) -> Option<Duration> {
+        match self.game_config.mode {
+            GameMode::Classic => None,
+            GameMode::Countdown { limit } => Some(limit.saturating_sub(self.elapsed_seconds)),
+        }
+    }
+
+    /// Resize the window to fit the current minefield
+    fn resize_window(&self) -> Task<Message> {
+        match self.resize_target() {
+            Some((window_id, size)) => iced_runtime::window::resize(window_id, size),
+            None => Task::none(),
+        }
+    }
+
+    /// The window to resize, and the size which fits the current minefield. There's none before the id of the window
+    /// is known, or when the game is embedded in another application, which owns the window.
+    fn resize_target(&self) -> Option<(window::Id, Size)> {
+        let (width, height) = self.desired_window_size();
+
+        self.window_id
+            .filter(|_| self.resizes_window)
+            .map(|window_id| (window_id, Size { width, height }))
+    }
+
+    /// Replace the minefield with one which can be cleared without guessing when stepping first on the given spot,
+    /// keeping the flags which have already been placed
+    fn generate_no_guess_field(&mut self, x: u16, y: u16) {
+        let flags: Vec<(u16, u16)> = self
+            .field
+            .flagged_spots()
+            .map(|(coords, _)| coords)
+            .collect();
+
+        let (field, generation) = Minefield::new(self.game_config.width, self.game_config.height)
+            .with_connectivity(self.field.connectivity())
+            .with_safe_chord(self.game_config.safe_chord)
+            .with_win_on_all_revealed(self.game_config.win_on_all_revealed)
+            .with_strict_flags(self.game_config.strict_flags)
+            .with_question_marks(self.question_marks())
+            .with_mines_solvable_seeded(
+                self.game_config.mines,
+                (x, y),
+                self.preferences.no_guess_attempts,
+                self.seed,
+            );
+
+        self.field = field;
+        for (fx, fy) in flags {
+            self.field.toggle_flag(fx, fy);
+        }
+
+        // The first step is kept free of mines, which can leave room for fewer of them than the config asks for
+        self.game_config.mines = self.field.mines();
+        self.count_flags();
+
+        self.no_guess_pending = false;
+        self.generation = Some(generation);
+        self.generation_warning = !generation.solvable;
+    }
+
+    /// Count the flags which still need to be placed, after flags have been put back or removed by undoing moves
+    fn count_flags(&mut self) {
+        let flags = self.field.flagged_spots().count();
+
+        self.remaining_flags = self.field.mines() as i64 - flags as i64;
+    }
+
+    /// Handle switching game state from `Ready` to `Running`, before the given move is taken. The game starts on its
+    /// first move, or on its first step (see `TimerStart`), and its board is settled on its first step, which may come
+    /// after flags were placed.
+    fn check_ready_to_running(&mut self, message: MinesweepMessage) {
+        if let MinesweepMessage::Step { x, y } = message {
+            if self.no_guess_pending || self.open_start_pending {
+                self.on_first_step(x, y);
+            }
+        }
+
+        let starts_timer = match self.preferences.timer_start {
+            TimerStart::FirstAction => true,
+            TimerStart::FirstReveal => matches!(message, MinesweepMessage::Step { .. }),
+        };
+        if self.game_state == GameState::Ready && starts_timer {
+            self.on_first_move();
+        }
+    }
+
+    /// Start the game, which happens once per game, on its first move
+    fn on_first_move(&mut self) {
+        self.elapsed_seconds = Duration::default();
+        self.game_state = GameState::Running(Instant::now());
+
+        log::debug!(
+            "Game started on a {}x{} board with {} mines, seed {}",
+            self.field.width(),
+            self.field.height(),
+            self.field.mines(),
+            self.seed
+        );
+    }
+
+    /// Settle the board around the first step of the game, which happens once per game: the mines are moved so that
+    /// the board can be cleared without guessing, or so that the step opens it up, if the player asked for either
+    fn on_first_step(&mut self, x: u16, y: u16) {
+        if self.no_guess_pending {
+            self.generate_no_guess_field(x, y);
+        } else if self.open_start_pending {
+            self.field.ensure_open_start_seeded(x, y, self.seed);
+            self.open_start_pending = false;
+        }
+    }
+
+    /// Handle game over
+    fn game_over(&mut self, is_won: bool) {
+        // Account for the time since the last tick, so that the final time is exact
+        self.elapsed_seconds = self.game_time(Instant::now());
+        self.game_state = GameState::Stopped { is_won };
+        self.auto_solving = false;
+        self.save_replay(is_won);
+
+        // Working out the 3BV looks at every spot, which takes too long on the largest boards
+        let spot_count = self.field.width() as u32 * self.field.height() as u32;
+        self.board_3bv = (spot_count <= Self::MAX_3BV_SPOTS).then(|| self.field.board_3bv());
+
+        // An application embedding the game is told about the games played in it, not about the replays watched or the
+        // tutorial
+        if self.playback.is_none() && !matches!(self.main_view, MainViewContent::Tutorial(_)) {
+            self.game_summary = true;
+
+            let game_config = self.game_config;
+            let millis = self.elapsed_seconds.as_millis() as u64;
+
+            self.events.push(if is_won {
+                GameEvent::Won {
+                    game_config,
+                    millis,
+                }
+            } else {
+                GameEvent::Lost {
+                    game_config,
+                    millis,
+                }
+            });
+        }
+
+        if is_won {
+            // A minefield won without flagging all its mines shows them flagged, like a minefield won by flagging them
+            if self.preferences.flag_mines_on_win && self.field.flag_remaining_mines() > 0 {
+                self.remaining_flags = 0;
+                self.redraw_field();
+            }
+
+            self.announce(format!(
+                "Minefield cleared, you won in {}",
+                format_time(self.elapsed_seconds.as_millis() as u64, false)
+            ));
+            self.play_sound(Sound::Win);
+        } else {
+            self.announce("Boom, game over".to_string());
+            self.play_sound(Sound::Explosion);
+            self.animate_explosion();
+        }
+
+        // Daily challenges have results of their own, kept apart from the statistics and high scores since their boards
+        // can be played again once they're known. Only the first game of each profile on each of them is recorded, so
+        // that a board whose mines are known can't be won again for a better time, even if that game was assisted.
+        if let (Some((date, difficulty_level)), false) = (&self.daily, self.stats_recorded) {
+            let results = self.daily_results.entry(date.clone()).or_default();
+            let played = results.iter().any(|result| {
+                result.difficulty_level == *difficulty_level && result.name == self.active_profile
+            });
+
+            if !played && self.playback.is_none() {
+                results.push(DailyResult {
+                    difficulty_level: *difficulty_level,
+                    name: self.active_profile.clone(),
+                    is_won,
+                    millis: self.elapsed_seconds.as_millis() as u64,
+                    assisted: self.assisted || self.hints > 0,
+                });
+                self.stats_recorded = true;
+
+                // Only the results of the last days are kept
+                while self.daily_results.len() > Self::MAX_DAILY_DAYS {
+                    self.daily_results.pop_first();
+                }
+            }
+        }
+
+        // Only the player's own unassisted games on a standard difficulty, played by the classic rules, are counted in
+        // the statistics, once each, even if a step on a mine is undone and the game goes on
+        if !self.stats_recorded
+            && !self.assisted
+            && self.hints == 0
+            && self.daily.is_none()
+            && !self.edited_board
+            && self.playback.is_none()
+            && self.field.connectivity() == Connectivity::Eight
+            && self.game_config.has_classic_rules()
+        {
+            if let Some(difficulty_level) = self
+                .game_difficulty()
+                .high_score_level(self.preferences.custom_games_record_high_scores)
+            {
+                self.stats
+                    .entry(difficulty_level)
+                    .or_default()
+                    .record(is_won, self.elapsed_seconds.as_millis() as u64);
+                self.stats_recorded = true;
+            }
+        }
+
+        // Games won with the help of an assist, played back from a replay, or played with variant rules are not recorded
+        // as high scores. Hints are only allowed when they lower the points the game is ranked by. Games won without
+        // any flag are recorded on high scores of their own, which are the only ones games won by revealing all the
+        // empty spots are ranked on, since those can only be won without flags by that rule.
+        let flagless = !self.flags_used;
+        let ranked_rules = if flagless {
+            GameConfig {
+                win_on_all_revealed: false,
+                ..self.game_config
+            }
+            .has_classic_rules()
+        } else {
+            self.game_config.has_classic_rules()
+        };
+
+        if is_won
+            && !self.assisted
+            && (self.hints == 0 || self.preferences.rank_by_points)
+            && !self.edited_board
+            && self.daily.is_none()
+            && self.playback.is_none()
+            && self.field.connectivity() == Connectivity::Eight
+            && ranked_rules
+        {
+            let seconds = self.elapsed_seconds.as_secs();
+            let millis = Some(self.elapsed_seconds.as_millis() as u64);
+
+            if let Some(difficulty_level) = self
+                .game_difficulty()
+                .high_score_level(self.preferences.custom_games_record_high_scores)
+            {
+                if let Some(index) = self.insert_high_score(
+                    difficulty_level,
+                    flagless,
+                    Score {
+                        seconds,
+                        millis,
+                        name: String::new(),
+                        date: Some(now_timestamp()),
+                        points: Some(Score::points_for(
+                            self.field.width() as u32 * self.field.height() as u32,
+                            self.field.mines(),
+                            self.elapsed_seconds.as_millis() as u64,
+                            self.hints,
+                        )),
+                        replay: self.saved_replay.clone(),
+                    },
+                ) {
+                    self.main_view = MainViewContent::EnterHighScore(
+                        HighScoreLocation {
+                            difficulty_level,
+                            flagless,
+                            index,
+                        },
+                        text_input::Id::unique(),
+                        None,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Try to insert a high score for the given difficulty, on its flagless high scores if `flagless`, and return the
+    /// vector index if insertion was successful.
+    fn insert_high_score(
+        &mut self,
+        difficulty_level: DifficultyLevel,
+        flagless: bool,
+        score: Score,
+    ) -> Option<usize> {
+        let max_high_scores = self.max_high_scores;
+        let by_points = self.preferences.rank_by_points;
+        let high_scores = self.high_score_board_mut(flagless);
+
+        if let Some(scores) = high_scores.get_mut(&difficulty_level) {
+            // The score goes before the first one it beats, or after the last one if there's room left
+            let insert_index = scores
+                .iter()
+                .take(max_high_scores)
+                .position(|s| score.rank(s, by_points) == Ordering::Less)
+                .unwrap_or(scores.len());
+            if insert_index >= max_high_scores {
+                return None;
+            }
+
+            scores.insert(insert_index, score);
+            scores.truncate(max_high_scores);
+
+            Some(insert_index)
+        } else {
+            high_scores.insert(difficulty_level, vec![score]);
+            Some(0)
+        }
+    }
+
+    /// The high scores of each difficulty level, either of the games won with flags or of those won without any
+    fn high_score_board(&self, flagless: bool) -> &BTreeMap<DifficultyLevel, Vec<Score>> {
+        if flagless {
+            &self.flagless_high_scores
+        } else {
+            &self.high_scores
+        }
+    }
+
+    fn high_score_board_mut(
+        &mut self,
+        flagless: bool,
+    ) -> &mut BTreeMap<DifficultyLevel, Vec<Score>> {
+        if flagless {
+            &mut self.flagless_high_scores
+        } else {
+            &mut self.high_scores
+        }
+    }
+
+    /// Make the profile with the given name the active one, keeping the high scores and stats of the profile which was
+    /// active. A profile which doesn't exist yet starts without any.
+    fn switch_profile(&mut self, name: String) {
+        let profile = self.profiles.remove(&name).unwrap_or_default();
+        let previous = std::mem::replace(&mut self.active_profile, name);
+
+        self.profiles.insert(
+            previous,
+            ProfileData {
+                high_scores: std::mem::replace(&mut self.high_scores, profile.high_scores),
+                flagless_high_scores: std::mem::replace(
+                    &mut self.flagless_high_scores,
+                    profile.flagless_high_scores,
+                ),
+                stats: std::mem::replace(&mut self.stats, profile.stats),
+            },
+        );
+    }
+
+    /// Names of all the player profiles, in alphabetical order
+    fn profile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.profiles.keys().cloned().collect();
+        names.push(self.active_profile.clone());
+        names.sort();
+
+        names
+    }
+
+    /// Sort the high scores of each difficulty level of every profile by the ranking the player chose, and only keep as
+    /// many as the player wants, after either was changed
+    fn rank_high_scores(&mut self) {
+        let max_high_scores = self.max_high_scores;
+        let by_points = self.preferences.rank_by_points;
+        let profiles = self
+            .profiles
+            .values_mut()
+            .flat_map(|p| [&mut p.high_scores, &mut p.flagless_high_scores]);
+
+        for scores in [&mut self.high_scores, &mut self.flagless_high_scores]
+            .into_iter()
+            .chain(profiles)
+            .flat_map(|high_scores| high_scores.values_mut())
+        {
+            scores.sort_by(|a, b| a.rank(b, by_points));
+            scores.truncate(max_high_scores);
+        }
+    }
+
+    /// Start the daily challenge of the given difficulty and `YYYY-MM-DD` date, which replaces the player's config for
+    /// the time of the game. Its board only depends on the date and difficulty, so that every player gets the same one.
+    fn start_daily_challenge(
+        &mut self,
+        difficulty_level: DifficultyLevel,
+        date: &str,
+    ) -> Task<Message> {
+        let own_config = self
+            .own_config
+            .take()
+            .unwrap_or((self.game_config, self.custom_game));
+        self.own_config = Some(own_config);
+        self.game_config = difficulty_level.game_config();
+        self.custom_game = false;
+        self.pending_seed = Some(Self::daily_seed(date, difficulty_level));
+        self.start_new_game();
+
+        // The mines are not moved around the first step, which differs from one player to the next
+        self.no_guess_pending = false;
+        self.open_start_pending = false;
+        self.daily = Some((date.to_string(), difficulty_level));
+        self.main_view = MainViewContent::Game;
+        self.redraw_field();
+
+        self.resize_window()
+    }
+
+    /// Seed of the daily challenge of the given difficulty and `YYYY-MM-DD` date
+    fn daily_seed(date: &str, difficulty_level: DifficultyLevel) -> u64 {
+        // FNV-1a, which unlike the hashers of the standard library gives the same seed on every platform and version
+        format!("{} {}", date, difficulty_level)
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+
+    /// Show the tutorial, on its own board which replaces the player's config for the time of the tutorial. The
+    /// tutorial games are neither counted in the statistics nor recorded as high scores.
+    fn start_tutorial(&mut self, tutorial: Tutorial) -> Task<Message> {
+        let own_config = self
+            .own_config
+            .take()
+            .unwrap_or((self.game_config, self.custom_game));
+        self.own_config = Some(own_config);
+        self.game_config = Tutorial::game_config();
+        self.custom_game = true;
+        self.start_new_game();
+
+        self.field = Tutorial::field().with_question_marks(self.preferences.question_marks);
+        self.no_guess_pending = false;
+        self.open_start_pending = false;
+        self.edited_board = true;
+        self.main_view = MainViewContent::Tutorial(tutorial);
+        self.redraw_field();
+
+        self.resize_window()
+    }
+
+    /// Start a new game and show it
+    fn reset_game(&mut self) {
+        // A new board uses the same settings as the player's last game, not those of a watched replay
+        if let Some((game_config, custom_game)) = self.own_config.take() {
+            self.game_config = game_config;
+            self.custom_game = custom_game;
+        }
+
+        self.start_new_game();
+        self.main_view = MainViewContent::Game;
+
+        self.redraw_field();
+    }
+
+    /// The board being played can be restarted once its mines are laid out for good, which is after the first step of a
+    /// game. Replays are watched as they were played, so their boards can't be restarted.
+    fn can_restart_same_board(&self) -> bool {
+        self.playback.is_none() && !matches!(self.game_state, GameState::Ready)
+    }
+
+    /// Start the board being played again, with its mines where they are, keeping its seed. Since the player already
+    /// knows where some of the mines are, the game is assisted, so it's not recorded as a high score.
+    fn restart_same_board(&mut self) {
+        let mines: Vec<(u16, u16)> = self.field.mine_spots().map(|(coords, _)| coords).collect();
+        let connectivity = self.field.connectivity();
+        let (width, height) = (self.field.width(), self.field.height());
+        let edited_board = self.edited_board;
+        let daily = self.daily.take();
+
+        self.pending_seed = Some(self.seed);
+        self.start_new_game();
+
+        self.field = Minefield::new(width, height)
+            .with_connectivity(connectivity)
+            .with_safe_chord(self.game_config.safe_chord)
+            .with_win_on_all_revealed(self.game_config.win_on_all_revealed)
+            .with_strict_flags(self.game_config.strict_flags)
+            .with_question_marks(self.preferences.question_marks)
+            .with_mines_at(&mines);
+        self.remaining_flags = self.field.mines() as i64;
+        self.no_guess_pending = false;
+        self.open_start_pending = false;
+        self.edited_board = edited_board;
+        self.daily = daily;
+        self.assisted = true;
+        self.main_view = MainViewContent::Game;
+
+        self.redraw_field();
+    }
+
+    /// Make the next certain move of the auto-solver, like the player would, or stop auto-solving and report it if the
+    /// next move would have to be a guess
+    fn auto_solve_move(&mut self) -> Task<Message> {
+        let message = match self.field.next_certain_move() {
+            Some(SolverMove::Step { x, y }) => MinesweepMessage::Step { x, y },
+            Some(SolverMove::Flag { x, y }) => MinesweepMessage::Flag { x, y },
+            None => {
+                self.auto_solving = false;
+
+                // The report is shown even without announcements, since the player is waiting for the solver
+                self.announcement =
+                    Some("Auto-solving stopped: the next move is a guess".to_string());
+
+                return Task::none();
+            }
+        };
+
+        // The moves are made for the player, so the game isn't scored
+        self.assisted = true;
+
+        self.update(Message::Minesweep(message))
+    }
+
+    /// Pause the game, if it is running
+    fn pause_game(&mut self) {
+        if let GameState::Running(i) = self.game_state {
+            let now = Instant::now();
+            self.elapsed_seconds += now - i;
+            self.game_state = GameState::Paused
+        }
+    }
+
+    /// Resume the game, if it is paused (unless the player paused it themselves)
+    fn resume_game(&mut self) {
+        if let (GameState::Paused, false) = (self.game_state, self.pause_requested) {
+            self.game_state = GameState::Running(Instant::now())
+        }
+    }
+
+    /// How long the banner telling why the configs could not be loaded or saved is shown
+    const PERSISTENCE_ERROR_TIME: Duration = Duration::from_secs(10);
+
+    /// Name of the directory holding the game's files, in the config directory of the platform
+    const CONFIG_DIR_NAME: &'static str = "iced-minesweep-rs";
+
+    /// Path of the file holding the game config and high scores, in the config directory of the platform
+    fn default_persistence_path() -> PathBuf {
+        Self::config_dir(|name| std::env::var_os(name))
+            .unwrap_or_default()
+            .join(Self::persistence_file_name())
+    }
+
+    /// Path of the file holding the game config and high scores in older versions, in the working directory
+    fn legacy_persistence_path() -> PathBuf {
+        PathBuf::from(Self::persistence_file_name())
+    }
+
+    fn persistence_file_name() -> String {
+        Self::APP_NAME.to_owned() + ".json"
+    }
+
+    /// The directory of the game's files in the config directory of the platform, found from the environment variables
+    /// looked up by name with `var`: `$XDG_CONFIG_HOME` (or `~/.config`) on Linux, `%APPDATA%` on Windows and
+    /// `~/Library/Application Support` on macOS. `None` if the config directory can't be found.
+    fn config_dir(var: impl Fn(&str) -> Option<std::ffi::OsString>) -> Option<PathBuf> {
+        let path = |name: &str| {
+            var(name)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        };
+
+        let config_dir = if cfg!(windows) {
+            path("APPDATA")
+        } else if cfg!(target_os = "macos") {
+            path("HOME").map(|home| home.join("Library").join("Application Support"))
+        } else {
+            path("XDG_CONFIG_HOME").or_else(|| path("HOME").map(|home| home.join(".config")))
+        };
+
+        config_dir.map(|dir| dir.join(Self::CONFIG_DIR_NAME))
+    }
+
+    /// Move the file at `legacy` to `path`, unless there's already a file at `path`. Failures are logged, leaving the
+    /// legacy file where it was.
+    fn migrate_persistence(legacy: &std::path::Path, path: &std::path::Path) {
+        if legacy == path || path.exists() || !legacy.exists() {
+            return;
+        }
+
+        // The config directory might be on another file system, so the file is copied instead of renamed
+        let migrated = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::copy(legacy, path))
+            .and_then(|_| std::fs::remove_file(legacy));
+
+        match migrated {
+            Ok(()) => log::info!("Moved configs from {:?} to {:?}", legacy, path),
+            Err(e) => log::warn!("Can't move configs from {:?} to {:?}: {}", legacy, path, e),
+        }
+    }
+
+    /// Path of a temporary file which is written before being renamed over the file at `path`. The saves of a game are
+    /// written one at a time (see `save_persistence_to`), so the temporary file is only named after the process, in case
+    /// another game is saving to the same file.
+    fn temporary_path(path: &std::path::Path) -> PathBuf {
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(format!(".{}.tmp", std::process::id()));
+
+        PathBuf::from(temporary)
+    }
+
+    /// Delete the temporary files left next to the file at `path` by saves which stopped half way through
+    fn remove_temporary_files(path: &std::path::Path) {
+        let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+            return;
+        };
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        let prefix = format!("{}.", name.to_string_lossy());
+
+        for entry in entries.flatten() {
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+            if file_name.starts_with(&prefix) && file_name.ends_with(".tmp") {
+                if let Err(e) = std::fs::remove_file(entry.path()) {
+                    log::warn!("Can't delete {:?}: {}", entry.path(), e);
+                }
+            }
+        }
+    }
+
+    /// Path of the copy of the file at `path` which is made when it can't be parsed, before it's replaced by the next save
+    fn backup_path(path: &std::path::Path) -> PathBuf {
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(".bak");
+
+        PathBuf::from(backup)
+    }
+
+    /// Load the game config and high scores from the file at `path`, or `None` if there's no such file yet. A file which
+    /// can't be parsed is backed up (see `backup_path`) before failing, so that the player's data is never lost.
+    fn load_persistence_from(path: &std::path::Path) -> Result<Option<GamePersistence>, String> {
+        let buf = match std::fs::read(path) {
+            Ok(buf) => buf,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Could not load configs from {:?}: {}", path, e)),
+        };
+
+        match serde_json::from_slice::<GamePersistence>(&buf[..]) {
+            Ok(mut world) => {
+                // Do some high scores sanitizing, for every profile
+                world.max_high_scores = world
+                    .max_high_scores
+                    .clamp(1, Self::MAX_HIGH_SCORES_PER_LEVEL);
+                let by_points = world.preferences.rank_by_points;
+                let profiles = world
+                    .profiles
+                    .values_mut()
+                    .flat_map(|p| [&mut p.high_scores, &mut p.flagless_high_scores]);
+                for scores in [&mut world.high_scores, &mut world.flagless_high_scores]
+                    .into_iter()
+                    .chain(profiles)
+                    .flat_map(|high_scores| high_scores.values_mut())
+                {
+                    scores.sort_by(|a, b| a.rank(b, by_points));
+                    scores.truncate(world.max_high_scores);
+                }
+
+                Ok(Some(world))
+            }
+            Err(e) => {
+                let backup = Self::backup_path(path);
+
+                match std::fs::copy(path, &backup) {
+                    Ok(_) => Err(format!(
+                        "Could not load configs from {:?} ({}), they were backed up to {:?}",
+                        path, e, backup
+                    )),
+                    Err(backup_error) => Err(format!(
+                        "Could not load configs from {:?} ({}), nor back them up: {}",
+                        path, e, backup_error
+                    )),
+                }
+            }
+        }
+    }
+
+    /// The controls which are active for the current game
+    fn active_controls(&self) -> ControlsConfig {
+        let difficulty_level = if self.preferences.controls_per_difficulty {
+            self.game_difficulty().try_into().ok()
+        } else {
+            None
+        };
+
+        controls_for(
+            difficulty_level,
+            &self.difficulty_controls,
+            &self.preferences.controls,
+        )
+    }
+
+    /// A draft of the current settings, to be edited in the settings view, with the given game difficulty picked
+    fn settings_draft(&self, game_difficulty: GameDifficulty) -> SettingsDraft {
+        let mut settings = SettingsDraft {
+            game_difficulty,
+            countdown: matches!(self.game_config.mode, GameMode::Countdown { .. }),
+            safe_chord: self.game_config.safe_chord,
+            win_on_all_revealed: self.game_config.win_on_all_revealed,
+            strict_flags: self.game_config.strict_flags,
+            max_high_scores: self.max_high_scores,
+            control_scheme: ControlScheme::from_config(&self.preferences.controls),
+            preferences: self.preferences,
+            difficulty_controls: self.difficulty_controls.clone(),
+            search: String::new(),
+            preset: None,
+            preset_name: String::new(),
+            preset_error: None,
+        };
+
+        // Show the preset of the current game, if it was picked from one
+        if let GameDifficulty::Custom(game_config) = game_difficulty {
+            if let Some(preset) = self
+                .presets
+                .iter()
+                .find(|preset| preset.game_config == game_config)
+            {
+                settings.preset = Some(preset.name.clone());
+                settings.preset_name = preset.name.clone();
+            }
+        }
+        settings.load_controls();
+
+        settings
+    }
+
+    /// The difficulty of the current game
+    fn game_difficulty(&self) -> GameDifficulty {
+        if self.custom_game {
+            GameDifficulty::Custom(self.game_config)
+        } else {
+            GameDifficulty::from_config(&self.game_config)
+        }
+    }
+
+    /// The game config, high scores and preferences which need to be saved to file
+    fn game_persistence(&self) -> GamePersistence {
+        GamePersistence {
+            game_config: self.saved_config.unwrap_or(
+                self.own_config
+                    .map_or(self.game_config, |(game_config, _)| game_config),
+            ),
+            high_scores: self.high_scores.clone(),
+            flagless_high_scores: self.flagless_high_scores.clone(),
+            max_high_scores: self.max_high_scores,
+            stats: self.stats.clone(),
+            active_profile: self.active_profile.clone(),
+            profiles: self.profiles.clone(),
+            daily_results: self.daily_results.clone(),
+            preferences: self.preferences,
+            difficulty_controls: self.difficulty_controls.clone(),
+            presets: self.presets.clone(),
+        }
+    }
+
+    /// Coordinates of all the spots which have not been revealed yet
+    fn hidden_spots(&self) -> Vec<(u16, u16)> {
+        self.field
+            .spots()
+            .filter(|(_, spot)| {
+                !matches!(
+                    spot.state,
+                    SpotState::RevealedEmpty {
+                        neighboring_mines: _
+                    } | SpotState::ExplodedMine
+                )
+            })
+            .map(|(&coords, _)| coords)
+            .collect()
+    }
+
+    /// Arm a step on a spot which is likely to be a mine instead of taking it, if the player wants to confirm such steps.
+    /// Returns `true` if the step was armed, in which case it's taken by stepping on the same spot again. Any other
+    /// action disarms it.
+    fn arm_risky_step(&mut self, message: MinesweepMessage) -> bool {
+        let probability = match message {
+            MinesweepMessage::Step { x, y }
+                if self.preferences.confirm_risky_steps
+                    && self.playback.is_none()
+                    && self.armed_step != Some((x, y))
+                    && matches!(self.game_state, GameState::Running(_))
+                    && matches!(
+                        self.field.spot(x, y).map(|spot| spot.state),
+                        Some(
+                            SpotState::HiddenEmpty { .. }
+                                | SpotState::HiddenMine
+                                | SpotState::QuestionedEmpty { .. }
+                                | SpotState::QuestionedMine
+                        )
+                    ) =>
+            {
+                self.field
+                    .mine_probability(x, y)
+                    .filter(|&p| p * 100.0 >= self.preferences.risky_step_threshold as f32)
+                    .map(|p| ((x, y), p))
+            }
+            _ => None,
+        };
+
+        match probability {
+            Some((coords, p)) => {
+                // The warning tells the player something about the spot, so it counts as a hint
+                self.hints += 1;
+                self.armed_step = Some(coords);
+                self.announcement = Some(format!(
+                    "Risky step: {:.0}% chance of a mine. Step again to confirm.",
+                    p * 100.0
+                ));
+
+                true
+            }
+            None => {
+                if self.armed_step.take().is_some() && !self.preferences.announcements {
+                    self.announcement = None;
+                }
+
+                false
+            }
+        }
+    }
+
+    /// Announce the result of revealing spots, given the spots which were hidden before. Steps which revealed nothing
+    /// don't change the announcement, and neither does stepping on a mine, which is announced as the end of the game.
+    fn announce_reveal(
+        &mut self,
+        origin: (u16, u16),
+        hidden_spots: &[(u16, u16)],
+        step_result: StepResult,
+    ) {
+        let StepResult::Phew { revealed } = step_result else {
+            return;
+        };
+
+        if revealed > 0 {
+            self.play_sound(Sound::Reveal);
+        }
+
+        match revealed {
+            0 => {}
+            1 if hidden_spots.contains(&origin) => {
+                let state = self.field.spot(origin.0, origin.1).map(|spot| spot.state);
+                let announcement = match state {
+                    Some(SpotState::RevealedEmpty {
+                        neighboring_mines: 1,
+                    }) => "Revealed, 1 neighboring mine".to_string(),
+                    Some(SpotState::RevealedEmpty { neighboring_mines }) => {
+                        format!("Revealed, {} neighboring mines", neighboring_mines)
+                    }
+                    _ => "Cleared 1 cell".to_string(),
+                };
+
+                self.announce(announcement);
+            }
+            // A single spot revealed around the origin, e.g. by an autostep
+            1 => self.announce("Cleared 1 cell".to_string()),
+            _ => self.announce(format!("Cleared {} cells", revealed)),
+        }
+    }
+
+    /// Tell the player why a step or an autostep was not taken, when it's something they can do about. Steps on spots
+    /// which are already revealed are common and speak for themselves, so they are not explained.
+    fn explain_step_not_taken(&mut self, step_result: StepResult) {
+        let StepResult::Invalid(reason) = step_result else {
+            return;
+        };
+
+        match reason {
+            InvalidStep::Flagged => {
+                self.announce("The spot is flagged, remove the flag first".to_string());
+            }
+            InvalidStep::FlagCount {
+                flagged,
+                neighboring_mines,
+            } if flagged < neighboring_mines => {
+                self.announce(format!(
+                    "Place more flags first, {} of {} around this spot",
+                    flagged, neighboring_mines
+                ));
+            }
+            InvalidStep::FlagCount { .. } => {
+                self.announce("Too many flags around this spot".to_string());
+            }
+            // A refused safe autostep tells the player that one of the flags is wrong
+            InvalidStep::WrongFlags => {
+                self.assisted = true;
+                self.announce("Autostep refused, a flag is wrong".to_string());
+            }
+            InvalidStep::OutOfBounds
+            | InvalidStep::AlreadyRevealed
+            | InvalidStep::NotRevealed
+            | InvalidStep::NothingToReveal => {}
+        }
+    }
+
+    /// Replace the announcement of the last action's result, if announcements are enabled
+    fn announce(&mut self, announcement: String) {
+        if self.preferences.announcements && self.playback.is_none() {
+            self.announcement = Some(announcement);
+        }
+    }
+
+    /// Play the given sound effect, if sound effects are turned on. The audio device is only opened for the first one.
+    fn play_sound(&mut self, sound: Sound) {
+        if self.preferences.sound_effects {
+            self.sound.get_or_insert_with(SoundPlayer::new).play(sound);
+        }
+    }
+
+    /// Start animating the mines which are shown once a mine has been stepped on, popping in around the exploded one
+    fn animate_explosion(&mut self) {
+        let Some(speed) = self.preferences.animation_speed.factor() else {
+            return;
+        };
+
+        let Some(origin) = self
+            .field
+            .spots()
+            .find(|(_, spot)| spot.state == SpotState::ExplodedMine)
+            .map(|(&coords, _)| coords)
+        else {
+            // The game was lost without stepping on a mine, e.g. when the time ran out
+            return;
+        };
+
+        let mines = self
+            .field
+            .spots()
+            .filter(|(_, spot)| {
+                matches!(
+                    spot.state,
+                    SpotState::HiddenMine | SpotState::QuestionedMine
+                )
+            })
+            .map(|(&coords, _)| coords)
+            .collect();
+
+        self.explosion_animation = Some(ExplosionAnimation::new(
+            origin,
+            mines,
+            Instant::now(),
+            speed,
+        ));
+    }
+
+    /// The given spots which were hidden before a move and are revealed since
+    fn revealed_among(&self, hidden_spots: Vec<(u16, u16)>) -> Vec<(u16, u16)> {
+        hidden_spots
+            .into_iter()
+            .filter(|&(x, y)| {
+                matches!(
+                    self.field.spot(x, y).map(|spot| spot.state),
+                    Some(SpotState::RevealedEmpty {
+                        neighboring_mines: _
+                    })
+                )
+            })
+            .collect()
+    }
+
+    /// Start animating the spots which were revealed by stepping on the spot at `origin`
+    fn animate_reveal(&mut self, origin: (u16, u16), revealed_spots: &[(u16, u16)]) {
+        // Without animations, the board goes straight to its final state, and is never busy
+        let Some(speed) = self.preferences.animation_speed.factor() else {
+            return;
+        };
+
+        if !self.preferences.reveal_animation {
+            return;
+        }
+
+        // A single revealed spot just shows up
+        if revealed_spots.len() > 1 {
+            self.reveal_animation = Some(RevealAnimation::new(
+                origin,
+                revealed_spots.to_vec(),
+                Instant::now(),
+                speed,
+            ));
+        }
+    }
+
+    /// Suggest a safe spot once, if the player hasn't made any move for long enough. Spots are only suggested when they
+    /// can be deduced from the revealed numbers, so nothing is suggested when the next move would be a guess.
+    fn hint_if_idle(&mut self, now: Instant) {
+        if !self.preferences.idle_hint || self.idle_hinted || self.playback.is_some() {
+            return;
+        }
+
+        let GameState::Running(_) = self.game_state else {
+            return;
+        };
+
+        let idle = self.game_time(now).saturating_sub(self.idle_since);
+        if idle < Duration::from_secs(self.preferences.idle_hint_seconds as u64) {
+            return;
+        }
+
+        self.idle_hinted = true;
+
+        if let Some(spot) = self.field.safe_spot() {
+            self.hints += 1;
+            self.hint_pulse = Some(HintPulse::new(
+                spot,
+                now,
+                self.preferences.animation_speed.factor(),
+            ));
+        }
+    }
+
+    /// Size of the spots drawn on the canvas, without padding
+    fn cell_size(&self) -> f32 {
+        self.spot_size - (Self::SPOT_PAD * 2.0)
+    }
+
+    /// Where the top-left corner of the minefield is drawn, on a canvas of the given size
+    fn field_origin(&self, size: Size) -> Point {
+        let f_width = self.shown_field().width() as f32 * self.spot_size;
+        let f_height = self.shown_field().height() as f32 * self.spot_size;
+
+        let f_o_x = (size.width - f_width) / 2.0;
+        let f_o_y = (size.height - f_height) / 2.0;
+
+        Point::new(f_o_x, f_o_y)
+    }
+
+    /// Draw the whole minefield again
+    fn redraw_field(&mut self) {
+        self.field_cache.clear();
+        self.changes_cache.clear();
+        self.changed_spots.clear();
+    }
+
+    /// Draw the given spots again over the cached minefield, along with their neighbors, whose numbers may be dimmed or
+    /// not since. The whole minefield is drawn again instead once too many spots changed since it was cached.
+    fn redraw_spots(&mut self, spots: impl IntoIterator<Item = (u16, u16)>) {
+        let (width, height) = (self.field.width(), self.field.height());
+        for (x, y) in spots {
+            for ny in y.saturating_sub(1)..=y.saturating_add(1).min(height - 1) {
+                for nx in x.saturating_sub(1)..=x.saturating_add(1).min(width - 1) {
+                    self.changed_spots.insert((nx, ny));
+                }
+            }
+        }
+
+        if self.changed_spots.len() > Self::MAX_CHANGED_SPOTS {
+            self.redraw_field();
+        } else {
+            self.changes_cache.clear();
+        }
+    }
+
+    /// The columns and rows of the spots which are shown in the minefield canvas of the given size: those in the
+    /// scrolled viewport and next to it, or all of them when the minefield isn't scrolled
+    fn shown_spots(&self, size: Size) -> (Range<u16>, Range<u16>) {
+        let (width, height) = (self.shown_field().width(), self.shown_field().height());
+
+        let viewport = match self.field_viewport {
+            Some(viewport) if self.is_field_scrollable() => viewport,
+            _ => return (0..width, 0..height),
+        };
+
+        let origin = self.field_origin(size);
+        let shown = |from: f32, length: f32, spots: u16| {
+            let first = ((from / self.spot_size).floor() as i32 - 1).clamp(0, spots as i32) as u16;
+            let last = (((from + length) / self.spot_size).ceil() as i32 + 1).clamp(0, spots as i32)
+                as u16;
+
+            first..last
+        };
+
+        (
+            shown(viewport.x - origin.x, viewport.width, width),
+            shown(viewport.y - origin.y, viewport.height, height),
+        )
+    }
+
+    /// Labels numbering the shown columns above the minefield and the shown rows left of it, in the margin around it.
+    /// Columns and rows are counted from 0, like the mines of the boards saved from the editor.
+    fn ruler_labels(&self, size: Size) -> Vec<Text> {
+        let origin = self.field_origin(size);
+        let (columns, rows) = self.shown_spots(size);
+
+        // The labels scale with the spots, but are kept small enough for the longest one to fit in the margin
+        let digits = (self.shown_field().width().max(self.shown_field().height()) - 1)
+            .to_string()
+            .len();
+        let text_size = (self.spot_size * Self::RULER_TEXT_SCALE)
+            .min(Self::FIELD_PAD * 0.7)
+            .min(Self::FIELD_PAD * 1.6 / digits as f32);
+        let label = |index: u16, position: Point| Text {
+            content: index.to_string(),
+            position,
+            size: iced::Pixels(text_size),
+            horizontal_alignment: alignment::Horizontal::Center,
+            vertical_alignment: alignment::Vertical::Center,
+            ..Text::default()
+        };
+
+        let column_labels = columns.map(|ix| {
+            label(
+                ix,
+                origin + Vector::new((ix as f32 + 0.5) * self.spot_size, -Self::FIELD_PAD / 2.0),
+            )
+        });
+        let row_labels = rows.map(|iy| {
+            label(
+                iy,
+                origin + Vector::new(-Self::FIELD_PAD / 2.0, (iy as f32 + 0.5) * self.spot_size),
+            )
+        });
+
+        column_labels.chain(row_labels).collect()
+    }
+
+    /// Draw the given spots of the shown minefield on the minefield canvas, shading the hidden ones by the given
+    /// probabilities of being mines, if any
+    fn draw_spots(
+        &self,
+        frame: &mut Frame,
+        spots: &[(u16, u16)],
+        probabilities: Option<&BTreeMap<(u16, u16), f32>>,
+    ) {
+        // The board editor shows where the mines are, and the numbers around them
+        let editing = matches!(self.main_view, MainViewContent::Editor(_));
+        let minefield = self.shown_field();
+        let palette = self.preferences.color_theme.field_palette();
+
+        // Hidden mines are shown once the game is over, and to a player who's peeking at them
+        let shows_mines =
+            editing || self.peek || matches!(self.game_state, GameState::Stopped { is_won: _ });
+
+        // determine where to draw the spots
+        let origin_point = self.field_origin(frame.size());
+        let cell_size = Size::new(self.cell_size(), self.cell_size());
+        let spot_bounds = |ix: u16, iy: u16| {
+            let fx = (ix as f32 * self.spot_size) + Self::SPOT_PAD;
+            let fy = (iy as f32 * self.spot_size) + Self::SPOT_PAD;
+
+            Rectangle::new(origin_point + Vector::new(fx, fy), cell_size)
+        };
+
+        let shown_state = |ix, iy| {
+            minefield
+                .spot(ix, iy)
+                .map(|spot| match spot.state {
+                    SpotState::HiddenEmpty { neighboring_mines } if editing => {
+                        SpotState::RevealedEmpty { neighboring_mines }
+                    }
+                    state => state,
+                })
+                .unwrap_or(SpotState::RevealedEmpty {
+                    neighboring_mines: 0,
+                })
+        };
+
+        // The hidden spots are filled all at once, as a single path, while the revealed ones are already drawn by
+        // the background
+        let hidden_spots = Path::new(|builder| {
+            for &(ix, iy) in spots {
+                if !matches!(
+                    shown_state(ix, iy),
+                    SpotState::RevealedEmpty { .. } | SpotState::ExplodedMine
+                ) {
+                    let bounds = spot_bounds(ix, iy);
+                    builder.rectangle(bounds.position(), bounds.size());
+                }
+            }
+        });
+        frame.fill(&hidden_spots, palette.hidden);
+
+        // draw what's on the spots
+        for &(ix, iy) in spots {
+            let bounds = spot_bounds(ix, iy);
+
+            let text = Text {
+                size: iced::Pixels(self.cell_size() - Self::CELL_PAD),
+                position: bounds.center(),
+                horizontal_alignment: alignment::Horizontal::Center,
+                vertical_alignment: alignment::Vertical::Center,
+                ..Text::default()
+            };
+
+            let state = shown_state(ix, iy);
+
+            match state {
+                minefield_rs::SpotState::HiddenEmpty {
+                    neighboring_mines: _,
+                } => {}
+                minefield_rs::SpotState::HiddenMine => {
+                    if shows_mines {
+                        frame.fill_text(Text {
+                            content: Self::MINE_CHAR.to_string(),
+                            position: text.position,
+                            color: self.mine_color(),
+                            font: Self::MINES_FLAGS_ICONS,
+                            size: iced::Pixels(self.cell_size() - Self::CELL_PAD),
+                            ..text
+                        });
+                    }
+                }
+                minefield_rs::SpotState::FlaggedEmpty {
+                    neighboring_mines: _,
+                } => {
+                    let is_wrong = matches!(self.game_state, GameState::Stopped { is_won: _ });
+
+                    frame.fill_text(Text {
+                        content: Self::FLAG_CHAR.to_string(),
+                        position: text.position,
+                        color: self.flag_color(!is_wrong),
+                        font: Self::MINES_FLAGS_ICONS,
+                        size: iced::Pixels(self.cell_size() - Self::CELL_PAD),
+                        ..text
+                    });
+
+                    // Wrong flags are crossed out, since their color alone can't be told apart
+                    if is_wrong && self.preferences.colorblind_glyphs {
+                        draw_cross(palette.text, bounds, frame);
+                    }
+                }
+                minefield_rs::SpotState::FlaggedMine => {
+                    frame.fill_text(Text {
+                        content: Self::FLAG_CHAR.to_string(),
+                        position: text.position,
+                        color: self.flag_color(true),
+                        font: Self::MINES_FLAGS_ICONS,
+                        size: iced::Pixels(self.cell_size() - Self::CELL_PAD),
+                        ..text
+                    });
+                }
+                minefield_rs::SpotState::QuestionedEmpty {
+                    neighboring_mines: _,
+                } => {
+                    frame.fill_text(Text {
+                        content: Self::QUESTION_CHAR.to_string(),
+                        position: text.position,
+                        color: palette.text,
+                        ..text
+                    });
+                }
+                minefield_rs::SpotState::QuestionedMine => {
+                    // Like hidden mines, questioned ones are shown once the game is over
+                    if shows_mines {
+                        frame.fill_text(Text {
+                            content: Self::MINE_CHAR.to_string(),
+                            position: text.position,
+                            color: self.mine_color(),
+                            font: Self::MINES_FLAGS_ICONS,
+                            size: iced::Pixels(self.cell_size() - Self::CELL_PAD),
+                            ..text
+                        });
+                    } else {
+                        frame.fill_text(Text {
+                            content: Self::QUESTION_CHAR.to_string(),
+                            position: text.position,
+                            color: palette.text,
+                            ..text
+                        });
+                    }
+                }
+                minefield_rs::SpotState::RevealedEmpty { neighboring_mines } => {
+                    // Satisfied numbers give no further information, so they are dimmed (if the player wants
+                    // to) to draw attention to the other ones
+                    let mut color = palette.numbers[neighboring_mines as usize];
+                    if self.preferences.dim_satisfied && minefield.is_satisfied(ix, iy) {
+                        color.a = Self::SATISFIED_NUMBER_ALPHA;
+                    }
+
+                    frame.fill_text(Text {
+                        content: Self::EMPTY_SPOT_CHARS[neighboring_mines as usize].to_string(),
+                        position: text.position,
+                        color,
+                        ..text
+                    });
+                }
+                minefield_rs::SpotState::ExplodedMine => {
+                    // Tint the background and outline the spot, leaving the glyph itself untouched on top
+                    if self.preferences.highlight_exploded {
+                        let highlight = self.preferences.exploded_highlight_color.color();
+
+                        frame.fill_rectangle(
+                            bounds.position(),
+                            bounds.size(),
+                            Color {
+                                a: Self::EXPLODED_HIGHLIGHT_ALPHA,
+                                ..highlight
+                            },
+                        );
+                        frame.stroke(
+                            &Path::rectangle(
+                                bounds.position() + Vector::new(1.0, 1.0),
+                                Size::new(bounds.width - 2.0, bounds.height - 2.0),
+                            ),
+                            Stroke {
+                                width: 2.0,
+                                style: stroke::Style::Solid(highlight),
+                                ..Stroke::default()
+                            },
+                        );
+                    }
+
+                    // The mine which was stepped on is outlined, since its color alone can't be told apart
+                    if self.preferences.colorblind_glyphs {
+                        frame.stroke(
+                            &Path::rectangle(
+                                bounds.position() + Vector::new(2.0, 2.0),
+                                Size::new(bounds.width - 4.0, bounds.height - 4.0),
+                            ),
+                            Stroke {
+                                width: 3.0,
+                                style: stroke::Style::Solid(palette.text),
+                                ..Stroke::default()
+                            },
+                        );
+                    }
+
+                    frame.fill_text(Text {
+                        content: Self::MINE_EXPLODED_CHAR.to_string(),
+                        position: text.position,
+                        color: self.exploded_mine_color(),
+                        font: Self::MINES_FLAGS_ICONS,
+                        size: iced::Pixels(self.cell_size() - Self::CELL_PAD),
+                        ..text
+                    });
+                }
+            }
+
+            // Shade the hidden spots by how likely they are to be mines, flagged ones being already decided
+            if let (
+                Some(probability),
+                SpotState::HiddenEmpty { .. }
+                | SpotState::HiddenMine
+                | SpotState::QuestionedEmpty { .. }
+                | SpotState::QuestionedMine,
+            ) = (probabilities.and_then(|p| p.get(&(ix, iy))), state)
+            {
+                frame.fill_rectangle(
+                    bounds.position(),
+                    bounds.size(),
+                    Color {
+                        a: probability * Self::PROBABILITY_SHADE_ALPHA,
+                        ..self.mine_color()
+                    },
+                );
+            }
+
+            // Outline the spot of a risky step which waits to be confirmed
+            if !editing && self.armed_step == Some((ix, iy)) {
+                frame.stroke(
+                    &Path::rectangle(
+                        bounds.position() + Vector::new(1.0, 1.0),
+                        Size::new(bounds.width - 2.0, bounds.height - 2.0),
+                    ),
+                    Stroke {
+                        width: 2.0,
+                        style: stroke::Style::Solid(Self::FLAG_COUNT_ERR_COLOR),
+                        ..Stroke::default()
+                    },
+                );
+            }
+        }
+
+        fn draw_cross(color: Color, bounds: Rectangle, frame: &mut Frame) {
+            let inset = bounds.width / 5.0;
+            let (left, top) = (bounds.x + inset, bounds.y + inset);
+            let (right, bottom) = (
+                bounds.x + bounds.width - inset,
+                bounds.y + bounds.height - inset,
+            );
+            let stroke = Stroke {
+                width: 2.0,
+                style: stroke::Style::Solid(color),
+                line_cap: LineCap::Round,
+                ..Stroke::default()
+            };
+
+            frame.stroke(
+                &Path::line(Point::new(left, top), Point::new(right, bottom)),
+                stroke,
+            );
+            frame.stroke(
+                &Path::line(Point::new(right, top), Point::new(left, bottom)),
+                stroke,
+            );
+        }
+    }
+
+    /// The coordinates of the minefield spot under the cursor, if any
+    fn spot_under_cursor(&self, bounds: Rectangle, cursor: Cursor) -> Option<(u16, u16)> {
+        cursor
+            .position()
+            .and_then(|position| self.spot_at(bounds, position))
+    }
+
+    /// Coordinates of the spot drawn at the given position of the window (e.g. where a finger touched it), if any
+    fn spot_at(&self, bounds: Rectangle, position: Point) -> Option<(u16, u16)> {
+        // determine where the spots are drawn
+        let f_width = self.shown_field().width() as f32 * self.spot_size;
+        let f_height = self.shown_field().height() as f32 * self.spot_size;
+
+        let origin_point = self.field_origin(bounds.size()) + Vector::new(bounds.x, bounds.y);
+        let origin_rectangle = Rectangle::new(origin_point, Size::new(f_width, f_height));
+
+        Cursor::Available(position)
+            .position_in(origin_rectangle)
+            .map(|position| {
+                let x = (position.x / self.spot_size).floor() as u16;
+                let y = (position.y / self.spot_size).floor() as u16;
+
+                (x, y)
+            })
+    }
+
+    /// The spot selected with the arrow keys, if it's still on the minefield (which might have been replaced by a
+    /// smaller one since)
+    fn selected_spot(&self, interaction: &FieldInteraction) -> Option<(u16, u16)> {
+        interaction
+            .selected
+            .filter(|&(x, y)| x < self.shown_field().width() && y < self.shown_field().height())
+    }
+
+    /// The spot under the cursor, which is highlighted while moves can be taken on it
+    fn hovered_spot(&self, interaction: &FieldInteraction) -> Option<(u16, u16)> {
+        interaction.hovered.filter(|&(x, y)| {
+            x < self.field.width()
+                && y < self.field.height()
+                && matches!(self.game_state, GameState::Ready | GameState::Running(_))
+        })
+    }
+
+    /// The buttons which autostep are held down, so that the neighbors which an autostep would reveal are highlighted
+    fn previews_auto_step(&self, interaction: &FieldInteraction) -> bool {
+        let controls = self.active_controls();
+
+        interaction.chording
+            || (interaction.left_pressed
+                && !controls.left_right_chord
+                && controls.left == SpotAction::AutoStep)
+            || (interaction.right_pressed && controls.right == SpotAction::AutoStep)
+            || (interaction.middle_pressed && controls.middle == SpotAction::AutoStep)
+    }
+
+    /// The action taken by the left button or a touch gesture, which flags instead of stepping in flagging mode (and
+    /// steps instead of flagging, so that a long press can still step)
+    fn primary_action(&self, action: SpotAction) -> SpotAction {
+        match (self.flagging_mode, action) {
+            (true, SpotAction::Step) => SpotAction::Flag,
+            (true, SpotAction::Flag) => SpotAction::Step,
+            _ => action,
+        }
+    }
+
+    /// Move the spot selected with the arrow keys by `dx` and `dy`, staying on the minefield. Without a selected spot,
+    /// the selection starts in the middle of the minefield.
+    fn move_selection(&self, selected: Option<(u16, u16)>, dx: i32, dy: i32) -> (u16, u16) {
+        let width = self.shown_field().width();
+        let height = self.shown_field().height();
+
+        match selected.filter(|&(x, y)| x < width && y < height) {
+            Some((x, y)) => (
+                (x as i32 + dx).clamp(0, width as i32 - 1) as u16,
+                (y as i32 + dy).clamp(0, height as i32 - 1) as u16,
+            ),
+            None => (width / 2, height / 2),
+        }
+    }
+
+    /// Removing the flag on the given spot is refused by the flag lock. Flags are only removed with Shift or Ctrl held
+    /// down, or when the spot is flagged for the second time in a quick succession.
+    fn is_flag_removal_locked(&self, interaction: &FieldInteraction, coords: (u16, u16)) -> bool {
+        let is_flagged = matches!(
+            self.field.spot(coords.0, coords.1).map(|spot| spot.state),
+            Some(SpotState::FlaggedEmpty { .. } | SpotState::FlaggedMine)
+        );
+        let is_deliberate = interaction.modifiers.shift()
+            || interaction.modifiers.control()
+            || interaction.locked_flag.is_some_and(|(locked, at)| {
+                locked == coords && at.elapsed() <= Self::FLAG_UNLOCK_TIME
+            });
+
+        is_flagged && !is_deliberate
+    }
+
+    /// Save game config and high scores to the file at `path`. The save is numbered when it's asked for, before it runs,
+    /// so that it can be told apart from the saves of newer configs.
+    pub fn save_persistence(
+        path: PathBuf,
+        configs: GamePersistence,
+    ) -> impl std::future::Future<Output = Result<(), String>> {
+        let generation = Self::next_save_generation();
+
+        async move {
+            Self::save_persistence_to(&path, &configs, generation)
+                .map_err(|e| format!("Could not save configs to {:?}: {}", path, e))
+        }
+    }
+
+    /// The number of a new save, higher than those of all the saves asked for before it
+    fn next_save_generation() -> u64 {
+        static SAVES: AtomicU64 = AtomicU64::new(1);
+
+        SAVES.fetch_add(1, AtomicOrdering::Relaxed)
+    }
+
+    /// Write the configs to a temporary file first, and only then rename it over the file at `path`, so that the
+    /// previous save is left untouched if the game stops in the middle of writing. Saves run concurrently and may
+    /// overlap, so they are written one at a time, and a save is skipped if a save with a higher `generation` (see
+    /// `next_save_generation`) was already written, since its configs are older.
+    fn save_persistence_to(
+        path: &std::path::Path,
+        configs: &GamePersistence,
+        generation: u64,
+    ) -> std::io::Result<()> {
+        static WRITTEN: Mutex<BTreeMap<PathBuf, u64>> = Mutex::new(BTreeMap::new());
+
+        let mut written = WRITTEN.lock().unwrap_or_else(PoisonError::into_inner);
+        if written
+            .get(path)
+            .is_some_and(|&written| written > generation)
+        {
+            return Ok(());
+        }
+
+        let temporary = Self::temporary_path(path);
+        let buf = serde_json::to_vec(configs)?;
+
+        // The config directory doesn't exist until the first save
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut f = std::fs::File::create(&temporary)?;
+        std::io::Write::write_all(&mut f, &buf[..])?;
+        f.sync_all()?;
+        drop(f);
+
+        std::fs::rename(&temporary, path)?;
+        written.insert(path.to_path_buf(), generation);
+
+        Ok(())
+    }
+}
+
+impl Default for Minesweep {
+    fn default() -> Self {
+        let game_config = GameDifficulty::EASY;
+        let preferences = Preferences::default();
+        let high_scores = BTreeMap::new();
+        let seed = rand::random();
+
+        Self {
+            field: Minefield::new(game_config.width, game_config.height)
+                .with_question_marks(preferences.question_marks)
+                .with_mines_seeded(game_config.mines, seed),
+            seed,
+            pending_seed: None,
+            startup: StartupConfig::default(),
+            persistence_path: Self::default_persistence_path(),
+            first_launch: false,
+            daily: None,
+            daily_results: BTreeMap::new(),
+            persistence_error: None,
+            field_viewport: None,
+            field_cache: Cache::default(),
+            changes_cache: Cache::default(),
+            changed_spots: BTreeSet::new(),
+            spot_size: Self::DEFAULT_SPOT_SIZE,
+            main_view: MainViewContent::Game,
+            game_state: GameState::default(),
+            game_config,
+            custom_game: false,
+            elapsed_seconds: Duration::default(),
+            remaining_flags: game_config.mines as i64,
+            high_scores,
+            flagless_high_scores: BTreeMap::new(),
+            empty_scores: Vec::new(),
+            max_high_scores: Self::DEFAULT_HIGH_SCORES_PER_LEVEL,
+            stats: BTreeMap::new(),
+            active_profile: Self::DEFAULT_PROFILE.to_string(),
+            profiles: BTreeMap::new(),
+            new_profile_name: String::new(),
+            stats_recorded: false,
+            window_id: None,
+            resizes_window: true,
+            events: Vec::new(),
+            monitor_size: None,
+            preferences,
+            presets: Vec::new(),
+            difficulty_controls: BTreeMap::new(),
+            reveal_animation: None,
+            explosion_animation: None,
+            assisted: false,
+            flags_used: false,
+            hints: 0,
+            clicks: 0,
+            board_3bv: None,
+            peek: false,
+            show_probabilities: false,
+            flagging_mode: false,
+            auto_solving: false,
+            edited_board: false,
+            no_guess_pending: false,
+            open_start_pending: false,
+            generation: None,
+            generation_warning: false,
+            game_summary: false,
+            recording: Vec::new(),
+            playback: None,
+            saved_replay: None,
+            unsaved_replay: None,
+            pause_requested: false,
+            armed_step: None,
+            idle_since: Duration::default(),
+            idle_hinted: false,
+            hint_pulse: None,
+            announcement: None,
+            sound: None,
+            own_config: None,
+            saved_config: None,
+        }
+    }
+}
+
+/// Setup of specific boards, and inspection of the state visible to the player, for tests driving `update`
+#[cfg(test)]
+impl Minesweep {
+    /// A game which is ready to start on a board with mines at the given coordinates. Finished games are not saved as
+    /// replays.
+    pub(crate) fn with_board(width: u16, height: u16, mines: &[(u16, u16)]) -> Self {
+        let mut minesweep = Self {
+            game_config: GameConfig {
+                width,
+                height,
+                mines: mines.len() as u32,
+                ..GameConfig::default()
+            },
+            preferences: Preferences {
+                save_replays: false,
+                ..Preferences::default()
+            },
+            ..Self::default()
+        };
+        minesweep.start_new_game();
+        minesweep.field = Minefield::new(width, height)
+            .with_connectivity(minesweep.preferences.connectivity())
+            .with_safe_chord(minesweep.game_config.safe_chord)
+            .with_win_on_all_revealed(minesweep.game_config.win_on_all_revealed)
+            .with_strict_flags(minesweep.game_config.strict_flags)
+            .with_question_marks(minesweep.preferences.question_marks)
+            .with_mines_at(mines);
+
+        minesweep
+    }
+
+    /// The same game, played with the given preferences (which still don't save replays)
+    pub(crate) fn with_preferences(mut self, preferences: Preferences) -> Self {
+        self.preferences = Preferences {
+            save_replays: false,
+            ..preferences
+        };
+        self.no_guess_pending = self.preferences.no_guess;
+        self.open_start_pending = self.preferences.open_start;
+        self.apply_question_marks();
+
+        self
+    }
+
+    pub(crate) fn game_state(&self) -> GameState {
+        self.game_state
+    }
+
+    pub(crate) fn remaining_flags(&self) -> i64 {
+        self.remaining_flags
+    }
+
+    pub(crate) fn elapsed_seconds(&self) -> Duration {
+        self.elapsed_seconds
+    }
+
+    pub(crate) fn field(&self) -> &Minefield {
+        &self.field
+    }
+
+    /// Location of the high score whose name is being entered, if the `Enter High Score` view is shown
+    pub(crate) fn high_score_entry(&self) -> Option<&HighScoreLocation> {
+        match &self.main_view {
+            MainViewContent::EnterHighScore(location, _, _) => Some(location),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn high_scores(&self, difficulty_level: DifficultyLevel) -> &[Score] {
+        self.high_scores
+            .get(&difficulty_level)
+            .map(|scores| &scores[..])
+            .unwrap_or_default()
+    }
+}
+
+impl canvas::Program<Message> for Minesweep {
+    type State = FieldInteraction;
+
+    fn update(
+        &self,
+        interaction: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> (event::Status, Option<Message>) {
+        // Scrolling with Ctrl held down zooms the minefield, in the board editor as well, instead of scrolling it
+        if let Event::Mouse(mouse::Event::WheelScrolled { delta }) = event {
+            let y = match delta {
+                mouse::ScrollDelta::Lines { y, .. } | mouse::ScrollDelta::Pixels { y, .. } => y,
+            };
+
+            if interaction.modifiers.control() && y != 0.0 && cursor.is_over(bounds) {
+                return (
+                    event::Status::Captured,
+                    Some(Message::Zoom { zoom_in: y > 0.0 }),
+                );
+            }
+        }
+
+        // The spot under the cursor is highlighted until the cursor leaves the minefield
+        if let Event::Mouse(mouse::Event::CursorMoved { .. } | mouse::Event::CursorLeft) = event {
+            interaction.hovered = self.spot_under_cursor(bounds, cursor);
+        }
+
+        // The tutorial board only takes moves while the tutorial asks for them, not while it waits for one of its buttons
+        if let MainViewContent::Tutorial(tutorial) = &self.main_view {
+            if !tutorial.takes_moves() {
+                return (event::Status::Ignored, None);
+            }
+        }
+
+        // In the board editor, the left button places and removes mines
+        if let MainViewContent::Editor(_) = self.main_view {
+            return match (event, self.spot_under_cursor(bounds, cursor)) {
+                (Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)), Some((x, y))) => (
+                    event::Status::Captured,
+                    Some(Message::Editor(EditorMessage::ToggleMine { x, y })),
+                ),
+                _ => (event::Status::Ignored, None),
+            };
+        }
+
+        let controls = self.active_controls();
+
+        // Spot on which a touch gesture ended, if it takes an action
+        let mut touch_coords = None;
+
+        let action = match event {
+            Event::Mouse(mouse_event) => match mouse_event {
+                mouse::Event::ButtonPressed(mouse_button) => match mouse_button {
+                    mouse::Button::Left => {
+                        interaction.left_pressed = true;
+
+                        if !controls.left_right_chord {
+                            Some(self.primary_action(controls.left))
+                        } else if interaction.right_pressed {
+                            interaction.chording = true;
+                            Some(SpotAction::AutoStep)
+                        } else {
+                            // The left button action is taken on release, since the right button might still be
+                            // pressed in order to autostep
+                            None
+                        }
+                    }
+                    mouse::Button::Right => {
+                        interaction.right_pressed = true;
+
+                        if controls.left_right_chord && interaction.left_pressed {
+                            interaction.chording = true;
+                            Some(SpotAction::AutoStep)
+                        } else {
+                            Some(controls.right)
+                        }
+                    }
+                    mouse::Button::Middle => {
+                        interaction.middle_pressed = true;
+                        Some(controls.middle)
+                    }
+                    mouse::Button::Other(_) => None,
+                    mouse::Button::Back => None,
+                    mouse::Button::Forward => None,
+                },
+                mouse::Event::ButtonReleased(mouse_button) => {
+                    let action = match mouse_button {
+                        mouse::Button::Left if interaction.left_pressed => {
+                            interaction.left_pressed = false;
+
+                            if controls.left_right_chord && !interaction.chording {
+                                Some(self.primary_action(controls.left))
+                            } else {
+                                None
+                            }
+                        }
+                        mouse::Button::Right => {
+                            interaction.right_pressed = false;
+                            None
+                        }
+                        mouse::Button::Middle => {
+                            interaction.middle_pressed = false;
+                            None
+                        }
+                        _ => None,
+                    };
+
+                    if !interaction.left_pressed && !interaction.right_pressed {
+                        interaction.chording = false;
+                    }
+
+                    action
+                }
+                _ => None,
+            },
+            Event::Touch(touch_event) => match touch_event {
+                touch::Event::FingerPressed { id, position } => {
+                    interaction.touches.push((id, position, Instant::now()));
+
+                    if interaction.touches.len() > 1 {
+                        interaction.multi_touch = true;
+                    }
+
+                    None
+                }
+                touch::Event::FingerMoved { id, position } => {
+                    // A finger which slides away from where it started is scrolling, not tapping
+                    let slid = interaction.touches.iter().any(|&(finger, start, _)| {
+                        finger == id && start.distance(position) > self.spot_size / 2.0
+                    });
+
+                    if slid {
+                        interaction.touch_cancelled = true;
+                    }
+
+                    None
+                }
+                touch::Event::FingerLifted { id, position: _ } => {
+                    let lifted = interaction
+                        .touches
+                        .iter()
+                        .position(|&(finger, _, _)| finger == id)
+                        .map(|index| interaction.touches.swap_remove(index));
+
+                    // The action is taken once the last finger is lifted
+                    if interaction.touches.is_empty() {
+                        let action = match lifted {
+                            Some((_, start, at)) if !interaction.touch_cancelled => {
+                                touch_coords = self.spot_at(bounds, start);
+
+                                if interaction.multi_touch {
+                                    Some(SpotAction::AutoStep)
+                                } else if at.elapsed() >= Self::LONG_PRESS_TIME {
+                                    Some(self.primary_action(SpotAction::Flag))
+                                } else {
+                                    Some(self.primary_action(SpotAction::Step))
+                                }
+                            }
+                            _ => None,
+                        };
+
+                        interaction.multi_touch = false;
+                        interaction.touch_cancelled = false;
+
+                        action
+                    } else {
+                        None
+                    }
+                }
+                touch::Event::FingerLost { id, position: _ } => {
+                    interaction.touches.retain(|&(finger, _, _)| finger != id);
+                    interaction.touch_cancelled = !interaction.touches.is_empty();
+
+                    if interaction.touches.is_empty() {
+                        interaction.multi_touch = false;
+                    }
+
+                    None
+                }
+            },
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                interaction.modifiers = modifiers;
+                None
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed { ref key, .. }) => {
+                let movement = match key.as_ref() {
+                    keyboard::Key::Named(keyboard::key::Named::ArrowLeft) => Some((-1, 0)),
+                    keyboard::Key::Named(keyboard::key::Named::ArrowRight) => Some((1, 0)),
+                    keyboard::Key::Named(keyboard::key::Named::ArrowUp) => Some((0, -1)),
+                    keyboard::Key::Named(keyboard::key::Named::ArrowDown) => Some((0, 1)),
+                    _ => None,
+                };
+
+                if let Some((dx, dy)) = movement {
+                    interaction.selected = Some(self.move_selection(interaction.selected, dx, dy));
+                    return (event::Status::Captured, None);
+                }
+
+                match key.as_ref() {
+                    // Without a selected spot, Space is left to pause the game
+                    _ if interaction.selected.is_none() => None,
+                    keyboard::Key::Named(keyboard::key::Named::Escape) => {
+                        interaction.selected = None;
+                        return (event::Status::Captured, None);
+                    }
+                    keyboard::Key::Named(keyboard::key::Named::Space) => Some(SpotAction::Step),
+                    keyboard::Key::Named(keyboard::key::Named::Enter) => Some(SpotAction::AutoStep),
+                    keyboard::Key::Character(c) if c.eq_ignore_ascii_case("f") => {
+                        Some(SpotAction::Flag)
+                    }
+                    _ => None,
+                }
+            }
+            Event::Keyboard(_) => None,
+        };
+
+        // Keys act on the spot selected with the arrow keys, and are kept from pausing the game while it's playing
+        let from_keyboard = matches!(event, Event::Keyboard(keyboard::Event::KeyPressed { .. }))
+            && action.is_some();
+        let keyboard_status = if from_keyboard && self.game_state != GameState::Paused {
+            event::Status::Captured
+        } else {
+            event::Status::Ignored
+        };
+
+        // The board is busy while revealed spots are still being animated, so that the player does not step on spots
+        // which they cannot see yet
+        let action = action.filter(|_| self.reveal_animation.is_none());
+
+        // Replays are only watched, never played, and nothing can be done while the game is paused
+        let action = action.filter(|_| self.playback.is_none());
+        let action = action.filter(|_| self.game_state != GameState::Paused);
+        let spot_coords = if from_keyboard {
+            self.selected_spot(interaction)
+        } else if let Event::Touch(_) = event {
+            touch_coords
+        } else {
+            self.spot_under_cursor(bounds, cursor)
+        };
+
+        // Autostepping only has an effect on revealed numbers, so over a hidden spot the middle button can take another
+        // action instead
+        let is_middle_press = matches!(
+            event,
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Middle))
+        );
+        let action = match (action, spot_coords) {
+            (Some(SpotAction::AutoStep), Some((x, y))) if is_middle_press => {
+                match self.field.spot(x, y).map(|spot| spot.state) {
+                    Some(
+                        SpotState::HiddenEmpty { .. }
+                        | SpotState::HiddenMine
+                        | SpotState::FlaggedEmpty { .. }
+                        | SpotState::FlaggedMine
+                        | SpotState::QuestionedEmpty { .. }
+                        | SpotState::QuestionedMine,
+                    ) => Some(self.preferences.middle_on_hidden.action()),
+                    _ => action,
+                }
+            }
+            _ => action,
+        };
+
+        // Double clicking a revealed number autosteps around it. Only numbers which were already revealed by the first
+        // click count, so that stepping on a hidden spot and then quickly on the number it revealed doesn't autostep.
+        let is_left_click = matches!(
+            event,
+            Event::Mouse(
+                mouse::Event::ButtonPressed(mouse::Button::Left)
+                    | mouse::Event::ButtonReleased(mouse::Button::Left)
+            )
+        );
+        let action = match (action, spot_coords) {
+            (Some(SpotAction::Step), Some(coords))
+                if is_left_click && self.preferences.double_click_autosteps =>
+            {
+                let now = Instant::now();
+                let interval = Duration::from_millis(self.preferences.double_click_millis as u64);
+                let is_double_click = interaction
+                    .last_click
+                    .is_some_and(|(spot, at)| spot == coords && now - at <= interval);
+
+                if is_double_click {
+                    interaction.last_click = None;
+                    Some(SpotAction::AutoStep)
+                } else {
+                    let is_number = matches!(
+                        self.field.spot(coords.0, coords.1).map(|spot| spot.state),
+                        Some(SpotState::RevealedEmpty {
+                            neighboring_mines: 1..
+                        })
+                    );
+                    interaction.last_click = is_number.then_some((coords, now));
+                    action
+                }
+            }
+            _ => action,
+        };
+
+        // Stepping on a satisfied number can autostep around it. Numbers which don't have enough flags around them are
+        // left to the careful expansion, or to the step itself, which does nothing on them.
+        let action = match (action, spot_coords) {
+            (Some(SpotAction::Step), Some((x, y)))
+                if self.preferences.step_on_satisfied_autosteps
+                    && self.field.is_satisfied(x, y) =>
+            {
+                Some(SpotAction::AutoStep)
+            }
+            _ => action,
+        };
+
+        // In careful expansion mode, stepping on a revealed spot reveals its neighbors instead
+        let action = match (action, spot_coords) {
+            (Some(SpotAction::Step), Some((x, y))) if self.preferences.careful_expansion => {
+                match self.field.spot(x, y).map(|spot| spot.state) {
+                    Some(SpotState::RevealedEmpty {
+                        neighboring_mines: 1..,
+                    }) => Some(SpotAction::RevealRing),
+                    _ => action,
+                }
+            }
+            _ => action,
+        };
+
+        // Flagging a revealed number with Shift held flags all its hidden neighbors, if they must all be mines
+        let action = match (action, spot_coords) {
+            (Some(SpotAction::Flag), Some((x, y))) if interaction.modifiers.shift() => {
+                match self.field.spot(x, y).map(|spot| spot.state) {
+                    Some(SpotState::RevealedEmpty {
+                        neighboring_mines: 1..,
+                    }) => Some(SpotAction::AutoFlag),
+                    _ => action,
+                }
+            }
+            _ => action,
+        };
+
+        // With the flag lock, a flag is only removed deliberately
+        let action = match (action, spot_coords) {
+            (Some(SpotAction::Flag), Some(coords)) if self.preferences.flag_lock => {
+                if self.is_flag_removal_locked(interaction, coords) {
+                    interaction.locked_flag = Some((coords, Instant::now()));
+                    None
+                } else {
+                    interaction.locked_flag = None;
+                    action
+                }
+            }
+            _ => action,
+        };
+
+        match (action, spot_coords) {
+            (Some(action), Some((x, y))) => match action.message(x, y) {
+                Some(message) => (event::Status::Captured, Some(message)),
+                None => (keyboard_status, None),
+            },
+            _ => (keyboard_status, None),
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        _interaction: &Self::State,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> mouse::Interaction {
+        if self.reveal_animation.is_some() && self.spot_under_cursor(bounds, cursor).is_some() {
+            mouse::Interaction::Working
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+
+    fn draw(
+        &self,
+        interaction: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<canvas::Geometry> {
+        // The board editor shows where the mines are, and the numbers around them
+        let editing = matches!(self.main_view, MainViewContent::Editor(_));
+        let minefield = self.shown_field();
+        let palette = self.preferences.color_theme.field_palette();
+
+        // Hidden mines are shown once the game is over, and to a player who's peeking at them
+        let shows_mines =
+            editing || self.peek || matches!(self.game_state, GameState::Stopped { is_won: _ });
+
+        let field = self.field_cache.draw(renderer, bounds.size(), |frame| {
+            // The probabilities are only found again when the cache is cleared, which it is after every move
+            let probabilities =
+                (self.show_probabilities && !shows_mines).then(|| minefield.mine_probabilities());
+
+            // Set the background
+            let background = Path::rectangle(Point::ORIGIN, frame.size());
+            let background_color = palette.revealed;
+            frame.fill(&background, background_color);
+
+            // Only the spots in the shown part of a scrolled minefield are drawn, so that a large minefield takes no
+            // longer to draw than the window it's shown in
+            let (columns, rows) = self.shown_spots(frame.size());
+            let spots: Vec<(u16, u16)> = rows
+                .flat_map(|iy| columns.clone().map(move |ix| (ix, iy)))
+                .collect();
+            self.draw_spots(frame, &spots, probabilities.as_ref());
+
+            if self.preferences.show_rulers {
+                let color = palette.text;
+                for text in self.ruler_labels(frame.size()) {
+                    frame.fill_text(Text { color, ..text });
+                }
+            }
+        });
+
+        // The spots which changed since the field was cached are drawn again on top of it, over their old look
+        let changes = self.changes_cache.draw(renderer, bounds.size(), |frame| {
+            let origin_point = self.field_origin(frame.size());
+            let spots: Vec<(u16, u16)> = self.changed_spots.iter().copied().collect();
+
+            let old_spots = Path::new(|builder| {
+                for &(ix, iy) in &spots {
+                    builder.rectangle(
+                        origin_point
+                            + Vector::new(ix as f32 * self.spot_size, iy as f32 * self.spot_size),
+                        Size::new(self.spot_size, self.spot_size),
+                    );
+                }
+            });
+            frame.fill(&old_spots, palette.revealed);
+
+            self.draw_spots(frame, &spots, None);
+        });
+
+        let mut geometry = vec![field, changes];
+
+        // Cover the revealed spots which have not been reached by the reveal animation yet with hidden spots which fade
+        // away. This is drawn on top of the cached field, since the cache cannot be animated.
+        if let Some(animation) = &self.reveal_animation {
+            let mut frame = Frame::new(renderer, bounds.size());
+            let origin_point = self.field_origin(frame.size());
+            let now = Instant::now();
+
+            for &((ix, iy), delay) in &animation.spots {
+                let alpha = animation.cover_alpha(delay, now);
+
+                if alpha > 0.0 {
+                    let fx = (ix as f32 * self.spot_size) + Self::SPOT_PAD;
+                    let fy = (iy as f32 * self.spot_size) + Self::SPOT_PAD;
+                    let p = origin_point + Vector::new(fx, fy);
+
+                    frame.fill_rectangle(
+                        p,
+                        Size::new(self.cell_size(), self.cell_size()),
+                        Color {
+                            a: alpha,
+                            ..palette.hidden
+                        },
+                    );
+                }
+            }
+
+            geometry.push(frame.into_geometry());
+        }
+
+        // Cover the mines which have not popped in yet, and grow the ones which are popping in, on top of the cached field
+        // as well
+        if let (false, Some(animation)) = (editing, &self.explosion_animation) {
+            let mut frame = Frame::new(renderer, bounds.size());
+            let origin_point = self.field_origin(frame.size());
+            let now = Instant::now();
+
+            for &((ix, iy), delay) in &animation.mines {
+                let fx = (ix as f32 * self.spot_size) + Self::SPOT_PAD;
+                let fy = (iy as f32 * self.spot_size) + Self::SPOT_PAD;
+                let spot_bounds = Rectangle::new(
+                    origin_point + Vector::new(fx, fy),
+                    Size::new(self.cell_size(), self.cell_size()),
+                );
+                let (cover, content, color) = if (ix, iy) == animation.origin {
+                    (
+                        palette.revealed,
+                        Self::MINE_EXPLODED_CHAR,
+                        self.exploded_mine_color(),
+                    )
+                } else {
+                    (palette.hidden, Self::MINE_CHAR, self.mine_color())
+                };
+
+                frame.fill_rectangle(spot_bounds.position(), spot_bounds.size(), cover);
+
+                let scale = animation.mine_scale(delay, now);
+                if scale > 0.0 {
+                    frame.fill_text(Text {
+                        content: content.to_string(),
+                        position: spot_bounds.center(),
+                        color,
+                        font: Self::MINES_FLAGS_ICONS,
+                        size: iced::Pixels((self.cell_size() - Self::CELL_PAD) * scale),
+                        horizontal_alignment: alignment::Horizontal::Center,
+                        vertical_alignment: alignment::Vertical::Center,
+                        ..Text::default()
+                    });
+                }
+            }
+
+            geometry.push(frame.into_geometry());
+        }
+
+        // Highlight the spot suggested to an idle player, on top of the cached field as well
+        if let (false, Some(pulse)) = (editing, &self.hint_pulse) {
+            let mut frame = Frame::new(renderer, bounds.size());
+            let origin_point = self.field_origin(frame.size());
+            let (ix, iy) = pulse.spot;
+            let fx = (ix as f32 * self.spot_size) + Self::SPOT_PAD;
+            let fy = (iy as f32 * self.spot_size) + Self::SPOT_PAD;
+
+            frame.stroke(
+                &Path::rectangle(
+                    origin_point + Vector::new(fx + 1.5, fy + 1.5),
+                    Size::new(self.cell_size() - 3.0, self.cell_size() - 3.0),
+                ),
+                Stroke {
+                    width: 3.0,
+                    style: stroke::Style::Solid(Color {
+                        a: pulse.alpha(Instant::now()),
+                        ..Self::WON_COLOR
+                    }),
+                    ..Stroke::default()
+                },
+            );
+
+            geometry.push(frame.into_geometry());
+        }
+
+        // Outline the spot under the cursor, and lighten its hidden neighbors while the buttons which would autostep
+        // on it are held down, on top of the cached field as well
+        if let (false, Some((ix, iy))) = (editing, self.hovered_spot(interaction)) {
+            let mut frame = Frame::new(renderer, bounds.size());
+            let origin_point = self.field_origin(frame.size());
+            let spot_position = |x: u16, y: u16| {
+                origin_point
+                    + Vector::new(
+                        (x as f32 * self.spot_size) + Self::SPOT_PAD,
+                        (y as f32 * self.spot_size) + Self::SPOT_PAD,
+                    )
+            };
+
+            let is_number = matches!(
+                self.field.spot(ix, iy).map(|spot| spot.state),
+                Some(SpotState::RevealedEmpty {
+                    neighboring_mines: 1..
+                })
+            );
+            if is_number && self.previews_auto_step(interaction) {
+                for (nx, ny) in self.field.neighbors_coords(ix, iy) {
+                    if let Some(
+                        SpotState::HiddenEmpty { .. }
+                        | SpotState::HiddenMine
+                        | SpotState::QuestionedEmpty { .. }
+                        | SpotState::QuestionedMine,
+                    ) = self.field.spot(nx, ny).map(|spot| spot.state)
+                    {
+                        frame.fill_rectangle(
+                            spot_position(nx, ny),
+                            Size::new(self.cell_size(), self.cell_size()),
+                            Color {
+                                a: Self::HOVER_ALPHA,
+                                ..palette.revealed
+                            },
+                        );
+                    }
+                }
+            }
+
+            frame.stroke(
+                &Path::rectangle(
+                    spot_position(ix, iy) + Vector::new(0.5, 0.5),
+                    Size::new(self.cell_size() - 1.0, self.cell_size() - 1.0),
+                ),
+                Stroke {
+                    width: 1.0,
+                    style: stroke::Style::Solid(Color {
+                        a: Self::HOVER_ALPHA,
+                        ..palette.text
+                    }),
+                    ..Stroke::default()
+                },
+            );
+
+            geometry.push(frame.into_geometry());
+        }
+
+        // Outline the spot selected with the arrow keys, on top of the cached field as well
+        if let (false, Some((ix, iy))) = (editing, self.selected_spot(interaction)) {
+            let mut frame = Frame::new(renderer, bounds.size());
+            let origin_point = self.field_origin(frame.size());
+            let fx = (ix as f32 * self.spot_size) + Self::SPOT_PAD;
+            let fy = (iy as f32 * self.spot_size) + Self::SPOT_PAD;
+
+            frame.stroke(
+                &Path::rectangle(
+                    origin_point + Vector::new(fx + 1.0, fy + 1.0),
+                    Size::new(self.cell_size() - 2.0, self.cell_size() - 2.0),
+                ),
+                Stroke {
+                    width: 2.0,
+                    style: stroke::Style::Solid(palette.text),
+                    ..Stroke::default()
+                },
+            );
+
+            geometry.push(frame.into_geometry());
+        }
+
+        // A paused game hides the minefield, so that the player can't keep thinking about it off the clock
+        if !editing && self.game_state == GameState::Paused {
+            let mut frame = Frame::new(renderer, bounds.size());
+
+            frame.fill_rectangle(Point::ORIGIN, frame.size(), palette.hidden);
+            frame.fill_text(Text {
+                content: "Paused — press Space to resume".to_string(),
+                position: frame.center(),
+                color: palette.text,
+                size: iced::Pixels(20.0),
+                font: Self::TEXT_FONT,
+                horizontal_alignment: alignment::Horizontal::Center,
+                vertical_alignment: alignment::Vertical::Center,
+                ..Text::default()
+            });
+
+            geometry.push(frame.into_geometry());
+        }
+
+        geometry
+    }
+}
+
+/// Current state of the game
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub(crate) enum GameState {
+    /// Game is ready to start running
+    #[default]
+    Ready,
+
+    /// Game is running
+    Running(Instant),
+
+    /// Game is paused
+    Paused,
+
+    /// Game is stopped, and was either won (`true`), or lost (`false`)
+    Stopped { is_won: bool },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameConfig {
+    pub width: u16,
+    pub height: u16,
+    pub mines: u32,
+
+    /// How the game time is kept. Configs saved by older versions are classic ones.
+    #[serde(default)]
+    pub mode: GameMode,
+
+    /// Autosteps are refused around numbers with wrong flags (see `Minefield::with_safe_chord`), instead of stepping on
+    /// a mine. Configs saved by older versions keep the classic autosteps.
+    #[serde(default)]
+    pub safe_chord: bool,
+
+    /// Games are won as soon as all the empty spots are revealed (see `Minefield::with_win_on_all_revealed`), without
+    /// flagging the mines. Configs saved by older versions keep the classic win, by flagging all the mines.
+    #[serde(default)]
+    pub win_on_all_revealed: bool,
+
+    /// No more flags can be placed than there are mines (see `Minefield::with_strict_flags`), so that the flag counter
+    /// never goes below zero. Configs saved by older versions allow any number of flags.
+    #[serde(default)]
+    pub strict_flags: bool,
+}
+
+/// How the game time is kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GameMode {
+    /// The game time counts up, without any limit
+    #[default]
+    Classic,
+
+    /// The game time counts down from the given limit, and the game is lost when it runs out
+    Countdown { limit: Duration },
+}
+
+impl From<GameDifficulty> for GameConfig {
+    fn from(val: GameDifficulty) -> Self {
+        match val {
+            GameDifficulty::Easy => GameDifficulty::EASY,
+            GameDifficulty::Medium => GameDifficulty::MEDIUM,
+            GameDifficulty::Hard => GameDifficulty::HARD,
+            GameDifficulty::Custom(gc) => gc,
+        }
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameDifficulty::EASY
+    }
+}
+
+impl GameConfig {
+    pub const MIN_SIZE: u16 = 2;
+    pub const MAX_SIZE: u16 = 200;
+
+    /// Boards with a higher density of mines are mostly unplayable, since they can hardly be opened up without guessing
+    pub const MAX_PLAYABLE_DENSITY: f32 = 0.35;
+
+    /// Whether games with this config are played by the classic rules, counting up without a time limit, and without
+    /// any of the variant rules. Only these games are recorded in the statistics and high scores.
+    pub fn has_classic_rules(&self) -> bool {
+        *self
+            == GameConfig {
+                width: self.width,
+                height: self.height,
+                mines: self.mines,
+                ..GameConfig::default()
+            }
+    }
+
+    /// Ratio of the number of mines to the number of spots of the minefield
+    pub fn mine_density(&self) -> f32 {
+        let spots = (self.width as u32 * self.height as u32).max(1);
+
+        self.mines as f32 / spots as f32
+    }
+
+    /// Number of mines which gives a minefield of the given size the given density, leaving at least one mine and one
+    /// empty spot (if there's room for both)
+    pub fn suggested_mines(width: u16, height: u16, density: f32) -> u32 {
+        let spots = width as u32 * height as u32;
+        let mines = (spots as f32 * density).round() as u32;
+
+        mines.clamp(1, spots.saturating_sub(1).max(1))
+    }
+
+    /// Check that a minefield with this config can actually be played: its width and height are within
+    /// `MIN_SIZE..=MAX_SIZE`, and it has at least one mine and at least one empty spot
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !(Self::MIN_SIZE..=Self::MAX_SIZE).contains(&self.width) {
+            Err(ConfigError::Width(self.width))
+        } else if !(Self::MIN_SIZE..=Self::MAX_SIZE).contains(&self.height) {
+            Err(ConfigError::Height(self.height))
+        } else if self.mines == 0 || self.mines as u64 >= self.width as u64 * self.height as u64 {
+            Err(ConfigError::Mines(self.mines))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The reasons for which a game config can't be played
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The width is out of the supported range
+    Width(u16),
 
-    const TOOLBAR_HEIGHT: f32 = 70.0;
-    const FIELD_PAD: f32 = 20.0;
-    /// Size of spor on canvas, including padding
-    const SPOT_SIZE: f32 = 30.0;
-    /// Interior padding of spot
-    const SPOT_PAD: f32 = 1.0;
-    const CELL_SIZE: f32 = Self::SPOT_SIZE - (Self::SPOT_PAD * 2.0);
-    const CELL_PAD: f32 = 8.0;
+    /// The height is out of the supported range
+    Height(u16),
 
-    #[allow(clippy::eq_op)]
-    const COLOR_RED: Color = Color::from_rgb(255.0 / 255.0, 0.0 / 255.0, 0.0 / 255.0);
-    #[allow(clippy::eq_op)]
-    const COLOR_LIGHT_RED: Color = Color::from_rgb(255.0 / 255.0, 128.0 / 255.0, 128.0 / 255.0);
-    #[allow(clippy::eq_op)]
-    const COLOR_GREEN: Color = Color::from_rgb(0.0 / 255.0, 255.0 / 255.0, 0.0 / 255.0);
-    const COLOR_GRAY: Color = Color::from_rgb(60.0 / 255.0, 60.0 / 255.0, 60.0 / 255.0);
-    const COLOR_DARK_GRAY: Color = Color::from_rgb(27.0 / 255.0, 27.0 / 255.0, 27.0 / 255.0);
+    /// There are no mines, or no spots left without a mine
+    Mines(u32),
+}
 
-    const MINE_CHAR: &'static str = "☢";
-    const MINE_COLOR: Color = Self::COLOR_RED;
-    const MINE_EXPLODED_CHAR: &'static str = "💥";
-    const MINE_EXPLODED_COLOR: Color = Self::COLOR_RED;
-    const FLAG_CHAR: &'static str = "⚐";
-    const FLAG_COLOR_CORRECT: Color = Self::COLOR_GREEN;
-    const FLAG_COLOR_WRONG: Color = Self::COLOR_RED;
-    const EMPTY_SPOT_CHARS: [&'static str; 9] = [" ", "1", "2", "3", "4", "5", "6", "7", "8"];
-    const EMPTY_SPOT_COLORS: [Color; Self::EMPTY_SPOT_CHARS.len()] = [
-        Color::WHITE,
-        Color::WHITE,
-        Color::WHITE,
-        Color::WHITE,
-        Color::WHITE,
-        Color::WHITE,
-        Color::WHITE,
-        Color::WHITE,
-        Color::WHITE,
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Width(w) => write!(
+                f,
+                "Width {} is not between {} and {}",
+                w,
+                GameConfig::MIN_SIZE,
+                GameConfig::MAX_SIZE
+            ),
+            ConfigError::Height(h) => write!(
+                f,
+                "Height {} is not between {} and {}",
+                h,
+                GameConfig::MIN_SIZE,
+                GameConfig::MAX_SIZE
+            ),
+            ConfigError::Mines(m) => write!(
+                f,
+                "{} mines don't leave both mines and empty spots on the minefield",
+                m
+            ),
+        }
+    }
+}
+
+/// Overrides of the game config and seed, requested when launching the game.
+///
+/// Precedence, from highest to lowest: command line arguments, environment variables, persisted config, default config.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StartupConfig {
+    /// Game config to start with, instead of the persisted one (which is still the one saved)
+    game_config: Option<GameConfig>,
+
+    /// Seed of the first minefield
+    seed: Option<u64>,
+
+    /// File holding the game config and high scores, instead of the one in the config directory of the platform
+    persistence_path: Option<PathBuf>,
+}
+
+impl StartupConfig {
+    pub const ENV_WIDTH: &'static str = "MINESWEEP_WIDTH";
+    pub const ENV_HEIGHT: &'static str = "MINESWEEP_HEIGHT";
+    pub const ENV_MINES: &'static str = "MINESWEEP_MINES";
+    pub const ENV_SEED: &'static str = "MINESWEEP_SEED";
+    pub const ENV_CONFIG_FILE: &'static str = "MINESWEEP_CONFIG_FILE";
+
+    /// Read the startup config from the `MINESWEEP_*` environment variables. Invalid values are logged and ignored,
+    /// while the valid ones are still applied.
+    pub fn from_env() -> Self {
+        let (startup, errors) = Self::from_vars(|name| std::env::var(name).ok());
+
+        for e in errors {
+            log::warn!("Ignoring startup config from environment: {}", e);
+        }
+
+        if startup != Self::default() {
+            log::info!("Applying startup config from environment: {:?}", startup);
+        }
+
+        startup
+    }
+
+    /// Parse the startup config from variables looked up by name with `var`, along with why any variables which were
+    /// left out are invalid. Each variable is parsed on its own, and dimensions which are not given (or not valid)
+    /// default to the ones of the default game config. A game config which can't be played is left out as a whole.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> (Self, Vec<String>) {
+        fn parse<T: std::str::FromStr>(
+            var: &impl Fn(&str) -> Option<String>,
+            name: &str,
+            errors: &mut Vec<String>,
+        ) -> Option<T> {
+            let value = var(name)?;
+            let parsed = value.trim().parse::<T>().ok();
+            if parsed.is_none() {
+                errors.push(format!("{}={:?} is not a valid number", name, value));
+            }
+
+            parsed
+        }
+
+        let mut errors = Vec::new();
+        let width = parse::<u16>(&var, Self::ENV_WIDTH, &mut errors);
+        let height = parse::<u16>(&var, Self::ENV_HEIGHT, &mut errors);
+        let mines = parse::<u32>(&var, Self::ENV_MINES, &mut errors);
+        let seed = parse::<u64>(&var, Self::ENV_SEED, &mut errors);
+
+        let game_config = if width.is_some() || height.is_some() || mines.is_some() {
+            let default = GameConfig::default();
+            let game_config = GameConfig {
+                width: width.unwrap_or(default.width),
+                height: height.unwrap_or(default.height),
+                mines: mines.unwrap_or(default.mines),
+                ..default
+            };
+
+            match game_config.validate() {
+                Ok(()) => Some(game_config),
+                Err(e) => {
+                    errors.push(e.to_string());
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let persistence_path = var(Self::ENV_CONFIG_FILE)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from);
+
+        (
+            Self {
+                game_config,
+                seed,
+                persistence_path,
+            },
+            errors,
+        )
+    }
+
+    /// Usage of the command line arguments, shown by `--help` and when they can't be parsed
+    pub const USAGE: &'static str = "\
+Usage: iced-minesweep-rs [OPTIONS]
+
+Options:
+  --difficulty <easy|medium|hard>  Start with a standard difficulty
+  --custom <WIDTHxHEIGHT:MINES>    Start with a custom game, e.g. 30x16:99
+  --seed <N>                       Seed of the first minefield's mines
+  --config <FILE>                  File holding the game config and high scores
+  -h, --help                       Show this help";
+
+    /// Override this startup config with the given command line arguments (without the program name), which take
+    /// precedence over the environment variables. Options can be given as `--seed 42` or `--seed=42`.
+    pub fn with_args(mut self, args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (option, inline_value) = match arg.split_once('=') {
+                Some((option, value)) => (option.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} needs a value", option))
+            };
+
+            match option.as_str() {
+                "--difficulty" => {
+                    let difficulty = value()?;
+                    let game_config = match difficulty.to_lowercase().as_str() {
+                        "easy" => GameDifficulty::EASY,
+                        "medium" => GameDifficulty::MEDIUM,
+                        "hard" => GameDifficulty::HARD,
+                        _ => return Err(format!("{:?} is not a difficulty", difficulty)),
+                    };
+
+                    self.game_config = Some(game_config);
+                }
+                "--custom" => {
+                    let custom = value()?;
+                    let invalid = || format!("{:?} is not a custom game like 30x16:99", custom);
+                    let (size, mines) = custom.split_once(':').ok_or_else(invalid)?;
+                    let (width, height) = size.split_once(['x', 'X']).ok_or_else(invalid)?;
+                    let game_config = GameConfig {
+                        width: width.trim().parse().map_err(|_| invalid())?,
+                        height: height.trim().parse().map_err(|_| invalid())?,
+                        mines: mines.trim().parse().map_err(|_| invalid())?,
+                        ..GameConfig::default()
+                    };
+                    game_config.validate().map_err(|e| e.to_string())?;
+
+                    self.game_config = Some(game_config);
+                }
+                "--seed" => {
+                    let seed = value()?;
+
+                    self.seed = Some(
+                        seed.trim()
+                            .parse()
+                            .map_err(|_| format!("{:?} is not a valid seed", seed))?,
+                    );
+                }
+                "--config" => {
+                    self.persistence_path = Some(PathBuf::from(value()?));
+                }
+                _ => return Err(format!("Unknown argument {:?}", option)),
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+/// A custom game config saved by the player under a name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Preset {
+    name: String,
+    game_config: GameConfig,
+}
+
+impl Display for Preset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (w:{}, h:{}, m:{})",
+            self.name, self.game_config.width, self.game_config.height, self.game_config.mines
+        )
+    }
+}
+
+/// An entry of the game difficulty picker: either one of the game difficulties, or one of the player's presets
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DifficultyChoice {
+    Difficulty(GameDifficulty),
+    Preset(Preset),
+}
+
+impl DifficultyChoice {
+    /// All the game difficulties, followed by the given presets
+    fn all(presets: &[Preset]) -> Vec<Self> {
+        GameDifficulty::ALL
+            .iter()
+            .map(|&game_difficulty| Self::Difficulty(game_difficulty))
+            .chain(presets.iter().cloned().map(Self::Preset))
+            .collect()
+    }
+}
+
+impl Display for DifficultyChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DifficultyChoice::Difficulty(game_difficulty) => game_difficulty.fmt(f),
+            DifficultyChoice::Preset(preset) => preset.fmt(f),
+        }
+    }
+}
+
+/// A description of the game difficulty, with a special entry for custom games
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameDifficulty {
+    Easy,
+    Medium,
+    Hard,
+    Custom(GameConfig),
+}
+
+impl GameDifficulty {
+    pub const ALL: &'static [GameDifficulty] = &[
+        Self::Easy,
+        Self::Medium,
+        Self::Hard,
+        Self::Custom(Self::DEFAULT_CUSTOM),
+    ];
+    pub const EASY: GameConfig = GameConfig {
+        width: 10,
+        height: 10,
+        mines: 10,
+        mode: GameMode::Classic,
+        safe_chord: false,
+        win_on_all_revealed: false,
+        strict_flags: false,
+    };
+    pub const MEDIUM: GameConfig = GameConfig {
+        width: 16,
+        height: 16,
+        mines: 40,
+        ..Self::EASY
+    };
+    pub const HARD: GameConfig = GameConfig {
+        width: 30,
+        height: 16,
+        mines: 99,
+        ..Self::EASY
+    };
+    pub const DEFAULT_CUSTOM: GameConfig = GameConfig {
+        width: 45,
+        height: 24,
+        mines: 150,
+        ..Self::EASY
+    };
+
+    /// Shortest time limit of countdown custom games
+    const MIN_COUNTDOWN_SECONDS: u64 = 60;
+
+    /// The difficulty level whose high scores a game of this difficulty is recorded on, if any.
+    ///
+    /// Custom games have no high scores of their own. A custom game whose config exactly matches a standard difficulty
+    /// is recorded on that difficulty's high scores only if `custom_matches_standard` is `true`.
+    pub fn high_score_level(&self, custom_matches_standard: bool) -> Option<DifficultyLevel> {
+        match self {
+            GameDifficulty::Custom(gc) if custom_matches_standard => {
+                Self::from_config(gc).try_into().ok()
+            }
+            _ => (*self).try_into().ok(),
+        }
+    }
+
+    /// The difficulty of a game config, whatever the mode of the game
+    pub fn from_config(config: &GameConfig) -> Self {
+        let classic = GameConfig {
+            width: config.width,
+            height: config.height,
+            mines: config.mines,
+            ..GameConfig::default()
+        };
+
+        if classic == Self::EASY {
+            Self::Easy
+        } else if classic == Self::MEDIUM {
+            Self::Medium
+        } else if classic == Self::HARD {
+            Self::Hard
+        } else {
+            Self::Custom(classic)
+        }
+    }
+
+    /// Time limit of countdown games of this difficulty. Custom games get some time for each mine.
+    pub fn countdown_limit(&self) -> Duration {
+        match self {
+            GameDifficulty::Easy => Duration::from_secs(120),
+            GameDifficulty::Medium => Duration::from_secs(360),
+            GameDifficulty::Hard => Duration::from_secs(900),
+            GameDifficulty::Custom(gc) => {
+                Duration::from_secs((gc.mines as u64 * 10).max(Self::MIN_COUNTDOWN_SECONDS))
+            }
+        }
+    }
+}
+
+impl Display for GameDifficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameDifficulty::Easy => write!(
+                f,
+                "Easy (w:{}, h:{}, m:{})",
+                Self::EASY.width,
+                Self::EASY.height,
+                Self::EASY.mines
+            ),
+            GameDifficulty::Medium => write!(
+                f,
+                "Medium (w:{}, h:{}, m:{})",
+                Self::MEDIUM.width,
+                Self::MEDIUM.height,
+                Self::MEDIUM.mines
+            ),
+            GameDifficulty::Hard => write!(
+                f,
+                "Hard (w:{}, h:{}, m:{})",
+                Self::HARD.width,
+                Self::HARD.height,
+                Self::HARD.mines
+            ),
+            GameDifficulty::Custom(gc) => write!(
+                f,
+                "Custom (w:{}, h:{}, m:{})",
+                gc.width, gc.height, gc.mines
+            ),
+        }
+    }
+}
+
+/// All standard difficulty levels (i.e. which do not describe a `GameDifficulty::Custom` game)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DifficultyLevel {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl TryFrom<GameDifficulty> for DifficultyLevel {
+    type Error = ();
+
+    fn try_from(game_difficulty: GameDifficulty) -> Result<Self, Self::Error> {
+        match game_difficulty {
+            GameDifficulty::Easy => Ok(Self::Easy),
+            GameDifficulty::Medium => Ok(Self::Medium),
+            GameDifficulty::Hard => Ok(Self::Hard),
+            GameDifficulty::Custom(_) => Err(()),
+        }
+    }
+}
+
+impl DifficultyLevel {
+    pub const ALL: &'static [DifficultyLevel] = &[Self::Easy, Self::Medium, Self::Hard];
+
+    /// The standard game config of this difficulty
+    pub fn game_config(&self) -> GameConfig {
+        match self {
+            DifficultyLevel::Easy => GameDifficulty::EASY,
+            DifficultyLevel::Medium => GameDifficulty::MEDIUM,
+            DifficultyLevel::Hard => GameDifficulty::HARD,
+        }
+    }
+}
+
+impl DifficultyLevel {
+    /// Title of the high scores of this difficulty, or of its flagless high scores
+    pub fn high_scores_title(&self, flagless: bool) -> String {
+        if flagless {
+            format!("{} without flags", self)
+        } else {
+            self.to_string()
+        }
+    }
+}
+
+impl Display for DifficultyLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DifficultyLevel::Easy => write!(f, "Easy"),
+            DifficultyLevel::Medium => write!(f, "Medium"),
+            DifficultyLevel::Hard => write!(f, "Hard"),
+        }
+    }
+}
+
+/// An action which can be taken on a spot of the minefield
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpotAction {
+    /// Do nothing
+    None,
+
+    /// Step on the spot
+    Step,
+
+    /// Toggle a flag on the spot
+    Flag,
+
+    /// Autostep around the spot
+    AutoStep,
+
+    /// Reveal the hidden neighbors of the spot, without requiring flags around it
+    RevealRing,
+
+    /// Flag the hidden neighbors of the spot, if they must all be mines
+    AutoFlag,
+}
+
+impl SpotAction {
+    pub const ALL: &'static [SpotAction] = &[
+        Self::None,
+        Self::Step,
+        Self::Flag,
+        Self::AutoStep,
+        Self::RevealRing,
+        Self::AutoFlag,
     ];
-    const REVEALED_SPOT_COLOR: Color = Self::COLOR_DARK_GRAY;
-    const HIDDEN_SPOT_COLOR: Color = Self::COLOR_GRAY;
 
-    const READY_COLOR: Color = Self::COLOR_GRAY;
-    const WON_COLOR: Color = Self::COLOR_GREEN;
-    const LOST_COLOR: Color = Self::COLOR_RED;
+    /// The message which takes this action on the spot at the given coordinates
+    fn message(self, x: u16, y: u16) -> Option<Message> {
+        match self {
+            SpotAction::None => None,
+            SpotAction::Step => Some(Message::Minesweep(MinesweepMessage::Step { x, y })),
+            SpotAction::Flag => Some(Message::Minesweep(MinesweepMessage::Flag { x, y })),
+            SpotAction::AutoStep => Some(Message::Minesweep(MinesweepMessage::AutoStep { x, y })),
+            SpotAction::RevealRing => {
+                Some(Message::Minesweep(MinesweepMessage::RevealRing { x, y }))
+            }
+            SpotAction::AutoFlag => Some(Message::Minesweep(MinesweepMessage::AutoFlag { x, y })),
+        }
+    }
+}
 
-    const FLAG_COUNT_OK_COLOR: Color = Color::WHITE;
-    const FLAG_COUNT_ERR_COLOR: Color = Self::COLOR_LIGHT_RED;
+impl Display for SpotAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpotAction::None => write!(f, "Nothing"),
+            SpotAction::Step => write!(f, "Step"),
+            SpotAction::Flag => write!(f, "Flag"),
+            SpotAction::AutoStep => write!(f, "Autostep"),
+            SpotAction::RevealRing => write!(f, "Reveal neighbors"),
+            SpotAction::AutoFlag => write!(f, "Flag neighbors"),
+        }
+    }
+}
 
-    const MAX_HIGH_SCORES_PER_LEVEL: usize = 3;
-    const MAX_HIGHSCORE_NAME_LEN: usize = 32;
+/// The mapping of mouse buttons to the actions they take on the minefield
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ControlsConfig {
+    pub left: SpotAction,
+    pub right: SpotAction,
+    pub middle: SpotAction,
+
+    /// Pressing the left and right buttons together autosteps around a spot. The left button action is then taken
+    /// when the button is released, instead of when it is pressed.
+    pub left_right_chord: bool,
+}
 
-    #[allow(dead_code)]
-    pub fn with_configs(mut self, game_config: GameConfig) -> Self {
-        self.game_config = game_config;
-        self.field = Minefield::new(self.game_config.width, self.game_config.height)
-            .with_mines(self.game_config.mines);
+impl From<ControlScheme> for ControlsConfig {
+    fn from(val: ControlScheme) -> Self {
+        match val {
+            ControlScheme::Gnome => ControlScheme::GNOME,
+            ControlScheme::WindowsClassic => ControlScheme::WINDOWS_CLASSIC,
+            ControlScheme::Custom(cc) => cc,
+        }
+    }
+}
 
-        self
+impl Default for ControlsConfig {
+    fn default() -> Self {
+        ControlScheme::GNOME
     }
+}
 
-    fn desired_window_size(&self) -> (f32, f32) {
-        let (field_width, field_height) = self.desired_field_size();
+/// A description of the mouse controls, with a special entry for custom button mappings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlScheme {
+    Gnome,
+    WindowsClassic,
+    Custom(ControlsConfig),
+}
 
-        let width = field_width;
-        let height = field_height + Self::TOOLBAR_HEIGHT;
+impl ControlScheme {
+    pub const ALL: &'static [ControlScheme] = &[
+        Self::Gnome,
+        Self::WindowsClassic,
+        Self::Custom(Self::DEFAULT_CUSTOM),
+    ];
+    pub const GNOME: ControlsConfig = ControlsConfig {
+        left: SpotAction::Step,
+        right: SpotAction::Flag,
+        middle: SpotAction::AutoStep,
+        left_right_chord: false,
+    };
+    pub const WINDOWS_CLASSIC: ControlsConfig = ControlsConfig {
+        left: SpotAction::Step,
+        right: SpotAction::Flag,
+        middle: SpotAction::None,
+        left_right_chord: true,
+    };
+    pub const DEFAULT_CUSTOM: ControlsConfig = ControlsConfig {
+        left: SpotAction::Step,
+        right: SpotAction::Flag,
+        middle: SpotAction::AutoStep,
+        left_right_chord: true,
+    };
 
-        (width, height)
+    pub fn from_config(config: &ControlsConfig) -> Self {
+        if *config == Self::GNOME {
+            Self::Gnome
+        } else if *config == Self::WINDOWS_CLASSIC {
+            Self::WindowsClassic
+        } else {
+            Self::Custom(*config)
+        }
     }
+}
 
-    fn desired_field_size(&self) -> (f32, f32) {
-        let width = (Self::SPOT_SIZE * self.field.width() as f32) + (Self::FIELD_PAD * 2.0);
-        let height = (Self::SPOT_SIZE * self.field.height() as f32) + (Self::FIELD_PAD * 2.0);
-
-        (width, height)
+impl Display for ControlScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ControlScheme::Gnome => write!(f, "GNOME (middle click autosteps)"),
+            ControlScheme::WindowsClassic => {
+                write!(f, "Windows classic (left + right click autosteps)")
+            }
+            ControlScheme::Custom(_) => write!(f, "Custom"),
+        }
     }
+}
 
-    /// Controls view
-    fn view_controls(&self) -> Element<Message> {
-        let text_color = match self.game_state {
-            GameState::Ready => Self::READY_COLOR,
-            GameState::Running(_) => Color::WHITE,
-            GameState::Paused => Self::READY_COLOR,
-            GameState::Stopped { is_won } => match is_won {
-                true => Self::WON_COLOR,
-                false => Self::LOST_COLOR,
-            },
-        };
+/// Colors which can be used for highlighting the mine which was stepped on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HighlightColor {
+    #[default]
+    Yellow,
+    Orange,
+    Magenta,
+    Cyan,
+    White,
+}
 
-        let time_text_size = 20;
-        let time_text = match self.game_state {
-            GameState::Ready => widget::text("---").size(time_text_size),
-            GameState::Running(_) | GameState::Paused => {
-                widget::text(self.elapsed_seconds.as_secs()).size(time_text_size)
-            }
-            GameState::Stopped { is_won: _ } => {
-                widget::text(self.elapsed_seconds.as_secs()).size(time_text_size)
-            }
-        };
+impl HighlightColor {
+    pub const ALL: &'static [HighlightColor] = &[
+        Self::Yellow,
+        Self::Orange,
+        Self::Magenta,
+        Self::Cyan,
+        Self::White,
+    ];
 
-        let display_seconds = widget::column![
-            widget::text("Time").size(10).color(text_color),
-            time_text.color(text_color)
-        ]
-        .align_x(Alignment::Center);
+    pub fn color(&self) -> Color {
+        match self {
+            HighlightColor::Yellow => Color::from_rgb(1.0, 0.85, 0.0),
+            HighlightColor::Orange => Color::from_rgb(1.0, 0.5, 0.0),
+            HighlightColor::Magenta => Color::from_rgb(1.0, 0.0, 1.0),
+            HighlightColor::Cyan => Color::from_rgb(0.0, 0.9, 1.0),
+            HighlightColor::White => Color::WHITE,
+        }
+    }
+}
 
-        let flags_text_size = 20;
+impl Display for HighlightColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HighlightColor::Yellow => write!(f, "Yellow"),
+            HighlightColor::Orange => write!(f, "Orange"),
+            HighlightColor::Magenta => write!(f, "Magenta"),
+            HighlightColor::Cyan => write!(f, "Cyan"),
+            HighlightColor::White => write!(f, "White"),
+        }
+    }
+}
 
-        let flags_text = match self.game_state {
-            GameState::Ready => widget::text("---").size(flags_text_size).color(text_color),
-            GameState::Running(_) => {
-                let flags_text_color = if self.remaining_flags >= 0 {
-                    Self::FLAG_COUNT_OK_COLOR
-                } else {
-                    Self::FLAG_COUNT_ERR_COLOR
-                };
+/// Color themes of the application, along with the colors of the minefield drawn with each of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ColorTheme {
+    #[default]
+    Dark,
+    Light,
 
-                widget::text(self.remaining_flags)
-                    .size(flags_text_size)
-                    .color(flags_text_color)
-            }
-            GameState::Paused => widget::text(self.remaining_flags)
-                .size(flags_text_size)
-                .color(text_color),
-            GameState::Stopped { is_won: _ } => widget::text(self.remaining_flags)
-                .size(flags_text_size)
-                .color(text_color),
-        };
-        let display_flags =
-            widget::column![widget::text("Flags").size(10).color(text_color), flags_text]
-                .align_x(Alignment::Center);
+    /// A dark theme with brighter hidden spots on black, and vivid colors for the revealed numbers
+    HighContrast,
+}
 
-        widget::row![
-            widget::row![widget::button(
-                widget::text(Self::REFRESH_BTN_CHAR).font(Self::COMMANDS_ICONS)
-            )
-            .on_press(Message::Reset)
-            .style(button::primary),]
-            .width(Length::Shrink)
-            .align_y(Alignment::Start),
-            widget::row![
-                widget::horizontal_space(),
-                display_seconds,
-                display_flags,
-                widget::horizontal_space()
-            ]
-            .spacing(20.0)
-            .width(Length::Fill)
-            .align_y(Alignment::Center),
-            widget::row![
-                widget::button(widget::text(Self::SETTINGS_BTN_CHAR).font(Self::MINES_FLAGS_ICONS))
-                    .on_press(Message::Settings(SettingsMessage::Show))
-                    .style(button::primary),
-                widget::button(widget::text(Self::ABOUT_BTN_CHAR).font(Self::COMMANDS_ICONS))
-                    .on_press(Message::Info)
-                    .style(button::primary),
-                widget::button(widget::text(Self::HIGH_SCORES_CHAR).font(Self::COMMANDS_ICONS))
-                    .on_press(Message::HighScores)
-                    .style(button::primary),
-            ]
-            .spacing(10.0)
-            .width(Length::Shrink)
-            .align_y(Alignment::End),
-        ]
-        .padding(10.0)
-        .spacing(10.0)
-        .align_y(Alignment::Center)
-        .width(Length::Fill)
-        .into()
+impl ColorTheme {
+    pub const ALL: &'static [ColorTheme] = &[Self::Dark, Self::Light, Self::HighContrast];
+
+    /// The theme of the widgets around the minefield
+    pub fn theme(&self) -> Theme {
+        match self {
+            ColorTheme::Dark => Theme::Dark,
+            ColorTheme::Light => Theme::Light,
+            ColorTheme::HighContrast => Theme::custom(
+                self.to_string(),
+                iced::theme::Palette {
+                    background: Color::BLACK,
+                    text: Color::WHITE,
+                    primary: Color::from_rgb(1.0, 0.85, 0.0),
+                    success: Color::from_rgb(0.0, 1.0, 0.0),
+                    danger: Color::from_rgb(1.0, 0.2, 0.2),
+                },
+            ),
+        }
     }
 
-    /// Minefield view
-    fn view_field(&self) -> Element<Message> {
-        let (field_width, field_height) = self.desired_field_size();
-        Canvas::new(self)
-            .width(field_width)
-            .height(field_height)
-            .into()
+    /// The colors of the minefield
+    pub fn field_palette(&self) -> FieldPalette {
+        match self {
+            ColorTheme::Dark => FieldPalette {
+                hidden: Minesweep::COLOR_GRAY,
+                revealed: Minesweep::COLOR_DARK_GRAY,
+                // The classic colors, lightened to stand out on the dark background
+                numbers: [
+                    Color::WHITE,
+                    Color::from_rgb8(100, 150, 255),
+                    Color::from_rgb8(80, 200, 80),
+                    Color::from_rgb8(255, 90, 90),
+                    Color::from_rgb8(140, 140, 255),
+                    Color::from_rgb8(220, 120, 120),
+                    Color::from_rgb8(60, 200, 200),
+                    Color::from_rgb8(230, 230, 230),
+                    Color::from_rgb8(160, 160, 160),
+                ],
+                text: Color::WHITE,
+            },
+            ColorTheme::Light => FieldPalette {
+                hidden: Color::from_rgb8(190, 190, 190),
+                revealed: Color::from_rgb8(235, 235, 235),
+                // The classic colors: blue, green, red, dark blue, maroon, teal, black and gray
+                numbers: [
+                    Color::BLACK,
+                    Color::from_rgb8(0, 0, 255),
+                    Color::from_rgb8(0, 128, 0),
+                    Color::from_rgb8(255, 0, 0),
+                    Color::from_rgb8(0, 0, 128),
+                    Color::from_rgb8(128, 0, 0),
+                    Color::from_rgb8(0, 128, 128),
+                    Color::BLACK,
+                    Color::from_rgb8(128, 128, 128),
+                ],
+                text: Color::BLACK,
+            },
+            ColorTheme::HighContrast => FieldPalette {
+                hidden: Color::from_rgb8(110, 110, 110),
+                revealed: Color::BLACK,
+                numbers: [
+                    Color::WHITE,
+                    Color::from_rgb8(80, 160, 255),
+                    Color::from_rgb8(0, 230, 0),
+                    Color::from_rgb8(255, 70, 70),
+                    Color::from_rgb8(200, 110, 255),
+                    Color::from_rgb8(255, 190, 0),
+                    Color::from_rgb8(0, 230, 230),
+                    Color::from_rgb8(255, 110, 200),
+                    Color::WHITE,
+                ],
+                text: Color::WHITE,
+            },
+        }
     }
+}
 
-    /// Settings view
-    fn view_settings(&self, game_difficulty: &GameDifficulty) -> Element<Message> {
-        let mut settings_page = widget::column![
-            widget::text("Game Difficulty"),
-            widget::pick_list(GameDifficulty::ALL, Some(*game_difficulty), |x| {
-                Message::Settings(SettingsMessage::Picked(x))
-            })
-        ]
-        .spacing(10.0);
+impl Display for ColorTheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColorTheme::Dark => write!(f, "Dark"),
+            ColorTheme::Light => write!(f, "Light"),
+            ColorTheme::HighContrast => write!(f, "High contrast"),
+        }
+    }
+}
 
-        if let GameDifficulty::Custom(game_config) = game_difficulty {
-            let width = game_config.width;
-            let height = game_config.height;
-            let mines = game_config.mines;
+/// Colors of the minefield, which depend on the color theme
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldPalette {
+    /// Background of the hidden, flagged and questioned spots
+    hidden: Color,
 
-            let custom_game = widget::column![
-                widget::text("Custom Game"),
-                widget::row![
-                    widget::text("Width:"),
-                    widget::text_input("", game_config.width.to_string().as_str()).on_input(
-                        move |s| {
-                            if let Ok(i) = s.parse::<u16>() {
-                                Message::Settings(SettingsMessage::CustomConfig(GameConfig {
-                                    width: i,
-                                    height,
-                                    mines,
-                                }))
-                            } else {
-                                Message::Settings(SettingsMessage::CustomConfig(GameConfig {
-                                    width,
-                                    height,
-                                    mines,
-                                }))
-                            }
-                        }
-                    )
-                ]
-                .spacing(10.0),
-                widget::row![
-                    widget::text("Height:"),
-                    widget::text_input("", game_config.height.to_string().as_str()).on_input(
-                        move |s| {
-                            if let Ok(i) = s.parse::<u16>() {
-                                Message::Settings(SettingsMessage::CustomConfig(GameConfig {
-                                    width,
-                                    height: i,
-                                    mines,
-                                }))
-                            } else {
-                                Message::Settings(SettingsMessage::CustomConfig(GameConfig {
-                                    width,
-                                    height,
-                                    mines,
-                                }))
-                            }
-                        }
-                    )
-                ]
-                .spacing(10.0),
-                widget::row![
-                    widget::text("Mines:"),
-                    widget::text_input("", game_config.mines.to_string().as_str()).on_input(
-                        move |s| {
-                            if let Ok(i) = s.parse::<u32>() {
-                                Message::Settings(SettingsMessage::CustomConfig(GameConfig {
-                                    width,
-                                    height,
-                                    mines: i,
-                                }))
-                            } else {
-                                Message::Settings(SettingsMessage::CustomConfig(GameConfig {
-                                    width,
-                                    height,
-                                    mines,
-                                }))
-                            }
-                        }
-                    )
-                ]
-                .spacing(10.0),
-            ]
-            .spacing(10.0);
+    /// Background of the revealed spots, and of the minefield itself
+    revealed: Color,
 
-            settings_page = settings_page.push(custom_game);
-        }
+    /// Color of the revealed numbers, indexed by their count of neighboring mines
+    numbers: [Color; 9],
 
-        widget::column![
-            settings_page.height(Length::Fill).width(Length::Fill),
-            widget::column![widget::row![
-                widget::button("Cancel")
-                    .on_press(Message::Settings(SettingsMessage::Discard))
-                    .style(button::primary),
-                widget::button("Apply")
-                    .on_press(Message::Settings(SettingsMessage::Set(*game_difficulty)))
-                    .style(button::primary),
-            ]
-            .spacing(10.0)
-            .width(Length::Shrink)
-            .align_y(Alignment::End)]
-            .width(Length::Fill)
-            .align_x(Alignment::End)
-        ]
-        .align_x(Alignment::End)
-        .width(Length::Fill)
-        .spacing(10.0)
-        .padding(Self::FIELD_PAD)
-        .into()
-    }
+    /// Color of the question marks, of the outline of the selected spot, and of the other text drawn on the minefield
+    text: Color,
+}
 
-    /// Info/"About" view
-    fn view_info(&self) -> Element<Message> {
-        let license_text = std::str::from_utf8(Self::LICESE_BYTES).unwrap_or("");
+/// The sections in which the options of the settings view are grouped, in the order in which they are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SettingsSection {
+    Gameplay,
+    Appearance,
+    Accessibility,
+    Controls,
+    Data,
+}
 
-        let content = widget::column![
-            widget::row![widget::text("About").font(Self::TEXT_FONT)],
-            widget::row![widget::text("Copyright (c) 2023 Bogdan Olar").size(15.0)].padding(10),
-            widget::row![
-                widget::text("https://github.com/BogdanOlar/iced-minesweep-rs").size(15.0)
-            ]
-            .padding(10),
-            widget::row![widget::text("License").font(Self::TEXT_FONT)],
-            widget::row![widget::text(license_text).font(Self::TEXT_FONT).size(12.0)].padding(10),
-            widget::column![widget::row![widget::button("Ok")
-                .on_press(Message::Info)
-                .style(button::primary),]
-            .spacing(10.0)
-            .width(Length::Shrink)
-            .align_y(Alignment::End)]
-            .width(Length::Fill)
-            .align_x(Alignment::End)
-            .padding(20.0)
-        ]
-        .align_x(Alignment::Start)
-        .spacing(10);
+impl Display for SettingsSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingsSection::Gameplay => write!(f, "Gameplay"),
+            SettingsSection::Appearance => write!(f, "Appearance"),
+            SettingsSection::Accessibility => write!(f, "Accessibility"),
+            SettingsSection::Controls => write!(f, "Controls"),
+            SettingsSection::Data => write!(f, "Data"),
+        }
+    }
+}
 
-        widget::column![widget::scrollable(container(content).width(Length::Fill)),]
-            .padding(Self::FIELD_PAD)
-            .into()
+impl SettingsSection {
+    /// Whether the lowercase text of an option (its section, keywords and labels) matches the search filter, which is
+    /// the case when it contains each of the words of the filter
+    fn matches(text: &str, search: &str) -> bool {
+        search
+            .split_whitespace()
+            .all(|word| text.contains(&word.to_lowercase()))
     }
+}
 
-    /// High Scores view
-    fn view_high_scores(&self) -> Element<Message> {
-        let mut content = widget::column![]
-            .spacing(10)
-            .width(Length::Fill)
-            .padding(20.0);
-        content = content.push(
-            widget::column![widget::text("High Scores").font(Self::TEXT_FONT).size(25.0)]
-                .width(Length::Fill)
-                .align_x(Alignment::Center),
-        );
+/// Settings which have been picked in the settings view, but not yet applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingsDraft {
+    game_difficulty: GameDifficulty,
 
-        for difficulty_level in DifficultyLevel::ALL {
-            content = content.push(widget::horizontal_rule(10.0));
+    /// Games count down from the time limit of the picked difficulty, instead of counting up
+    countdown: bool,
 
-            content = content.push(
-                widget::row![widget::text(difficulty_level.to_string()).font(Self::TEXT_FONT)]
-                    .width(Length::Fill)
-                    .align_y(Alignment::Center),
-            );
+    /// Autosteps are refused around numbers with wrong flags
+    safe_chord: bool,
 
-            let scores = if let Some(scores) = self.high_scores.get(difficulty_level) {
-                scores
-            } else {
-                &self.empty_scores
-            };
+    /// Games are won by revealing all the empty spots, without flagging the mines
+    win_on_all_revealed: bool,
 
-            for i in 0..Self::MAX_HIGH_SCORES_PER_LEVEL {
-                if let Some(score) = scores.get(i) {
-                    content = content.push(
-                        widget::row![
-                            widget::column![widget::text(format!("# {}. ", i + 1)).size(15.0),]
-                                .width(Length::Shrink)
-                                .height(Length::Shrink)
-                                .align_x(Alignment::Start),
-                            widget::column![widget::text(score.name.as_str()).size(15.0)]
-                                .width(Length::Fill)
-                                .height(Length::Shrink)
-                                .align_x(Alignment::Start),
-                            widget::column![widget::text(score.seconds.to_string()).size(15.0)]
-                                .width(Length::Shrink)
-                                .height(Length::Shrink)
-                                .align_x(Alignment::End),
-                        ]
-                        .width(Length::Fill)
-                        .spacing(40.0)
-                        .align_y(Alignment::End),
-                    );
-                } else {
-                    content = content.push(
-                        widget::row![
-                            widget::column![widget::text(format!("# {}. ", i + 1))
-                                .size(15.0)
-                                .color(Self::READY_COLOR),]
-                            .width(Length::Shrink)
-                            .height(Length::Shrink)
-                            .align_x(Alignment::Start),
-                            widget::column![widget::text("Empty")
-                                .size(15.0)
-                                .color(Self::READY_COLOR),]
-                            .width(Length::Fill)
-                            .height(Length::Shrink)
-                            .align_x(Alignment::Start),
-                            widget::horizontal_space(),
-                        ]
-                        .width(Length::Fill)
-                        .spacing(40.0)
-                        .align_y(Alignment::End),
-                    );
-                }
-            }
-        }
+    /// No more flags can be placed than there are mines
+    strict_flags: bool,
 
-        content = content.push(
-            widget::column![widget::row![widget::button("Ok")
-                .on_press(Message::HighScores)
-                .style(button::primary),]
-            .spacing(10.0)
-            .width(Length::Shrink)
-            .align_y(Alignment::End)]
-            .width(Length::Fill)
-            .align_x(Alignment::End)
-            .padding(20.0),
-        );
+    /// Number of high scores kept for each difficulty level
+    max_high_scores: usize,
 
-        widget::column![widget::scrollable(container(content).width(Length::Fill)),]
-            .width(Length::Fill)
-            .padding(Self::FIELD_PAD)
-            .into()
-    }
+    /// The control scheme being edited, for the picked difficulty level if controls are remembered per difficulty
+    control_scheme: ControlScheme,
 
-    /// New high score view
-    fn view_record_high_score(
-        &self,
-        hs: HighScoreLocation,
-        name_input_id: &text_input::Id,
-    ) -> Element<Message> {
-        let mut content = widget::column![]
-            .spacing(10)
-            .width(Length::Fill)
-            .padding(20.0);
+    preferences: Preferences,
+    difficulty_controls: BTreeMap<DifficultyLevel, ControlsConfig>,
 
-        content = content.push(
-            widget::column![widget::text("New High Score!")
-                .font(Self::TEXT_FONT)
-                .size(25.0)]
-            .width(Length::Fill)
-            .align_x(Alignment::Center),
-        );
+    /// Only the options matching this text are shown in the settings view
+    search: String,
 
-        content = content.push(widget::horizontal_rule(10.0));
+    /// Name of the picked preset, as long as its game config hasn't been edited
+    preset: Option<String>,
 
-        content = content.push(
-            widget::row![widget::text(hs.difficulty_level.to_string()).font(Self::TEXT_FONT)]
-                .width(Length::Fill)
-                .align_y(Alignment::Center),
-        );
+    /// Name under which the custom game config is saved as a preset
+    preset_name: String,
+
+    /// Why the custom game config could not be saved as a preset
+    preset_error: Option<String>,
+}
 
-        let scores = if let Some(scores) = self.high_scores.get(&hs.difficulty_level) {
-            scores
+impl SettingsDraft {
+    /// The game config of the picked difficulty, in the picked game mode
+    fn game_config(&self) -> GameConfig {
+        let mode = if self.countdown {
+            GameMode::Countdown {
+                limit: self.game_difficulty.countdown_limit(),
+            }
         } else {
-            &self.empty_scores
+            GameMode::Classic
         };
 
-        for i in 0..Self::MAX_HIGH_SCORES_PER_LEVEL {
-            if let Some(score) = scores.get(i) {
-                if i == hs.index {
-                    let widget_name_input = widget::text_input(
-                        "Your name",
-                        &self.high_scores.get(&hs.difficulty_level).unwrap()[hs.index].name,
-                    )
-                    .on_input(move |s| Message::HighScore(RecordHighScore::NameChanged(s)))
-                    .on_submit(Message::HighScore(RecordHighScore::RecordName))
-                    .id(name_input_id.clone());
+        GameConfig {
+            mode,
+            safe_chord: self.safe_chord,
+            win_on_all_revealed: self.win_on_all_revealed,
+            strict_flags: self.strict_flags,
+            ..self.game_difficulty.into()
+        }
+    }
 
-                    content = content.push(
-                        widget::row![
-                            widget::column![widget::text(format!("# {}. ", i + 1)).size(15.0),]
-                                .width(Length::Shrink)
-                                .height(Length::Shrink)
-                                .align_x(Alignment::Start),
-                            widget::column![widget_name_input]
-                                .width(Length::Fill)
-                                .height(Length::Shrink)
-                                .align_x(Alignment::Start),
-                            widget::column![widget::text(score.seconds.to_string()).size(15.0)]
-                                .width(Length::Shrink)
-                                .height(Length::Shrink)
-                                .align_x(Alignment::End),
-                        ]
-                        .width(Length::Fill)
-                        .spacing(40.0)
-                        .align_y(Alignment::End),
-                    );
-                } else {
-                    content = content.push(
-                        widget::row![
-                            widget::column![widget::text(format!("# {}. ", i + 1)).size(15.0),]
-                                .width(Length::Shrink)
-                                .height(Length::Shrink)
-                                .align_x(Alignment::Start),
-                            widget::column![widget::text(score.name.as_str()).size(15.0)]
-                                .width(Length::Fill)
-                                .height(Length::Shrink)
-                                .align_x(Alignment::Start),
-                            widget::column![widget::text(score.seconds.to_string()).size(15.0)]
-                                .width(Length::Shrink)
-                                .height(Length::Shrink)
-                                .align_x(Alignment::End),
-                        ]
-                        .width(Length::Fill)
-                        .spacing(40.0)
-                        .align_y(Alignment::End),
-                    );
-                }
-            } else {
-                content = content.push(
-                    widget::row![
-                        widget::column![widget::text(format!("# {}. ", i + 1))
-                            .size(15.0)
-                            .color(Self::READY_COLOR),]
-                        .width(Length::Shrink)
-                        .height(Length::Shrink)
-                        .align_x(Alignment::Start),
-                        widget::column![widget::text("Empty").size(15.0).color(Self::READY_COLOR),]
-                            .width(Length::Fill)
-                            .height(Length::Shrink)
-                            .align_x(Alignment::Start),
-                        widget::horizontal_space(),
-                    ]
-                    .width(Length::Fill)
-                    .spacing(40.0)
-                    .align_y(Alignment::End),
-                );
+    /// The difficulty level whose own controls are being edited, if controls are remembered per difficulty
+    fn controls_level(&self) -> Option<DifficultyLevel> {
+        if self.preferences.controls_per_difficulty {
+            self.game_difficulty.try_into().ok()
+        } else {
+            None
+        }
+    }
+
+    /// Store the control scheme being edited, either for the picked difficulty level or as default controls
+    fn store_controls(&mut self) {
+        let controls = self.control_scheme.into();
+
+        match self.controls_level() {
+            Some(difficulty_level) => {
+                self.difficulty_controls.insert(difficulty_level, controls);
             }
+            None => self.preferences.controls = controls,
         }
+    }
 
-        widget::column![
-            content.height(Length::Fill).width(Length::Fill),
-            widget::column![widget::row![
-                widget::button("Cancel")
-                    .on_press(Message::HighScore(RecordHighScore::Discard))
-                    .style(button::primary),
-                widget::button("Apply")
-                    .on_press(Message::HighScore(RecordHighScore::RecordName))
-                    .style(button::primary),
-            ]
-            .spacing(10.0)
-            .width(Length::Shrink)
-            .align_y(Alignment::End)]
-            .width(Length::Fill)
-            .align_x(Alignment::End)
-        ]
-        .align_x(Alignment::End)
-        .width(Length::Fill)
-        .spacing(10.0)
-        .padding(Self::FIELD_PAD)
-        .into()
+    /// Start editing the control scheme stored for the picked difficulty level, or the default controls
+    fn load_controls(&mut self) {
+        self.control_scheme = ControlScheme::from_config(&controls_for(
+            self.controls_level(),
+            &self.difficulty_controls,
+            &self.preferences.controls,
+        ));
     }
+}
+
+/// The controls stored for the given difficulty level, falling back to the `default` controls for custom games and
+/// for difficulty levels which have no controls of their own
+fn controls_for(
+    difficulty_level: Option<DifficultyLevel>,
+    difficulty_controls: &BTreeMap<DifficultyLevel, ControlsConfig>,
+    default: &ControlsConfig,
+) -> ControlsConfig {
+    difficulty_level
+        .and_then(|difficulty_level| difficulty_controls.get(&difficulty_level))
+        .copied()
+        .unwrap_or(*default)
+}
+
+/// Animation of spots which were revealed at once (e.g. by a flood reveal), fading them in ring by ring around the
+/// spot which was stepped on
+#[derive(Debug, Clone)]
+struct RevealAnimation {
+    /// When the animation started
+    start: Instant,
+
+    /// The revealed spots, together with the delay after `start` when each of them starts fading in
+    spots: Vec<((u16, u16), Duration)>,
+
+    /// Duration of the whole animation
+    duration: Duration,
 
-    /// Handle switching game state from `Ready` to `Running`
-    fn check_ready_to_running(&mut self) {
-        if let GameState::Ready = self.game_state {
-            self.elapsed_seconds = Duration::default();
-            self.game_state = GameState::Running(Instant::now());
+    /// How long it takes a single spot to fade in
+    fade: Duration,
+}
+
+impl RevealAnimation {
+    /// Delay between the fading in of consecutive rings of spots
+    const RING_DELAY: Duration = Duration::from_millis(25);
+
+    /// How long it takes a single spot to fade in
+    const FADE: Duration = Duration::from_millis(120);
+
+    /// Upper limit for the duration of the whole animation, so that the board is never busy for long
+    const MAX_DURATION: Duration = Duration::from_millis(400);
+
+    /// Create the animation of the given revealed spots, with all its durations scaled by `speed`
+    fn new(origin: (u16, u16), spots: Vec<(u16, u16)>, start: Instant, speed: f32) -> Self {
+        let fade = Self::FADE.div_f32(speed);
+        let max_duration = Self::MAX_DURATION.div_f32(speed);
+        let ring =
+            |(x, y): (u16, u16)| -> u32 { x.abs_diff(origin.0).max(y.abs_diff(origin.1)) as u32 };
+        let max_ring = spots
+            .iter()
+            .map(|&coords| ring(coords))
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let ring_delay = Self::RING_DELAY
+            .div_f32(speed)
+            .min((max_duration - fade) / max_ring);
+
+        Self {
+            start,
+            spots: spots
+                .into_iter()
+                .map(|coords| (coords, ring_delay * ring(coords)))
+                .collect(),
+            duration: (ring_delay * max_ring) + fade,
+            fade,
         }
     }
 
-    /// Handle game over
-    fn game_over(&mut self, is_won: bool) {
-        self.game_state = GameState::Stopped { is_won };
+    /// Opacity of the hidden spot covering a revealed spot which starts fading in after `delay`
+    fn cover_alpha(&self, delay: Duration, now: Instant) -> f32 {
+        let fading = now.saturating_duration_since(self.start + delay);
 
-        if is_won {
-            let seconds = self.elapsed_seconds.as_secs();
+        1.0 - (fading.as_secs_f32() / self.fade.as_secs_f32()).min(1.0)
+    }
 
-            if let Ok(difficulty_level) = GameDifficulty::from_config(&self.game_config).try_into()
-            {
-                if let Some(index) = self.insert_high_score(
-                    difficulty_level,
-                    Score {
-                        seconds,
-                        name: String::new(),
-                    },
-                ) {
-                    self.main_view = MainViewContent::EnterHighScore(
-                        HighScoreLocation {
-                            difficulty_level,
-                            index,
-                        },
-                        text_input::Id::unique(),
-                    );
-                }
-            }
-        }
+    fn is_finished(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.start) >= self.duration
     }
+}
 
-    /// Try to insert a high score for the given difficulty and return the vector index if insertion was successful.
-    fn insert_high_score(
-        &mut self,
-        difficulty_level: DifficultyLevel,
-        score: Score,
-    ) -> Option<usize> {
-        if let Some(scores) = self.high_scores.get_mut(&difficulty_level) {
-            let mut insert_index = None;
+/// Animation of the mines which are shown once a mine has been stepped on, popping in one after the other, starting with
+/// the exploded one and spreading away from it
+#[derive(Debug, Clone)]
+struct ExplosionAnimation {
+    /// When the animation started
+    start: Instant,
 
-            for i in 0..Self::MAX_HIGH_SCORES_PER_LEVEL {
-                if let Some(s) = scores.get(i) {
-                    if score.seconds < s.seconds {
-                        scores.insert(i, score);
-                        scores.truncate(Self::MAX_HIGH_SCORES_PER_LEVEL);
-                        insert_index = Some(i);
-                        break;
-                    }
-                } else {
-                    scores.push(score);
-                    insert_index = Some(i);
-                    break;
-                }
-            }
+    /// The exploded mine
+    origin: (u16, u16),
+
+    /// The mines, including the exploded one, together with the delay after `start` when each of them pops in
+    mines: Vec<((u16, u16), Duration)>,
+
+    /// Duration of the whole animation
+    duration: Duration,
+
+    /// How long it takes a single mine to pop in
+    pop: Duration,
+}
+
+impl ExplosionAnimation {
+    /// Duration of the whole animation, at normal speed
+    const DURATION: Duration = Duration::from_millis(500);
+
+    /// How long it takes a single mine to pop in, at normal speed
+    const POP: Duration = Duration::from_millis(150);
+
+    /// How far a mine overshoots its final size while popping in (the usual "back" easing constant, about 10%)
+    const OVERSHOOT: f32 = 1.70158;
+
+    /// Create the animation of the given hidden mines around the exploded one at `origin`, with all its durations
+    /// scaled by `speed`
+    fn new(origin: (u16, u16), mines: Vec<(u16, u16)>, start: Instant, speed: f32) -> Self {
+        let pop = Self::POP.div_f32(speed);
+        let duration = Self::DURATION.div_f32(speed);
+        let distance =
+            |(x, y): (u16, u16)| -> u32 { x.abs_diff(origin.0).max(y.abs_diff(origin.1)) as u32 };
+        let max_distance = mines
+            .iter()
+            .map(|&coords| distance(coords))
+            .max()
+            .unwrap_or(0)
+            .max(1);
+
+        Self {
+            start,
+            origin,
+            mines: std::iter::once(origin)
+                .chain(mines)
+                .map(|coords| (coords, (duration - pop) * distance(coords) / max_distance))
+                .collect(),
+            duration,
+            pop,
+        }
+    }
 
-            insert_index
+    /// Size of a mine which starts popping in after `delay`, relative to its final size. It grows from nothing to a bit
+    /// more than its final size, then settles.
+    fn mine_scale(&self, delay: Duration, now: Instant) -> f32 {
+        let popping = now.saturating_duration_since(self.start + delay);
+        let t = (popping.as_secs_f32() / self.pop.as_secs_f32()).min(1.0) - 1.0;
+
+        if t <= -1.0 {
+            0.0
         } else {
-            self.high_scores.insert(difficulty_level, vec![score]);
-            Some(0)
+            1.0 + (Self::OVERSHOOT + 1.0) * t.powi(3) + Self::OVERSHOOT * t.powi(2)
         }
     }
 
-    /// Pause the game, if it is running
-    fn pause_game(&mut self) {
-        if let GameState::Running(i) = self.game_state {
-            let now = Instant::now();
-            self.elapsed_seconds += now - i;
-            self.game_state = GameState::Paused
+    fn is_finished(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.start) >= self.duration
+    }
+}
+
+/// A single pulse of the outline of a spot, suggesting a move to an idle player
+#[derive(Debug, Clone)]
+struct HintPulse {
+    /// The suggested spot
+    spot: (u16, u16),
+
+    /// When the pulse started
+    start: Instant,
+
+    /// Duration of the pulse
+    duration: Duration,
+
+    /// The outline fades in and out, instead of being shown as is for the whole duration
+    animated: bool,
+}
+
+impl HintPulse {
+    const DURATION: Duration = Duration::from_millis(1500);
+
+    /// Create the pulse of the given spot, with its duration scaled by `speed`. Without a speed (animations are turned
+    /// off), the spot is outlined for the unscaled duration.
+    fn new(spot: (u16, u16), start: Instant, speed: Option<f32>) -> Self {
+        Self {
+            spot,
+            start,
+            duration: speed.map_or(Self::DURATION, |speed| Self::DURATION.div_f32(speed)),
+            animated: speed.is_some(),
         }
     }
 
-    /// Resume the game, if it is paused
-    fn resume_game(&mut self) {
-        if let GameState::Paused = self.game_state {
-            self.game_state = GameState::Running(Instant::now())
+    /// Opacity of the outline of the spot
+    fn alpha(&self, now: Instant) -> f32 {
+        if !self.animated {
+            return 1.0;
         }
+
+        let progress =
+            now.saturating_duration_since(self.start).as_secs_f32() / self.duration.as_secs_f32();
+
+        (progress.min(1.0) * std::f32::consts::PI).sin()
     }
 
-    /// Load game config and high scores from file
-    fn load_persistence() -> Option<GamePersistence> {
-        let path = Minesweep::APP_NAME.to_owned() + ".json";
-        if let Ok(mut file) = std::fs::File::open(path) {
-            let mut buf = vec![];
-            if std::io::Read::read_to_end(&mut file, &mut buf).is_ok() {
-                if let Ok(mut world) = serde_json::from_slice::<GamePersistence>(&buf[..]) {
-                    // Do some high scores sanitizing
-                    for scores in world.high_scores.values_mut() {
-                        scores.sort_by(|s1, s2| s1.seconds.cmp(&s2.seconds));
-                        scores.truncate(Minesweep::MAX_HIGH_SCORES_PER_LEVEL);
-                    }
+    fn is_finished(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.start) >= self.duration
+    }
+}
 
-                    return Some(world);
-                }
-            }
+/// Speed of all animations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AnimationSpeed {
+    /// No animations are shown, the game always jumps straight to its final state
+    Off,
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl AnimationSpeed {
+    pub const ALL: &'static [AnimationSpeed] = &[Self::Off, Self::Slow, Self::Normal, Self::Fast];
+
+    /// The factor by which the speed of animations is multiplied, or `None` if animations are off
+    pub fn factor(&self) -> Option<f32> {
+        match self {
+            AnimationSpeed::Off => None,
+            AnimationSpeed::Slow => Some(0.5),
+            AnimationSpeed::Normal => Some(1.0),
+            AnimationSpeed::Fast => Some(2.0),
         }
+    }
+}
 
-        None
+impl Display for AnimationSpeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnimationSpeed::Off => write!(f, "Off"),
+            AnimationSpeed::Slow => write!(f, "Slow"),
+            AnimationSpeed::Normal => write!(f, "Normal"),
+            AnimationSpeed::Fast => write!(f, "Fast"),
+        }
     }
+}
 
-    /// Save game config and high scores to file
-    pub async fn save_persistence(configs: GamePersistence) {
-        let path = Self::APP_NAME.to_owned() + ".json";
-        if let Ok(mut f) = std::fs::File::create(path) {
-            if let Ok(buf) = serde_json::to_vec(&configs) {
-                let _ = std::io::Write::write_all(&mut f, &buf[..]);
-            }
+/// Action taken by the middle button over a hidden spot, where autostepping has no effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MiddleOnHidden {
+    #[default]
+    Nothing,
+    Flag,
+    Step,
+}
+
+impl MiddleOnHidden {
+    pub const ALL: &'static [MiddleOnHidden] = &[Self::Nothing, Self::Flag, Self::Step];
+
+    fn action(self) -> SpotAction {
+        match self {
+            MiddleOnHidden::Nothing => SpotAction::None,
+            MiddleOnHidden::Flag => SpotAction::Flag,
+            MiddleOnHidden::Step => SpotAction::Step,
         }
     }
 }
 
-impl Default for Minesweep {
-    fn default() -> Self {
-        let game_config = GameDifficulty::EASY;
-        let high_scores = BTreeMap::new();
+/// Move which starts the game timer, and with it the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TimerStart {
+    /// The first step or flag
+    #[default]
+    FirstAction,
 
-        Self {
-            field: Minefield::new(game_config.width, game_config.height)
-                .with_mines(game_config.mines),
-            field_cache: Cache::default(),
-            main_view: MainViewContent::Game,
-            game_state: GameState::default(),
-            game_config,
-            elapsed_seconds: Duration::default(),
-            remaining_flags: game_config.mines as i64,
-            high_scores,
-            empty_scores: Vec::new(),
-            window_id: None,
+    /// The first step, so that flags can be placed beforehand without being timed
+    FirstReveal,
+}
+
+impl TimerStart {
+    pub const ALL: &'static [TimerStart] = &[Self::FirstAction, Self::FirstReveal];
+}
+
+impl Display for TimerStart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimerStart::FirstAction => write!(f, "First step or flag"),
+            TimerStart::FirstReveal => write!(f, "First reveal"),
         }
     }
 }
 
-impl canvas::Program<Message> for Minesweep {
-    type State = ();
+impl Display for MiddleOnHidden {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MiddleOnHidden::Nothing => write!(f, "Nothing"),
+            MiddleOnHidden::Flag => write!(f, "Flag"),
+            MiddleOnHidden::Step => write!(f, "Step"),
+        }
+    }
+}
+
+/// State of the mouse buttons, keys and fingers interacting with the minefield canvas
+#[derive(Debug, Default)]
+pub struct FieldInteraction {
+    left_pressed: bool,
+    right_pressed: bool,
+    middle_pressed: bool,
+
+    /// Spot under the cursor, which is highlighted
+    hovered: Option<(u16, u16)>,
+
+    /// Left and right buttons have been pressed together, and their individual actions should not be taken
+    chording: bool,
+
+    /// Keyboard modifiers which are currently held down
+    modifiers: keyboard::Modifiers,
+
+    /// Spot on which removing a flag was last refused by the flag lock, and when
+    locked_flag: Option<((u16, u16), Instant)>,
+
+    /// Revealed number which was last clicked with the left button, and when, so that clicking it again soon after
+    /// autosteps around it
+    last_click: Option<((u16, u16), Instant)>,
+
+    /// Spot selected with the arrow keys, on which Space steps, F flags (Shift+F flags around a number) and Enter
+    /// autosteps
+    selected: Option<(u16, u16)>,
+
+    /// Fingers touching the minefield, with where and when they started touching it
+    touches: Vec<(touch::Finger, Point, Instant)>,
+
+    /// Several fingers have touched the minefield together since none did, so that lifting them autosteps
+    multi_touch: bool,
+
+    /// A finger has slid away or was lost since none touched the minefield, so that lifting them takes no action
+    touch_cancelled: bool,
+}
+
+/// Player preferences which are not part of a game config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    /// Mapping of mouse buttons to minefield actions
+    controls: ControlsConfig,
+
+    /// Won custom games whose config matches a standard difficulty are recorded on that difficulty's high scores
+    custom_games_record_high_scores: bool,
+
+    /// High scores are ranked by their points (see `Score::points_for`) instead of their raw time
+    rank_by_points: bool,
+
+    /// Animate spots revealed at once by a step. No steps can be taken while the animation is running.
+    reveal_animation: bool,
+
+    /// The result of each action is announced in a status line below the minefield
+    announcements: bool,
+
+    /// Speed of all animations, which can also be turned off altogether
+    animation_speed: AnimationSpeed,
+
+    /// Use the controls remembered for the difficulty level of the game, instead of `controls`
+    controls_per_difficulty: bool,
+
+    /// Stepping on a revealed number reveals its hidden neighbors, without requiring flags around it
+    careful_expansion: bool,
+
+    /// Stepping on a revealed number which has as many flags around it as neighboring mines autosteps around it, so
+    /// that no chording gesture is needed
+    step_on_satisfied_autosteps: bool,
+
+    /// Double clicking a revealed number with the left button autosteps around it
+    double_click_autosteps: bool,
+
+    /// Longest time between the two clicks of a double click, in milliseconds
+    double_click_millis: u32,
+
+    /// Action taken by the middle button over a hidden spot, when it's mapped to autostepping
+    middle_on_hidden: MiddleOnHidden,
+
+    /// Move which starts the game timer
+    timer_start: TimerStart,
+
+    /// Placing a flag autosteps around the revealed neighbors which then have enough flags around them
+    auto_step_on_flag: bool,
+
+    /// The mines left unflagged when a game is won are flagged, so that the final board reads cleanly
+    flag_mines_on_win: bool,
 
-    fn update(
-        &self,
-        _interaction: &mut Self::State,
-        event: Event,
-        bounds: Rectangle,
-        cursor: Cursor,
-    ) -> (event::Status, Option<Message>) {
-        // determine where to draw the spots
-        let f_width = self.field.width() as f32 * Self::SPOT_SIZE;
-        let f_height = self.field.height() as f32 * Self::SPOT_SIZE;
+    /// Steps on spots which are likely to be mines must be confirmed by stepping on them again
+    confirm_risky_steps: bool,
 
-        let f_o_x = (bounds.width - f_width) / 2.0;
-        let f_o_y = (bounds.height - f_height) / 2.0;
-        let origin_point = Point::new(bounds.x + f_o_x, bounds.y + f_o_y);
-        let origin_rectangle = Rectangle::new(origin_point, Size::new(f_width, f_height));
+    /// Probability of a mine (in percent) from which a step is risky
+    risky_step_threshold: u8,
 
-        if let Some(position) = cursor.position_in(origin_rectangle) {
-            let x = (position.x / Self::SPOT_SIZE).floor() as u16;
-            let y = (position.y / Self::SPOT_SIZE).floor() as u16;
+    /// A spot which is certainly safe pulses once, when no move has been made for `idle_hint_seconds`
+    idle_hint: bool,
 
-            match event {
-                Event::Mouse(mouse_event) => match mouse_event {
-                    mouse::Event::ButtonPressed(mouse_button) => match mouse_button {
-                        mouse::Button::Left => (
-                            event::Status::Captured,
-                            Some(Message::Minesweep(MinesweepMessage::Step { x, y })),
-                        ),
-                        mouse::Button::Right => (
-                            event::Status::Captured,
-                            Some(Message::Minesweep(MinesweepMessage::Flag { x, y })),
-                        ),
-                        mouse::Button::Middle => (
-                            event::Status::Captured,
-                            Some(Message::Minesweep(MinesweepMessage::AutoStep { x, y })),
-                        ),
-                        mouse::Button::Other(_) => (event::Status::Ignored, None),
-                        mouse::Button::Back => (event::Status::Ignored, None),
-                        mouse::Button::Forward => (event::Status::Ignored, None),
-                    },
-                    _ => (event::Status::Ignored, None),
-                },
-                Event::Touch(_t) => {
-                    // TODO: add handling for touch (WASM on mobile devices)
-                    (event::Status::Ignored, None)
-                }
-                Event::Keyboard(_) => (event::Status::Ignored, None),
-            }
-        } else {
-            (event::Status::Ignored, None)
-        }
-    }
+    /// Game time without any move, in seconds, after which a safe spot is suggested
+    idle_hint_seconds: u32,
 
-    fn draw(
-        &self,
-        _state: &Self::State,
-        renderer: &Renderer,
-        _theme: &Theme,
-        bounds: Rectangle,
-        _cursor: Cursor,
-    ) -> Vec<canvas::Geometry> {
-        let field = self.field_cache.draw(renderer, bounds.size(), |frame| {
-            // Set the background
-            let background = Path::rectangle(Point::ORIGIN, frame.size());
-            let background_color = Self::REVEALED_SPOT_COLOR;
-            frame.fill(&background, background_color);
+    /// Flags can only be removed deliberately: with Shift or Ctrl held down, or by flagging the same spot twice in a
+    /// quick succession
+    flag_lock: bool,
 
-            // determine where to draw the spots
-            let f_width = self.field.width() as f32 * Self::SPOT_SIZE;
-            let f_height = self.field.height() as f32 * Self::SPOT_SIZE;
-
-            let f_o_x = (frame.width() - f_width) / 2.0;
-            let f_o_y = (frame.height() - f_height) / 2.0;
-            let origin_point = Point::new(f_o_x, f_o_y);
-
-            // draw the spots
-            for (&(ix, iy), spot) in self.field.spots() {
-                let fx = (ix as f32 * Self::SPOT_SIZE) + Self::SPOT_PAD;
-                let fy = (iy as f32 * Self::SPOT_SIZE) + Self::SPOT_PAD;
-                let p = origin_point + Vector::new(fx, fy);
-
-                let bounds = Rectangle::new(p, Size::new(Self::CELL_SIZE, Self::CELL_SIZE));
-                let rounded_rectangle_radius = 0.0;
-
-                let text = Text {
-                    size: iced::Pixels(Self::CELL_SIZE - Self::CELL_PAD),
-                    position: bounds.center(),
-                    horizontal_alignment: alignment::Horizontal::Center,
-                    vertical_alignment: alignment::Vertical::Center,
-                    ..Text::default()
-                };
+    /// Flagging a flagged spot marks it with a question mark, which is cleared by flagging it once more
+    question_marks: bool,
 
-                match spot.state {
-                    minefield_rs::SpotState::HiddenEmpty {
-                        neighboring_mines: _,
-                    } => {
-                        draw_rounded_rectangle(
-                            rounded_rectangle_radius,
-                            Self::HIDDEN_SPOT_COLOR,
-                            bounds,
-                            frame,
-                        );
-                    }
-                    minefield_rs::SpotState::HiddenMine => {
-                        draw_rounded_rectangle(
-                            rounded_rectangle_radius,
-                            Self::HIDDEN_SPOT_COLOR,
-                            bounds,
-                            frame,
-                        );
+    /// The minefield is generated on the first step, such that it can be cleared without guessing
+    no_guess: bool,
 
-                        if let GameState::Stopped { is_won: _ } = self.game_state {
-                            frame.fill_text(Text {
-                                content: Self::MINE_CHAR.to_string(),
-                                position: text.position,
-                                color: Self::MINE_COLOR,
-                                font: Self::MINES_FLAGS_ICONS,
-                                size: iced::Pixels(Self::CELL_SIZE - Self::CELL_PAD),
-                                ..text
-                            });
-                        }
-                    }
-                    minefield_rs::SpotState::FlaggedEmpty {
-                        neighboring_mines: _,
-                    } => {
-                        draw_rounded_rectangle(
-                            rounded_rectangle_radius,
-                            Self::HIDDEN_SPOT_COLOR,
-                            bounds,
-                            frame,
-                        );
+    /// Maximum number of minefields generated while looking for one which can be cleared without guessing
+    no_guess_attempts: u32,
 
-                        let color = match self.game_state {
-                            GameState::Ready | GameState::Running(_) | GameState::Paused => {
-                                Self::FLAG_COLOR_CORRECT
-                            }
-                            GameState::Stopped { is_won: _ } => Self::FLAG_COLOR_WRONG,
-                        };
+    /// The mines around the first step are moved away, so that it opens up an area
+    open_start: bool,
 
-                        frame.fill_text(Text {
-                            content: Self::FLAG_CHAR.to_string(),
-                            position: text.position,
-                            color,
-                            font: Self::MINES_FLAGS_ICONS,
-                            size: iced::Pixels(Self::CELL_SIZE - Self::CELL_PAD),
-                            ..text
-                        });
-                    }
-                    minefield_rs::SpotState::FlaggedMine => {
-                        draw_rounded_rectangle(
-                            rounded_rectangle_radius,
-                            Self::HIDDEN_SPOT_COLOR,
-                            bounds,
-                            frame,
-                        );
+    /// Finished games are saved as replays
+    save_replays: bool,
 
-                        frame.fill_text(Text {
-                            content: Self::FLAG_CHAR.to_string(),
-                            position: text.position,
-                            color: Self::FLAG_COLOR_CORRECT,
-                            font: Self::MINES_FLAGS_ICONS,
-                            size: iced::Pixels(Self::CELL_SIZE - Self::CELL_PAD),
-                            ..text
-                        });
-                    }
-                    minefield_rs::SpotState::RevealedEmpty { neighboring_mines } => {
-                        draw_rounded_rectangle(
-                            rounded_rectangle_radius,
-                            Self::REVEALED_SPOT_COLOR,
-                            bounds,
-                            frame,
-                        );
+    /// Final game times and high scores are shown with hundredths of a second
+    precise_times: bool,
 
-                        frame.fill_text(Text {
-                            content: Self::EMPTY_SPOT_CHARS[neighboring_mines as usize].to_string(),
-                            position: text.position,
-                            color: Self::EMPTY_SPOT_COLORS[neighboring_mines as usize],
-                            ..text
-                        });
-                    }
-                    minefield_rs::SpotState::ExplodedMine => {
-                        draw_rounded_rectangle(
-                            rounded_rectangle_radius,
-                            Self::REVEALED_SPOT_COLOR,
-                            bounds,
-                            frame,
-                        );
+    /// The seed of the current board is shown in the toolbar, so that it can be shared
+    show_seed: bool,
 
-                        frame.fill_text(Text {
-                            content: Self::MINE_EXPLODED_CHAR.to_string(),
-                            position: text.position,
-                            color: Self::MINE_EXPLODED_COLOR,
-                            font: Self::MINES_FLAGS_ICONS,
-                            size: iced::Pixels(Self::CELL_SIZE - Self::CELL_PAD),
-                            ..text
-                        });
-                    }
-                }
-            }
-        });
+    /// Remaining flags are shown as a row of flag icons, when there are only a few of them
+    flag_icons: bool,
 
-        fn draw_rounded_rectangle(radius: f32, fill: Color, bounds: Rectangle, frame: &mut Frame) {
-            let s_position = Point::new(bounds.position().x + (radius / 2.0), bounds.position().y);
-            let s_size = Size::new(bounds.width - (radius * 1.0), bounds.height);
+    /// The number of empty spots which are left to reveal is shown in the toolbar
+    show_remaining_cells: bool,
 
-            frame.fill_rectangle(s_position, s_size, fill);
+    /// The columns and rows of the board are numbered in its margin, so that spots can be told apart
+    show_rulers: bool,
 
-            let wide_stroke = || -> Stroke {
-                Stroke {
-                    width: radius,
-                    style: stroke::Style::Solid(fill),
-                    line_cap: LineCap::Round,
-                    ..Stroke::default()
-                }
-            };
+    /// Revealed numbers which have as many flags around them as neighboring mines are dimmed
+    dim_satisfied: bool,
 
-            let left_line = Path::line(
-                Point::new(
-                    bounds.position().x + (radius / 2.0),
-                    bounds.position().y + (radius / 2.0),
-                ),
-                Point::new(
-                    bounds.position().x + (radius / 2.0),
-                    bounds.position().y + bounds.height - (radius / 2.0),
-                ),
-            );
-            frame.stroke(&left_line, wide_stroke());
+    /// Only the orthogonally adjacent spots are neighbors, for numbers and flood reveals (a harder variant)
+    orthogonal_neighbors: bool,
 
-            let right_line = Path::line(
-                Point::new(
-                    bounds.position().x + (radius / 2.0) + s_size.width,
-                    bounds.position().y + (radius / 2.0),
-                ),
-                Point::new(
-                    bounds.position().x + (radius / 2.0) + s_size.width,
-                    bounds.position().y + bounds.height - (radius / 2.0),
-                ),
-            );
-            frame.stroke(&right_line, wide_stroke());
-        }
+    /// Maximum width of the window, or the width of the monitor if `None`
+    max_window_width: Option<u32>,
 
-        vec![field]
-    }
-}
+    /// Maximum height of the window, or the height of the monitor if `None`
+    max_window_height: Option<u32>,
 
-/// Current state of the game
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
-enum GameState {
-    /// Game is ready to start running
-    Ready,
+    /// The mine which was stepped on is highlighted with `exploded_highlight_color`, to stand out among the others
+    highlight_exploded: bool,
 
-    /// Game is running
-    Running(Instant),
+    /// Color of the highlight of the mine which was stepped on
+    exploded_highlight_color: HighlightColor,
 
-    /// Game is paused
-    Paused,
+    /// Colors of the application and of the minefield
+    color_theme: ColorTheme,
 
-    /// Game is stopped, and was either won (`true`), or lost (`false`)
-    Stopped { is_won: bool },
-}
+    /// Sounds are played when spots are revealed or flagged, when a mine is stepped on and when the game is won
+    sound_effects: bool,
 
-impl Default for GameState {
-    fn default() -> Self {
-        Self::Ready
-    }
+    /// Wrong flags and the mine which was stepped on are told apart by their shapes instead of their colors, and mines
+    /// and flags are drawn in the color of the text
+    colorblind_glyphs: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub struct GameConfig {
-    pub width: u16,
-    pub height: u16,
-    pub mines: u32,
+impl Preferences {
+    const DEFAULT_NO_GUESS_ATTEMPTS: u32 = 1000;
+    const DEFAULT_RISKY_STEP_THRESHOLD: u8 = 50;
+    const RISKY_STEP_THRESHOLDS: &'static [u8] = &[25, 50, 75, 90, 100];
+    const DEFAULT_IDLE_HINT_SECONDS: u32 = 15;
+    const IDLE_HINT_SECONDS: &'static [u32] = &[5, 10, 15, 30, 60];
+    const DEFAULT_DOUBLE_CLICK_MILLIS: u32 = 400;
+    const DOUBLE_CLICK_MILLIS: &'static [u32] = &[250, 400, 500, 750];
 }
 
-impl From<GameDifficulty> for GameConfig {
-    fn from(val: GameDifficulty) -> Self {
-        match val {
-            GameDifficulty::Easy => GameDifficulty::EASY,
-            GameDifficulty::Medium => GameDifficulty::MEDIUM,
-            GameDifficulty::Hard => GameDifficulty::HARD,
-            GameDifficulty::Custom(gc) => gc,
+impl Preferences {
+    /// Which spots count as neighbors on new minefields
+    fn connectivity(&self) -> Connectivity {
+        if self.orthogonal_neighbors {
+            Connectivity::Four
+        } else {
+            Connectivity::Eight
         }
     }
 }
 
-impl Default for GameConfig {
+impl Default for Preferences {
     fn default() -> Self {
         Self {
-            width: 10,
-            height: 10,
-            mines: 10,
+            controls: ControlsConfig::default(),
+            custom_games_record_high_scores: true,
+            rank_by_points: false,
+            reveal_animation: true,
+            announcements: false,
+            animation_speed: AnimationSpeed::default(),
+            controls_per_difficulty: false,
+            careful_expansion: false,
+            step_on_satisfied_autosteps: false,
+            double_click_autosteps: false,
+            double_click_millis: Self::DEFAULT_DOUBLE_CLICK_MILLIS,
+            middle_on_hidden: MiddleOnHidden::default(),
+            timer_start: TimerStart::default(),
+            auto_step_on_flag: false,
+            flag_mines_on_win: true,
+            flag_lock: false,
+            question_marks: true,
+            confirm_risky_steps: false,
+            risky_step_threshold: Self::DEFAULT_RISKY_STEP_THRESHOLD,
+            idle_hint: false,
+            idle_hint_seconds: Self::DEFAULT_IDLE_HINT_SECONDS,
+            no_guess: false,
+            no_guess_attempts: Self::DEFAULT_NO_GUESS_ATTEMPTS,
+            open_start: false,
+            save_replays: true,
+            precise_times: true,
+            show_seed: false,
+            flag_icons: false,
+            show_remaining_cells: false,
+            show_rulers: false,
+            dim_satisfied: false,
+            orthogonal_neighbors: false,
+            max_window_width: None,
+            max_window_height: None,
+            highlight_exploded: true,
+            exploded_highlight_color: HighlightColor::default(),
+            color_theme: ColorTheme::default(),
+            sound_effects: false,
+            colorblind_glyphs: false,
         }
     }
 }
 
-/// A description of the game difficulty, with a special entry for custom games
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum GameDifficulty {
-    Easy,
-    Medium,
-    Hard,
-    Custom(GameConfig),
+/// A description of a high score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Score {
+    name: String,
+    seconds: u64,
+
+    /// Exact time of the game, in milliseconds. Scores saved by older versions only have whole `seconds`.
+    #[serde(default)]
+    millis: Option<u64>,
+
+    /// Time at which the game was won, in seconds since the unix epoch. Scores saved by older versions don't have it.
+    #[serde(default)]
+    date: Option<u64>,
+
+    /// Points of the game (see `points_for`). Scores saved by older versions don't have them.
+    #[serde(default)]
+    points: Option<u64>,
+
+    /// Replay of the game, if it was saved
+    #[serde(default)]
+    replay: Option<PathBuf>,
 }
 
-impl GameDifficulty {
-    pub const ALL: &'static [GameDifficulty] = &[
-        Self::Easy,
-        Self::Medium,
-        Self::Hard,
-        Self::Custom(Self::DEFAULT_CUSTOM),
-    ];
-    pub const EASY: GameConfig = GameConfig {
-        width: 10,
-        height: 10,
-        mines: 10,
-    };
-    pub const MEDIUM: GameConfig = GameConfig {
-        width: 16,
-        height: 16,
-        mines: 40,
-    };
-    pub const HARD: GameConfig = GameConfig {
-        width: 30,
-        height: 16,
-        mines: 99,
-    };
-    pub const DEFAULT_CUSTOM: GameConfig = GameConfig {
-        width: 45,
-        height: 24,
-        mines: 150,
-    };
+impl Score {
+    /// Points scored for each mine of a game won in one second
+    const POINTS_PER_MINE: f64 = 1000.0;
 
-    pub fn from_config(config: &GameConfig) -> Self {
-        if *config == Self::EASY {
-            Self::Easy
-        } else if *config == Self::MEDIUM {
-            Self::Medium
-        } else if *config == Self::HARD {
-            Self::Hard
+    /// Factor by which each hint lowers the points of a game
+    const HINT_PENALTY: f64 = 0.8;
+
+    /// Points of a game won in `millis` on a board with the given number of spots and mines, so that games on
+    /// different boards can be compared: more mines and a higher density of mines score more, a longer time scores
+    /// less, and so does each hint given.
+    fn points_for(spots: u32, mines: u32, millis: u64, hints: u32) -> u64 {
+        let density = mines as f64 / spots.max(1) as f64;
+        let seconds = (millis as f64 / 1000.0).max(1.0);
+
+        (mines as f64 * (1.0 + density) * Self::POINTS_PER_MINE / seconds
+            * Self::HINT_PENALTY.powi(hints as i32))
+        .round() as u64
+    }
+
+    /// Order of this score and the `other` one on the high scores, the better one first: the faster one, or the one
+    /// with more points if `by_points` is set, scores without points coming last
+    fn rank(&self, other: &Score, by_points: bool) -> Ordering {
+        if by_points {
+            other.points.cmp(&self.points)
         } else {
-            Self::Custom(*config)
+            self.millis().cmp(&other.millis())
         }
     }
-}
 
-impl Display for GameDifficulty {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            GameDifficulty::Easy => write!(
-                f,
-                "Easy (w:{}, h:{}, m:{})",
-                Self::EASY.width,
-                Self::EASY.height,
-                Self::EASY.mines
-            ),
-            GameDifficulty::Medium => write!(
-                f,
-                "Medium (w:{}, h:{}, m:{})",
-                Self::MEDIUM.width,
-                Self::MEDIUM.height,
-                Self::MEDIUM.mines
-            ),
-            GameDifficulty::Hard => write!(
-                f,
-                "Hard (w:{}, h:{}, m:{})",
-                Self::HARD.width,
-                Self::HARD.height,
-                Self::HARD.mines
-            ),
-            GameDifficulty::Custom(gc) => write!(
-                f,
-                "Custom (w:{}, h:{}, m:{})",
-                gc.width, gc.height, gc.mines
-            ),
-        }
+    /// Time of the game in milliseconds, as exact as it was recorded
+    fn millis(&self) -> u64 {
+        self.millis.unwrap_or(self.seconds * 1000)
+    }
+
+    /// Time of the game, formatted like the game timer. Hundredths of a second are only shown if the score has them.
+    fn time(&self, hundredths: bool) -> String {
+        format_time(self.millis(), hundredths && self.millis.is_some())
     }
 }
 
-/// All standard difficulty levels (i.e. which do not describe a `GameDifficulty::Custom` game)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
-pub enum DifficultyLevel {
-    Easy,
-    Medium,
-    Hard,
+/// Lifetime statistics of the games played on a difficulty level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    /// Number of games which were lost or won
+    played: u32,
+
+    /// Number of games which were won
+    won: u32,
+
+    /// Time of the fastest won game, in milliseconds
+    best_millis: Option<u64>,
+
+    /// Sum of the times of all the won games, in milliseconds
+    total_won_millis: u64,
+
+    /// Number of games won in a row, up to the last one
+    current_streak: u32,
+
+    /// Highest number of games ever won in a row
+    longest_streak: u32,
 }
 
-impl TryFrom<GameDifficulty> for DifficultyLevel {
-    type Error = ();
+impl Stats {
+    /// Count a game which was won or lost after the given time
+    fn record(&mut self, is_won: bool, millis: u64) {
+        self.played += 1;
 
-    fn try_from(game_difficulty: GameDifficulty) -> Result<Self, Self::Error> {
-        match game_difficulty {
-            GameDifficulty::Easy => Ok(Self::Easy),
-            GameDifficulty::Medium => Ok(Self::Medium),
-            GameDifficulty::Hard => Ok(Self::Hard),
-            GameDifficulty::Custom(_) => Err(()),
+        if is_won {
+            self.won += 1;
+            self.best_millis = Some(self.best_millis.map_or(millis, |best| best.min(millis)));
+            self.total_won_millis += millis;
+            self.current_streak += 1;
+            self.longest_streak = self.longest_streak.max(self.current_streak);
+        } else {
+            self.current_streak = 0;
         }
     }
-}
 
-impl DifficultyLevel {
-    pub const ALL: &'static [DifficultyLevel] = &[Self::Easy, Self::Medium, Self::Hard];
-}
+    /// Percentage of the games played which were won, if any were played
+    fn win_rate(&self) -> Option<f64> {
+        (self.played > 0).then(|| self.won as f64 * 100.0 / self.played as f64)
+    }
 
-impl Display for DifficultyLevel {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            DifficultyLevel::Easy => write!(f, "Easy"),
-            DifficultyLevel::Medium => write!(f, "Medium"),
-            DifficultyLevel::Hard => write!(f, "Hard"),
-        }
+    /// Average time of the won games, in milliseconds, if any were won
+    fn average_millis(&self) -> Option<u64> {
+        (self.won > 0).then(|| self.total_won_millis / self.won as u64)
     }
 }
 
-/// A description of a high score
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Score {
-    name: String,
-    seconds: u64,
+/// Format a game time given in milliseconds as seconds for short games, and as `mm:ss` from one minute on. With
+/// `hundredths`, these are followed by hundredths of a second (e.g. `ss.cc` or `mm:ss.cc`).
+fn format_time(millis: u64, hundredths: bool) -> String {
+    const MINUTE: u64 = 60_000;
+
+    // The format only depends on the whole seconds, which are also what's displayed, so that it switches exactly
+    // when the timer shows the first minute
+    let time = if millis < MINUTE {
+        format!("{}", millis / 1000)
+    } else {
+        format!("{:02}:{:02}", millis / MINUTE, (millis % MINUTE) / 1000)
+    };
+
+    if hundredths {
+        format!("{}.{:02}", time, (millis % 1000) / 10)
+    } else {
+        time
+    }
 }
 
 /// Struct for describing the location of a high score in a BTreeMap of the form `BTreeMap<DifficultyLevel, Vec<Score>>`
@@ -1607,12 +9185,100 @@ pub struct Score {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HighScoreLocation {
     difficulty_level: DifficultyLevel,
+
+    /// The high score is one of the games won without any flag (see `Minesweep::flagless_high_scores`)
+    #[serde(default)]
+    flagless: bool,
     index: usize,
 }
 
 /// A record of the game config and the associated high scores
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GamePersistence {
     game_config: GameConfig,
     high_scores: BTreeMap<DifficultyLevel, Vec<Score>>,
+
+    /// High scores of the games won without any flag. Configs saved by older versions don't have them.
+    #[serde(default)]
+    flagless_high_scores: BTreeMap<DifficultyLevel, Vec<Score>>,
+    #[serde(default = "GamePersistence::default_max_high_scores")]
+    max_high_scores: usize,
+    #[serde(default)]
+    preferences: Preferences,
+    #[serde(default)]
+    difficulty_controls: BTreeMap<DifficultyLevel, ControlsConfig>,
+    #[serde(default)]
+    presets: Vec<Preset>,
+    #[serde(default)]
+    stats: BTreeMap<DifficultyLevel, Stats>,
+
+    /// Name of the profile which owns `high_scores` and `stats`. Configs saved by older versions only have the high
+    /// scores and stats of the default profile.
+    #[serde(default = "GamePersistence::default_profile")]
+    active_profile: String,
+
+    /// High scores and stats of the other profiles, by name
+    #[serde(default)]
+    profiles: BTreeMap<String, ProfileData>,
+
+    /// Results of the daily challenges of the last days, by `YYYY-MM-DD` date, whichever profile played them
+    #[serde(default)]
+    daily_results: BTreeMap<String, Vec<DailyResult>>,
+}
+
+impl GamePersistence {
+    fn default_max_high_scores() -> usize {
+        Minesweep::DEFAULT_HIGH_SCORES_PER_LEVEL
+    }
+
+    fn default_profile() -> String {
+        Minesweep::DEFAULT_PROFILE.to_string()
+    }
+}
+
+/// A daily challenge which was played to its end
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyResult {
+    difficulty_level: DifficultyLevel,
+
+    /// Name of the profile which played it
+    name: String,
+
+    is_won: bool,
+    millis: u64,
+
+    /// The game was played with the help of an assist or a hint, which keeps it off the leaderboard. Results saved by
+    /// older versions were never assisted.
+    #[serde(default)]
+    assisted: bool,
+}
+
+/// High scores and stats of a player profile which isn't the active one
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileData {
+    high_scores: BTreeMap<DifficultyLevel, Vec<Score>>,
+    flagless_high_scores: BTreeMap<DifficultyLevel, Vec<Score>>,
+    stats: BTreeMap<DifficultyLevel, Stats>,
+}
+
+impl Default for GamePersistence {
+    fn default() -> Self {
+        Self {
+            game_config: GameConfig::default(),
+            high_scores: BTreeMap::new(),
+            flagless_high_scores: BTreeMap::new(),
+            max_high_scores: Self::default_max_high_scores(),
+            preferences: Preferences::default(),
+            difficulty_controls: BTreeMap::new(),
+            presets: Vec::new(),
+            stats: BTreeMap::new(),
+            active_profile: Self::default_profile(),
+            profiles: BTreeMap::new(),
+            daily_results: BTreeMap::new(),
+        }
+    }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,3201 @@
+use super::*;
+
+/// Mines of an easy board, all on its first row. Stepping anywhere below the second row reveals all the other spots.
+fn easy_mines() -> Vec<(u16, u16)> {
+    (0..GameDifficulty::EASY.width).map(|x| (x, 0)).collect()
+}
+
+fn easy_game() -> Minesweep {
+    Minesweep::with_board(
+        GameDifficulty::EASY.width,
+        GameDifficulty::EASY.height,
+        &easy_mines(),
+    )
+}
+
+fn step(x: u16, y: u16) -> Message {
+    Message::Minesweep(MinesweepMessage::Step { x, y })
+}
+
+fn flag(x: u16, y: u16) -> Message {
+    Message::Minesweep(MinesweepMessage::Flag { x, y })
+}
+
+/// Flag all the given mines, in order. The board is cleared once all its mines are flagged and its other spots revealed.
+fn flag_all(mines: &[(u16, u16)]) -> Vec<Message> {
+    mines.iter().map(|&(x, y)| flag(x, y)).collect()
+}
+
+fn play(minesweep: &mut Minesweep, messages: impl IntoIterator<Item = Message>) {
+    for message in messages {
+        let _ = minesweep.update(message);
+    }
+}
+
+#[test]
+fn new_game_is_ready() {
+    let minesweep = easy_game();
+
+    assert_eq!(minesweep.game_state(), GameState::Ready);
+    assert_eq!(minesweep.remaining_flags(), 10);
+    assert_eq!(minesweep.elapsed_seconds(), Duration::ZERO);
+    assert!(minesweep.high_score_entry().is_none());
+}
+
+#[test]
+fn first_step_starts_the_game() {
+    let mut minesweep = easy_game();
+
+    play(&mut minesweep, [step(0, 1)]);
+
+    assert!(matches!(minesweep.game_state(), GameState::Running(_)));
+    assert_eq!(
+        minesweep.field().spot(0, 1).map(|spot| spot.state),
+        Some(SpotState::RevealedEmpty {
+            neighboring_mines: 2
+        })
+    );
+}
+
+#[test]
+fn open_start_moves_the_mines_away_from_the_first_step() {
+    let mut minesweep = easy_game().with_preferences(Preferences {
+        open_start: true,
+        ..Preferences::default()
+    });
+
+    // Without the open start, this would be a step on a mine
+    play(&mut minesweep, [step(1, 0)]);
+
+    assert!(matches!(minesweep.game_state(), GameState::Running(_)));
+    assert_eq!(
+        minesweep.field().spot(1, 0).map(|spot| spot.state),
+        Some(SpotState::RevealedEmpty {
+            neighboring_mines: 0
+        })
+    );
+    assert_eq!(minesweep.field().mine_spots().count(), 10);
+
+    // Later steps are left alone
+    let mine = minesweep
+        .field()
+        .mine_spots()
+        .map(|(coords, _)| coords)
+        .next()
+        .unwrap();
+    play(&mut minesweep, [step(mine.0, mine.1)]);
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: false });
+}
+
+#[test]
+fn stepping_on_a_mine_loses() {
+    let mut minesweep = easy_game();
+
+    play(&mut minesweep, [step(0, 1), step(3, 0)]);
+
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: false });
+    assert!(minesweep.high_score_entry().is_none());
+    assert!(minesweep.high_scores(DifficultyLevel::Easy).is_empty());
+
+    // Nothing can be done once the game is over
+    play(&mut minesweep, [step(9, 9), flag(5, 0)]);
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: false });
+    assert_eq!(minesweep.remaining_flags(), 10);
+}
+
+#[test]
+fn clearing_the_board_wins_and_enters_a_high_score() {
+    let mut minesweep = easy_game();
+
+    play(&mut minesweep, [step(9, 9)]);
+    assert!(matches!(minesweep.game_state(), GameState::Running(_)));
+
+    play(&mut minesweep, flag_all(&easy_mines()));
+    assert!(minesweep.field().is_cleared());
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: true });
+
+    let entry = minesweep.high_score_entry().expect("a high score to enter");
+    assert_eq!(entry.difficulty_level, DifficultyLevel::Easy);
+    assert_eq!(entry.index, 0);
+    assert_eq!(minesweep.high_scores(DifficultyLevel::Easy).len(), 1);
+    assert!(minesweep.high_scores(DifficultyLevel::Easy)[0]
+        .date
+        .is_some());
+}
+
+#[test]
+fn scores_saved_without_a_date_are_loaded() {
+    let score: Score = serde_json::from_str(r#"{"name":"Ada","seconds":7}"#).unwrap();
+
+    assert_eq!(score.date, None);
+    assert_eq!(score.millis(), 7000);
+}
+
+#[test]
+fn recorded_high_score_keeps_the_name() {
+    let mut minesweep = easy_game();
+
+    play(&mut minesweep, [step(9, 9)]);
+    play(&mut minesweep, flag_all(&easy_mines()));
+    play(
+        &mut minesweep,
+        [
+            Message::HighScore(RecordHighScore::NameChanged("Ada".to_string())),
+            Message::HighScore(RecordHighScore::RecordName),
+        ],
+    );
+
+    assert!(minesweep.high_score_entry().is_none());
+    assert!(matches!(minesweep.main_view, MainViewContent::HighScores));
+
+    let scores = minesweep.high_scores(DifficultyLevel::Easy);
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0].name, "Ada");
+}
+
+#[test]
+fn discarded_high_score_is_removed() {
+    let mut minesweep = easy_game();
+
+    play(&mut minesweep, [step(9, 9)]);
+    play(&mut minesweep, flag_all(&easy_mines()));
+    play(
+        &mut minesweep,
+        [
+            Message::HighScore(RecordHighScore::NameChanged("Ada".to_string())),
+            Message::HighScore(RecordHighScore::Discard),
+        ],
+    );
+
+    assert!(minesweep.high_score_entry().is_none());
+    assert!(minesweep.high_scores(DifficultyLevel::Easy).is_empty());
+}
+
+#[test]
+fn games_won_without_flags_have_high_scores_of_their_own() {
+    let flagless_game = || {
+        let mut minesweep = easy_game();
+        minesweep.game_config.win_on_all_revealed = true;
+        minesweep.field = Minefield::new(10, 10)
+            .with_mines_at(&easy_mines())
+            .with_win_on_all_revealed(true);
+
+        minesweep
+    };
+
+    let mut minesweep = flagless_game();
+    play(&mut minesweep, [step(0, 5)]);
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: true });
+    let entry = minesweep.high_score_entry().expect("a high score to enter");
+    assert!(entry.flagless);
+    play(
+        &mut minesweep,
+        [
+            Message::HighScore(RecordHighScore::NameChanged("Ada".to_string())),
+            Message::HighScore(RecordHighScore::RecordName),
+        ],
+    );
+    assert!(matches!(
+        &minesweep.take_events()[..],
+        [GameEvent::Won { .. }, GameEvent::HighScore { flagless: true, name, .. }] if name == "Ada"
+    ));
+    assert!(minesweep.high_scores(DifficultyLevel::Easy).is_empty());
+    assert_eq!(
+        minesweep.flagless_high_scores[&DifficultyLevel::Easy][0].name,
+        "Ada"
+    );
+
+    // A single flag, even one which is removed again, keeps the win off the flagless high scores. Since the game was
+    // won by revealing all the empty spots, it isn't ranked among the classic games either.
+    let mut minesweep = flagless_game();
+    play(&mut minesweep, [flag(0, 0), flag(0, 0), step(0, 5)]);
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: true });
+    assert!(minesweep.high_score_entry().is_none());
+    assert!(minesweep.high_scores(DifficultyLevel::Easy).is_empty());
+    assert!(minesweep.flagless_high_scores.is_empty());
+}
+
+#[test]
+fn games_with_variant_rules_are_not_recorded() {
+    let mut minesweep = easy_game();
+    minesweep.game_config.safe_chord = true;
+    minesweep.field = Minefield::new(10, 10)
+        .with_mines_at(&easy_mines())
+        .with_safe_chord(true);
+
+    play(&mut minesweep, [step(9, 9)]);
+    play(&mut minesweep, flag_all(&easy_mines()));
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: true });
+    assert!(minesweep.high_score_entry().is_none());
+    assert!(minesweep.high_scores.is_empty());
+    assert!(minesweep.stats.is_empty());
+
+    // A game won by revealing all the empty spots, with a flag placed on the way
+    minesweep.start_new_game();
+    minesweep.game_config.safe_chord = false;
+    minesweep.game_config.win_on_all_revealed = true;
+    minesweep.field = Minefield::new(10, 10)
+        .with_mines_at(&easy_mines())
+        .with_win_on_all_revealed(true);
+
+    play(&mut minesweep, [flag(0, 0), step(9, 9)]);
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: true });
+    assert!(minesweep.high_score_entry().is_none());
+    assert!(minesweep.high_scores(DifficultyLevel::Easy).is_empty());
+    assert!(minesweep.stats.is_empty());
+}
+
+#[test]
+fn recorded_high_scores_can_be_renamed_and_deleted() {
+    let mut minesweep = easy_game();
+    for (seconds, name) in [(10, "Ada"), (20, "Bob"), (30, "Cyd")] {
+        minesweep.insert_high_score(
+            DifficultyLevel::Easy,
+            false,
+            Score {
+                name: name.to_string(),
+                seconds,
+                millis: None,
+                date: None,
+                points: None,
+                replay: None,
+            },
+        );
+    }
+    let location = |index| HighScoreLocation {
+        difficulty_level: DifficultyLevel::Easy,
+        flagless: false,
+        index,
+    };
+    let names = |minesweep: &Minesweep| {
+        minesweep
+            .high_scores(DifficultyLevel::Easy)
+            .iter()
+            .map(|score| score.name.clone())
+            .collect::<Vec<_>>()
+    };
+
+    // A cancelled rename keeps the previous name, and the high score
+    play(
+        &mut minesweep,
+        [
+            Message::HighScores,
+            Message::HighScore(RecordHighScore::Rename(location(1))),
+            Message::HighScore(RecordHighScore::NameChanged("Bo".to_string())),
+            Message::HighScore(RecordHighScore::Discard),
+        ],
+    );
+    assert!(matches!(minesweep.main_view, MainViewContent::HighScores));
+    assert_eq!(names(&minesweep), ["Ada", "Bob", "Cyd"]);
+
+    play(
+        &mut minesweep,
+        [
+            Message::HighScore(RecordHighScore::Rename(location(1))),
+            Message::HighScore(RecordHighScore::NameChanged("Bea".to_string())),
+            Message::HighScore(RecordHighScore::RecordName),
+        ],
+    );
+    assert!(matches!(minesweep.main_view, MainViewContent::HighScores));
+    assert_eq!(names(&minesweep), ["Ada", "Bea", "Cyd"]);
+
+    // The lower high scores move up in place of the deleted one
+    play(
+        &mut minesweep,
+        [Message::HighScore(RecordHighScore::Delete(location(0)))],
+    );
+    assert_eq!(names(&minesweep), ["Bea", "Cyd"]);
+}
+
+#[test]
+fn custom_board_win_is_not_recorded() {
+    let mut minesweep = Minesweep::with_board(5, 5, &[(0, 0)]);
+
+    play(&mut minesweep, [step(4, 4), flag(0, 0)]);
+
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: true });
+    assert!(minesweep.high_score_entry().is_none());
+}
+
+#[test]
+fn assisted_win_is_not_recorded() {
+    // Mines on the first row, except for its last spot which is left hidden by the first step, with the last mine
+    // below it. Flagging the mines around the number next to that spot autosteps on it.
+    let mut mines = easy_mines();
+    mines[9] = (9, 1);
+    let mut minesweep = Minesweep::with_board(
+        GameDifficulty::EASY.width,
+        GameDifficulty::EASY.height,
+        &mines,
+    )
+    .with_preferences(Preferences {
+        auto_step_on_flag: true,
+        ..Preferences::default()
+    });
+
+    play(&mut minesweep, [step(9, 9)]);
+    play(&mut minesweep, flag_all(&mines[..9]));
+    assert!(matches!(minesweep.game_state(), GameState::Running(_)));
+    assert_eq!(
+        minesweep.field().spot(9, 0).map(|spot| spot.state),
+        Some(SpotState::HiddenEmpty {
+            neighboring_mines: 2
+        })
+    );
+
+    play(&mut minesweep, [flag(9, 1)]);
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: true });
+    assert!(minesweep.high_score_entry().is_none());
+}
+
+#[test]
+fn idle_player_is_suggested_a_safe_spot_once() {
+    // The spot left hidden in the top-right corner can be deduced from the numbers around it
+    let mut mines = easy_mines();
+    mines[9] = (9, 1);
+    let mut minesweep = Minesweep::with_board(
+        GameDifficulty::EASY.width,
+        GameDifficulty::EASY.height,
+        &mines,
+    )
+    .with_preferences(Preferences {
+        idle_hint: true,
+        idle_hint_seconds: 10,
+        ..Preferences::default()
+    });
+
+    play(&mut minesweep, [step(9, 9)]);
+    let GameState::Running(since) = minesweep.game_state() else {
+        panic!("the game should be running");
+    };
+
+    play(
+        &mut minesweep,
+        [Message::Tick(since + Duration::from_secs(5))],
+    );
+    assert!(minesweep.hint_pulse.is_none());
+
+    play(
+        &mut minesweep,
+        [Message::Tick(since + Duration::from_secs(11))],
+    );
+    assert_eq!(minesweep.hint_pulse.as_ref().map(|p| p.spot), Some((9, 0)));
+    assert_eq!(minesweep.hints, 1);
+
+    // The spot is only suggested once, until the player makes a move
+    minesweep.hint_pulse = None;
+    play(
+        &mut minesweep,
+        [Message::Tick(since + Duration::from_secs(30))],
+    );
+    assert!(minesweep.hint_pulse.is_none());
+
+    play(
+        &mut minesweep,
+        [flag(0, 0), Message::Tick(since + Duration::from_secs(41))],
+    );
+    assert_eq!(minesweep.hint_pulse.as_ref().map(|p| p.spot), Some((9, 0)));
+}
+
+#[test]
+fn undoing_a_step_on_a_mine_resumes_the_game() {
+    let mut minesweep = easy_game();
+    let undo = Message::Minesweep(MinesweepMessage::Undo);
+    let redo = Message::Minesweep(MinesweepMessage::Redo);
+
+    play(&mut minesweep, [step(9, 9), flag(0, 0), step(3, 0)]);
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: false });
+
+    play(&mut minesweep, [undo.clone()]);
+    assert!(matches!(minesweep.game_state(), GameState::Running(_)));
+    assert!(!minesweep.field().is_exploded());
+    assert_eq!(minesweep.remaining_flags(), 9);
+
+    // Undoing the flag gives it back, and redoing it places it again
+    play(&mut minesweep, [undo.clone()]);
+    assert_eq!(minesweep.remaining_flags(), 10);
+    play(&mut minesweep, [redo.clone()]);
+    assert_eq!(minesweep.remaining_flags(), 9);
+
+    // Redoing the step on the mine loses the game again
+    play(&mut minesweep, [redo.clone()]);
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: false });
+
+    // A game won after undoing is not recorded as a high score
+    play(&mut minesweep, [undo]);
+    play(&mut minesweep, flag_all(&easy_mines()[1..]));
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: true });
+    assert!(minesweep.high_score_entry().is_none());
+
+    // Won games can't be undone
+    play(&mut minesweep, [Message::Minesweep(MinesweepMessage::Undo)]);
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: true });
+}
+
+#[test]
+fn flags_are_counted() {
+    let mut minesweep = easy_game();
+
+    // Placing the first flag starts the game
+    play(&mut minesweep, [flag(0, 0)]);
+    assert!(matches!(minesweep.game_state(), GameState::Running(_)));
+    assert_eq!(minesweep.remaining_flags(), 9);
+
+    // Flags don't need to be placed on mines
+    play(&mut minesweep, [flag(0, 5)]);
+    assert_eq!(minesweep.remaining_flags(), 8);
+
+    // Removing a flag gives it back
+    play(&mut minesweep, [flag(0, 0)]);
+    assert_eq!(minesweep.remaining_flags(), 9);
+
+    // Revealed spots can't be flagged
+    play(&mut minesweep, [step(9, 9), flag(9, 9)]);
+    assert_eq!(minesweep.remaining_flags(), 9);
+}
+
+#[test]
+fn flags_go_through_question_marks() {
+    let mut minesweep = easy_game();
+
+    play(&mut minesweep, [flag(0, 5), flag(0, 5)]);
+    assert_eq!(minesweep.remaining_flags(), 10);
+    assert_eq!(
+        minesweep.field().spot(0, 5).map(|spot| spot.state),
+        Some(SpotState::QuestionedEmpty {
+            neighboring_mines: 0
+        })
+    );
+
+    // Question marks can be stepped on, unlike flags
+    play(&mut minesweep, [step(0, 5)]);
+    assert_eq!(
+        minesweep.field().spot(0, 5).map(|spot| spot.state),
+        Some(SpotState::RevealedEmpty {
+            neighboring_mines: 0
+        })
+    );
+
+    // Without question marks, flags are simply removed
+    let mut minesweep = easy_game().with_preferences(Preferences {
+        question_marks: false,
+        ..Preferences::default()
+    });
+    play(&mut minesweep, [flag(0, 5), flag(0, 5)]);
+    assert_eq!(minesweep.remaining_flags(), 10);
+    assert_eq!(
+        minesweep.field().spot(0, 5).map(|spot| spot.state),
+        Some(SpotState::HiddenEmpty {
+            neighboring_mines: 0
+        })
+    );
+}
+
+#[test]
+fn more_flags_than_mines_are_counted_below_zero() {
+    let mut minesweep = Minesweep::with_board(3, 3, &[(0, 0)]);
+
+    play(&mut minesweep, [flag(0, 0), flag(1, 0), flag(2, 0)]);
+
+    assert_eq!(minesweep.remaining_flags(), -2);
+}
+
+#[test]
+fn nothing_can_be_done_while_paused() {
+    let mut minesweep = easy_game();
+
+    play(&mut minesweep, [step(0, 1), Message::TogglePause]);
+    assert_eq!(minesweep.game_state(), GameState::Paused);
+
+    let elapsed = minesweep.elapsed_seconds();
+    play(&mut minesweep, [step(9, 9), flag(5, 0)]);
+    assert_eq!(minesweep.game_state(), GameState::Paused);
+    assert!(!minesweep.field().is_cleared());
+    assert_eq!(minesweep.remaining_flags(), 10);
+    assert_eq!(minesweep.elapsed_seconds(), elapsed);
+
+    play(&mut minesweep, [Message::TogglePause, step(9, 9)]);
+    play(&mut minesweep, flag_all(&easy_mines()));
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: true });
+}
+
+#[test]
+fn reset_starts_a_new_game() {
+    let mut minesweep = easy_game();
+
+    play(&mut minesweep, [flag(5, 5), step(3, 0), Message::Reset]);
+
+    assert_eq!(minesweep.game_state(), GameState::Ready);
+    assert_eq!(minesweep.remaining_flags(), 10);
+    assert_eq!(minesweep.elapsed_seconds(), Duration::ZERO);
+    assert!(minesweep.field().spots().all(|(_, spot)| matches!(
+        spot.state,
+        SpotState::HiddenEmpty { .. } | SpotState::HiddenMine
+    )));
+}
+
+#[test]
+fn the_same_board_can_be_restarted() {
+    // Boards are only restarted once their mines are laid out by the first step
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [Message::RestartSameBoard]);
+    assert_eq!(minesweep.game_state(), GameState::Ready);
+    assert!(!minesweep.assisted);
+
+    let mines = |minesweep: &Minesweep| {
+        let mut mines: Vec<(u16, u16)> = minesweep
+            .field()
+            .mine_spots()
+            .map(|(coords, _)| coords)
+            .collect();
+        mines.sort();
+        mines
+    };
+    let layout = mines(&minesweep);
+    let seed = minesweep.seed;
+    play(&mut minesweep, [step(0, 5), step(3, 0)]);
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: false });
+
+    play(&mut minesweep, [Message::RestartSameBoard]);
+    assert_eq!(minesweep.game_state(), GameState::Ready);
+    assert_eq!(minesweep.remaining_flags(), 10);
+    assert_eq!(minesweep.seed, seed);
+    assert_eq!(mines(&minesweep), layout);
+    assert!(minesweep.assisted);
+    assert!(minesweep.field().spots().all(|(_, spot)| matches!(
+        spot.state,
+        SpotState::HiddenEmpty { .. } | SpotState::HiddenMine
+    )));
+
+    // A running game is only restarted once confirmed
+    play(&mut minesweep, [step(9, 9), Message::RestartSameBoard]);
+    assert!(matches!(
+        minesweep.main_view,
+        MainViewContent::ConfirmReset { same_board: true }
+    ));
+    play(&mut minesweep, [Message::ConfirmReset(true)]);
+    assert_eq!(minesweep.game_state(), GameState::Ready);
+    assert_eq!(mines(&minesweep), layout);
+}
+
+#[test]
+fn running_games_are_only_reset_once_confirmed() {
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [step(0, 5), Message::Reset]);
+    assert!(matches!(
+        minesweep.main_view,
+        MainViewContent::ConfirmReset { same_board: false }
+    ));
+    assert_eq!(minesweep.game_state(), GameState::Paused);
+
+    // Declining gets back to the game, which goes on
+    play(&mut minesweep, [Message::ConfirmReset(false)]);
+    assert!(matches!(minesweep.main_view, MainViewContent::Game));
+    assert!(matches!(minesweep.game_state(), GameState::Running(_)));
+    assert!(minesweep
+        .field()
+        .spots()
+        .any(|(_, spot)| matches!(spot.state, SpotState::RevealedEmpty { .. })));
+
+    play(
+        &mut minesweep,
+        [Message::Reset, Message::ConfirmReset(true)],
+    );
+    assert!(matches!(minesweep.main_view, MainViewContent::Game));
+    assert_eq!(minesweep.game_state(), GameState::Ready);
+    assert_eq!(minesweep.elapsed_seconds(), Duration::ZERO);
+
+    // A game paused by the player is still in progress, and stays paused if the reset is declined
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [step(0, 5), Message::TogglePause]);
+    assert_eq!(minesweep.game_state(), GameState::Paused);
+    for message in [Message::Reset, Message::RestartSameBoard] {
+        play(&mut minesweep, [message]);
+        assert!(matches!(
+            minesweep.main_view,
+            MainViewContent::ConfirmReset { .. }
+        ));
+        play(&mut minesweep, [Message::ConfirmReset(false)]);
+        assert!(matches!(minesweep.main_view, MainViewContent::Game));
+        assert_eq!(minesweep.game_state(), GameState::Paused);
+    }
+    play(
+        &mut minesweep,
+        [Message::Reset, Message::ConfirmReset(true)],
+    );
+    assert_eq!(minesweep.game_state(), GameState::Ready);
+
+    // A game which isn't in progress is reset right away
+    play(&mut minesweep, [Message::Reset]);
+    assert!(matches!(minesweep.main_view, MainViewContent::Game));
+}
+
+#[test]
+fn the_new_board_key_is_ignored_outside_the_game() {
+    let mut minesweep = easy_game();
+    play(
+        &mut minesweep,
+        [step(0, 5), Message::Settings(SettingsMessage::Show)],
+    );
+    assert_eq!(minesweep.game_state(), GameState::Paused);
+
+    play(&mut minesweep, [Message::ResetKey]);
+    assert!(matches!(minesweep.main_view, MainViewContent::Settings(_)));
+    assert_eq!(minesweep.game_state(), GameState::Paused);
+    assert!(minesweep
+        .field()
+        .spots()
+        .any(|(_, spot)| matches!(spot.state, SpotState::RevealedEmpty { .. })));
+
+    // Back in the game, it goes on where it was left
+    play(&mut minesweep, [Message::CloseView]);
+    assert!(matches!(minesweep.main_view, MainViewContent::Game));
+    assert!(matches!(minesweep.game_state(), GameState::Running(_)));
+}
+
+#[test]
+fn the_new_board_button_resets_from_any_view() {
+    for view in [
+        Message::Settings(SettingsMessage::Show),
+        Message::HighScores,
+    ] {
+        // A board which wasn't played yet is replaced right away, back in the game view
+        let mut minesweep = easy_game();
+        let seed = minesweep.seed;
+        play(&mut minesweep, [view.clone(), Message::Reset]);
+        assert!(matches!(minesweep.main_view, MainViewContent::Game));
+        assert_eq!(minesweep.game_state(), GameState::Ready);
+        assert_ne!(minesweep.seed, seed);
+
+        // A game in progress is only thrown away once the player confirms it
+        let mut minesweep = easy_game();
+        play(&mut minesweep, [step(0, 5), view, Message::Reset]);
+        assert!(matches!(
+            minesweep.main_view,
+            MainViewContent::ConfirmReset { same_board: false }
+        ));
+        play(&mut minesweep, [Message::ConfirmReset(true)]);
+        assert!(matches!(minesweep.main_view, MainViewContent::Game));
+        assert_eq!(minesweep.game_state(), GameState::Ready);
+    }
+}
+
+#[test]
+fn high_scores_are_ranked_by_time() {
+    let mut minesweep = easy_game();
+    minesweep.max_high_scores = 3;
+    let score = |millis| Score {
+        name: String::new(),
+        seconds: millis / 1000,
+        millis: Some(millis),
+        date: None,
+        points: None,
+        replay: None,
+    };
+
+    assert_eq!(
+        minesweep.insert_high_score(DifficultyLevel::Easy, false, score(5000)),
+        Some(0)
+    );
+    assert_eq!(
+        minesweep.insert_high_score(DifficultyLevel::Easy, false, score(3000)),
+        Some(0)
+    );
+    assert_eq!(
+        minesweep.insert_high_score(DifficultyLevel::Easy, false, score(4000)),
+        Some(1)
+    );
+
+    // Only the best scores are kept
+    assert_eq!(
+        minesweep.insert_high_score(DifficultyLevel::Easy, false, score(6000)),
+        None
+    );
+    assert_eq!(
+        minesweep.insert_high_score(DifficultyLevel::Easy, false, score(1000)),
+        Some(0)
+    );
+
+    let millis: Vec<u64> = minesweep
+        .high_scores(DifficultyLevel::Easy)
+        .iter()
+        .map(Score::millis)
+        .collect();
+    assert_eq!(millis, [1000, 3000, 4000]);
+
+    // Scores saved by older versions only have whole seconds
+    let old_score = Score {
+        name: String::new(),
+        seconds: 2,
+        millis: None,
+        date: None,
+        points: None,
+        replay: None,
+    };
+    assert_eq!(
+        minesweep.insert_high_score(DifficultyLevel::Easy, false, old_score),
+        Some(1)
+    );
+
+    // Fewer high scores can be kept from the settings, which drops the worst ones
+    play(&mut minesweep, [Message::Settings(SettingsMessage::Show)]);
+    let mut settings = settings(&minesweep).clone();
+    settings.max_high_scores = 2;
+    play(
+        &mut minesweep,
+        [Message::Settings(SettingsMessage::Set(Box::new(settings)))],
+    );
+    let millis: Vec<u64> = minesweep
+        .high_scores(DifficultyLevel::Easy)
+        .iter()
+        .map(Score::millis)
+        .collect();
+    assert_eq!(millis, [1000, 2000]);
+}
+
+#[test]
+fn high_scores_can_be_ranked_by_points() {
+    // Harder boards score more for the same time, and hints lower the points
+    let easy = Score::points_for(100, 10, 3000, 0);
+    let hard = Score::points_for(30 * 16, 99, 3000, 0);
+    assert!(hard > easy * 5);
+    assert!(Score::points_for(100, 10, 3000, 1) < easy);
+    assert!(Score::points_for(100, 10, 6000, 0) < easy);
+
+    let mut minesweep = easy_game().with_preferences(Preferences {
+        rank_by_points: true,
+        ..Preferences::default()
+    });
+    let score = |millis, points| Score {
+        name: String::new(),
+        seconds: millis / 1000,
+        millis: Some(millis),
+        date: None,
+        points,
+        replay: None,
+    };
+    for (millis, points) in [(3000, Some(200)), (5000, Some(300)), (4000, None)] {
+        minesweep.insert_high_score(DifficultyLevel::Easy, false, score(millis, points));
+    }
+    let millis = |minesweep: &Minesweep| {
+        minesweep
+            .high_scores(DifficultyLevel::Easy)
+            .iter()
+            .map(Score::millis)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(millis(&minesweep), [5000, 3000, 4000]);
+
+    // The high scores are sorted again once the ranking is changed
+    play(&mut minesweep, [Message::Settings(SettingsMessage::Show)]);
+    let mut settings = settings(&minesweep).clone();
+    settings.preferences.rank_by_points = false;
+    play(
+        &mut minesweep,
+        [Message::Settings(SettingsMessage::Set(Box::new(settings)))],
+    );
+    assert_eq!(millis(&minesweep), [3000, 4000, 5000]);
+}
+
+#[test]
+fn hinted_wins_are_only_recorded_when_ranking_by_points() {
+    for rank_by_points in [false, true] {
+        let mut minesweep = easy_game().with_preferences(Preferences {
+            rank_by_points,
+            ..Preferences::default()
+        });
+        minesweep.hints = 1;
+
+        play(&mut minesweep, [step(9, 9)]);
+        play(&mut minesweep, flag_all(&easy_mines()));
+
+        assert_eq!(minesweep.high_score_entry().is_some(), rank_by_points);
+        if rank_by_points {
+            let points = minesweep.high_scores(DifficultyLevel::Easy)[0].points;
+            assert!(points.is_some_and(|points| points > 0));
+        }
+    }
+}
+
+fn settings(minesweep: &Minesweep) -> &SettingsDraft {
+    match &minesweep.main_view {
+        MainViewContent::Settings(settings) => settings,
+        _ => panic!("the settings view should be shown"),
+    }
+}
+
+#[test]
+fn custom_configs_are_saved_as_presets() {
+    let mut minesweep = easy_game();
+    let huge = GameConfig {
+        width: 100,
+        height: 100,
+        mines: 1000,
+        ..GameConfig::default()
+    };
+
+    play(
+        &mut minesweep,
+        [
+            Message::Settings(SettingsMessage::Show),
+            Message::Settings(SettingsMessage::Picked(GameDifficulty::Custom(huge))),
+            Message::Settings(SettingsMessage::SavePreset),
+        ],
+    );
+    assert!(settings(&minesweep).preset_error.is_some());
+    assert!(minesweep.presets.is_empty());
+
+    play(
+        &mut minesweep,
+        [
+            Message::Settings(SettingsMessage::PresetName(" Huge ".to_string())),
+            Message::Settings(SettingsMessage::SavePreset),
+        ],
+    );
+    assert_eq!(settings(&minesweep).preset.as_deref(), Some("Huge"));
+    assert_eq!(
+        minesweep.presets,
+        [Preset {
+            name: "Huge".to_string(),
+            game_config: huge
+        }]
+    );
+
+    // Invalid configs can't be saved
+    play(
+        &mut minesweep,
+        [
+            Message::Settings(SettingsMessage::CustomConfig(GameConfig {
+                mines: 0,
+                ..huge
+            })),
+            Message::Settings(SettingsMessage::SavePreset),
+        ],
+    );
+    assert!(settings(&minesweep).preset.is_none());
+    assert!(settings(&minesweep).preset_error.is_some());
+    assert_eq!(minesweep.presets[0].game_config, huge);
+
+    // Picking a preset edits its config as a custom game
+    play(
+        &mut minesweep,
+        [
+            Message::Settings(SettingsMessage::Picked(GameDifficulty::Easy)),
+            Message::Settings(SettingsMessage::PickedPreset("Huge".to_string())),
+        ],
+    );
+    assert_eq!(
+        settings(&minesweep).game_difficulty,
+        GameDifficulty::Custom(huge)
+    );
+
+    play(
+        &mut minesweep,
+        [Message::Settings(SettingsMessage::DeletePreset(
+            "Huge".to_string(),
+        ))],
+    );
+    assert!(settings(&minesweep).preset.is_none());
+    assert!(minesweep.presets.is_empty());
+}
+
+#[test]
+fn invalid_presets_are_not_loaded() {
+    let mut minesweep = easy_game();
+    let preset = |name: &str, mines| Preset {
+        name: name.to_string(),
+        game_config: GameConfig {
+            width: 50,
+            height: 50,
+            mines,
+            ..GameConfig::default()
+        },
+    };
+
+    play(
+        &mut minesweep,
+        [Message::Persistence(PersistenceMessage::LoadedConfigs(
+            Some(Box::new(GamePersistence {
+                presets: vec![preset("Sparse", 50), preset("Broken", 5000)],
+                ..GamePersistence::default()
+            })),
+        ))],
+    );
+
+    assert_eq!(minesweep.presets, [preset("Sparse", 50)]);
+}
+
+#[test]
+fn interrupted_save_keeps_the_previous_one() {
+    let dir = std::env::temp_dir().join(format!("minesweep-save-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("configs.json");
+
+    let mut minesweep = easy_game();
+    minesweep.insert_high_score(
+        DifficultyLevel::Easy,
+        false,
+        Score {
+            name: "Ada".to_string(),
+            seconds: 7,
+            millis: Some(7250),
+            date: Some(0),
+            points: None,
+            replay: None,
+        },
+    );
+    Minesweep::save_persistence_to(
+        &path,
+        &minesweep.game_persistence(),
+        Minesweep::next_save_generation(),
+    )
+    .unwrap();
+    let files = |dir: &std::path::Path| std::fs::read_dir(dir).unwrap().count();
+    assert_eq!(files(&dir), 1);
+
+    // A save which stops half way through only leaves a truncated temporary file behind
+    let buf = serde_json::to_vec(&GamePersistence::default()).unwrap();
+    let interrupted = Minesweep::temporary_path(&path);
+    std::fs::write(&interrupted, &buf[..buf.len() / 2]).unwrap();
+
+    let loaded = Minesweep::load_persistence_from(&path)
+        .unwrap()
+        .expect("the previous save");
+    assert_eq!(loaded.high_scores[&DifficultyLevel::Easy][0].name, "Ada");
+
+    // The temporary files left behind are deleted when the game starts
+    let _ = Minesweep::initialize(StartupConfig {
+        persistence_path: Some(path.clone()),
+        ..StartupConfig::default()
+    });
+    assert!(!interrupted.exists());
+    assert_eq!(files(&dir), 1);
+
+    let save = |configs: &GamePersistence, generation| {
+        Minesweep::save_persistence_to(&path, configs, generation).unwrap();
+        Minesweep::load_persistence_from(&path)
+            .unwrap()
+            .expect("a save")
+    };
+
+    // A save which is overtaken by the save of newer configs doesn't overwrite them
+    let (older, newer) = (
+        Minesweep::next_save_generation(),
+        Minesweep::next_save_generation(),
+    );
+    assert!(save(&GamePersistence::default(), newer)
+        .high_scores
+        .is_empty());
+    assert!(save(&minesweep.game_persistence(), older)
+        .high_scores
+        .is_empty());
+    let loaded = save(
+        &minesweep.game_persistence(),
+        Minesweep::next_save_generation(),
+    );
+    assert_eq!(loaded.high_scores[&DifficultyLevel::Easy][0].name, "Ada");
+
+    // Overlapping saves are written one at a time, so the last one renamed is whole
+    let saves = minesweep.game_persistence();
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                for _ in 0..10 {
+                    Minesweep::save_persistence_to(
+                        &path,
+                        &saves,
+                        Minesweep::next_save_generation(),
+                    )
+                    .unwrap();
+                }
+            });
+        }
+    });
+    let loaded = Minesweep::load_persistence_from(&path)
+        .unwrap()
+        .expect("the last save");
+    assert_eq!(loaded.high_scores[&DifficultyLevel::Easy][0].name, "Ada");
+    assert_eq!(files(&dir), 1);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn format_time_switches_to_minutes() {
+    assert_eq!(format_time(0, false), "0");
+    assert_eq!(format_time(59_999, false), "59");
+    assert_eq!(format_time(59_999, true), "59.99");
+    assert_eq!(format_time(60_000, false), "01:00");
+    assert_eq!(format_time(61_234, true), "01:01.23");
+    assert_eq!(format_time(3_600_000, false), "60:00");
+}
+
+#[test]
+fn startup_config_is_read_from_vars() {
+    let vars = |vars: &'static [(&'static str, &'static str)]| {
+        move |name: &str| {
+            vars.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, value)| value.to_string())
+        }
+    };
+
+    assert_eq!(
+        StartupConfig::from_vars(vars(&[])),
+        (StartupConfig::default(), vec![])
+    );
+
+    // Dimensions which are not given are the ones of the default game config
+    let default = GameConfig::default();
+    assert_eq!(
+        StartupConfig::from_vars(vars(&[
+            (StartupConfig::ENV_MINES, " 20 "),
+            (StartupConfig::ENV_SEED, "42"),
+        ])),
+        (
+            StartupConfig {
+                game_config: Some(GameConfig {
+                    mines: 20,
+                    ..default
+                }),
+                seed: Some(42),
+                persistence_path: None,
+            },
+            vec![]
+        )
+    );
+    assert_eq!(
+        StartupConfig::from_vars(vars(&[(
+            StartupConfig::ENV_CONFIG_FILE,
+            "/tmp/scores.json"
+        )])),
+        (
+            StartupConfig {
+                persistence_path: Some(PathBuf::from("/tmp/scores.json")),
+                ..StartupConfig::default()
+            },
+            vec![]
+        )
+    );
+
+    // An invalid variable is left out, without discarding the valid ones
+    let (startup, errors) = StartupConfig::from_vars(vars(&[
+        (StartupConfig::ENV_WIDTH, "wide"),
+        (StartupConfig::ENV_MINES, "20"),
+        (StartupConfig::ENV_SEED, "-1"),
+        (StartupConfig::ENV_CONFIG_FILE, "/tmp/scores.json"),
+    ]));
+    assert_eq!(
+        startup,
+        StartupConfig {
+            game_config: Some(GameConfig {
+                mines: 20,
+                ..default
+            }),
+            seed: None,
+            persistence_path: Some(PathBuf::from("/tmp/scores.json")),
+        }
+    );
+    assert_eq!(errors.len(), 2);
+
+    // The resulting game config must be playable, but the seed is still applied
+    let (startup, errors) = StartupConfig::from_vars(vars(&[
+        (StartupConfig::ENV_WIDTH, "2"),
+        (StartupConfig::ENV_HEIGHT, "2"),
+        (StartupConfig::ENV_MINES, "4"),
+        (StartupConfig::ENV_SEED, "42"),
+    ]));
+    assert_eq!(
+        startup,
+        StartupConfig {
+            seed: Some(42),
+            ..StartupConfig::default()
+        }
+    );
+    assert_eq!(errors, vec![ConfigError::Mines(4).to_string()]);
+}
+
+#[test]
+fn startup_config_is_overridden_by_args() {
+    let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+    let from_env = StartupConfig {
+        game_config: Some(GameDifficulty::EASY),
+        seed: Some(1),
+        persistence_path: Some(PathBuf::from("env.json")),
+    };
+
+    assert_eq!(from_env.clone().with_args(args(&[])), Ok(from_env.clone()));
+    assert_eq!(
+        from_env.clone().with_args(args(&[
+            "--difficulty",
+            "Hard",
+            "--seed=42",
+            "--config",
+            "args.json"
+        ])),
+        Ok(StartupConfig {
+            game_config: Some(GameDifficulty::HARD),
+            seed: Some(42),
+            persistence_path: Some(PathBuf::from("args.json")),
+        })
+    );
+    assert_eq!(
+        StartupConfig::default().with_args(args(&["--custom", "30x20:99"])),
+        Ok(StartupConfig {
+            game_config: Some(GameConfig {
+                width: 30,
+                height: 20,
+                mines: 99,
+                ..GameConfig::default()
+            }),
+            ..StartupConfig::default()
+        })
+    );
+
+    for invalid in [
+        &["--difficulty", "epic"][..],
+        &["--custom", "30x20"],
+        &["--custom=2x2:4"],
+        &["--seed"],
+        &["--size", "10"],
+    ] {
+        assert!(from_env.clone().with_args(args(invalid)).is_err());
+    }
+}
+
+#[test]
+fn the_game_config_requested_at_launch_is_not_saved() {
+    let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+    let vars = |name: &str| match name {
+        StartupConfig::ENV_WIDTH => Some("30".to_string()),
+        StartupConfig::ENV_HEIGHT => Some("20".to_string()),
+        StartupConfig::ENV_MINES => Some("99".to_string()),
+        _ => None,
+    };
+
+    // The game config can be requested by the command line arguments, or by the environment variables
+    let from_args = StartupConfig::default()
+        .with_args(args(&["--custom", "30x20:99"]))
+        .unwrap();
+    let (from_env, errors) = StartupConfig::from_vars(vars);
+    assert!(errors.is_empty());
+
+    for startup in [from_args, from_env] {
+        let mut minesweep = Minesweep {
+            startup,
+            ..Minesweep::default()
+        };
+
+        play(
+            &mut minesweep,
+            [Message::Persistence(PersistenceMessage::LoadedConfigs(
+                Some(Box::new(GamePersistence {
+                    game_config: GameDifficulty::HARD,
+                    ..GamePersistence::default()
+                })),
+            ))],
+        );
+
+        // The game config requested at launch is played, even on the next boards, while the persisted one is kept
+        let launch_config = GameConfig {
+            width: 30,
+            height: 20,
+            mines: 99,
+            ..GameConfig::default()
+        };
+        assert_eq!(minesweep.game_config, launch_config);
+        assert!(minesweep.custom_game);
+        assert_eq!(
+            (minesweep.field.width(), minesweep.field.height()),
+            (30, 20)
+        );
+        assert_eq!(
+            minesweep.game_persistence().game_config,
+            GameDifficulty::HARD
+        );
+
+        play(&mut minesweep, [Message::Reset]);
+        assert_eq!(minesweep.game_config, launch_config);
+        assert_eq!(
+            minesweep.game_persistence().game_config,
+            GameDifficulty::HARD
+        );
+
+        // Settings applied by the player are their own, and saved
+        let settings = minesweep.settings_draft(GameDifficulty::Easy);
+        play(
+            &mut minesweep,
+            [Message::Settings(SettingsMessage::Set(Box::new(settings)))],
+        );
+        assert_eq!(
+            minesweep.game_persistence().game_config,
+            GameDifficulty::EASY
+        );
+    }
+}
+
+#[test]
+fn configs_are_kept_in_the_config_directory() {
+    let vars = |vars: &'static [(&'static str, &'static str)]| {
+        move |name: &str| {
+            vars.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, value)| value.into())
+        }
+    };
+
+    assert_eq!(Minesweep::config_dir(vars(&[])), None);
+
+    if cfg!(target_os = "linux") {
+        assert_eq!(
+            Minesweep::config_dir(vars(&[("HOME", "/home/ada")])),
+            Some(PathBuf::from("/home/ada/.config/iced-minesweep-rs"))
+        );
+        assert_eq!(
+            Minesweep::config_dir(vars(&[("HOME", "/home/ada"), ("XDG_CONFIG_HOME", "/xdg")])),
+            Some(PathBuf::from("/xdg/iced-minesweep-rs"))
+        );
+    }
+
+    // A file saved by an older version is moved once, without replacing a newer one
+    let dir = std::env::temp_dir().join(format!("minesweep-migrate-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let legacy = dir.join("legacy.json");
+    let path = dir.join("config").join("configs.json");
+
+    std::fs::write(&legacy, "old").unwrap();
+    Minesweep::migrate_persistence(&legacy, &path);
+    assert!(!legacy.exists());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "old");
+
+    std::fs::write(&legacy, "older").unwrap();
+    Minesweep::migrate_persistence(&legacy, &path);
+    assert!(legacy.exists());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "old");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn window_fits_small_minefields() {
+    let minesweep = easy_game();
+    let (field_width, field_height) = minesweep.desired_field_size();
+
+    assert_eq!(
+        minesweep.desired_window_size(),
+        (field_width, field_height + Minesweep::TOOLBAR_HEIGHT)
+    );
+    assert!(!minesweep.is_field_scrollable());
+}
+
+#[test]
+fn window_is_clamped_for_large_minefields() {
+    let mut minesweep = Minesweep::with_board(200, 200, &[(0, 0)]);
+
+    let monitor = Minesweep::FALLBACK_MONITOR_SIZE;
+    assert_eq!(
+        minesweep.desired_window_size(),
+        (
+            monitor.width,
+            monitor.height - Minesweep::MONITOR_RESERVED_HEIGHT
+        )
+    );
+    assert!(minesweep.is_field_scrollable());
+
+    // The detected monitor size is used instead of the fallback one
+    play(
+        &mut minesweep,
+        [Message::MonitorSize(Some(Size::new(1000.0, 800.0)))],
+    );
+    assert_eq!(
+        minesweep.desired_window_size(),
+        (1000.0, 800.0 - Minesweep::MONITOR_RESERVED_HEIGHT)
+    );
+
+    // The maximum size configured by the player takes precedence, but never goes below the minimum window size
+    let minesweep = minesweep.with_preferences(Preferences {
+        max_window_width: Some(100),
+        max_window_height: Some(500),
+        ..Preferences::default()
+    });
+    assert_eq!(
+        minesweep.desired_window_size(),
+        (Minesweep::MIN_WINDOW_SIZE.width, 500.0)
+    );
+    assert!(minesweep.is_field_scrollable());
+
+    // ... and never above the detected monitor size, since the window can't be resized back by the player
+    let minesweep = minesweep.with_preferences(Preferences {
+        max_window_width: Some(5000),
+        max_window_height: Some(5000),
+        ..Preferences::default()
+    });
+    assert_eq!(
+        minesweep.desired_window_size(),
+        (1000.0, 800.0 - Minesweep::MONITOR_RESERVED_HEIGHT)
+    );
+}
+
+#[test]
+fn minimap_shows_the_scrolled_part_of_large_minefields() {
+    let mut minesweep = Minesweep::with_board(200, 100, &[(0, 0)]);
+    assert!(minesweep.minimap_viewport().is_none());
+
+    // A quarter of the grid is shown, below and right of its first half
+    let grid = minesweep.grid_size();
+    play(
+        &mut minesweep,
+        [Message::FieldScrolled(Rectangle::new(
+            Point::new(
+                Minesweep::FIELD_PAD + grid.width / 2.0,
+                Minesweep::FIELD_PAD + grid.height / 2.0,
+            ),
+            Size::new(grid.width / 4.0, grid.height / 4.0),
+        ))],
+    );
+    assert_eq!(
+        minesweep.minimap_viewport(),
+        Some(Rectangle::new(Point::new(0.5, 0.5), Size::new(0.25, 0.25)))
+    );
+
+    // The padding around the grid isn't part of the minimap
+    play(
+        &mut minesweep,
+        [Message::FieldScrolled(Rectangle::new(
+            Point::ORIGIN,
+            Size::new(grid.width / 4.0, grid.height / 4.0),
+        ))],
+    );
+    let viewport = minesweep.minimap_viewport().unwrap();
+    assert_eq!(viewport.position(), Point::ORIGIN);
+    assert!(viewport.width < 0.25 && viewport.height < 0.25);
+
+    // The minimap has the proportions of the minefield
+    let minimap = Minimap::new(
+        minesweep.field(),
+        None,
+        MinimapColors {
+            hidden: Color::BLACK,
+            revealed: Color::WHITE,
+            flagged: Color::WHITE,
+            viewport: Color::WHITE,
+        },
+    );
+    assert_eq!(minimap.size(), Size::new(150.0, 75.0));
+}
+
+#[test]
+fn only_the_shown_spots_of_large_minefields_are_drawn() {
+    let mut minesweep = Minesweep::with_board(200, 100, &[(0, 0)]);
+    let (field_width, field_height) = minesweep.desired_field_size();
+    let size = Size::new(field_width, field_height);
+    assert_eq!(minesweep.shown_spots(size), (0..200, 0..100));
+
+    // The spots in the viewport are drawn, along with those right next to it
+    let spot_size = minesweep.spot_size;
+    play(
+        &mut minesweep,
+        [Message::FieldScrolled(Rectangle::new(
+            Point::new(
+                Minesweep::FIELD_PAD + 10.5 * spot_size,
+                Minesweep::FIELD_PAD + 20.5 * spot_size,
+            ),
+            Size::new(30.0 * spot_size, 15.0 * spot_size),
+        ))],
+    );
+    assert_eq!(minesweep.shown_spots(size), (9..42, 19..37));
+
+    // The edges of the minefield aren't drawn past
+    play(
+        &mut minesweep,
+        [Message::FieldScrolled(Rectangle::new(
+            Point::ORIGIN,
+            Size::new(30.0 * spot_size, 15.0 * spot_size),
+        ))],
+    );
+    let (columns, rows) = minesweep.shown_spots(size);
+    assert_eq!((columns.start, rows.start), (0, 0));
+    assert!(columns.end < 200 && rows.end < 100);
+}
+
+#[test]
+fn rulers_number_the_shown_columns_and_rows_in_the_margin() {
+    let minesweep = Minesweep::with_board(200, 100, &[(0, 0)]);
+    let (field_width, field_height) = minesweep.desired_field_size();
+    let size = Size::new(field_width, field_height);
+    let labels = minesweep.ruler_labels(size);
+    assert_eq!(labels.len(), 300);
+
+    // Columns are numbered above the minefield, and rows left of it, all within the margin
+    let origin = minesweep.field_origin(size);
+    let column = labels.iter().find(|text| text.content == "12").unwrap();
+    assert_eq!(column.position.x, origin.x + 12.5 * minesweep.spot_size);
+    assert!(column.position.y < origin.y && column.position.y > 0.0);
+    let row = labels
+        .iter()
+        .rev()
+        .find(|text| text.content == "99")
+        .unwrap();
+    assert!(row.position.x < origin.x && row.position.x > 0.0);
+    assert!(labels
+        .iter()
+        .all(|text| text.size.0 * text.content.len() as f32 <= 2.0 * Minesweep::FIELD_PAD));
+}
+
+#[test]
+fn moves_only_draw_the_spots_they_change_again() {
+    let mut minesweep = Minesweep::with_board(60, 60, &[(0, 0), (59, 59)]);
+    play(&mut minesweep, [flag(30, 30)]);
+
+    // The first move of a game may move its mines, so the whole minefield is drawn again
+    assert!(minesweep.changed_spots.is_empty());
+
+    // A flag is drawn again along with its neighbors, whose numbers may now be satisfied
+    play(&mut minesweep, [flag(10, 20)]);
+    let expected: BTreeSet<(u16, u16)> = (19..=21)
+        .flat_map(|y| (9..=11).map(move |x| (x, y)))
+        .collect();
+    assert_eq!(minesweep.changed_spots, expected);
+
+    // Spots keep being drawn over the cached minefield until it's drawn again
+    play(&mut minesweep, [flag(0, 59)]);
+    assert_eq!(minesweep.changed_spots.len(), 9 + 4);
+
+    // A step which reveals most of the minefield draws all of it again
+    play(&mut minesweep, [step(59, 0)]);
+    assert!(minesweep.changed_spots.is_empty());
+}
+
+fn key_press(key: keyboard::Key) -> Event {
+    Event::Keyboard(keyboard::Event::KeyPressed {
+        key: key.clone(),
+        modified_key: key,
+        physical_key: keyboard::key::Physical::Unidentified(
+            keyboard::key::NativeCode::Unidentified,
+        ),
+        location: keyboard::Location::Standard,
+        modifiers: keyboard::Modifiers::default(),
+        text: None,
+    })
+}
+
+/// Bounds of the minefield canvas, in the tests which let it handle events
+const FIELD_BOUNDS: Size = Size::new(1000.0, 1000.0);
+
+/// Let the minefield canvas handle the given event, as if the mouse was not over it
+fn field_event(
+    minesweep: &Minesweep,
+    interaction: &mut FieldInteraction,
+    event: Event,
+) -> (event::Status, Option<Message>) {
+    let bounds = Rectangle::with_size(FIELD_BOUNDS);
+
+    canvas::Program::update(minesweep, interaction, event, bounds, Cursor::Unavailable)
+}
+
+/// Position of the middle of the given spot on the minefield canvas
+fn spot_position(minesweep: &Minesweep, x: u16, y: u16) -> Point {
+    minesweep.field_origin(FIELD_BOUNDS)
+        + Vector::new(
+            (x as f32 + 0.5) * minesweep.spot_size,
+            (y as f32 + 0.5) * minesweep.spot_size,
+        )
+}
+
+fn finger_pressed(finger: u64, position: Point) -> Event {
+    Event::Touch(touch::Event::FingerPressed {
+        id: touch::Finger(finger),
+        position,
+    })
+}
+
+fn finger_lifted(finger: u64, position: Point) -> Event {
+    Event::Touch(touch::Event::FingerLifted {
+        id: touch::Finger(finger),
+        position,
+    })
+}
+
+#[test]
+fn keyboard_selects_and_acts_on_spots() {
+    let mut minesweep = easy_game().with_preferences(Preferences {
+        reveal_animation: false,
+        ..Preferences::default()
+    });
+    let mut interaction = FieldInteraction::default();
+    let space = keyboard::Key::Named(keyboard::key::Named::Space);
+    let left = keyboard::Key::Named(keyboard::key::Named::ArrowLeft);
+    let up = keyboard::Key::Named(keyboard::key::Named::ArrowUp);
+
+    // Without a selected spot, Space is left to pause the game
+    let (status, message) = field_event(&minesweep, &mut interaction, key_press(space.clone()));
+    assert_eq!(status, event::Status::Ignored);
+    assert!(message.is_none());
+
+    // The selection starts in the middle of the minefield, and stays on it
+    field_event(&minesweep, &mut interaction, key_press(up.clone()));
+    assert_eq!(interaction.selected, Some((5, 5)));
+    for _ in 0..10 {
+        field_event(&minesweep, &mut interaction, key_press(left.clone()));
+    }
+    assert_eq!(interaction.selected, Some((0, 5)));
+
+    // Space steps on the selected spot, and F flags it
+    let (status, message) = field_event(&minesweep, &mut interaction, key_press(space));
+    assert_eq!(status, event::Status::Captured);
+    play(&mut minesweep, message);
+    assert!(matches!(minesweep.game_state(), GameState::Running(_)));
+
+    for _ in 0..5 {
+        field_event(&minesweep, &mut interaction, key_press(up.clone()));
+    }
+    let f = keyboard::Key::Character("f".into());
+    let (_, message) = field_event(&minesweep, &mut interaction, key_press(f));
+    play(&mut minesweep, message);
+    assert_eq!(minesweep.remaining_flags(), 9);
+
+    // Escape clears the selection
+    let escape = keyboard::Key::Named(keyboard::key::Named::Escape);
+    field_event(&minesweep, &mut interaction, key_press(escape));
+    assert!(interaction.selected.is_none());
+}
+
+#[test]
+fn the_spot_under_the_cursor_is_hovered_and_autosteps_are_previewed() {
+    let mut minesweep = easy_game().with_preferences(Preferences {
+        reveal_animation: false,
+        ..Preferences::default()
+    });
+    let mut interaction = FieldInteraction::default();
+    let bounds = Rectangle::with_size(FIELD_BOUNDS);
+    play(&mut minesweep, [step(0, 5)]);
+
+    let position = spot_position(&minesweep, 3, 1);
+    let moved = Event::Mouse(mouse::Event::CursorMoved { position });
+    canvas::Program::update(
+        &minesweep,
+        &mut interaction,
+        moved,
+        bounds,
+        Cursor::Available(position),
+    );
+    assert_eq!(interaction.hovered, Some((3, 1)));
+    assert_eq!(minesweep.hovered_spot(&interaction), Some((3, 1)));
+    assert!(!minesweep.previews_auto_step(&interaction));
+
+    // Holding the middle button, which autosteps with the default controls, previews the autostep
+    let middle = Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Middle));
+    canvas::Program::update(
+        &minesweep,
+        &mut interaction,
+        middle,
+        bounds,
+        Cursor::Available(position),
+    );
+    assert!(minesweep.previews_auto_step(&interaction));
+
+    let released = Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Middle));
+    canvas::Program::update(
+        &minesweep,
+        &mut interaction,
+        released,
+        bounds,
+        Cursor::Available(position),
+    );
+    assert!(!minesweep.previews_auto_step(&interaction));
+
+    // Nothing is hovered once the cursor leaves the minefield
+    field_event(
+        &minesweep,
+        &mut interaction,
+        Event::Mouse(mouse::Event::CursorLeft),
+    );
+    assert!(interaction.hovered.is_none());
+}
+
+#[test]
+fn touch_taps_and_long_presses() {
+    let mut minesweep = easy_game().with_preferences(Preferences {
+        reveal_animation: false,
+        ..Preferences::default()
+    });
+    let mut interaction = FieldInteraction::default();
+
+    // A tap steps on the spot
+    let at = spot_position(&minesweep, 0, 5);
+    field_event(&minesweep, &mut interaction, finger_pressed(0, at));
+    let (status, message) = field_event(&minesweep, &mut interaction, finger_lifted(0, at));
+    assert_eq!(status, event::Status::Captured);
+    assert!(matches!(
+        message,
+        Some(Message::Minesweep(MinesweepMessage::Step { x: 0, y: 5 }))
+    ));
+    play(&mut minesweep, message);
+
+    // A long press flags it
+    let at = spot_position(&minesweep, 0, 0);
+    field_event(&minesweep, &mut interaction, finger_pressed(1, at));
+    interaction.touches[0].2 -= Minesweep::LONG_PRESS_TIME;
+    let (_, message) = field_event(&minesweep, &mut interaction, finger_lifted(1, at));
+    assert!(matches!(
+        message,
+        Some(Message::Minesweep(MinesweepMessage::Flag { x: 0, y: 0 }))
+    ));
+
+    // A two finger tap autosteps around it
+    let at = spot_position(&minesweep, 1, 1);
+    field_event(&minesweep, &mut interaction, finger_pressed(2, at));
+    field_event(&minesweep, &mut interaction, finger_pressed(3, at));
+    let (_, message) = field_event(&minesweep, &mut interaction, finger_lifted(3, at));
+    assert!(message.is_none());
+    let (_, message) = field_event(&minesweep, &mut interaction, finger_lifted(2, at));
+    assert!(matches!(
+        message,
+        Some(Message::Minesweep(MinesweepMessage::AutoStep {
+            x: 1,
+            y: 1
+        }))
+    ));
+
+    // A finger which slides away is scrolling, so it takes no action
+    let from = spot_position(&minesweep, 2, 5);
+    let to = spot_position(&minesweep, 2, 7);
+    field_event(&minesweep, &mut interaction, finger_pressed(4, from));
+    field_event(
+        &minesweep,
+        &mut interaction,
+        Event::Touch(touch::Event::FingerMoved {
+            id: touch::Finger(4),
+            position: to,
+        }),
+    );
+    let (_, message) = field_event(&minesweep, &mut interaction, finger_lifted(4, to));
+    assert!(message.is_none());
+}
+
+#[test]
+fn double_clicking_a_revealed_number_autosteps_around_it() {
+    let mut minesweep = easy_game().with_preferences(Preferences {
+        double_click_autosteps: true,
+        reveal_animation: false,
+        ..Preferences::default()
+    });
+    let mut interaction = FieldInteraction::default();
+    let mut click = |minesweep: &Minesweep, x, y| {
+        let cursor = Cursor::Available(spot_position(minesweep, x, y));
+        let bounds = Rectangle::with_size(FIELD_BOUNDS);
+        let pressed = canvas::Program::update(
+            minesweep,
+            &mut interaction,
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+            bounds,
+            cursor,
+        )
+        .1;
+        let released = canvas::Program::update(
+            minesweep,
+            &mut interaction,
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)),
+            bounds,
+            cursor,
+        )
+        .1;
+
+        pressed.or(released)
+    };
+
+    // Clicking a hidden spot twice steps on it, and then on the number it revealed, which does nothing
+    for _ in 0..2 {
+        assert!(matches!(
+            click(&minesweep, 0, 1),
+            Some(Message::Minesweep(MinesweepMessage::Step { x: 0, y: 1 }))
+        ));
+        play(&mut minesweep, [step(0, 1)]);
+    }
+
+    // A double click on the revealed number autosteps around it
+    let message = click(&minesweep, 0, 1);
+    assert!(matches!(
+        message,
+        Some(Message::Minesweep(MinesweepMessage::AutoStep {
+            x: 0,
+            y: 1
+        }))
+    ));
+
+    // The double click is over, so the next click steps again
+    assert!(matches!(
+        click(&minesweep, 0, 1),
+        Some(Message::Minesweep(MinesweepMessage::Step { x: 0, y: 1 }))
+    ));
+}
+
+#[test]
+fn flagging_mode_flags_with_the_left_button_and_touch_taps() {
+    let mut minesweep = easy_game().with_preferences(Preferences {
+        reveal_animation: false,
+        ..Preferences::default()
+    });
+    let mut interaction = FieldInteraction::default();
+    let bounds = Rectangle::with_size(FIELD_BOUNDS);
+    play(&mut minesweep, [step(0, 5), Message::ToggleFlaggingMode]);
+
+    let position = spot_position(&minesweep, 0, 0);
+    let (_, message) = canvas::Program::update(
+        &minesweep,
+        &mut interaction,
+        Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+        bounds,
+        Cursor::Available(position),
+    );
+    assert!(matches!(
+        message,
+        Some(Message::Minesweep(MinesweepMessage::Flag { x: 0, y: 0 }))
+    ));
+
+    // A tap flags as well, and a long press steps instead
+    let at = spot_position(&minesweep, 1, 0);
+    field_event(&minesweep, &mut interaction, finger_pressed(0, at));
+    let (_, message) = field_event(&minesweep, &mut interaction, finger_lifted(0, at));
+    assert!(matches!(
+        message,
+        Some(Message::Minesweep(MinesweepMessage::Flag { x: 1, y: 0 }))
+    ));
+    field_event(&minesweep, &mut interaction, finger_pressed(1, at));
+    interaction.touches[0].2 -= Minesweep::LONG_PRESS_TIME;
+    let (_, message) = field_event(&minesweep, &mut interaction, finger_lifted(1, at));
+    assert!(matches!(
+        message,
+        Some(Message::Minesweep(MinesweepMessage::Step { x: 1, y: 0 }))
+    ));
+
+    // The mode stays on for the next games, until it's toggled off
+    play(
+        &mut minesweep,
+        [Message::Reset, Message::ConfirmReset(true)],
+    );
+    let at = spot_position(&minesweep, 0, 5);
+    field_event(&minesweep, &mut interaction, finger_pressed(2, at));
+    let (_, message) = field_event(&minesweep, &mut interaction, finger_lifted(2, at));
+    assert!(matches!(
+        message,
+        Some(Message::Minesweep(MinesweepMessage::Flag { x: 0, y: 5 }))
+    ));
+    play(&mut minesweep, [Message::ToggleFlaggingMode]);
+    field_event(&minesweep, &mut interaction, finger_pressed(3, at));
+    let (_, message) = field_event(&minesweep, &mut interaction, finger_lifted(3, at));
+    assert!(matches!(
+        message,
+        Some(Message::Minesweep(MinesweepMessage::Step { x: 0, y: 5 }))
+    ));
+}
+
+#[test]
+fn shift_flagging_a_number_flags_its_hidden_neighbors() {
+    let mut minesweep = easy_game().with_preferences(Preferences {
+        reveal_animation: false,
+        ..Preferences::default()
+    });
+    let mut interaction = FieldInteraction::default();
+    play(&mut minesweep, [step(0, 5)]);
+    let flags = minesweep.remaining_flags;
+
+    // Without Shift, flagging a number does nothing
+    interaction.selected = Some((0, 1));
+    let f = keyboard::Key::Character("f".into());
+    let (_, message) = field_event(&minesweep, &mut interaction, key_press(f.clone()));
+    assert!(matches!(
+        message,
+        Some(Message::Minesweep(MinesweepMessage::Flag { x: 0, y: 1 }))
+    ));
+    play(&mut minesweep, message);
+    assert_eq!(minesweep.remaining_flags, flags);
+
+    // With Shift, both mines above the 2 get flagged at once
+    interaction.modifiers = keyboard::Modifiers::SHIFT;
+    let (_, message) = field_event(&minesweep, &mut interaction, key_press(f));
+    assert!(matches!(
+        message,
+        Some(Message::Minesweep(MinesweepMessage::AutoFlag {
+            x: 0,
+            y: 1
+        }))
+    ));
+    play(&mut minesweep, message);
+    assert_eq!(minesweep.remaining_flags, flags - 2);
+    assert_eq!(minesweep.field.flagged_neighbors(0, 1), 2);
+    assert!(minesweep.assisted);
+
+    // Nothing is left to flag around it
+    play(
+        &mut minesweep,
+        [Message::Minesweep(MinesweepMessage::AutoFlag {
+            x: 0,
+            y: 1,
+        })],
+    );
+    assert_eq!(minesweep.remaining_flags, flags - 2);
+}
+
+#[test]
+fn switching_to_a_larger_board_grows_the_window() {
+    let mut minesweep = easy_game();
+    let easy_size = minesweep.desired_window_size();
+
+    // The window can't be resized before its id is known
+    assert!(minesweep.resize_target().is_none());
+
+    let window_id = window::Id::unique();
+    play(
+        &mut minesweep,
+        [
+            Message::WindowId(Some(window_id)),
+            Message::Settings(SettingsMessage::Show),
+            Message::Settings(SettingsMessage::Picked(GameDifficulty::Hard)),
+        ],
+    );
+    let settings = settings(&minesweep).clone();
+    play(
+        &mut minesweep,
+        [Message::Settings(SettingsMessage::Set(Box::new(settings)))],
+    );
+
+    assert_eq!(minesweep.game_config, GameDifficulty::HARD);
+    let hard_size = minesweep.desired_window_size();
+    assert!(hard_size.0 > easy_size.0 && hard_size.1 > easy_size.1);
+
+    // The window which is resized is the one whose id was captured, to the size of the larger board
+    let (resized_id, size) = minesweep.resize_target().expect("the window to be resized");
+    assert_eq!(resized_id, window_id);
+    assert_eq!((size.width, size.height), hard_size);
+
+    // An embedded game leaves the window to the application which owns it
+    minesweep.resizes_window = false;
+    assert!(minesweep.resize_target().is_none());
+}
+
+#[test]
+fn invalid_custom_configs_are_not_applied() {
+    let mut minesweep = easy_game();
+    let full = GameConfig {
+        width: 10,
+        height: 10,
+        mines: 100,
+        ..GameConfig::default()
+    };
+
+    play(
+        &mut minesweep,
+        [
+            Message::Settings(SettingsMessage::Show),
+            Message::Settings(SettingsMessage::Picked(GameDifficulty::Custom(full))),
+        ],
+    );
+    let draft = settings(&minesweep).clone();
+    play(
+        &mut minesweep,
+        [Message::Settings(SettingsMessage::Set(Box::new(draft)))],
+    );
+
+    // The settings stay shown, and the game is left as it was
+    assert_eq!(
+        settings(&minesweep).game_difficulty,
+        GameDifficulty::Custom(full)
+    );
+    assert_eq!(minesweep.game_config, GameDifficulty::EASY);
+    assert_eq!(full.validate(), Err(ConfigError::Mines(100)));
+}
+
+#[test]
+fn mine_density_and_suggested_mines() {
+    assert_eq!(GameDifficulty::EASY.mine_density(), 0.1);
+    assert!(GameDifficulty::HARD.mine_density() < GameConfig::MAX_PLAYABLE_DENSITY);
+
+    // The suggested mines give the requested density, while leaving both mines and empty spots
+    let density = GameDifficulty::MEDIUM.mine_density();
+    assert_eq!(GameConfig::suggested_mines(16, 16, density), 40);
+    assert_eq!(GameConfig::suggested_mines(20, 10, 0.5), 100);
+    assert_eq!(GameConfig::suggested_mines(2, 2, 0.01), 1);
+    assert_eq!(GameConfig::suggested_mines(2, 2, 1.0), 3);
+}
+
+#[test]
+fn custom_games_matching_a_standard_difficulty_are_recorded_on_it_if_enabled() {
+    let custom = |width, height, mines| {
+        GameDifficulty::Custom(GameConfig {
+            width,
+            height,
+            mines,
+            ..GameConfig::default()
+        })
+    };
+
+    let cases = [
+        (custom(10, 10, 10), Some(DifficultyLevel::Easy)),
+        (custom(16, 16, 40), Some(DifficultyLevel::Medium)),
+        (custom(30, 16, 99), Some(DifficultyLevel::Hard)),
+        (custom(30, 16, 98), None),
+        (custom(10, 10, 11), None),
+    ];
+
+    for (difficulty, level) in cases {
+        assert_eq!(difficulty.high_score_level(true), level);
+        assert_eq!(difficulty.high_score_level(false), None);
+    }
+
+    // The standard difficulties are recorded whatever the setting
+    for enabled in [true, false] {
+        assert_eq!(
+            GameDifficulty::Easy.high_score_level(enabled),
+            Some(DifficultyLevel::Easy)
+        );
+        assert_eq!(
+            GameDifficulty::Hard.high_score_level(enabled),
+            Some(DifficultyLevel::Hard)
+        );
+    }
+}
+
+#[test]
+fn games_are_counted_in_the_statistics() {
+    let mut minesweep = easy_game();
+
+    // A won game
+    play(&mut minesweep, [step(9, 9)]);
+    play(&mut minesweep, flag_all(&easy_mines()));
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: true });
+
+    // A lost game, which is only counted once even if the step on the mine is undone
+    minesweep.start_new_game();
+    minesweep.field = Minefield::new(10, 10).with_mines_at(&easy_mines());
+    play(
+        &mut minesweep,
+        [
+            step(9, 9),
+            step(3, 0),
+            Message::Minesweep(MinesweepMessage::Undo),
+        ],
+    );
+    play(&mut minesweep, flag_all(&easy_mines()));
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: true });
+
+    let stats = minesweep.stats[&DifficultyLevel::Easy];
+    assert_eq!(stats.played, 2);
+    assert_eq!(stats.won, 1);
+    assert_eq!(stats.win_rate(), Some(50.0));
+    assert_eq!(stats.current_streak, 0);
+    assert_eq!(stats.longest_streak, 1);
+    assert!(stats.best_millis.is_some());
+    assert_eq!(stats.average_millis(), stats.best_millis);
+
+    play(&mut minesweep, [Message::ResetStats]);
+    assert!(minesweep.stats.is_empty());
+}
+
+#[test]
+fn assisted_games_are_not_counted_in_the_statistics() {
+    let mut minesweep = easy_game();
+
+    // A game won after undoing a move
+    play(
+        &mut minesweep,
+        [
+            step(9, 9),
+            flag(0, 0),
+            Message::Minesweep(MinesweepMessage::Undo),
+        ],
+    );
+    play(&mut minesweep, flag_all(&easy_mines()));
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: true });
+    assert!(minesweep.stats.is_empty());
+
+    // A game won after peeking at the mines
+    minesweep.start_new_game();
+    minesweep.field = Minefield::new(10, 10).with_mines_at(&easy_mines());
+    play(
+        &mut minesweep,
+        [Message::TogglePeek, step(9, 9), Message::TogglePeek],
+    );
+    play(&mut minesweep, flag_all(&easy_mines()));
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: true });
+    assert!(minesweep.stats.is_empty());
+}
+
+#[test]
+fn replays_and_boards_are_kept_next_to_the_configs() {
+    let dir = std::env::temp_dir().join(format!("minesweep-replays-{}", std::process::id()));
+    let mut minesweep = easy_game();
+    minesweep.persistence_path = dir.join("configs.json");
+    minesweep.preferences.save_replays = true;
+
+    play(&mut minesweep, [step(9, 9)]);
+    play(&mut minesweep, flag_all(&easy_mines()));
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: true });
+
+    // The replay is only written in the background, but the high score already refers to its file
+    let path = minesweep.saved_replay.clone().expect("a saved replay");
+    assert_eq!(path.parent(), Some(dir.join(REPLAYS_DIR).as_path()));
+    assert_eq!(
+        minesweep.high_scores(DifficultyLevel::Easy)[0].replay,
+        Some(path)
+    );
+
+    // Boards are saved next to them
+    assert_eq!(minesweep.boards_dir(), dir.join(BOARDS_DIR));
+}
+
+#[test]
+fn persisted_high_scores_are_kept_to_the_configured_number() {
+    let dir = std::env::temp_dir().join(format!("minesweep-scores-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("configs.json");
+
+    // Configs saved by older versions keep the default number of high scores
+    let mut persistence = serde_json::to_value(GamePersistence::default()).unwrap();
+    persistence
+        .as_object_mut()
+        .unwrap()
+        .remove("max_high_scores");
+    std::fs::write(&path, persistence.to_string()).unwrap();
+    let loaded = Minesweep::load_persistence_from(&path).unwrap().unwrap();
+    assert_eq!(
+        loaded.max_high_scores,
+        Minesweep::DEFAULT_HIGH_SCORES_PER_LEVEL
+    );
+
+    // Fewer high scores are kept, best first, if the configs say so
+    let mut minesweep = easy_game();
+    for seconds in [9, 3, 5] {
+        minesweep.insert_high_score(
+            DifficultyLevel::Easy,
+            false,
+            Score {
+                name: String::new(),
+                seconds,
+                millis: None,
+                date: None,
+                points: None,
+                replay: None,
+            },
+        );
+    }
+    let persistence = GamePersistence {
+        max_high_scores: 2,
+        ..minesweep.game_persistence()
+    };
+    Minesweep::save_persistence_to(&path, &persistence, Minesweep::next_save_generation()).unwrap();
+    let loaded = Minesweep::load_persistence_from(&path).unwrap().unwrap();
+    let seconds: Vec<u64> = loaded.high_scores[&DifficultyLevel::Easy]
+        .iter()
+        .map(|score| score.seconds)
+        .collect();
+    assert_eq!(seconds, [3, 5]);
+
+    // A number of high scores too large to be shown is brought down
+    let persistence = GamePersistence {
+        max_high_scores: usize::MAX,
+        ..persistence
+    };
+    Minesweep::save_persistence_to(&path, &persistence, Minesweep::next_save_generation()).unwrap();
+    let loaded = Minesweep::load_persistence_from(&path).unwrap().unwrap();
+    assert_eq!(loaded.max_high_scores, Minesweep::MAX_HIGH_SCORES_PER_LEVEL);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn numbers_differ_per_count_in_every_theme() {
+    for theme in ColorTheme::ALL {
+        let palette = theme.field_palette();
+        for count in 1..8 {
+            assert_ne!(palette.numbers[count], palette.revealed, "{theme}: {count}");
+            for other in count + 1..=8 {
+                assert_ne!(
+                    palette.numbers[count], palette.numbers[other],
+                    "{theme}: {count} and {other}"
+                );
+            }
+        }
+    }
+
+    // Preferences saved before themes existed get the default one
+    let preferences: Preferences = serde_json::from_str(r#"{"flag_icons":true}"#).unwrap();
+    assert_eq!(preferences.color_theme, ColorTheme::Dark);
+    assert!(preferences.flag_icons);
+}
+
+#[test]
+fn colorblind_glyphs_draw_flags_and_mines_in_the_text_color() {
+    let minesweep = easy_game();
+    assert_ne!(minesweep.flag_color(true), minesweep.flag_color(false));
+
+    let minesweep = minesweep.with_preferences(Preferences {
+        colorblind_glyphs: true,
+        ..Preferences::default()
+    });
+    let text = minesweep.preferences.color_theme.field_palette().text;
+    assert_eq!(minesweep.flag_color(true), text);
+    assert_eq!(minesweep.flag_color(false), text);
+    assert_eq!(minesweep.mine_color(), text);
+    assert_eq!(minesweep.exploded_mine_color(), text);
+
+    // The choice is persisted with the other preferences, and is off for those saved before it existed
+    let json = serde_json::to_string(&minesweep.preferences).unwrap();
+    let preferences: Preferences = serde_json::from_str(&json).unwrap();
+    assert!(preferences.colorblind_glyphs);
+    let preferences: Preferences = serde_json::from_str(r#"{"flag_icons":true}"#).unwrap();
+    assert!(!preferences.colorblind_glyphs);
+}
+
+#[test]
+fn ctrl_scrolling_zooms_the_board() {
+    let mut minesweep = easy_game();
+    let mut interaction = FieldInteraction::default();
+    let bounds = Rectangle::with_size(FIELD_BOUNDS);
+    let cursor = Cursor::Available(bounds.center());
+    let scroll = |y| {
+        Event::Mouse(mouse::Event::WheelScrolled {
+            delta: mouse::ScrollDelta::Lines { x: 0.0, y },
+        })
+    };
+
+    // Without Ctrl, scrolling is left to the scrollable around the minefield
+    let (status, message) =
+        canvas::Program::update(&minesweep, &mut interaction, scroll(1.0), bounds, cursor);
+    assert_eq!(status, event::Status::Ignored);
+    assert!(message.is_none());
+
+    interaction.modifiers = keyboard::Modifiers::CTRL;
+    let (status, message) =
+        canvas::Program::update(&minesweep, &mut interaction, scroll(1.0), bounds, cursor);
+    assert_eq!(status, event::Status::Captured);
+    assert!(matches!(message, Some(Message::Zoom { zoom_in: true })));
+
+    let size = minesweep.desired_field_size();
+    play(&mut minesweep, [Message::Zoom { zoom_in: true }]);
+    assert!(minesweep.desired_field_size().0 > size.0);
+
+    // Spots are still found where they are drawn
+    let position = spot_position(&minesweep, 4, 7);
+    assert_eq!(minesweep.spot_at(bounds, position), Some((4, 7)));
+
+    // Zooming is kept to a legible range
+    play(
+        &mut minesweep,
+        (0..50).map(|_| Message::Zoom { zoom_in: false }),
+    );
+    assert_eq!(minesweep.spot_size, Minesweep::MIN_SPOT_SIZE);
+    play(
+        &mut minesweep,
+        (0..50).map(|_| Message::Zoom { zoom_in: true }),
+    );
+    assert_eq!(minesweep.spot_size, Minesweep::MAX_SPOT_SIZE);
+}
+
+#[test]
+fn peeking_at_the_mines_makes_the_game_assisted() {
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [step(0, 5), Message::TogglePeek]);
+    assert!(minesweep.peek);
+    assert!(minesweep.assisted);
+    assert!(matches!(minesweep.game_state, GameState::Running(_)));
+
+    play(&mut minesweep, [Message::TogglePeek]);
+    assert!(!minesweep.peek);
+    assert!(minesweep.assisted);
+
+    // Once the game is over the mines are shown anyway, so peeking is left as it was, until a new game starts
+    play(&mut minesweep, [Message::TogglePeek, step(0, 0)]);
+    play(&mut minesweep, [Message::TogglePeek]);
+    assert!(minesweep.peek);
+    play(&mut minesweep, [Message::Reset]);
+    assert!(!minesweep.peek);
+    assert!(!minesweep.assisted);
+}
+
+#[test]
+fn showing_the_mine_probabilities_makes_the_games_assisted() {
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [step(0, 5), Message::ToggleProbabilities]);
+    assert!(minesweep.show_probabilities);
+    assert!(minesweep.assisted);
+
+    // The overlay stays on for the next games, which are assisted from the start
+    play(
+        &mut minesweep,
+        [Message::Reset, Message::ConfirmReset(true)],
+    );
+    assert!(minesweep.show_probabilities);
+    assert!(minesweep.assisted);
+
+    play(&mut minesweep, [Message::ToggleProbabilities]);
+    assert!(!minesweep.show_probabilities);
+    assert!(minesweep.assisted);
+    play(&mut minesweep, [Message::Reset]);
+    assert!(!minesweep.assisted);
+}
+
+#[test]
+fn the_auto_solver_makes_the_certain_moves_until_it_must_guess() {
+    // The first step is always a guess
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [Message::AutoSolve]);
+    assert!(!minesweep.auto_solving);
+    assert!(minesweep.announcement.is_some());
+    assert_eq!(minesweep.game_state(), GameState::Ready);
+
+    // Once the empty spots are revealed, the mines are flagged one on each tick
+    play(&mut minesweep, [step(0, 5), Message::AutoSolve]);
+    assert!(minesweep.auto_solving);
+    assert!(minesweep.assisted);
+    assert_eq!(minesweep.field.flagged_spots().count(), 1);
+
+    play(
+        &mut minesweep,
+        (0..20).map(|_| Message::Tick(Instant::now())),
+    );
+    assert!(!minesweep.auto_solving);
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: true });
+    assert!(minesweep.high_score_entry().is_none());
+}
+
+#[test]
+fn mines_pop_in_around_the_exploded_one() {
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [step(0, 5), step(3, 0)]);
+    let animation = minesweep.explosion_animation.clone().unwrap();
+    assert_eq!(animation.origin, (3, 0));
+    assert_eq!(animation.mines.len(), easy_mines().len());
+    assert_eq!(animation.mines[0], ((3, 0), Duration::ZERO));
+
+    // The mines furthest from the exploded one are the last to pop in, growing past their final size and settling
+    let start = animation.start;
+    let (_, last) = animation
+        .mines
+        .iter()
+        .find(|(coords, _)| *coords == (GameDifficulty::EASY.width - 1, 0))
+        .unwrap();
+    assert_eq!(*last, animation.duration - animation.pop);
+    assert_eq!(animation.mine_scale(*last, start), 0.0);
+    assert!(animation.mine_scale(*last, start + *last + animation.pop / 2) > 1.0);
+    assert_eq!(animation.mine_scale(*last, start + animation.duration), 1.0);
+
+    play(
+        &mut minesweep,
+        [Message::AnimationFrame(start + animation.duration)],
+    );
+    assert!(minesweep.explosion_animation.is_none());
+
+    // Without animations, the mines are shown at once
+    let mut minesweep = easy_game().with_preferences(Preferences {
+        animation_speed: AnimationSpeed::Off,
+        ..Preferences::default()
+    });
+    play(&mut minesweep, [step(0, 5), step(3, 0)]);
+    assert!(minesweep.explosion_animation.is_none());
+}
+
+#[test]
+fn sound_effects_are_only_played_once_turned_on() {
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [step(0, 5), flag(0, 0)]);
+    assert!(minesweep.sound.is_none());
+
+    // Without an audio device, the game goes on silently
+    let mut minesweep = easy_game().with_preferences(Preferences {
+        sound_effects: true,
+        ..Preferences::default()
+    });
+    play(&mut minesweep, [step(0, 5), flag(0, 0)]);
+    assert!(minesweep.sound.is_some());
+    play(&mut minesweep, [step(1, 0)]);
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: false });
+}
+
+#[test]
+fn strict_flags_keep_the_flag_counter_from_going_below_zero() {
+    let mut minesweep = easy_game().with_preferences(Preferences {
+        announcements: true,
+        reveal_animation: false,
+        ..Preferences::default()
+    });
+    play(
+        &mut minesweep,
+        [
+            Message::Settings(SettingsMessage::Show),
+            Message::Settings(SettingsMessage::StrictFlags(true)),
+        ],
+    );
+    let settings = settings(&minesweep).clone();
+    play(
+        &mut minesweep,
+        [Message::Settings(SettingsMessage::Set(Box::new(settings)))],
+    );
+    assert!(minesweep.game_config.strict_flags);
+
+    // All the flags are placed on the bottom row, so no other spot can be flagged
+    minesweep.field = Minefield::new(10, 10)
+        .with_mines_at(&easy_mines())
+        .with_strict_flags(true);
+    play(&mut minesweep, (0..10).map(|x| flag(x, 9)));
+    assert_eq!(minesweep.remaining_flags(), 0);
+    play(&mut minesweep, [flag(0, 0)]);
+    assert_eq!(minesweep.remaining_flags(), 0);
+    assert_eq!(
+        minesweep.announcement.as_deref(),
+        Some("No flags left, remove one first")
+    );
+
+    // Removing a flag makes room for another one
+    play(&mut minesweep, [flag(0, 9), flag(0, 0)]);
+    assert_eq!(minesweep.remaining_flags(), 0);
+    assert_eq!(
+        minesweep.field.spot(0, 0).map(|spot| spot.state),
+        Some(SpotState::FlaggedMine)
+    );
+
+    // Game configs saved before strict flags existed allow any number of flags
+    let config: GameConfig =
+        serde_json::from_str(r#"{"width":10,"height":10,"mines":10}"#).unwrap();
+    assert!(!config.strict_flags);
+}
+
+#[test]
+fn safe_autosteps_are_refused_around_wrong_flags() {
+    let mut minesweep = easy_game();
+    play(
+        &mut minesweep,
+        [
+            Message::Settings(SettingsMessage::Show),
+            Message::Settings(SettingsMessage::SafeChord(true)),
+        ],
+    );
+    let settings = settings(&minesweep).clone();
+    play(
+        &mut minesweep,
+        [Message::Settings(SettingsMessage::Set(Box::new(settings)))],
+    );
+    assert!(minesweep.game_config.safe_chord);
+    assert_eq!(minesweep.game_difficulty(), GameDifficulty::Easy);
+
+    // Flag one of the mines above the 2, and a spot below it which is empty
+    minesweep.field = Minefield::new(10, 10)
+        .with_mines_at(&easy_mines())
+        .with_safe_chord(true);
+    play(&mut minesweep, [step(0, 1), flag(0, 0), flag(1, 2)]);
+    play(
+        &mut minesweep,
+        [Message::Minesweep(MinesweepMessage::AutoStep {
+            x: 0,
+            y: 1,
+        })],
+    );
+    assert!(matches!(minesweep.game_state, GameState::Running(_)));
+    assert!(minesweep.assisted);
+
+    // Classic autosteps only count the flags
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [step(0, 1), flag(0, 0), flag(1, 2)]);
+    play(
+        &mut minesweep,
+        [Message::Minesweep(MinesweepMessage::AutoStep {
+            x: 0,
+            y: 1,
+        })],
+    );
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: false });
+
+    // Game configs saved before safe autosteps existed keep the classic ones
+    let config: GameConfig =
+        serde_json::from_str(r#"{"width":10,"height":10,"mines":10}"#).unwrap();
+    assert!(!config.safe_chord);
+}
+
+#[test]
+fn the_timer_can_start_on_the_first_reveal_instead_of_the_first_flag() {
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [flag(0, 0)]);
+    assert!(matches!(minesweep.game_state, GameState::Running(_)));
+
+    // Flags placed before the first reveal are kept, without starting the game
+    let mut minesweep = easy_game().with_preferences(Preferences {
+        timer_start: TimerStart::FirstReveal,
+        reveal_animation: false,
+        ..Preferences::default()
+    });
+    play(&mut minesweep, [flag(0, 0), flag(1, 0)]);
+    assert_eq!(minesweep.game_state, GameState::Ready);
+    assert_eq!(minesweep.remaining_flags(), 8);
+
+    play(&mut minesweep, [step(0, 5)]);
+    assert!(matches!(minesweep.game_state, GameState::Running(_)));
+    assert_eq!(minesweep.field.flagged_spots().count(), 2);
+
+    // The setting is kept along with the other preferences
+    let preferences: Preferences =
+        serde_json::from_str(r#"{"timer_start":"FirstReveal"}"#).unwrap();
+    assert_eq!(preferences.timer_start, TimerStart::FirstReveal);
+    let preferences: Preferences = serde_json::from_str("{}").unwrap();
+    assert_eq!(preferences.timer_start, TimerStart::FirstAction);
+}
+
+#[test]
+fn steps_not_taken_are_explained_to_the_player() {
+    let mut minesweep = easy_game().with_preferences(Preferences {
+        announcements: true,
+        reveal_animation: false,
+        ..Preferences::default()
+    });
+    let autostep = |x, y| Message::Minesweep(MinesweepMessage::AutoStep { x, y });
+
+    // The 2 at the left of the second row is next to two mines
+    play(&mut minesweep, [step(0, 1), autostep(0, 1)]);
+    assert_eq!(
+        minesweep.announcement.as_deref(),
+        Some("Place more flags first, 0 of 2 around this spot")
+    );
+
+    play(&mut minesweep, [flag(0, 0), step(0, 0)]);
+    assert_eq!(
+        minesweep.announcement.as_deref(),
+        Some("The spot is flagged, remove the flag first")
+    );
+
+    // Steps on revealed spots leave the announcement as it was
+    play(&mut minesweep, [step(0, 1)]);
+    assert_eq!(
+        minesweep.announcement.as_deref(),
+        Some("The spot is flagged, remove the flag first")
+    );
+}
+
+#[test]
+fn the_new_board_button_face_follows_the_game() {
+    let mut minesweep = easy_game();
+    assert_eq!(minesweep.face_char(), Minesweep::PLAYING_FACE_CHAR);
+
+    play(&mut minesweep, [step(0, 0)]);
+    assert_eq!(minesweep.face_char(), Minesweep::LOST_FACE_CHAR);
+
+    let mut minesweep = easy_game().with_preferences(Preferences {
+        reveal_animation: false,
+        ..Preferences::default()
+    });
+    play(&mut minesweep, [step(0, 5)]);
+    assert_eq!(minesweep.face_char(), Minesweep::PLAYING_FACE_CHAR);
+    play(&mut minesweep, flag_all(&easy_mines()));
+    assert_eq!(minesweep.face_char(), Minesweep::WON_FACE_CHAR);
+}
+
+#[test]
+fn games_can_be_won_by_revealing_all_the_empty_spots() {
+    // Classic games are only won once all the mines are flagged too
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [step(0, 5)]);
+    assert_eq!(minesweep.field().remaining_safe_cells(), 0);
+    assert!(matches!(minesweep.game_state, GameState::Running(_)));
+    play(&mut minesweep, flag_all(&easy_mines()));
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: true });
+
+    let mut minesweep = easy_game();
+    play(
+        &mut minesweep,
+        [
+            Message::Settings(SettingsMessage::Show),
+            Message::Settings(SettingsMessage::WinOnAllRevealed(true)),
+        ],
+    );
+    let settings = settings(&minesweep).clone();
+    play(
+        &mut minesweep,
+        [Message::Settings(SettingsMessage::Set(Box::new(settings)))],
+    );
+    assert!(minesweep.game_config.win_on_all_revealed);
+    assert_eq!(minesweep.game_difficulty(), GameDifficulty::Easy);
+
+    // Flagging all the mines still wins
+    minesweep.field = Minefield::new(10, 10)
+        .with_mines_at(&easy_mines())
+        .with_win_on_all_revealed(true);
+    play(&mut minesweep, [step(0, 5)]);
+    play(&mut minesweep, flag_all(&easy_mines()));
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: true });
+
+    // Revealing all the empty spots wins right away, and the mines left are flagged for show
+    minesweep.field = Minefield::new(10, 10)
+        .with_mines_at(&easy_mines())
+        .with_win_on_all_revealed(true);
+    minesweep.game_state = GameState::Ready;
+    minesweep.remaining_flags = 10;
+    play(&mut minesweep, [flag(0, 0), step(0, 5)]);
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: true });
+    assert_eq!(minesweep.remaining_flags(), 0);
+    assert!(easy_mines()
+        .iter()
+        .all(|&(x, y)| minesweep.field().spot(x, y).unwrap().state == SpotState::FlaggedMine));
+
+    // The mines left can be kept unflagged instead
+    minesweep.preferences.flag_mines_on_win = false;
+    minesweep.field = Minefield::new(10, 10)
+        .with_mines_at(&easy_mines())
+        .with_win_on_all_revealed(true);
+    minesweep.game_state = GameState::Ready;
+    minesweep.remaining_flags = 10;
+    play(&mut minesweep, [flag(0, 0), step(0, 5)]);
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: true });
+    assert_eq!(minesweep.remaining_flags(), 9);
+    assert_eq!(
+        minesweep.field().spot(1, 0).unwrap().state,
+        SpotState::HiddenMine
+    );
+
+    // Game configs saved before this win existed keep the classic one
+    let config: GameConfig =
+        serde_json::from_str(r#"{"width":10,"height":10,"mines":10}"#).unwrap();
+    assert!(!config.win_on_all_revealed);
+}
+
+#[test]
+fn countdown_games_are_lost_when_the_time_runs_out() {
+    let mut minesweep = easy_game();
+    play(
+        &mut minesweep,
+        [
+            Message::Settings(SettingsMessage::Show),
+            Message::Settings(SettingsMessage::Countdown(true)),
+        ],
+    );
+    let settings = settings(&minesweep).clone();
+    play(
+        &mut minesweep,
+        [Message::Settings(SettingsMessage::Set(Box::new(settings)))],
+    );
+    let limit = GameDifficulty::Easy.countdown_limit();
+    assert_eq!(minesweep.game_config.mode, GameMode::Countdown { limit });
+    assert_eq!(minesweep.game_difficulty(), GameDifficulty::Easy);
+    assert_eq!(minesweep.time_left(), Some(limit));
+
+    minesweep.field = Minefield::new(10, 10).with_mines_at(&easy_mines());
+    play(&mut minesweep, [step(0, 5)]);
+    let GameState::Running(start) = minesweep.game_state else {
+        panic!("the game should be running");
+    };
+
+    play(&mut minesweep, [Message::Tick(start + limit / 2)]);
+    assert!(matches!(minesweep.game_state, GameState::Running(_)));
+    assert_eq!(minesweep.time_left(), Some(limit / 2));
+
+    play(&mut minesweep, [Message::Tick(start + limit)]);
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: false });
+    assert_eq!(minesweep.time_left(), Some(Duration::ZERO));
+
+    // The game can't go on by undoing a move, and isn't counted in the statistics
+    play(&mut minesweep, [Message::Minesweep(MinesweepMessage::Undo)]);
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: false });
+    assert!(minesweep.stats.is_empty());
+
+    // Game configs saved before countdown games existed are classic ones
+    let config: GameConfig =
+        serde_json::from_str(r#"{"width":10,"height":10,"mines":10}"#).unwrap();
+    assert_eq!(config, GameDifficulty::EASY);
+}
+
+#[test]
+fn corrupt_configs_are_backed_up_and_reported() {
+    let dir = std::env::temp_dir().join(format!("minesweep-corrupt-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("configs.json");
+
+    // A missing file is not an error, the default configs are used
+    assert!(Minesweep::load_persistence_from(&path).unwrap().is_none());
+
+    std::fs::write(&path, "{ not json").unwrap();
+    let error = Minesweep::load_persistence_from(&path).unwrap_err();
+    assert!(error.contains("backed up"));
+    assert_eq!(
+        std::fs::read(Minesweep::backup_path(&path)).unwrap(),
+        b"{ not json"
+    );
+
+    // The error is shown in a banner until it's dismissed, or it's been shown long enough
+    let mut minesweep = easy_game();
+    play(
+        &mut minesweep,
+        [Message::Persistence(PersistenceMessage::Error(
+            error.clone(),
+        ))],
+    );
+    let (shown, since) = minesweep.persistence_error.clone().unwrap();
+    assert_eq!(shown, error);
+
+    play(
+        &mut minesweep,
+        [Message::Persistence(PersistenceMessage::ExpireError(since))],
+    );
+    assert!(minesweep.persistence_error.is_some());
+    play(
+        &mut minesweep,
+        [Message::Persistence(PersistenceMessage::ExpireError(
+            since + Minesweep::PERSISTENCE_ERROR_TIME,
+        ))],
+    );
+    assert!(minesweep.persistence_error.is_none());
+
+    play(
+        &mut minesweep,
+        [
+            Message::Persistence(PersistenceMessage::Error(error)),
+            Message::Persistence(PersistenceMessage::DismissError),
+        ],
+    );
+    assert!(minesweep.persistence_error.is_none());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn replays_can_be_paused_stepped_through_and_sped_up() {
+    let dir = std::env::temp_dir().join(format!("minesweep-playback-{}", std::process::id()));
+    let replay = Replay {
+        header: ReplayHeader {
+            version: REPLAY_VERSION,
+            game_config: GameDifficulty::EASY,
+            custom_game: false,
+            seconds: 3,
+            date: 0,
+            is_won: false,
+            auto_step_on_flag: false,
+            orthogonal_neighbors: false,
+            question_marks: false,
+        },
+        mines: easy_mines(),
+        events: vec![
+            ReplayEvent {
+                millis: 0,
+                message: MinesweepMessage::Step { x: 0, y: 5 },
+            },
+            ReplayEvent {
+                millis: 2000,
+                message: MinesweepMessage::Flag { x: 0, y: 0 },
+            },
+            ReplayEvent {
+                millis: 3000,
+                message: MinesweepMessage::Flag { x: 1, y: 0 },
+            },
+        ],
+    };
+    let path = replay.new_file_path(&dir, None);
+    replay.save_to(&path).unwrap();
+
+    let mut minesweep = easy_game();
+    let start = Instant::now();
+    play(
+        &mut minesweep,
+        [Message::PlayReplay(path), Message::ReplayFrame(start)],
+    );
+    assert!(matches!(minesweep.game_state(), GameState::Running(_)));
+
+    // The game timer follows the replay, which doesn't move while it's paused
+    play(
+        &mut minesweep,
+        [
+            Message::TogglePause,
+            Message::ReplayFrame(start + Duration::from_secs(5)),
+        ],
+    );
+    assert!(minesweep.playback.as_ref().unwrap().paused);
+    assert_eq!(minesweep.elapsed_seconds, Duration::ZERO);
+    assert_eq!(minesweep.field.flagged_spots().count(), 0);
+
+    play(&mut minesweep, [Message::Playback(PlaybackMessage::Step)]);
+    assert_eq!(minesweep.elapsed_seconds, Duration::from_secs(2));
+    assert_eq!(minesweep.field.flagged_spots().count(), 1);
+
+    // At twice the speed, the last move comes after half a second instead of a second
+    let resumed = start + Duration::from_secs(10);
+    play(
+        &mut minesweep,
+        [
+            Message::Playback(PlaybackMessage::CycleSpeed),
+            Message::Playback(PlaybackMessage::TogglePause),
+            Message::ReplayFrame(resumed),
+            Message::ReplayFrame(resumed + Duration::from_millis(500)),
+        ],
+    );
+    assert_eq!(minesweep.playback.as_ref().unwrap().speed(), 2.0);
+    assert_eq!(minesweep.field.flagged_spots().count(), 2);
+    assert!(minesweep.playback.as_ref().unwrap().is_finished());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn each_profile_has_its_own_high_scores_and_stats() {
+    let mut minesweep = easy_game();
+    assert_eq!(minesweep.active_profile, Minesweep::DEFAULT_PROFILE);
+    play(&mut minesweep, [step(9, 9)]);
+    play(&mut minesweep, flag_all(&easy_mines()));
+    play(
+        &mut minesweep,
+        [Message::HighScore(RecordHighScore::RecordName)],
+    );
+
+    play(
+        &mut minesweep,
+        [
+            Message::Profile(ProfileMessage::NameChanged("Ada".to_string())),
+            Message::Profile(ProfileMessage::Add),
+        ],
+    );
+    assert_eq!(minesweep.active_profile, "Ada");
+    assert!(minesweep.high_scores(DifficultyLevel::Easy).is_empty());
+    assert!(minesweep.stats.is_empty());
+
+    for seconds in [20, 30] {
+        minesweep.insert_high_score(
+            DifficultyLevel::Easy,
+            false,
+            Score {
+                name: "Ada".to_string(),
+                seconds,
+                millis: None,
+                date: None,
+                points: None,
+                replay: None,
+            },
+        );
+    }
+    assert_eq!(minesweep.high_scores(DifficultyLevel::Easy).len(), 2);
+
+    play(
+        &mut minesweep,
+        [Message::Profile(ProfileMessage::Picked(
+            Minesweep::DEFAULT_PROFILE.to_string(),
+        ))],
+    );
+    assert_eq!(minesweep.high_scores(DifficultyLevel::Easy).len(), 1);
+    assert_eq!(minesweep.stats[&DifficultyLevel::Easy].won, 1);
+    assert_eq!(
+        minesweep.profile_names(),
+        ["Ada", Minesweep::DEFAULT_PROFILE]
+    );
+
+    // Deleting the active profile makes another one active, and the last one is kept
+    play(
+        &mut minesweep,
+        [
+            Message::Profile(ProfileMessage::Delete),
+            Message::Profile(ProfileMessage::Delete),
+        ],
+    );
+    assert_eq!(minesweep.active_profile, "Ada");
+    assert_eq!(minesweep.high_scores(DifficultyLevel::Easy).len(), 2);
+    assert!(minesweep.profiles.is_empty());
+
+    // Configs saved by older versions have the high scores of the default profile
+    let mut persistence = serde_json::to_value(GamePersistence::default()).unwrap();
+    let object = persistence.as_object_mut().unwrap();
+    object.remove("active_profile");
+    object.remove("profiles");
+    let loaded: GamePersistence = serde_json::from_value(persistence).unwrap();
+    assert_eq!(loaded.active_profile, Minesweep::DEFAULT_PROFILE);
+    assert!(loaded.profiles.is_empty());
+}
+
+#[test]
+fn finished_games_are_summed_up_over_the_board() {
+    let mut minesweep = easy_game();
+    assert!(!minesweep.shows_game_summary());
+
+    // A lost game is summed up until the summary is dismissed with Escape, or the step on the mine is undone
+    play(&mut minesweep, [step(9, 9), step(0, 0)]);
+    assert!(minesweep.shows_game_summary());
+    play(&mut minesweep, [Message::CloseView]);
+    assert!(!minesweep.shows_game_summary());
+    assert!(matches!(minesweep.main_view, MainViewContent::Game));
+
+    play(
+        &mut minesweep,
+        [step(1, 0), Message::Minesweep(MinesweepMessage::Undo)],
+    );
+    assert!(matches!(minesweep.game_state(), GameState::Running(_)));
+    assert!(!minesweep.shows_game_summary());
+
+    // A new board starts without the summary
+    play(&mut minesweep, [step(2, 0), Message::Reset]);
+    assert!(!minesweep.shows_game_summary());
+
+    // A win which is a high score is entered first, and summed up once the player gets back to the game from the high
+    // scores
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [step(9, 9)]);
+    play(&mut minesweep, flag_all(&easy_mines()));
+    assert!(minesweep.high_score_entry().is_some());
+    play(
+        &mut minesweep,
+        [
+            Message::HighScore(RecordHighScore::RecordName),
+            Message::HighScores,
+        ],
+    );
+    assert!(matches!(minesweep.main_view, MainViewContent::Game));
+    assert!(minesweep.shows_game_summary());
+}
+
+#[test]
+fn escape_closes_the_view_shown_over_the_game() {
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [step(0, 5)]);
+
+    for open in [
+        Message::Info,
+        Message::HighScores,
+        Message::Stats,
+        Message::Settings(SettingsMessage::Show),
+        Message::Reset,
+    ] {
+        play(&mut minesweep, [open]);
+        assert!(!matches!(minesweep.main_view, MainViewContent::Game));
+
+        play(&mut minesweep, [Message::CloseView]);
+        assert!(matches!(minesweep.main_view, MainViewContent::Game));
+        assert!(matches!(minesweep.game_state(), GameState::Running(_)));
+    }
+
+    // Nothing happens over the game itself
+    play(&mut minesweep, [Message::CloseView]);
+    assert!(matches!(minesweep.main_view, MainViewContent::Game));
+}
+
+#[test]
+fn placing_all_the_flags_without_clearing_the_board_is_pointed_out() {
+    let mut minesweep = easy_game();
+    play(&mut minesweep, flag_all(&easy_mines()[..9]));
+    assert!(!minesweep.all_flags_placed_uncleared());
+
+    // The last flag is put on an empty spot instead of the last mine
+    play(&mut minesweep, [flag(9, 9)]);
+    assert!(minesweep.all_flags_placed_uncleared());
+
+    // Spots left to reveal also keep the board from being cleared
+    play(&mut minesweep, [flag(9, 9), flag(9, 0)]);
+    assert!(minesweep.all_flags_placed_uncleared());
+
+    play(&mut minesweep, [step(0, 5)]);
+    assert!(!minesweep.all_flags_placed_uncleared());
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: true });
+}
+
+fn tutorial_step(minesweep: &Minesweep) -> Option<TutorialStep> {
+    match &minesweep.main_view {
+        MainViewContent::Tutorial(tutorial) => Some(tutorial.step()),
+        _ => None,
+    }
+}
+
+#[test]
+fn the_tutorial_walks_through_a_game_on_its_own_board() {
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [Message::Tutorial(TutorialMessage::Show)]);
+    assert_eq!(tutorial_step(&minesweep), Some(TutorialStep::Welcome));
+    assert_eq!(minesweep.field().width(), Tutorial::WIDTH);
+
+    // Only the move which is asked for goes on to the next part
+    play(
+        &mut minesweep,
+        [Message::Tutorial(TutorialMessage::Start), step(4, 4)],
+    );
+    assert_eq!(tutorial_step(&minesweep), Some(TutorialStep::Step));
+    play(&mut minesweep, [step(0, 0)]);
+    assert_eq!(tutorial_step(&minesweep), Some(TutorialStep::Flag));
+    play(&mut minesweep, [flag(2, 3)]);
+    assert_eq!(tutorial_step(&minesweep), Some(TutorialStep::AutoStep));
+    play(
+        &mut minesweep,
+        [Message::Minesweep(MinesweepMessage::AutoStep {
+            x: 1,
+            y: 3,
+        })],
+    );
+    assert_eq!(tutorial_step(&minesweep), Some(TutorialStep::Done));
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: true });
+    assert!(minesweep.high_score_entry().is_none());
+    assert!(minesweep.stats.is_empty());
+
+    // The player's own board is back once the tutorial is left
+    play(&mut minesweep, [Message::Tutorial(TutorialMessage::Close)]);
+    assert_eq!(tutorial_step(&minesweep), None);
+    assert_eq!(minesweep.game_config, GameDifficulty::EASY);
+    assert_eq!(minesweep.game_state(), GameState::Ready);
+}
+
+#[test]
+fn the_tutorial_can_be_tried_again_after_a_step_on_its_mine() {
+    let mut minesweep = easy_game();
+    play(
+        &mut minesweep,
+        [
+            Message::Tutorial(TutorialMessage::Show),
+            Message::Tutorial(TutorialMessage::Start),
+            step(0, 0),
+            step(Tutorial::MINE.0, Tutorial::MINE.1),
+        ],
+    );
+    assert_eq!(tutorial_step(&minesweep), Some(TutorialStep::Exploded));
+
+    play(&mut minesweep, [Message::Tutorial(TutorialMessage::Retry)]);
+    assert_eq!(tutorial_step(&minesweep), Some(TutorialStep::Step));
+    assert_eq!(minesweep.game_state(), GameState::Ready);
+    assert_eq!(minesweep.field().revealed_spots().count(), 0);
+
+    // Esc skips the tutorial
+    play(&mut minesweep, [Message::CloseView]);
+    assert!(matches!(minesweep.main_view, MainViewContent::Game));
+}
+
+#[test]
+fn games_played_are_reported_to_an_embedding_application() {
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [step(0, 5), step(0, 0)]);
+
+    let events = minesweep.take_events();
+    assert!(matches!(
+        events[..],
+        [GameEvent::Lost { game_config, .. }] if game_config == GameDifficulty::EASY
+    ));
+    assert!(minesweep.take_events().is_empty());
+
+    // A new high score is reported once it's named
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [step(0, 5)]);
+    play(&mut minesweep, flag_all(&easy_mines()));
+    play(
+        &mut minesweep,
+        [
+            Message::HighScore(RecordHighScore::NameChanged("Ada".to_string())),
+            Message::HighScore(RecordHighScore::RecordName),
+        ],
+    );
+
+    let events = minesweep.take_events();
+    assert!(matches!(events[0], GameEvent::Won { .. }));
+    assert!(matches!(
+        &events[1..],
+        [GameEvent::HighScore { difficulty_level: DifficultyLevel::Easy, rank: 0, name, .. }] if name == "Ada"
+    ));
+}
+
+#[test]
+fn the_3bv_and_clicks_of_a_finished_game_are_counted() {
+    let mut minesweep = easy_game();
+    play(&mut minesweep, [step(0, 5), flag(0, 0)]);
+    assert_eq!(minesweep.clicks, 2);
+    assert_eq!(minesweep.board_3bv, None);
+
+    // Undoing doesn't count as a click
+    play(&mut minesweep, [Message::Minesweep(MinesweepMessage::Undo)]);
+    play(&mut minesweep, flag_all(&easy_mines()));
+    assert_eq!(minesweep.game_state(), GameState::Stopped { is_won: true });
+    assert_eq!(minesweep.clicks, 12);
+
+    // A single step opens up the whole board below its row of mines
+    assert_eq!(minesweep.board_3bv, Some(1));
+    assert!(minesweep.efficiency().is_some());
+
+    play(&mut minesweep, [Message::Reset]);
+    assert_eq!(minesweep.clicks, 0);
+    assert_eq!(minesweep.board_3bv, None);
+}
+
+#[test]
+fn boards_written_by_hand_are_played_from_file() {
+    let dir = std::env::temp_dir().join(format!("minesweep-board-file-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let grid = dir.join("puzzle.txt");
+    std::fs::write(&grid, "# Two mines on the left\n*...\n....\n*...\n").unwrap();
+    let outside = dir.join("outside.board");
+    std::fs::write(&outside, r#"{"width": 4, "height": 3, "mines": [[4, 0]]}"#).unwrap();
+
+    let mut minesweep = easy_game();
+    let editor_error = |minesweep: &Minesweep| match &minesweep.main_view {
+        MainViewContent::Editor(editor) => editor.error.clone(),
+        _ => None,
+    };
+
+    // Mines outside of the board are rejected, and the editor is still shown
+    play(
+        &mut minesweep,
+        [
+            Message::Editor(EditorMessage::Show),
+            Message::Editor(EditorMessage::FilePath(
+                outside.to_string_lossy().into_owned(),
+            )),
+            Message::Editor(EditorMessage::PlayFile),
+        ],
+    );
+    assert_eq!(
+        editor_error(&minesweep).as_deref(),
+        Some("Mine at (4, 0) is outside of the 4x3 board")
+    );
+
+    // A grid is played on a matching custom board
+    play(
+        &mut minesweep,
+        [
+            Message::Editor(EditorMessage::FilePath(grid.to_string_lossy().into_owned())),
+            Message::Editor(EditorMessage::PlayFile),
+        ],
+    );
+    assert!(matches!(minesweep.main_view, MainViewContent::Game));
+    assert_eq!(
+        (
+            minesweep.game_config.width,
+            minesweep.game_config.height,
+            minesweep.game_config.mines
+        ),
+        (4, 3, 2)
+    );
+    assert!(minesweep.custom_game);
+    assert_eq!(
+        minesweep.field.spot(0, 2).map(|spot| spot.state),
+        Some(SpotState::HiddenMine)
+    );
+
+    // Rows of different lengths and unknown spots are rejected as well
+    assert!(SavedBoard::from_grid("*..\n..\n").is_err());
+    assert!(SavedBoard::from_grid("*.x\n").is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn daily_challenges_give_everyone_the_same_board_and_keep_their_own_results() {
+    let mines = |minesweep: &Minesweep| -> BTreeSet<(u16, u16)> {
+        minesweep
+            .field
+            .mine_spots()
+            .map(|(coords, _)| coords)
+            .collect()
+    };
+
+    // The same daily challenge is played on the same board, whatever came before it
+    let mut minesweep = easy_game();
+    let _ = minesweep.start_daily_challenge(DifficultyLevel::Medium, "2026-10-14");
+    let board = mines(&minesweep);
+    assert_eq!(minesweep.game_config, GameDifficulty::MEDIUM);
+    play(&mut minesweep, [flag(0, 0), Message::Reset]);
+    let _ = minesweep.start_daily_challenge(DifficultyLevel::Medium, "2026-10-14");
+    assert_eq!(mines(&minesweep), board);
+
+    // Other days and difficulties have other boards
+    let _ = minesweep.start_daily_challenge(DifficultyLevel::Medium, "2026-10-15");
+    assert_ne!(mines(&minesweep), board);
+
+    // A won daily challenge is kept with the others of its date, but isn't a high score
+    let _ = minesweep.start_daily_challenge(DifficultyLevel::Easy, "2026-10-14");
+    let board = mines(&minesweep);
+    let safe_spots: Vec<(u16, u16)> = minesweep
+        .field
+        .spots()
+        .map(|(&coords, _)| coords)
+        .filter(|coords| !board.contains(coords))
+        .collect();
+    play(
+        &mut minesweep,
+        safe_spots
+            .into_iter()
+            .map(|(x, y)| step(x, y))
+            .chain(flag_all(&board.iter().copied().collect::<Vec<_>>())),
+    );
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: true });
+    assert!(matches!(minesweep.main_view, MainViewContent::Game));
+    let results = &minesweep.game_persistence().daily_results["2026-10-14"];
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_won && results[0].difficulty_level == DifficultyLevel::Easy);
+    assert!(minesweep.high_scores.is_empty());
+
+    // Only the first game of a daily challenge is recorded, so a board whose mines are known can't be won again
+    let _ = minesweep.start_daily_challenge(DifficultyLevel::Medium, "2026-10-14");
+    let board = mines(&minesweep);
+    let safe_spots: Vec<(u16, u16)> = minesweep
+        .field
+        .spots()
+        .map(|(&coords, _)| coords)
+        .filter(|coords| !board.contains(coords))
+        .collect();
+    let &(mine_x, mine_y) = board.iter().next().unwrap();
+    play(
+        &mut minesweep,
+        [step(safe_spots[0].0, safe_spots[0].1), step(mine_x, mine_y)],
+    );
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: false });
+    let _ = minesweep.start_daily_challenge(DifficultyLevel::Medium, "2026-10-14");
+    play(
+        &mut minesweep,
+        safe_spots
+            .into_iter()
+            .map(|(x, y)| step(x, y))
+            .chain(flag_all(&board.iter().copied().collect::<Vec<_>>())),
+    );
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: true });
+    let results = &minesweep.game_persistence().daily_results["2026-10-14"];
+    assert_eq!(results.len(), 2);
+    assert!(!results[1].is_won && results[1].difficulty_level == DifficultyLevel::Medium);
+
+    // A new game gets back to the player's own config
+    play(&mut minesweep, [Message::Reset]);
+    assert_eq!(minesweep.game_config, GameDifficulty::EASY);
+    assert!(minesweep.daily.is_none());
+}
+
+#[test]
+fn assisted_daily_challenges_are_played_all_the_same() {
+    let mut minesweep = easy_game();
+    let _ = minesweep.start_daily_challenge(DifficultyLevel::Easy, "2026-10-14");
+    let board: Vec<(u16, u16)> = minesweep
+        .field
+        .mine_spots()
+        .map(|(coords, _)| coords)
+        .collect();
+    let safe_spots: Vec<(u16, u16)> = minesweep
+        .field
+        .spots()
+        .map(|(&coords, _)| coords)
+        .filter(|coords| !board.contains(coords))
+        .collect();
+
+    // The first game is lost after peeking at the mines
+    play(
+        &mut minesweep,
+        [
+            Message::TogglePeek,
+            step(safe_spots[0].0, safe_spots[0].1),
+            step(board[0].0, board[0].1),
+        ],
+    );
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: false });
+
+    // Winning it again doesn't replace its result
+    let _ = minesweep.start_daily_challenge(DifficultyLevel::Easy, "2026-10-14");
+    play(
+        &mut minesweep,
+        safe_spots
+            .into_iter()
+            .map(|(x, y)| step(x, y))
+            .chain(flag_all(&board)),
+    );
+    assert_eq!(minesweep.game_state, GameState::Stopped { is_won: true });
+    let results = &minesweep.daily_results["2026-10-14"];
+    assert_eq!(results.len(), 1);
+    assert!(!results[0].is_won && results[0].assisted);
+}
+
+#[test]
+fn settings_search_matches_all_its_words() {
+    let text = "accessibility colorblind glyphs colorblind glyphs: tell flags and mines apart by their shapes";
+
+    assert!(SettingsSection::matches(text, ""));
+    assert!(SettingsSection::matches(text, "  Accessibility "));
+    assert!(SettingsSection::matches(text, "shapes mines"));
+    assert!(!SettingsSection::matches(text, "shapes sound"));
+}
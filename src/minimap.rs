@@ -0,0 +1,215 @@
+use crate::minesweep::Message;
+use iced::{
+    mouse::{self, Cursor},
+    widget::canvas::{self, event, Event, Frame, Path, Stroke},
+    Color, Point, Rectangle, Renderer, Size, Theme,
+};
+use minefield_rs::Minefield;
+
+/// Longest side of the minimap, whatever the size of the minefield
+const MINIMAP_SIZE: f32 = 150.0;
+
+/// Shortest side of the minimap, so that very narrow minefields can still be panned
+const MIN_MINIMAP_SIZE: f32 = 20.0;
+
+/// Most blocks drawn along each side of the minimap. Each block shows how many of the spots it covers are revealed or
+/// flagged, so large minefields don't need a rectangle per spot.
+const MAX_BLOCKS: u16 = 75;
+
+/// Colors in which the minimap is drawn, taken from the palette of the minefield
+#[derive(Debug, Clone, Copy)]
+pub struct MinimapColors {
+    pub hidden: Color,
+    pub revealed: Color,
+    pub flagged: Color,
+    pub viewport: Color,
+}
+
+/// A zoomed out overview of a minefield which doesn't fit in the window, showing where spots are revealed or flagged
+/// and which part of the minefield is shown. Clicking or dragging in the minimap pans the minefield to that spot.
+pub struct Minimap<'a> {
+    field: &'a Minefield,
+
+    /// The part of the minefield which is shown, as fractions of its width and height
+    viewport: Option<Rectangle>,
+
+    colors: MinimapColors,
+}
+
+impl<'a> Minimap<'a> {
+    pub fn new(field: &'a Minefield, viewport: Option<Rectangle>, colors: MinimapColors) -> Self {
+        Self {
+            field,
+            viewport,
+            colors,
+        }
+    }
+
+    /// Size of the minimap, which has the proportions of the minefield
+    pub fn size(&self) -> Size {
+        let (width, height) = (self.field.width() as f32, self.field.height() as f32);
+
+        if width >= height {
+            Size::new(
+                MINIMAP_SIZE,
+                (MINIMAP_SIZE * height / width).max(MIN_MINIMAP_SIZE),
+            )
+        } else {
+            Size::new(
+                (MINIMAP_SIZE * width / height).max(MIN_MINIMAP_SIZE),
+                MINIMAP_SIZE,
+            )
+        }
+    }
+
+    /// Ask for the minefield to be panned so that it's centered on the spot under the cursor
+    fn pan(bounds: Rectangle, cursor: Point) -> Message {
+        let x = ((cursor.x - bounds.x) / bounds.width).clamp(0.0, 1.0);
+        let y = ((cursor.y - bounds.y) / bounds.height).clamp(0.0, 1.0);
+
+        Message::PanField(Point::new(x, y))
+    }
+}
+
+/// Which block each spot along one side of the minefield falls in, and how many spots each block covers
+fn blocks(spots: u16) -> (u16, Vec<u32>) {
+    let blocks = spots.min(MAX_BLOCKS);
+    let mut counts = vec![0; blocks as usize];
+    for i in 0..spots {
+        counts[block(i, spots, blocks) as usize] += 1;
+    }
+
+    (blocks, counts)
+}
+
+fn block(i: u16, spots: u16, blocks: u16) -> u16 {
+    (i as u32 * blocks as u32 / spots as u32) as u16
+}
+
+impl canvas::Program<Message> for Minimap<'_> {
+    /// The viewport is being dragged
+    type State = bool;
+
+    fn update(
+        &self,
+        dragging: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> (event::Status, Option<Message>) {
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                match cursor.position_over(bounds) {
+                    Some(position) => {
+                        *dragging = true;
+                        (event::Status::Captured, Some(Self::pan(bounds, position)))
+                    }
+                    None => (event::Status::Ignored, None),
+                }
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) if *dragging => {
+                (event::Status::Captured, Some(Self::pan(bounds, position)))
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) if *dragging => {
+                *dragging = false;
+                (event::Status::Captured, None)
+            }
+            // Clicks on the minimap are not meant for the spots below it
+            Event::Mouse(mouse::Event::ButtonPressed(_) | mouse::Event::ButtonReleased(_))
+                if cursor.is_over(bounds) =>
+            {
+                (event::Status::Captured, None)
+            }
+            _ => (event::Status::Ignored, None),
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        dragging: &Self::State,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> mouse::Interaction {
+        if *dragging {
+            mouse::Interaction::Grabbing
+        } else if cursor.is_over(bounds) {
+            mouse::Interaction::Grab
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+
+    fn draw(
+        &self,
+        _dragging: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        frame.fill_rectangle(Point::ORIGIN, bounds.size(), self.colors.hidden);
+
+        let (width, height) = (self.field.width(), self.field.height());
+        let (columns, column_spots) = blocks(width);
+        let (rows, row_spots) = blocks(height);
+
+        // Only the revealed and flagged spots are looked at, which are usually much fewer than all the spots
+        let mut revealed = vec![0u32; columns as usize * rows as usize];
+        let mut flagged = vec![0u32; columns as usize * rows as usize];
+        let index = |(x, y): (u16, u16)| {
+            block(y, height, rows) as usize * columns as usize + block(x, width, columns) as usize
+        };
+        for (coords, _) in self.field.revealed_spots() {
+            revealed[index(coords)] += 1;
+        }
+        for (coords, _) in self.field.flagged_spots() {
+            flagged[index(coords)] += 1;
+        }
+
+        let block_size = Size::new(bounds.width / columns as f32, bounds.height / rows as f32);
+        for by in 0..rows {
+            for bx in 0..columns {
+                let i = by as usize * columns as usize + bx as usize;
+                let spots = (column_spots[bx as usize] * row_spots[by as usize]) as f32;
+                let top_left =
+                    Point::new(bx as f32 * block_size.width, by as f32 * block_size.height);
+
+                if revealed[i] > 0 {
+                    let color = Color {
+                        a: revealed[i] as f32 / spots,
+                        ..self.colors.revealed
+                    };
+                    frame.fill_rectangle(top_left, block_size, color);
+                }
+
+                // Flags are few, so any flag in a block stands out
+                if flagged[i] > 0 {
+                    let color = Color {
+                        a: 0.5 + 0.5 * flagged[i] as f32 / spots,
+                        ..self.colors.flagged
+                    };
+                    frame.fill_rectangle(top_left, block_size, color);
+                }
+            }
+        }
+
+        if let Some(viewport) = self.viewport {
+            let rectangle = Path::rectangle(
+                Point::new(viewport.x * bounds.width, viewport.y * bounds.height),
+                Size::new(
+                    viewport.width * bounds.width,
+                    viewport.height * bounds.height,
+                ),
+            );
+            frame.stroke(
+                &rectangle,
+                Stroke::default()
+                    .with_color(self.colors.viewport)
+                    .with_width(2.0),
+            );
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,392 @@
+use crate::minesweep::{GameConfig, GameDifficulty, MinesweepMessage};
+use minefield_rs::Connectivity;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Version of the replay file format. Replays saved with a different version cannot be played back.
+pub const REPLAY_VERSION: u32 = 1;
+
+/// Name of the directory where replays are saved, next to the file of the game configs
+pub const REPLAYS_DIR: &str = "replays";
+
+/// Extension of replay files
+pub const REPLAY_EXTENSION: &str = "replay";
+
+/// A recorded game, which can be played back.
+///
+/// Replay files are made of JSON lines: the first line is the `ReplayHeader`, the second one holds the coordinates of
+/// the mines, and each of the following lines holds a `ReplayEvent`. This allows listing the replays by only parsing
+/// their first line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    pub header: ReplayHeader,
+
+    /// Coordinates `(x, y)` of all the mines of the minefield
+    pub mines: Vec<(u16, u16)>,
+
+    /// Everything the player did, in order
+    pub events: Vec<ReplayEvent>,
+}
+
+/// Metadata of a recorded game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayHeader {
+    /// Version of the replay file format
+    pub version: u32,
+
+    /// The specifications of the recorded game
+    pub game_config: GameConfig,
+
+    /// The recorded game was configured as a custom game
+    pub custom_game: bool,
+
+    /// Duration of the game, in seconds
+    pub seconds: u64,
+
+    /// Time at which the game ended, in seconds since the unix epoch
+    pub date: u64,
+
+    /// The recorded game was won
+    pub is_won: bool,
+
+    /// Placing a flag autostepped around the numbers it satisfied, which must also happen when playing back
+    pub auto_step_on_flag: bool,
+
+    /// The recorded game only counted orthogonally adjacent spots as neighbors
+    #[serde(default)]
+    pub orthogonal_neighbors: bool,
+
+    /// Flags were replaced by question marks before being cleared, which must also happen when playing back
+    #[serde(default)]
+    pub question_marks: bool,
+}
+
+impl ReplayHeader {
+    pub fn connectivity(&self) -> Connectivity {
+        if self.orthogonal_neighbors {
+            Connectivity::Four
+        } else {
+            Connectivity::Eight
+        }
+    }
+
+    pub fn game_difficulty(&self) -> GameDifficulty {
+        if self.custom_game {
+            GameDifficulty::Custom(self.game_config)
+        } else {
+            GameDifficulty::from_config(&self.game_config)
+        }
+    }
+}
+
+/// A player action, at the given time since the beginning of the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayEvent {
+    /// Game time at which the action was taken, in milliseconds
+    pub millis: u64,
+
+    pub message: MinesweepMessage,
+}
+
+impl ReplayEvent {
+    pub fn game_time(&self) -> Duration {
+        Duration::from_millis(self.millis)
+    }
+}
+
+/// A replay file found in the replays directory, with its header, or the reason why it can't be played back
+#[derive(Debug, Clone)]
+pub struct ReplayEntry {
+    pub path: PathBuf,
+    pub header: Result<ReplayHeader, ReplayError>,
+}
+
+/// Reasons for which a replay can't be played back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The replay file could not be read
+    Io(String),
+
+    /// The replay file does not hold a valid replay
+    Corrupt(String),
+
+    /// The replay was saved with a different version of the replay file format
+    Version(u32),
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "Could not read replay: {}", e),
+            ReplayError::Corrupt(e) => write!(f, "Corrupt replay: {}", e),
+            ReplayError::Version(v) => write!(
+                f,
+                "Replay version {} is not supported (expected version {})",
+                v, REPLAY_VERSION
+            ),
+        }
+    }
+}
+
+impl Replay {
+    /// Path of a file in `dir` which doesn't exist yet, named after the date of the game, and other than `taken`
+    pub fn new_file_path(&self, dir: &Path, taken: Option<&Path>) -> PathBuf {
+        let level = self.header.game_difficulty().to_string();
+        let level = level.split_whitespace().next().unwrap_or_default();
+        let mut path = dir.join(format!(
+            "{}-{}.{}",
+            self.header.date, level, REPLAY_EXTENSION
+        ));
+        let mut n = 1;
+        while path.exists() || Some(path.as_path()) == taken {
+            n += 1;
+            path = dir.join(format!(
+                "{}-{}-{}.{}",
+                self.header.date, level, n, REPLAY_EXTENSION
+            ));
+        }
+
+        path
+    }
+
+    /// Write the replay to the file at `path`, creating its directory if needed
+    pub fn save_to(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{}", serde_json::to_string(&self.header)?)?;
+        writeln!(writer, "{}", serde_json::to_string(&self.mines)?)?;
+        for event in &self.events {
+            writeln!(writer, "{}", serde_json::to_string(event)?)?;
+        }
+        writer.flush()
+    }
+
+    /// Load the whole replay from the given file
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        let mut lines =
+            BufReader::new(File::open(path).map_err(|e| ReplayError::Io(e.to_string()))?).lines();
+
+        let header = Self::parse_header(lines.next())?;
+        let mines = serde_json::from_str(&Self::next_line(lines.next())?)
+            .map_err(|e| ReplayError::Corrupt(e.to_string()))?;
+        let events = lines
+            .map(|line| {
+                serde_json::from_str(&Self::next_line(Some(line))?)
+                    .map_err(|e| ReplayError::Corrupt(e.to_string()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            header,
+            mines,
+            events,
+        })
+    }
+
+    /// Only load the header of the replay from the given file, without reading the rest of it
+    pub fn load_header(path: &Path) -> Result<ReplayHeader, ReplayError> {
+        let file = File::open(path).map_err(|e| ReplayError::Io(e.to_string()))?;
+
+        Self::parse_header(BufReader::new(file).lines().next())
+    }
+
+    /// List all replay files in `dir`, most recent first
+    pub fn list(dir: &Path) -> Vec<ReplayEntry> {
+        let mut entries: Vec<ReplayEntry> = std::fs::read_dir(dir)
+            .map(|read_dir| {
+                read_dir
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.extension().is_some_and(|e| e == REPLAY_EXTENSION))
+                    .map(|path| ReplayEntry {
+                        header: Self::load_header(&path),
+                        path,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        entries.sort_by(|a, b| b.path.cmp(&a.path));
+
+        entries
+    }
+
+    fn parse_header(line: Option<std::io::Result<String>>) -> Result<ReplayHeader, ReplayError> {
+        let line = Self::next_line(line)?;
+
+        // Check the version first, since the rest of the header might have changed in other versions
+        #[derive(Deserialize)]
+        struct Version {
+            version: u32,
+        }
+
+        let version = serde_json::from_str::<Version>(&line)
+            .map_err(|e| ReplayError::Corrupt(e.to_string()))?
+            .version;
+
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::Version(version));
+        }
+
+        serde_json::from_str(&line).map_err(|e| ReplayError::Corrupt(e.to_string()))
+    }
+
+    fn next_line(line: Option<std::io::Result<String>>) -> Result<String, ReplayError> {
+        match line {
+            Some(Ok(line)) => Ok(line),
+            Some(Err(e)) => Err(ReplayError::Io(e.to_string())),
+            None => Err(ReplayError::Corrupt("unexpected end of file".to_string())),
+        }
+    }
+}
+
+/// Speeds at which a replay can be played back, as factors of the recorded time
+pub const PLAYBACK_SPEEDS: [f32; 5] = [0.5, 1.0, 2.0, 4.0, 8.0];
+
+/// Playback of a replay, which keeps track of the events which have already been played
+#[derive(Debug, Clone)]
+pub struct Playback {
+    pub replay: Replay,
+
+    /// Index of the next event to be played
+    next: usize,
+
+    /// Game time of the replay which has been played back so far
+    position: Duration,
+
+    /// Time of the last frame which moved the playback forward, if it is playing
+    last_frame: Option<Instant>,
+
+    /// Index of the playback speed in `PLAYBACK_SPEEDS`
+    speed: usize,
+
+    /// The playback is paused, so that the game stays as it is until it's resumed or stepped through
+    pub paused: bool,
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            next: 0,
+            position: Duration::ZERO,
+            last_frame: None,
+            speed: 1,
+            paused: false,
+        }
+    }
+
+    /// Game time of the replay which has been played back so far
+    pub fn position(&self) -> Duration {
+        self.position
+    }
+
+    /// Factor by which the recorded time is sped up
+    pub fn speed(&self) -> f32 {
+        PLAYBACK_SPEEDS[self.speed]
+    }
+
+    /// Switch to the next playback speed, going back to the slowest one after the fastest one
+    pub fn cycle_speed(&mut self) {
+        self.speed = (self.speed + 1) % PLAYBACK_SPEEDS.len();
+    }
+
+    /// Pause or resume the playback
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.last_frame = None;
+    }
+
+    /// Move the playback forward to the given time, at the playback speed, and take the messages of the events which
+    /// became due. Nothing moves while the playback is paused.
+    pub fn advance(&mut self, now: Instant) -> Vec<MinesweepMessage> {
+        if self.paused {
+            return Vec::new();
+        }
+
+        if let Some(last_frame) = self.last_frame {
+            self.position += now
+                .saturating_duration_since(last_frame)
+                .mul_f32(self.speed());
+        }
+        self.last_frame = Some(now);
+
+        self.due_messages(self.position)
+    }
+
+    /// Pause the playback and take the message of the next event, moving the playback to the time it was recorded at
+    pub fn step(&mut self) -> Option<MinesweepMessage> {
+        self.paused = true;
+        self.last_frame = None;
+
+        let event = self.replay.events.get(self.next)?;
+        self.position = self.position.max(event.game_time());
+        self.next += 1;
+
+        Some(event.message)
+    }
+
+    /// Take the messages of all the events which are due at the given game time, and haven't been played yet
+    fn due_messages(&mut self, game_time: Duration) -> Vec<MinesweepMessage> {
+        let due: Vec<MinesweepMessage> = self.replay.events[self.next..]
+            .iter()
+            .take_while(|event| event.game_time() <= game_time)
+            .map(|event| event.message)
+            .collect();
+
+        self.next += due.len();
+
+        due
+    }
+
+    /// All the events of the replay have been played
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.replay.events.len()
+    }
+}
+
+/// The current time, in seconds since the unix epoch
+pub fn now_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Format a time given in seconds since the unix epoch as a UTC `YYYY-MM-DD HH:MM` date
+pub fn format_timestamp(timestamp: u64) -> String {
+    let seconds_of_day = timestamp % 86400;
+
+    format!(
+        "{} {:02}:{:02}",
+        format_date(timestamp),
+        seconds_of_day / 3600,
+        (seconds_of_day % 3600) / 60
+    )
+}
+
+/// Format the UTC day of a time given in seconds since the unix epoch as a `YYYY-MM-DD` date
+pub fn format_date(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+
+    // Convert days since the epoch to a civil date (see http://howardhinnant.github.io/date_algorithms.html)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
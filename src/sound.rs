@@ -0,0 +1,107 @@
+//! Sound effects, played on a thread of their own so that the game never waits for the audio device.
+//!
+//! Playing sounds needs the `sound` feature. Without it, or if the audio device can't be opened (e.g. in CI or other
+//! headless environments), the sound effects are silently skipped.
+
+use std::sync::mpsc;
+
+/// The sounds which are played in response to the moves on the minefield
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sound {
+    /// Spots have been revealed
+    Reveal,
+
+    /// A flag has been placed or removed
+    Flag,
+
+    /// A mine has been stepped on
+    Explosion,
+
+    /// The minefield has been cleared
+    Win,
+}
+
+impl Sound {
+    /// The embedded WAV file of this sound
+    #[cfg_attr(not(feature = "sound"), allow(dead_code))]
+    fn wav(self) -> &'static [u8] {
+        match self {
+            Sound::Reveal => include_bytes!("../res/sounds/reveal.wav"),
+            Sound::Flag => include_bytes!("../res/sounds/flag.wav"),
+            Sound::Explosion => include_bytes!("../res/sounds/explosion.wav"),
+            Sound::Win => include_bytes!("../res/sounds/win.wav"),
+        }
+    }
+}
+
+/// Plays sounds on the audio thread, which is started when the player is created
+#[derive(Debug)]
+pub struct SoundPlayer {
+    /// Sends the sounds to the audio thread, if it could be started
+    sender: Option<mpsc::Sender<Sound>>,
+}
+
+impl SoundPlayer {
+    /// Sound effects can be played by this build (which doesn't mean that there's an audio device to play them on)
+    pub const AVAILABLE: bool = cfg!(feature = "sound");
+
+    /// Start the audio thread, which keeps the audio device open until the player is dropped
+    pub fn new() -> Self {
+        Self {
+            sender: Self::spawn(),
+        }
+    }
+
+    #[cfg(feature = "sound")]
+    fn spawn() -> Option<mpsc::Sender<Sound>> {
+        use rodio::{Decoder, OutputStream, Source};
+        use std::io::Cursor;
+
+        let (sender, receiver) = mpsc::channel::<Sound>();
+
+        let spawned = std::thread::Builder::new()
+            .name("sound".to_string())
+            .spawn(move || {
+                let (_stream, handle) = match OutputStream::try_default() {
+                    Ok(output) => output,
+                    Err(e) => {
+                        log::warn!("No sound effects, the audio device can't be opened: {}", e);
+                        return;
+                    }
+                };
+
+                // The thread ends when the player is dropped
+                for sound in receiver {
+                    match Decoder::new(Cursor::new(sound.wav())) {
+                        Ok(source) => {
+                            if let Err(e) = handle.play_raw(source.convert_samples()) {
+                                log::warn!("Sound effect {:?} can't be played: {}", sound, e);
+                            }
+                        }
+                        Err(e) => log::warn!("Sound effect {:?} can't be decoded: {}", sound, e),
+                    }
+                }
+            });
+
+        match spawned {
+            Ok(_) => Some(sender),
+            Err(e) => {
+                log::warn!("No sound effects, the audio thread can't be started: {}", e);
+                None
+            }
+        }
+    }
+
+    #[cfg(not(feature = "sound"))]
+    fn spawn() -> Option<mpsc::Sender<Sound>> {
+        None
+    }
+
+    /// Play the given sound, without waiting for it to finish. Nothing is played if the audio thread is gone.
+    pub fn play(&self, sound: Sound) {
+        if let Some(sender) = &self.sender {
+            // The audio thread is gone if the audio device couldn't be opened
+            let _ = sender.send(sound);
+        }
+    }
+}
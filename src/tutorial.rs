@@ -0,0 +1,180 @@
+use crate::minesweep::{ControlsConfig, GameConfig, MinesweepMessage, SpotAction};
+use minefield_rs::Minefield;
+
+/// A short walkthrough of the game for first-time players, played on a small board with a single mine. Each part of
+/// the tutorial asks for one move, and goes on once the player takes it.
+///
+/// The board is laid out so that stepping on its top left corner reveals all of it, except the mine (below the center)
+/// and the spot right below the mine, which is then revealed by autostepping next to the flagged mine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tutorial {
+    step: TutorialStep,
+}
+
+/// The parts of the tutorial, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialStep {
+    /// What the tutorial is about, before any move is asked for
+    Welcome,
+
+    /// Step on the top left corner, which opens up the board
+    Step,
+
+    /// Flag the mine below the center of the board
+    Flag,
+
+    /// Autostep next to the flagged mine, which reveals the last hidden spot
+    AutoStep,
+
+    /// A mine was stepped on, so the board needs to be set up again
+    Exploded,
+
+    /// The board was cleared
+    Done,
+}
+
+impl Tutorial {
+    pub const WIDTH: u16 = 5;
+    pub const HEIGHT: u16 = 5;
+
+    /// The only mine of the tutorial board
+    pub const MINE: (u16, u16) = (2, 3);
+
+    /// A tutorial which starts with its welcome text
+    pub fn new() -> Self {
+        Self {
+            step: TutorialStep::Welcome,
+        }
+    }
+
+    /// A tutorial which starts over from its first move, on a board which is set up again
+    pub fn retry() -> Self {
+        Self {
+            step: TutorialStep::Step,
+        }
+    }
+
+    /// The game config of the tutorial board, with the classic rules
+    pub fn game_config() -> GameConfig {
+        GameConfig {
+            width: Self::WIDTH,
+            height: Self::HEIGHT,
+            mines: 1,
+            ..GameConfig::default()
+        }
+    }
+
+    /// The tutorial board, with all its spots hidden
+    pub fn field() -> Minefield {
+        let (field, _) = Minefield::from_mines(Self::WIDTH, Self::HEIGHT, &[Self::MINE]);
+
+        field
+    }
+
+    pub fn step(&self) -> TutorialStep {
+        self.step
+    }
+
+    /// The tutorial is waiting for a move on the board, rather than for one of its buttons
+    pub fn takes_moves(&self) -> bool {
+        matches!(
+            self.step,
+            TutorialStep::Step | TutorialStep::Flag | TutorialStep::AutoStep
+        )
+    }
+
+    /// Go on from the welcome text to the first move
+    pub fn start(&mut self) {
+        if self.step == TutorialStep::Welcome {
+            self.step = TutorialStep::Step;
+        }
+    }
+
+    /// Go on to the next part of the tutorial once the player took the move it asks for, or once the game is over
+    /// whatever the moves taken (`is_won` is `None` while the game goes on)
+    pub fn observe(&mut self, message: MinesweepMessage, is_won: Option<bool>) {
+        self.step = match (self.step, is_won) {
+            (TutorialStep::Welcome | TutorialStep::Done, _) => self.step,
+            (_, Some(true)) => TutorialStep::Done,
+            (_, Some(false)) => TutorialStep::Exploded,
+            (step, None) if step.expected() == Some(message) => match step {
+                TutorialStep::Step => TutorialStep::Flag,
+                TutorialStep::Flag => TutorialStep::AutoStep,
+                _ => TutorialStep::Done,
+            },
+            (step, None) => step,
+        };
+    }
+
+    /// What the player is asked to do, naming the mouse buttons of the given controls
+    pub fn prompt(&self, controls: &ControlsConfig) -> String {
+        match self.step {
+            TutorialStep::Welcome => {
+                "Welcome to Minesweep! The goal is to reveal every spot which has no mine, and to flag the mines. \
+                This short tutorial shows how, on a small board with a single mine. It can be skipped, and started \
+                again from the settings."
+                    .to_string()
+            }
+            TutorialStep::Step => format!(
+                "Step on the top left spot, with {}. A spot without any mine around it opens up its neighbors.",
+                button_for(SpotAction::Step, controls)
+            ),
+            TutorialStep::Flag => format!(
+                "Each number counts the mines around its spot. The 1 in the center has a single hidden neighbor, right \
+                below it, so that spot must be the mine. Flag it, with {}.",
+                button_for(SpotAction::Flag, controls)
+            ),
+            TutorialStep::AutoStep => format!(
+                "The 1 left of the flag already has its mine flagged, so its other hidden neighbor is safe. Autostep \
+                on that 1, with {}, to reveal its neighbors which aren't flagged.",
+                button_for(SpotAction::AutoStep, controls)
+            ),
+            TutorialStep::Exploded => {
+                "Boom, that was the mine! The board can be set up again, to try once more.".to_string()
+            }
+            TutorialStep::Done => {
+                "Board cleared, well done! Bigger boards have more mines, and the numbers tell where they are. Have fun!"
+                    .to_string()
+            }
+        }
+    }
+}
+
+impl Default for Tutorial {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TutorialStep {
+    /// The move this part of the tutorial asks for, if any
+    fn expected(&self) -> Option<MinesweepMessage> {
+        match self {
+            TutorialStep::Step => Some(MinesweepMessage::Step { x: 0, y: 0 }),
+            TutorialStep::Flag => Some(MinesweepMessage::Flag {
+                x: Tutorial::MINE.0,
+                y: Tutorial::MINE.1,
+            }),
+            TutorialStep::AutoStep => Some(MinesweepMessage::AutoStep {
+                x: Tutorial::MINE.0 - 1,
+                y: Tutorial::MINE.1,
+            }),
+            TutorialStep::Welcome | TutorialStep::Exploded | TutorialStep::Done => None,
+        }
+    }
+}
+
+/// How the player takes the given action with their mouse buttons
+fn button_for(action: SpotAction, controls: &ControlsConfig) -> &'static str {
+    if controls.left == action {
+        "a left click"
+    } else if controls.right == action {
+        "a right click"
+    } else if controls.middle == action {
+        "a middle click"
+    } else if action == SpotAction::AutoStep && controls.left_right_chord {
+        "the left and right buttons pressed together"
+    } else {
+        "the button mapped to it in the controls settings"
+    }
+}